@@ -0,0 +1,13 @@
+fn main() {
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+
+    if std::env::var_os("CARGO_FEATURE_OVN_NB").is_some() {
+        ovsdb_codegen::generate("schemas/ovn-nb.ovsschema", &out_dir).unwrap();
+    }
+    if std::env::var_os("CARGO_FEATURE_OVN_SB").is_some() {
+        ovsdb_codegen::generate("schemas/ovn-sb.ovsschema", &out_dir).unwrap();
+    }
+    if std::env::var_os("CARGO_FEATURE_VSWITCH").is_some() {
+        ovsdb_codegen::generate("schemas/vswitch.ovsschema", &out_dir).unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+//! Shared by the helpers running the commands of the OVN and Open vSwitch
+//! tools
+
+use ovsdb_client::{
+    transact::{Operation, OperationResult, TransactClient},
+    Error,
+};
+use ovsdb_schema::OvsdbTable;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Returns the columns of a row to insert, failing rather than leaving out
+/// those which cannot be sent to OVSDB.
+pub(crate) fn columns<T: OvsdbTable>(row: &T) -> Result<HashMap<String, Value>, Error> {
+    row.try_to_map().map_err(Error::Conversion)
+}
+
+/// Match the row with the given name.
+#[cfg(any(feature = "ovn-nb", feature = "vswitch"))]
+pub(crate) fn named(name: &str) -> ovsdb_client::transact::Condition {
+    use ovsdb_client::transact::{Condition, Function};
+
+    Condition::new("name", Function::Equal, serde_json::json!(name))
+}
+
+/// Run the operations, failing if the last one matched no row, such as when
+/// the parent of an inserted row does not exist.
+pub(crate) async fn commit(
+    client: &impl TransactClient,
+    db_name: &str,
+    operations: Vec<Operation>,
+    missing: impl FnOnce() -> String,
+) -> Result<Vec<OperationResult>, Error> {
+    let results = client.transact(db_name, operations).await?;
+
+    // NOTE(mnaser): The rows inserted without a parent are not root rows, so they
+    //               are garbage collected by the server.
+    match results.last().and_then(|result| result.count) {
+        Some(0) => Err(Error::UnexpectedResponse(missing())),
+        _ => Ok(results),
+    }
+}
+
+/// Returns the UUID of the row inserted by the operation at the index.
+#[cfg(any(feature = "ovn-nb", feature = "vswitch"))]
+pub(crate) fn inserted(results: &[OperationResult], index: usize) -> Result<uuid::Uuid, Error> {
+    results
+        .get(index)
+        .and_then(OperationResult::uuid)
+        .ok_or_else(|| Error::UnexpectedResponse("insert returned no UUID".to_owned()))
+}
@@ -0,0 +1,37 @@
+//! Bindings for well-known OVSDB schemas
+//!
+//! Each database is a module behind its own feature, generated by
+//! `ovsdb-codegen` at build time from the schema vendored in `schemas/`.
+
+/// Tables of the `OVN_Northbound` database
+#[cfg(feature = "ovn-nb")]
+pub mod ovn_nb {
+    include!(concat!(env!("OUT_DIR"), "/ovn-nb.rs"));
+}
+
+/// Tables of the `OVN_Southbound` database
+#[cfg(feature = "ovn-sb")]
+pub mod ovn_sb {
+    include!(concat!(env!("OUT_DIR"), "/ovn-sb.rs"));
+}
+
+/// Tables of the `Open_vSwitch` database
+#[cfg(feature = "vswitch")]
+pub mod vswitch {
+    include!(concat!(env!("OUT_DIR"), "/vswitch.rs"));
+}
+
+/// ovn-nbctl and ovn-sbctl style helpers, running their transactions with
+/// `ovsdb-client`
+#[cfg(all(feature = "client", any(feature = "ovn-nb", feature = "ovn-sb")))]
+pub mod ovn;
+
+/// ovs-vsctl style helpers, running their transactions with `ovsdb-client`
+#[cfg(all(feature = "client", feature = "vswitch"))]
+pub mod ovs;
+
+#[cfg(all(
+    feature = "client",
+    any(feature = "ovn-nb", feature = "ovn-sb", feature = "vswitch")
+))]
+mod commands;
@@ -0,0 +1,249 @@
+//! Monitoring of the `OVN_Southbound` database for an agent running on a
+//! chassis, such as `ovn-controller`
+//!
+//! Rather than replicating the flows and bindings of every datapath, the
+//! monitor only reports those of the datapaths with a port bound to the
+//! chassis, its conditions following the ports as they are bound and
+//! released with "monitor_cond_change".
+
+use super::sb::DATABASE;
+use crate::ovn_sb::{
+    Chassis, ChassisPrivate, DatapathBinding, Encap, LogicalFlow, MacBinding, MulticastGroup,
+    PortBinding,
+};
+use ovsdb_client::{
+    monitor::Updates,
+    rpc::RpcClient,
+    schema::{MonitorCondRequest, RowUpdate2, TableUpdate2, Update2Notification},
+    transact::{Condition, Function},
+    Error, OvsdbClient,
+};
+use ovsdb_schema::extract_uuid;
+use serde_json::{json, Value};
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+/// The monitor of the rows of the southbound database relevant to a
+/// chassis, keeping track of the datapaths of the ports bound to it
+///
+/// Every notification of the monitor is given to [`ChassisMonitor::apply`],
+/// which changes its conditions once the ports bound to the chassis span
+/// other datapaths:
+///
+/// * every `Chassis` and `Encap`, to reach the other chassis
+/// * the `Chassis_Private` row of the chassis
+/// * the `Port_Binding` rows bound or requested to the chassis, and those
+///   of its datapaths
+/// * the `Datapath_Binding`, `Logical_Flow`, `MAC_Binding` and
+///   `Multicast_Group` rows of its datapaths
+///
+/// The flows applied to groups of datapaths, through `logical_dp_group`,
+/// are not reported.
+#[derive(Clone, Debug)]
+pub struct ChassisMonitor {
+    chassis: String,
+
+    id: Value,
+
+    /// The UUID of the `Chassis` row, once it is reported
+    uuid: Option<Uuid>,
+
+    /// The datapath and chassis of the reported port bindings
+    bindings: HashMap<Uuid, (Uuid, BTreeSet<Uuid>)>,
+
+    /// The datapaths the conditions were last built for
+    datapaths: BTreeSet<Uuid>,
+}
+
+impl ChassisMonitor {
+    /// Create the monitor of the chassis with the given name, as found in
+    /// its `Chassis` row and the `system-id` of its Open vSwitch.
+    pub fn new(chassis: &str) -> Self {
+        ChassisMonitor {
+            chassis: chassis.to_owned(),
+            id: json!([DATABASE, "chassis", chassis]),
+            uuid: None,
+            bindings: HashMap::new(),
+            datapaths: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the id of the monitor.
+    pub fn id(&self) -> &Value {
+        &self.id
+    }
+
+    /// Returns the UUID of the `Chassis` row, once it is reported.
+    pub fn chassis(&self) -> Option<Uuid> {
+        self.uuid
+    }
+
+    /// Returns the datapaths with a port bound to the chassis.
+    pub fn datapaths(&self) -> BTreeSet<Uuid> {
+        let Some(chassis) = self.uuid else {
+            return BTreeSet::new();
+        };
+
+        self.bindings
+            .values()
+            .filter(|(_, bound)| bound.contains(&chassis))
+            .map(|(datapath, _)| *datapath)
+            .collect()
+    }
+
+    /// Returns the requests of the monitor, with the conditions of the
+    /// chassis and datapaths known so far.
+    pub fn requests(&self) -> HashMap<String, MonitorCondRequest> {
+        let request = |conditions: Option<Vec<Condition>>| MonitorCondRequest {
+            conditions,
+            ..Default::default()
+        };
+        let datapaths = |column: &str| -> Vec<Condition> {
+            self.datapaths
+                .iter()
+                .map(|datapath| equal(column, json!(["uuid", datapath])))
+                .collect()
+        };
+
+        let mut bindings = datapaths("datapath");
+        if let Some(uuid) = self.uuid {
+            bindings.push(equal("chassis", json!(["uuid", uuid])));
+            bindings.push(equal("requested_chassis", json!(["uuid", uuid])));
+        }
+
+        HashMap::from([
+            (Chassis::TABLE.to_owned(), request(None)),
+            (Encap::TABLE.to_owned(), request(None)),
+            (
+                ChassisPrivate::TABLE.to_owned(),
+                request(Some(vec![equal("name", json!(self.chassis))])),
+            ),
+            (PortBinding::TABLE.to_owned(), request(Some(any(bindings)))),
+            (
+                DatapathBinding::TABLE.to_owned(),
+                request(Some(any(datapaths("_uuid")))),
+            ),
+            (
+                LogicalFlow::TABLE.to_owned(),
+                request(Some(any(datapaths("logical_datapath")))),
+            ),
+            (
+                MacBinding::TABLE.to_owned(),
+                request(Some(any(datapaths("datapath")))),
+            ),
+            (
+                MulticastGroup::TABLE.to_owned(),
+                request(Some(any(datapaths("datapath")))),
+            ),
+        ])
+    }
+
+    /// Create the monitor, returning its initial rows along with the stream
+    /// of its notifications, which are to be given to `apply`.
+    pub async fn start(
+        &mut self,
+        client: &OvsdbClient,
+    ) -> Result<(TableUpdate2<Value>, Updates<Update2Notification<Value>>), Error> {
+        let (initial, updates) = client
+            .monitor_cond_updates(DATABASE, self.id.clone(), self.requests())
+            .await?;
+        self.apply(client, &initial).await?;
+
+        Ok((initial, updates))
+    }
+
+    /// Keep track of the chassis and the ports bound to it from the initial
+    /// rows or a notification of the monitor, changing its conditions if
+    /// they span other datapaths, returning whether they did.
+    pub async fn apply(
+        &mut self,
+        client: &impl RpcClient,
+        message: &TableUpdate2<Value>,
+    ) -> Result<bool, Error> {
+        if !self.update(message) {
+            return Ok(false);
+        }
+
+        client
+            .monitor_cond_change(self.id.clone(), self.id.clone(), self.requests())
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Keep track of the rows of a message, returning whether the conditions
+    /// have to change.
+    pub fn update(&mut self, message: &TableUpdate2<Value>) -> bool {
+        let known = self.uuid;
+
+        for (uuid, update) in message.get(Chassis::TABLE).into_iter().flatten() {
+            match update {
+                RowUpdate2::Initial(row) | RowUpdate2::Insert(row)
+                    if row["name"] == self.chassis.as_str() =>
+                {
+                    self.uuid = Some(*uuid);
+                }
+                RowUpdate2::Delete if self.uuid == Some(*uuid) => self.uuid = None,
+                _ => {}
+            }
+        }
+
+        for (uuid, update) in message.get(PortBinding::TABLE).into_iter().flatten() {
+            match update {
+                RowUpdate2::Initial(row) | RowUpdate2::Insert(row) => {
+                    let datapath = extract_uuid(&row["datapath"]).unwrap_or_default();
+                    self.bindings
+                        .insert(*uuid, (datapath, uuids(&row["chassis"])));
+                }
+                RowUpdate2::Modify(diff) => {
+                    if let Some((_, chassis)) = self.bindings.get_mut(uuid) {
+                        // NOTE(mnaser): The changes of a set column are sent as the
+                        //               elements added or removed.
+                        *chassis = chassis
+                            .symmetric_difference(&uuids(&diff["chassis"]))
+                            .copied()
+                            .collect();
+                    }
+                }
+                RowUpdate2::Delete => {
+                    self.bindings.remove(uuid);
+                }
+            }
+        }
+
+        let datapaths = self.datapaths();
+        if known == self.uuid && datapaths == self.datapaths {
+            return false;
+        }
+
+        self.datapaths = datapaths;
+        true
+    }
+}
+
+fn equal(column: &str, value: Value) -> Condition {
+    Condition::new(column, Function::Equal, value)
+}
+
+/// Returns the conditions, or one matching no row without any, as a monitor
+/// without conditions reports every row.
+fn any(conditions: Vec<Condition>) -> Vec<Condition> {
+    if !conditions.is_empty() {
+        return conditions;
+    }
+
+    vec![Condition::uuid(Uuid::nil())]
+}
+
+/// Returns the UUIDs of a set column, given as a set or a single UUID.
+fn uuids(value: &Value) -> BTreeSet<Uuid> {
+    match value {
+        Value::Array(array) if array.len() == 2 && array[0] == "set" => array[1]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(extract_uuid)
+            .collect(),
+        value => extract_uuid(value).into_iter().collect(),
+    }
+}
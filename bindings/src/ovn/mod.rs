@@ -0,0 +1,17 @@
+//! The helpers build the operations of a command, wiring the rows they
+//! insert to their parents with named UUIDs, and run them as a single
+//! transaction.
+//!
+//! Both OVN databases also hold the remotes of their servers, as rows of
+//! their `Connection` and `SSL` tables referenced by their global table, so
+//! that `ovsdb-server` can be started with
+//! `--remote=db:OVN_Northbound,NB_Global,connections` and configured from
+//! within the database.
+
+#[cfg(feature = "ovn-sb")]
+pub mod chassis;
+#[cfg(feature = "ovn-nb")]
+pub mod nb;
+mod remotes;
+#[cfg(feature = "ovn-sb")]
+pub mod sb;
@@ -0,0 +1,323 @@
+//! Helpers for the `OVN_Northbound` database, after the commands of
+//! `ovn-nbctl`
+
+use super::remotes;
+use crate::commands::{columns, commit, inserted, named};
+use crate::ovn_nb::{
+    Acl, AclAction, AclDirection, Connection, LogicalRouter, LogicalRouterPort, LogicalSwitch,
+    LogicalSwitchPort, NbGlobal, Ssl,
+};
+use ovsdb_client::{
+    crud,
+    transact::{Operation, OperationResult, TransactClient},
+    Error, OvsdbClient,
+};
+use ovsdb_schema::{Mutation, Mutator, OvsdbAtom, OvsdbTable, OvsdbValue};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// The name of the database
+pub const DATABASE: &str = "OVN_Northbound";
+
+/// The operations of `ovn-nbctl ls-add`, inserting the switch.
+pub fn ls_add_operations(switch: &LogicalSwitch) -> Result<Vec<Operation>, Error> {
+    Ok(vec![Operation::Insert {
+        table: LogicalSwitch::TABLE.to_owned(),
+        row: columns(switch)?,
+        uuid_name: None,
+    }])
+}
+
+/// Create a switch without ports, returning its UUID.
+pub async fn ls_add(client: &impl TransactClient, name: &str) -> Result<Uuid, Error> {
+    let mut switch = LogicalSwitch::new();
+    switch.name = name.to_owned();
+
+    let results = client
+        .transact(DATABASE, ls_add_operations(&switch)?)
+        .await?;
+    inserted(&results, 0)
+}
+
+/// The operations of `ovn-nbctl lsp-add`, inserting the port and adding it
+/// to the ports of the switch with the given name.
+pub fn lsp_add_operations(switch: &str, port: &LogicalSwitchPort) -> Result<Vec<Operation>, Error> {
+    insert_into(LogicalSwitch::TABLE, switch, "ports", port)
+}
+
+/// Add a port to a switch, returning its UUID.
+pub async fn lsp_add(
+    client: &impl TransactClient,
+    switch: &str,
+    name: &str,
+) -> Result<Uuid, Error> {
+    let mut port = LogicalSwitchPort::new();
+    port.name = name.to_owned();
+
+    let operations = lsp_add_operations(switch, &port)?;
+    let results = commit(client, DATABASE, operations, || not_found("switch", switch)).await?;
+    inserted(&results, 0)
+}
+
+/// The operations of `ovn-nbctl lsp-set-addresses`, replacing the addresses
+/// of the port with the given name, such as `00:00:00:00:00:01 10.0.0.1`,
+/// `router`, `dynamic` or `unknown`.
+pub fn lsp_set_addresses_operations(port: &str, addresses: &[&str]) -> Vec<Operation> {
+    vec![Operation::Update {
+        table: LogicalSwitchPort::TABLE.to_owned(),
+        conditions: vec![named(port)],
+        row: HashMap::from([("addresses".to_owned(), json!(["set", addresses]))]),
+    }]
+}
+
+/// Replace the addresses of a port.
+pub async fn lsp_set_addresses(
+    client: &impl TransactClient,
+    port: &str,
+    addresses: &[&str],
+) -> Result<(), Error> {
+    let operations = lsp_set_addresses_operations(port, addresses);
+    commit(client, DATABASE, operations, || not_found("port", port)).await?;
+
+    Ok(())
+}
+
+/// The operations of `ovn-nbctl lr-add`, inserting the router.
+pub fn lr_add_operations(router: &LogicalRouter) -> Result<Vec<Operation>, Error> {
+    Ok(vec![Operation::Insert {
+        table: LogicalRouter::TABLE.to_owned(),
+        row: columns(router)?,
+        uuid_name: None,
+    }])
+}
+
+/// Create a router without ports, returning its UUID.
+pub async fn lr_add(client: &impl TransactClient, name: &str) -> Result<Uuid, Error> {
+    let mut router = LogicalRouter::new();
+    router.name = name.to_owned();
+
+    let results = client
+        .transact(DATABASE, lr_add_operations(&router)?)
+        .await?;
+    inserted(&results, 0)
+}
+
+/// The operations of `ovn-nbctl lrp-add`, inserting the port and adding it
+/// to the ports of the router with the given name.
+pub fn lrp_add_operations(router: &str, port: &LogicalRouterPort) -> Result<Vec<Operation>, Error> {
+    insert_into(LogicalRouter::TABLE, router, "ports", port)
+}
+
+/// Add a port to a router, with its MAC address and at least one network,
+/// such as `192.168.0.1/24`, returning its UUID.
+pub async fn lrp_add(
+    client: &impl TransactClient,
+    router: &str,
+    name: &str,
+    mac: &str,
+    networks: &[&str],
+) -> Result<Uuid, Error> {
+    let mut port = LogicalRouterPort::new();
+    port.name = name.to_owned();
+    port.mac = mac.to_owned();
+    port.networks = networks.iter().map(|&network| network.to_owned()).collect();
+
+    let operations = lrp_add_operations(router, &port)?;
+    let results = commit(client, DATABASE, operations, || not_found("router", router)).await?;
+    inserted(&results, 0)
+}
+
+/// The operations of `ovn-nbctl acl-add`, inserting the ACL and adding it to
+/// the ACLs of the switch with the given name.
+pub fn acl_add_operations(switch: &str, acl: &Acl) -> Result<Vec<Operation>, Error> {
+    insert_into(LogicalSwitch::TABLE, switch, "acls", acl)
+}
+
+/// Add an ACL to a switch, returning its UUID.
+pub async fn acl_add(
+    client: &impl TransactClient,
+    switch: &str,
+    direction: AclDirection,
+    priority: i64,
+    r#match: &str,
+    action: AclAction,
+) -> Result<Uuid, Error> {
+    let mut acl = Acl::new();
+    acl.direction = direction;
+    acl.priority = priority;
+    acl.r#match = r#match.to_owned();
+    acl.action = action;
+
+    let operations = acl_add_operations(switch, &acl)?;
+    let results = commit(client, DATABASE, operations, || not_found("switch", switch)).await?;
+    inserted(&results, 0)
+}
+
+/// Returns a connection to the target, such as `ptcp:6641:0.0.0.0` or
+/// `pssl:6641`, with the defaults of the other columns.
+pub fn connection(target: &str) -> Connection {
+    let mut connection = Connection::new();
+    connection.target = target.to_owned();
+    connection
+}
+
+/// The operations of `ovn-nbctl set-connection`, replacing the connections
+/// of `NB_Global` by the given ones.
+pub fn set_connection_operations(connections: &[Connection]) -> Result<Vec<Operation>, Error> {
+    remotes::replace(NbGlobal::TABLE, "connections", connections)
+}
+
+/// Replace the connections of `NB_Global` by the given ones, which
+/// `ovsdb-server` listens on or connects to when started with
+/// `--remote=db:OVN_Northbound,NB_Global,connections`.
+pub async fn set_connection(
+    client: &impl TransactClient,
+    connections: &[Connection],
+) -> Result<(), Error> {
+    let operations = set_connection_operations(connections)?;
+    remotes::commit(client, DATABASE, NbGlobal::TABLE, operations).await
+}
+
+/// Returns the connections of `NB_Global`.
+pub async fn get_connection(client: &impl TransactClient) -> Result<Vec<Connection>, Error> {
+    crud::list(client, DATABASE).await
+}
+
+/// Remove every connection of `NB_Global`.
+pub async fn del_connection(client: &impl TransactClient) -> Result<(), Error> {
+    set_connection(client, &[]).await
+}
+
+/// The operations of `ovn-nbctl set-ssl`, replacing the SSL configuration
+/// of `NB_Global`.
+pub fn set_ssl_operations(ssl: &Ssl) -> Result<Vec<Operation>, Error> {
+    remotes::replace(NbGlobal::TABLE, "ssl", std::slice::from_ref(ssl))
+}
+
+/// Replace the SSL configuration of `NB_Global`, used by its `pssl` and
+/// `ssl` connections.
+pub async fn set_ssl(client: &impl TransactClient, ssl: &Ssl) -> Result<(), Error> {
+    remotes::commit(client, DATABASE, NbGlobal::TABLE, set_ssl_operations(ssl)?).await
+}
+
+/// Returns the SSL configuration of `NB_Global`, if any.
+pub async fn get_ssl(client: &impl TransactClient) -> Result<Option<Ssl>, Error> {
+    Ok(crud::list(client, DATABASE).await?.into_iter().next())
+}
+
+/// Remove the SSL configuration of `NB_Global`.
+pub async fn del_ssl(client: &impl TransactClient) -> Result<(), Error> {
+    let operations = remotes::replace::<Ssl>(NbGlobal::TABLE, "ssl", &[])?;
+    remotes::commit(client, DATABASE, NbGlobal::TABLE, operations).await
+}
+
+/// What `commit_and_wait` waits for, as with `ovn-nbctl --wait`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wait {
+    /// ovn-northd updated the southbound database, as reported by `sb_cfg`
+    Sb,
+
+    /// Every chassis applied the change, as reported by `hv_cfg`
+    Hv,
+}
+
+/// The operations bumping `nb_cfg`, appended to a transaction by
+/// `commit_and_wait`, the first one selecting its value before the bump.
+pub fn bump_nb_cfg_operations() -> Vec<Operation> {
+    vec![
+        Operation::Select {
+            table: NbGlobal::TABLE.to_owned(),
+            conditions: Vec::new(),
+            columns: Some(vec!["nb_cfg".to_owned()]),
+        },
+        Operation::Mutate {
+            table: NbGlobal::TABLE.to_owned(),
+            conditions: Vec::new(),
+            mutations: vec![Mutation::new(
+                "nb_cfg",
+                Mutator::Add,
+                OvsdbValue::Atom(OvsdbAtom::Integer(1)),
+            )],
+        },
+    ]
+}
+
+/// Run the operations along with a bump of `nb_cfg`, and wait for the change
+/// to reach the southbound database or the chassis, returning the results of
+/// the operations.
+///
+/// The timeout only applies to the wait, which fails with `Error::TimedOut`
+/// once it elapsed, the transaction having been committed regardless.
+pub async fn commit_and_wait(
+    client: &OvsdbClient,
+    mut operations: Vec<Operation>,
+    wait: Wait,
+    timeout: Duration,
+) -> Result<Vec<OperationResult>, Error> {
+    let count = operations.len();
+    operations.extend(bump_nb_cfg_operations());
+
+    let mut results = commit(client, DATABASE, operations, || {
+        format!(
+            "{} has no row, {DATABASE} is not initialized",
+            NbGlobal::TABLE
+        )
+    })
+    .await?;
+
+    // NOTE(mnaser): Transactions are serialized by the server, so the value selected
+    //               within this one is the one it bumped.
+    let nb_cfg = results[count]
+        .rows
+        .iter()
+        .flatten()
+        .find_map(|row| row.get("nb_cfg")?.as_i64())
+        .ok_or_else(|| Error::UnexpectedResponse("select returned no nb_cfg".to_owned()))?
+        + 1;
+
+    client
+        .wait_until::<NbGlobal>(
+            DATABASE,
+            |global| match wait {
+                Wait::Sb => global.sb_cfg >= nb_cfg,
+                Wait::Hv => global.hv_cfg >= nb_cfg,
+            },
+            timeout,
+        )
+        .await?;
+
+    results.truncate(count);
+    Ok(results)
+}
+
+/// Returns the operations inserting the row and adding it to the column of
+/// the parent with the given name, such as a port to its switch.
+fn insert_into<T: OvsdbTable>(
+    parent: &str,
+    name: &str,
+    column: &str,
+    row: &T,
+) -> Result<Vec<Operation>, Error> {
+    Ok(vec![
+        Operation::Insert {
+            table: T::TABLE.to_owned(),
+            row: columns(row)?,
+            uuid_name: Some("row".to_owned()),
+        },
+        Operation::Mutate {
+            table: parent.to_owned(),
+            conditions: vec![named(name)],
+            mutations: vec![Mutation::new(
+                column,
+                Mutator::Insert,
+                OvsdbValue::Set(vec![OvsdbAtom::NamedUuid("row".to_owned())]),
+            )],
+        },
+    ])
+}
+
+fn not_found(kind: &str, name: &str) -> String {
+    format!("{name}: {kind} name not found")
+}
@@ -0,0 +1,58 @@
+use crate::commands::columns;
+use ovsdb_client::{
+    transact::{Operation, TransactClient},
+    Error,
+};
+use ovsdb_schema::OvsdbTable;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Returns the operations replacing the rows referenced by the column of the
+/// global table by new ones, the previous rows being garbage collected as
+/// they are not root rows.
+pub(crate) fn replace<T: OvsdbTable>(
+    global: &str,
+    column: &str,
+    rows: &[T],
+) -> Result<Vec<Operation>, Error> {
+    let name = |index: usize| format!("row{index}");
+
+    let mut operations: Vec<Operation> = rows
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            Ok(Operation::Insert {
+                table: T::TABLE.to_owned(),
+                row: columns(row)?,
+                uuid_name: Some(name(index)),
+            })
+        })
+        .collect::<Result<_, Error>>()?;
+
+    let references: Vec<Value> = (0..rows.len())
+        .map(|index| json!(["named-uuid", name(index)]))
+        .collect();
+    operations.push(Operation::Update {
+        table: global.to_owned(),
+        conditions: Vec::new(),
+        row: HashMap::from([(column.to_owned(), json!(["set", references]))]),
+    });
+
+    Ok(operations)
+}
+
+/// Run the operations of `replace`, failing if the database has no global
+/// row, as before it is initialized.
+pub(crate) async fn commit(
+    client: &impl TransactClient,
+    db_name: &str,
+    global: &str,
+    operations: Vec<Operation>,
+) -> Result<(), Error> {
+    crate::commands::commit(client, db_name, operations, || {
+        format!("{global} has no row, {db_name} is not initialized")
+    })
+    .await?;
+
+    Ok(())
+}
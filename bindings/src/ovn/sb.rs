@@ -0,0 +1,71 @@
+//! Helpers for the `OVN_Southbound` database, after the commands of
+//! `ovn-sbctl`
+
+use super::remotes;
+use crate::ovn_sb::{Connection, SbGlobal, Ssl};
+use ovsdb_client::{
+    crud,
+    transact::{Operation, TransactClient},
+    Error,
+};
+
+/// The name of the database
+pub const DATABASE: &str = "OVN_Southbound";
+
+/// Returns a connection to the target, such as `ptcp:6642:0.0.0.0` or
+/// `pssl:6642`, with the defaults of the other columns.
+pub fn connection(target: &str) -> Connection {
+    let mut connection = Connection::new();
+    connection.target = target.to_owned();
+    connection
+}
+
+/// The operations of `ovn-sbctl set-connection`, replacing the connections
+/// of `SB_Global` by the given ones.
+pub fn set_connection_operations(connections: &[Connection]) -> Result<Vec<Operation>, Error> {
+    remotes::replace(SbGlobal::TABLE, "connections", connections)
+}
+
+/// Replace the connections of `SB_Global` by the given ones, which
+/// `ovsdb-server` listens on or connects to when started with
+/// `--remote=db:OVN_Southbound,SB_Global,connections`.
+pub async fn set_connection(
+    client: &impl TransactClient,
+    connections: &[Connection],
+) -> Result<(), Error> {
+    let operations = set_connection_operations(connections)?;
+    remotes::commit(client, DATABASE, SbGlobal::TABLE, operations).await
+}
+
+/// Returns the connections of `SB_Global`.
+pub async fn get_connection(client: &impl TransactClient) -> Result<Vec<Connection>, Error> {
+    crud::list(client, DATABASE).await
+}
+
+/// Remove every connection of `SB_Global`.
+pub async fn del_connection(client: &impl TransactClient) -> Result<(), Error> {
+    set_connection(client, &[]).await
+}
+
+/// The operations of `ovn-sbctl set-ssl`, replacing the SSL configuration
+/// of `SB_Global`.
+pub fn set_ssl_operations(ssl: &Ssl) -> Result<Vec<Operation>, Error> {
+    remotes::replace(SbGlobal::TABLE, "ssl", std::slice::from_ref(ssl))
+}
+
+/// Replace the SSL configuration of `SB_Global`, used by its `pssl` and
+/// `ssl` connections.
+pub async fn set_ssl(client: &impl TransactClient, ssl: &Ssl) -> Result<(), Error> {
+    remotes::commit(client, DATABASE, SbGlobal::TABLE, set_ssl_operations(ssl)?).await
+}
+
+/// Returns the SSL configuration of `SB_Global`, if any.
+pub async fn get_ssl(client: &impl TransactClient) -> Result<Option<Ssl>, Error> {
+    Ok(crud::list(client, DATABASE).await?.into_iter().next())
+}
+
+/// Remove the SSL configuration of `SB_Global`.
+pub async fn del_ssl(client: &impl TransactClient) -> Result<(), Error> {
+    let operations = remotes::replace::<Ssl>(SbGlobal::TABLE, "ssl", &[])?;
+    remotes::commit(client, DATABASE, SbGlobal::TABLE, operations).await
+}
@@ -0,0 +1,5 @@
+//! The helpers build the operations of a command, wiring the rows they
+//! insert to their parents with named UUIDs, and run them as a single
+//! transaction.
+
+pub mod vsctl;
@@ -0,0 +1,236 @@
+//! Helpers for the `Open_vSwitch` database, after the commands of
+//! `ovs-vsctl`
+//!
+//! Bridges, ports and interfaces are not root rows, so a bridge only exists
+//! once it is referenced by the `bridges` of the single `Open_vSwitch` row,
+//! and a port once it is referenced by the `ports` of its bridge.
+
+use crate::{
+    commands::{columns, commit, inserted, named},
+    vswitch::{Bridge, Interface, OpenVSwitch, Port},
+};
+use ovsdb_client::{
+    transact::{Operation, TransactClient},
+    Error,
+};
+use ovsdb_schema::{Mutation, Mutator, OvsdbAtom, OvsdbTable, OvsdbValue};
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// The name of the database
+pub const DATABASE: &str = "Open_vSwitch";
+
+/// The operations of `ovs-vsctl add-br`, inserting the bridge along with its
+/// internal port and interface of the same name, and adding it to the
+/// bridges of `Open_vSwitch`.
+pub fn add_br_operations(bridge: &Bridge) -> Result<Vec<Operation>, Error> {
+    let mut interface = Interface::new();
+    interface.name = bridge.name.clone();
+    interface.r#type = "internal".to_owned();
+
+    let mut port = Port::new();
+    port.name = bridge.name.clone();
+
+    let mut operations = port_operations(&port, &interface)?;
+    operations.push(insert(bridge, "bridge", "ports", "port")?);
+    operations.push(add_to(OpenVSwitch::TABLE, None, "bridges", "bridge"));
+    Ok(operations)
+}
+
+/// Create a bridge, returning its UUID.
+pub async fn add_br(client: &impl TransactClient, name: &str) -> Result<Uuid, Error> {
+    let mut bridge = Bridge::new();
+    bridge.name = name.to_owned();
+
+    let results = commit(client, DATABASE, add_br_operations(&bridge)?, || {
+        format!(
+            "{} has no row, {DATABASE} is not initialized",
+            OpenVSwitch::TABLE
+        )
+    })
+    .await?;
+    inserted(&results, 2)
+}
+
+/// The operations of `ovs-vsctl add-port`, inserting the port with its
+/// interface and adding it to the ports of the bridge with the given name.
+pub fn add_port_operations(
+    bridge: &str,
+    port: &Port,
+    interface: &Interface,
+) -> Result<Vec<Operation>, Error> {
+    let mut operations = port_operations(port, interface)?;
+    operations.push(add_to(Bridge::TABLE, Some(bridge), "ports", "port"));
+    Ok(operations)
+}
+
+/// Add a port to a bridge, with an interface of the same name, returning
+/// the UUID of the port.
+pub async fn add_port(
+    client: &impl TransactClient,
+    bridge: &str,
+    name: &str,
+) -> Result<Uuid, Error> {
+    let mut port = Port::new();
+    port.name = name.to_owned();
+    let mut interface = Interface::new();
+    interface.name = name.to_owned();
+
+    let operations = add_port_operations(bridge, &port, &interface)?;
+    let results = commit(client, DATABASE, operations, || no_bridge(bridge)).await?;
+    inserted(&results, 1)
+}
+
+/// The operations of `ovs-vsctl set Interface <name> options:<key>=<value>`,
+/// setting the options of the interface with the given name, such as the
+/// `remote_ip` of a tunnel, the other options being kept.
+pub fn set_interface_options_operations(
+    interface: &str,
+    options: &HashMap<String, String>,
+) -> Vec<Operation> {
+    let keys = options
+        .keys()
+        .map(|key| OvsdbAtom::String(key.clone()))
+        .collect();
+    let pairs = options
+        .iter()
+        .map(|(key, value)| {
+            (
+                OvsdbAtom::String(key.clone()),
+                OvsdbAtom::String(value.clone()),
+            )
+        })
+        .collect();
+
+    vec![Operation::Mutate {
+        table: Interface::TABLE.to_owned(),
+        conditions: vec![named(interface)],
+        mutations: vec![
+            Mutation::new("options", Mutator::Delete, OvsdbValue::Set(keys)),
+            Mutation::new("options", Mutator::Insert, OvsdbValue::Map(pairs)),
+        ],
+    }]
+}
+
+/// Set options of an interface, keeping the others.
+pub async fn set_interface_options(
+    client: &impl TransactClient,
+    interface: &str,
+    options: &HashMap<String, String>,
+) -> Result<(), Error> {
+    let operations = set_interface_options_operations(interface, options);
+    commit(client, DATABASE, operations, || {
+        format!("no row \"{interface}\" in table Interface")
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Returns the value of the key in the external IDs of the bridge, as
+/// `ovs-vsctl br-get-external-id`.
+pub async fn get_external_id(
+    client: &impl TransactClient,
+    bridge: &str,
+    key: &str,
+) -> Result<Option<String>, Error> {
+    let operation = Operation::Select {
+        table: Bridge::TABLE.to_owned(),
+        conditions: vec![named(bridge)],
+        columns: None,
+    };
+    let results = client.transact(DATABASE, vec![operation]).await?;
+
+    let row = results
+        .into_iter()
+        .next()
+        .and_then(|result| result.rows)
+        .and_then(|rows| rows.into_iter().next())
+        .ok_or_else(|| Error::UnexpectedResponse(no_bridge(bridge)))?;
+    let mut bridge = Bridge::from_map(&row).map_err(Error::Conversion)?;
+
+    Ok(bridge.external_ids.remove(key))
+}
+
+/// Set the value of the key in the external IDs of the bridge, or remove
+/// the key without a value, as `ovs-vsctl br-set-external-id`.
+pub async fn set_external_id(
+    client: &impl TransactClient,
+    bridge: &str,
+    key: &str,
+    value: Option<&str>,
+) -> Result<(), Error> {
+    let mut mutations = vec![Mutation::new(
+        "external_ids",
+        Mutator::Delete,
+        OvsdbValue::Set(vec![OvsdbAtom::String(key.to_owned())]),
+    )];
+    if let Some(value) = value {
+        mutations.push(Mutation::new(
+            "external_ids",
+            Mutator::Insert,
+            OvsdbValue::Map(vec![(
+                OvsdbAtom::String(key.to_owned()),
+                OvsdbAtom::String(value.to_owned()),
+            )]),
+        ));
+    }
+
+    let operation = Operation::Mutate {
+        table: Bridge::TABLE.to_owned(),
+        conditions: vec![named(bridge)],
+        mutations,
+    };
+    commit(client, DATABASE, vec![operation], || no_bridge(bridge)).await?;
+
+    Ok(())
+}
+
+/// Returns the operations inserting the interface, then the port holding it.
+fn port_operations(port: &Port, interface: &Interface) -> Result<Vec<Operation>, Error> {
+    Ok(vec![
+        Operation::Insert {
+            table: Interface::TABLE.to_owned(),
+            row: columns(interface)?,
+            uuid_name: Some("interface".to_owned()),
+        },
+        insert(port, "port", "interfaces", "interface")?,
+    ])
+}
+
+/// Returns the operation inserting the row as the given name, with the
+/// column referencing the row inserted as another name.
+fn insert<T: OvsdbTable>(
+    row: &T,
+    name: &str,
+    column: &str,
+    child: &str,
+) -> Result<Operation, Error> {
+    let mut row = columns(row)?;
+    row.insert(column.to_owned(), json!(["named-uuid", child]));
+
+    Ok(Operation::Insert {
+        table: T::TABLE.to_owned(),
+        row,
+        uuid_name: Some(name.to_owned()),
+    })
+}
+
+/// Returns the operation adding the row inserted as the given name to the
+/// column of the parent, by name or the only row of the table without one.
+fn add_to(parent: &str, name: Option<&str>, column: &str, child: &str) -> Operation {
+    Operation::Mutate {
+        table: parent.to_owned(),
+        conditions: name.map(named).into_iter().collect(),
+        mutations: vec![Mutation::new(
+            column,
+            Mutator::Insert,
+            OvsdbValue::Set(vec![OvsdbAtom::NamedUuid(child.to_owned())]),
+        )],
+    }
+}
+
+fn no_bridge(bridge: &str) -> String {
+    format!("no bridge named {bridge}")
+}
@@ -0,0 +1,272 @@
+use ovsdb_bindings::{
+    ovn::{chassis::ChassisMonitor, nb, sb},
+    ovn_nb::{
+        Acl, AclAction, AclDirection, Connection, LogicalRouter, LogicalRouterPort, LogicalSwitch,
+        LogicalSwitchPort, NbGlobal, Ssl,
+    },
+};
+use ovsdb_client::{
+    crud,
+    schema::TableUpdate2,
+    transact::{Condition, Function},
+    Error,
+};
+use ovsdb_testing::MockServer;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+fn server() -> MockServer {
+    MockServer::new()
+        .with_schema_file("schemas/ovn-nb.ovsschema")
+        .unwrap()
+        .with_schema_file("schemas/ovn-sb.ovsschema")
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_connections() {
+    let server = server();
+    server.transact(
+        nb::DATABASE,
+        vec![json!({"op": "insert", "table": "NB_Global", "row": {}})],
+    );
+    let client = server.connect();
+
+    let mut probed = nb::connection("pssl:6641");
+    probed.inactivity_probe = Some(60000);
+    nb::set_connection(&client, &[nb::connection("ptcp:6641:127.0.0.1"), probed])
+        .await
+        .unwrap();
+
+    let global: Vec<NbGlobal> = crud::list(&client, nb::DATABASE).await.unwrap();
+    let connections = nb::get_connection(&client).await.unwrap();
+    assert_eq!(connections.len(), 2);
+    assert_eq!(global[0].connections.len(), 2);
+    for connection in &connections {
+        assert!(global[0].connections.contains(&connection._uuid.unwrap()));
+    }
+    let probed: Vec<&Connection> = connections
+        .iter()
+        .filter(|connection| connection.target == "pssl:6641")
+        .collect();
+    assert_eq!(probed[0].inactivity_probe, Some(60000));
+
+    nb::del_connection(&client).await.unwrap();
+    let global: Vec<NbGlobal> = crud::list(&client, nb::DATABASE).await.unwrap();
+    assert!(global[0].connections.is_empty());
+    assert!(nb::get_connection(&client).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_ssl() {
+    let server = server();
+    server.transact(
+        nb::DATABASE,
+        vec![json!({"op": "insert", "table": "NB_Global", "row": {}})],
+    );
+    let client = server.connect();
+
+    let mut ssl = Ssl::new();
+    ssl.private_key = "/etc/ovn/key.pem".to_owned();
+    ssl.certificate = "/etc/ovn/cert.pem".to_owned();
+    ssl.ca_cert = "/etc/ovn/cacert.pem".to_owned();
+    nb::set_ssl(&client, &ssl).await.unwrap();
+
+    let configured = nb::get_ssl(&client).await.unwrap().unwrap();
+    assert_eq!(configured.certificate, "/etc/ovn/cert.pem");
+    let global: Vec<NbGlobal> = crud::list(&client, nb::DATABASE).await.unwrap();
+    assert_eq!(global[0].ssl, configured._uuid);
+
+    nb::del_ssl(&client).await.unwrap();
+    let global: Vec<NbGlobal> = crud::list(&client, nb::DATABASE).await.unwrap();
+    assert_eq!(global[0].ssl, None);
+}
+
+#[tokio::test]
+async fn test_uninitialized() {
+    let server = server();
+    let client = server.connect();
+
+    let error = sb::set_connection(&client, &[sb::connection("ptcp:6642")])
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::UnexpectedResponse(_)));
+}
+
+#[tokio::test]
+async fn test_topology() {
+    let server = server();
+    let client = server.connect();
+
+    let switch = nb::ls_add(&client, "ls0").await.unwrap();
+    let port = nb::lsp_add(&client, "ls0", "lsp0").await.unwrap();
+    nb::lsp_set_addresses(&client, "lsp0", &["00:00:00:00:00:01 10.0.0.1"])
+        .await
+        .unwrap();
+    let acl = nb::acl_add(
+        &client,
+        "ls0",
+        AclDirection::ToLport,
+        1001,
+        "outport == \"lsp0\" && ip4",
+        AclAction::AllowRelated,
+    )
+    .await
+    .unwrap();
+
+    let switches: Vec<LogicalSwitch> = crud::list(&client, nb::DATABASE).await.unwrap();
+    assert_eq!(switches[0]._uuid, Some(switch));
+    assert_eq!(switches[0].ports, vec![port]);
+    assert_eq!(switches[0].acls, vec![acl]);
+    let ports: Vec<LogicalSwitchPort> = crud::list(&client, nb::DATABASE).await.unwrap();
+    assert_eq!(ports[0].addresses, vec!["00:00:00:00:00:01 10.0.0.1"]);
+    let acls: Vec<Acl> = crud::list(&client, nb::DATABASE).await.unwrap();
+    assert_eq!(acls[0].priority, 1001);
+
+    let router = nb::lr_add(&client, "lr0").await.unwrap();
+    let port = nb::lrp_add(
+        &client,
+        "lr0",
+        "lrp0",
+        "00:00:00:00:ff:01",
+        &["10.0.0.254/24"],
+    )
+    .await
+    .unwrap();
+    let routers: Vec<LogicalRouter> = crud::list(&client, nb::DATABASE).await.unwrap();
+    assert_eq!(routers[0]._uuid, Some(router));
+    assert_eq!(routers[0].ports, vec![port]);
+    let ports: Vec<LogicalRouterPort> = crud::list(&client, nb::DATABASE).await.unwrap();
+    assert_eq!(ports[0].networks, vec!["10.0.0.254/24"]);
+}
+
+#[tokio::test]
+async fn test_missing_parent() {
+    let server = server();
+    let client = server.connect();
+
+    let error = nb::lsp_add(&client, "ls0", "lsp0").await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Unexpected response: ls0: switch name not found"
+    );
+    let error = nb::lsp_set_addresses(&client, "lsp1", &["router"])
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::UnexpectedResponse(_)));
+}
+
+#[tokio::test]
+async fn test_commit_and_wait() {
+    let server = server();
+    server.transact(
+        nb::DATABASE,
+        vec![json!({"op": "insert", "table": "NB_Global", "row": {}})],
+    );
+    let client = server.connect();
+
+    let mut switch = LogicalSwitch::new();
+    switch.name = "ls0".to_owned();
+    let committing = nb::commit_and_wait(
+        &client,
+        nb::ls_add_operations(&switch).unwrap(),
+        nb::Wait::Sb,
+        Duration::from_secs(5),
+    );
+    let northd = async {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        server.transact(
+            nb::DATABASE,
+            vec![json!({"op": "update", "table": "NB_Global", "where": [], "row": {"sb_cfg": 1}})],
+        );
+    };
+    let (results, ()) = tokio::join!(committing, northd);
+    let results = results.unwrap();
+    assert_eq!(results.len(), 1);
+    assert!(results[0].uuid().is_some());
+
+    let error = nb::commit_and_wait(&client, Vec::new(), nb::Wait::Hv, Duration::from_millis(50))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::TimedOut));
+
+    let global: Vec<NbGlobal> = crud::list(&client, nb::DATABASE).await.unwrap();
+    assert_eq!(global[0].nb_cfg, 2);
+    assert_eq!(global[0].sb_cfg, 1);
+}
+
+#[test]
+fn test_chassis_monitor() {
+    let chassis = "8a2b6e9c-0d3f-4b4e-9b8e-3f1a2c4d5e6f";
+    let mut monitor = ChassisMonitor::new("hv1");
+
+    let requests = monitor.requests();
+    assert_eq!(requests["Chassis"].conditions, None);
+    assert_eq!(
+        requests["Chassis_Private"].conditions,
+        Some(vec![Condition::new("name", Function::Equal, json!("hv1"))])
+    );
+    assert_eq!(
+        requests["Logical_Flow"].conditions,
+        Some(vec![Condition::uuid(uuid::Uuid::nil())])
+    );
+
+    let update = |value: Value| -> TableUpdate2<Value> { serde_json::from_value(value).unwrap() };
+
+    assert!(monitor.update(&update(json!({
+        "Chassis": {
+            chassis: {"initial": {"name": "hv1"}},
+            "2c1f0b4a-7d6e-4c3b-8a9f-1e2d3c4b5a69": {"initial": {"name": "hv2"}},
+        },
+    }))));
+    assert_eq!(monitor.chassis(), Some(chassis.parse().unwrap()));
+    assert_eq!(
+        monitor.requests()["Port_Binding"].conditions,
+        Some(vec![
+            Condition::new("chassis", Function::Equal, json!(["uuid", chassis])),
+            Condition::new(
+                "requested_chassis",
+                Function::Equal,
+                json!(["uuid", chassis])
+            ),
+        ])
+    );
+
+    let port = "5d4c3b2a-1f0e-4d9c-8b7a-6f5e4d3c2b1a";
+    let datapath = "0f1e2d3c-4b5a-4968-8776-a5b4c3d2e1f0";
+    assert!(monitor.update(&update(json!({
+        "Port_Binding": {
+            port: {"insert": {"datapath": ["uuid", datapath], "chassis": ["uuid", chassis]}},
+        },
+    }))));
+    assert_eq!(
+        monitor.datapaths(),
+        [datapath.parse().unwrap()].into_iter().collect()
+    );
+    assert_eq!(
+        monitor.requests()["Logical_Flow"].conditions,
+        Some(vec![Condition::new(
+            "logical_datapath",
+            Function::Equal,
+            json!(["uuid", datapath])
+        )])
+    );
+
+    // NOTE(mnaser): A port of a datapath already monitored leaves the
+    //               conditions as they are.
+    assert!(!monitor.update(&update(json!({
+        "Port_Binding": {
+            "9a8b7c6d-5e4f-4a3b-8c2d-1e0f9a8b7c6d": {
+                "insert": {"datapath": ["uuid", datapath], "chassis": ["uuid", chassis]},
+            },
+        },
+    }))));
+
+    assert!(!monitor.update(&update(json!({
+        "Port_Binding": {port: {"modify": {"chassis": ["uuid", chassis]}}},
+    }))));
+    assert!(monitor.update(&update(json!({
+        "Port_Binding": {"9a8b7c6d-5e4f-4a3b-8c2d-1e0f9a8b7c6d": {"delete": null}},
+    }))));
+    assert!(monitor.datapaths().is_empty());
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "ovn-nb")]
+
+use ovsdb_bindings::ovn_nb::{
+    Acl, AclAction, AclDirection, LogicalRouter, LogicalRouterPort, LogicalSwitch, NbGlobal, QoS,
+    QoSActionKey,
+};
+use ovsdb_schema::{OvsdbEnum, Ref};
+use serde_json::json;
+use uuid::Uuid;
+
+const PORT_UUID: &str = "601c7161-97df-42ae-b377-3baf21830d8f";
+
+#[test]
+fn test_table_names() {
+    assert_eq!(NbGlobal::TABLE, "NB_Global");
+    assert_eq!(LogicalSwitch::TABLE, "Logical_Switch");
+    assert_eq!(Acl::TABLE, "ACL");
+    assert_eq!(QoS::TABLE, "QoS");
+}
+
+#[test]
+fn test_acl_round_trip() {
+    let mut acl = Acl::new();
+    acl.priority = 1001;
+    acl.direction = AclDirection::ToLport;
+    acl.action = AclAction::AllowRelated;
+    acl.r#match = "outport == \"lsp0\" && ip4".to_string();
+
+    let map = acl.to_map();
+    assert_eq!(map["action"], json!("allow-related"));
+    assert_eq!(map["direction"], json!("to-lport"));
+
+    let decoded = Acl::from_map(&map).unwrap();
+    assert_eq!(decoded.action, AclAction::AllowRelated);
+    assert_eq!(decoded.r#match, acl.r#match);
+    assert!(AclAction::VALUES.contains(&"allow-stateless"));
+}
+
+#[test]
+fn test_enum_map_keys() {
+    let map = serde_json::from_value(json!({
+        "priority": 100,
+        "direction": "from-lport",
+        "match": "inport == \"lsp0\"",
+        "action": ["map", [["dscp", 46]]],
+    }))
+    .unwrap();
+
+    let qos = QoS::from_map(&map).unwrap();
+    assert_eq!(qos.action[&QoSActionKey::Dscp], 46);
+    assert_eq!(qos.to_map()["action"], json!(["map", [["dscp", 46]]]));
+}
+
+#[test]
+fn test_references() {
+    let uuid = Uuid::parse_str(PORT_UUID).unwrap();
+    let map = serde_json::from_value(json!({
+        "name": "lr0",
+        "ports": ["uuid", PORT_UUID],
+    }))
+    .unwrap();
+
+    let router = LogicalRouter::from_map(&map).unwrap();
+    let ports: Vec<Ref<LogicalRouterPort>> = router.ports_refs();
+    assert_eq!(ports, vec![Ref::new(uuid)]);
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "ovn-sb")]
+
+use ovsdb_bindings::ovn_sb::{
+    Chassis, DatapathBinding, Dhcpv6Options, Encap, EncapType, LogicalFlow, LogicalFlowPipeline,
+    PortBinding, SbGlobal,
+};
+use ovsdb_schema::Ref;
+use serde_json::json;
+use uuid::Uuid;
+
+const CHASSIS_UUID: &str = "601c7161-97df-42ae-b377-3baf21830d8f";
+const DATAPATH_UUID: &str = "4a4b8f3e-2d7a-4bb4-9c1e-0f0d3c2b1a09";
+
+#[test]
+fn test_table_names() {
+    assert_eq!(SbGlobal::TABLE, "SB_Global");
+    assert_eq!(PortBinding::TABLE, "Port_Binding");
+    assert_eq!(Dhcpv6Options::TABLE, "DHCPv6_Options");
+}
+
+#[test]
+fn test_logical_flow() {
+    let map = serde_json::from_value(json!({
+        "logical_datapath": ["uuid", DATAPATH_UUID],
+        "pipeline": "ingress",
+        "table_id": 8,
+        "priority": 50,
+        "match": "eth.dst == ff:ff:ff:ff:ff:ff",
+        "actions": "outport = \"_MC_flood\"; output;",
+        "external_ids": ["map", [["stage-name", "ls_in_l2_lkup"]]],
+    }))
+    .unwrap();
+
+    let flow = LogicalFlow::from_map(&map).unwrap();
+    assert_eq!(flow.pipeline, LogicalFlowPipeline::Ingress);
+    assert_eq!(flow.table_id, 8);
+    assert_eq!(flow.r#match, "eth.dst == ff:ff:ff:ff:ff:ff");
+    assert_eq!(flow.external_ids["stage-name"], "ls_in_l2_lkup");
+
+    let datapath: Option<Ref<DatapathBinding>> = flow.logical_datapath_ref();
+    assert_eq!(
+        datapath,
+        Some(Ref::new(Uuid::parse_str(DATAPATH_UUID).unwrap()))
+    );
+}
+
+#[test]
+fn test_port_binding_chassis() {
+    let uuid = Uuid::parse_str(CHASSIS_UUID).unwrap();
+    let map = serde_json::from_value(json!({
+        "logical_port": "lsp0",
+        "tunnel_key": 1,
+        "datapath": ["uuid", DATAPATH_UUID],
+        "chassis": ["uuid", CHASSIS_UUID],
+        "up": true,
+    }))
+    .unwrap();
+
+    let binding = PortBinding::from_map(&map).unwrap();
+    assert_eq!(binding.logical_port, "lsp0");
+    assert_eq!(binding.up, Some(true));
+
+    let chassis: Option<Ref<Chassis>> = binding.chassis_ref();
+    assert_eq!(chassis, Some(Ref::new(uuid)));
+}
+
+#[test]
+fn test_encap_round_trip() {
+    let mut encap = Encap::new();
+    encap.r#type = EncapType::Geneve;
+    encap.ip = "192.0.2.10".to_string();
+    encap.chassis_name = "compute-0".to_string();
+
+    let map = encap.to_map();
+    assert_eq!(map["type"], json!("geneve"));
+    assert_eq!(Encap::from_map(&map).unwrap().r#type, EncapType::Geneve);
+}
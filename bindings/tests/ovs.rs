@@ -0,0 +1,130 @@
+use ovsdb_bindings::{
+    ovs::vsctl,
+    vswitch::{Bridge, Interface, OpenVSwitch, Port},
+};
+use ovsdb_client::{crud, Error};
+use ovsdb_testing::MockServer;
+use serde_json::json;
+use std::collections::HashMap;
+
+fn server() -> MockServer {
+    let server = MockServer::new()
+        .with_schema_file("schemas/vswitch.ovsschema")
+        .unwrap();
+    server.transact(
+        vsctl::DATABASE,
+        vec![json!({"op": "insert", "table": "Open_vSwitch", "row": {}})],
+    );
+    server
+}
+
+#[tokio::test]
+async fn test_bridges() {
+    let server = server();
+    let client = server.connect();
+
+    let bridge = vsctl::add_br(&client, "br-int").await.unwrap();
+    let port = vsctl::add_port(&client, "br-int", "tap0").await.unwrap();
+
+    let root: Vec<OpenVSwitch> = crud::list(&client, vsctl::DATABASE).await.unwrap();
+    assert_eq!(root[0].bridges, vec![bridge]);
+    let bridges: Vec<Bridge> = crud::list(&client, vsctl::DATABASE).await.unwrap();
+    assert_eq!(bridges[0].name, "br-int");
+    assert_eq!(bridges[0].ports.len(), 2);
+    assert!(bridges[0].ports.contains(&port));
+
+    let ports: Vec<Port> = crud::list(&client, vsctl::DATABASE).await.unwrap();
+    let interfaces: Vec<Interface> = crud::list(&client, vsctl::DATABASE).await.unwrap();
+    assert_eq!(ports.len(), 2);
+    for port in &ports {
+        let interface = interfaces
+            .iter()
+            .find(|interface| interface.name == port.name)
+            .unwrap();
+        assert_eq!(port.interfaces, vec![interface._uuid.unwrap()]);
+    }
+    let internal = interfaces
+        .iter()
+        .find(|interface| interface.name == "br-int")
+        .unwrap();
+    assert_eq!(internal.r#type, "internal");
+
+    let error = vsctl::add_port(&client, "br-ex", "eth0").await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Unexpected response: no bridge named br-ex"
+    );
+}
+
+#[tokio::test]
+async fn test_interface_options() {
+    let server = server();
+    let client = server.connect();
+    vsctl::add_br(&client, "br-int").await.unwrap();
+    vsctl::add_port(&client, "br-int", "vxlan0").await.unwrap();
+
+    let options = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    };
+    vsctl::set_interface_options(
+        &client,
+        "vxlan0",
+        &options(&[("remote_ip", "10.0.0.1"), ("key", "flow")]),
+    )
+    .await
+    .unwrap();
+    vsctl::set_interface_options(&client, "vxlan0", &options(&[("remote_ip", "10.0.0.2")]))
+        .await
+        .unwrap();
+
+    let interfaces: Vec<Interface> = crud::list(&client, vsctl::DATABASE).await.unwrap();
+    let vxlan = interfaces
+        .iter()
+        .find(|interface| interface.name == "vxlan0")
+        .unwrap();
+    assert_eq!(
+        vxlan.options,
+        options(&[("remote_ip", "10.0.0.2"), ("key", "flow")])
+    );
+}
+
+#[tokio::test]
+async fn test_external_ids() {
+    let server = server();
+    let client = server.connect();
+    vsctl::add_br(&client, "br-int").await.unwrap();
+
+    assert_eq!(
+        vsctl::get_external_id(&client, "br-int", "bridge-id")
+            .await
+            .unwrap(),
+        None
+    );
+    vsctl::set_external_id(&client, "br-int", "bridge-id", Some("br-int"))
+        .await
+        .unwrap();
+    assert_eq!(
+        vsctl::get_external_id(&client, "br-int", "bridge-id")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("br-int")
+    );
+    vsctl::set_external_id(&client, "br-int", "bridge-id", None)
+        .await
+        .unwrap();
+    assert_eq!(
+        vsctl::get_external_id(&client, "br-int", "bridge-id")
+            .await
+            .unwrap(),
+        None
+    );
+
+    let error = vsctl::get_external_id(&client, "br-ex", "bridge-id")
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::UnexpectedResponse(_)));
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "vswitch")]
+
+use ovsdb_bindings::vswitch::{
+    Bridge, BridgeFailMode, BridgeProtocols, Interface, OpenVSwitch, Port, PortVlanMode, QoS,
+};
+use ovsdb_schema::Ref;
+use serde_json::json;
+use uuid::Uuid;
+
+const PORT_UUID: &str = "601c7161-97df-42ae-b377-3baf21830d8f";
+const QUEUE_UUID: &str = "4a4b8f3e-2d7a-4bb4-9c1e-0f0d3c2b1a09";
+
+#[test]
+fn test_table_names() {
+    assert_eq!(OpenVSwitch::TABLE, "Open_vSwitch");
+    assert_eq!(Bridge::TABLE, "Bridge");
+    assert_eq!(Interface::TABLE, "Interface");
+}
+
+#[test]
+fn test_bridge() {
+    let uuid = Uuid::parse_str(PORT_UUID).unwrap();
+    let map = serde_json::from_value(json!({
+        "name": "br-int",
+        "fail_mode": "secure",
+        "protocols": ["set", ["OpenFlow13", "OpenFlow15"]],
+        "ports": ["uuid", PORT_UUID],
+    }))
+    .unwrap();
+
+    let bridge = Bridge::from_map(&map).unwrap();
+    assert_eq!(bridge.name, "br-int");
+    assert_eq!(bridge.fail_mode, Some(BridgeFailMode::Secure));
+    assert_eq!(
+        bridge.protocols,
+        vec![BridgeProtocols::OpenFlow13, BridgeProtocols::OpenFlow15]
+    );
+
+    let ports: Vec<Ref<Port>> = bridge.ports_refs();
+    assert_eq!(ports, vec![Ref::new(uuid)]);
+}
+
+#[test]
+fn test_port_round_trip() {
+    let mut port = Port::new();
+    port.name = "tap0".to_string();
+    port.tag = Some(100);
+    port.vlan_mode = Some(PortVlanMode::NativeUntagged);
+    port.trunks = vec![100, 200];
+
+    let map = port.to_map();
+    assert_eq!(map["vlan_mode"], json!("native-untagged"));
+
+    let decoded = Port::from_map(&map).unwrap();
+    assert_eq!(decoded.vlan_mode, Some(PortVlanMode::NativeUntagged));
+    assert_eq!(decoded.trunks, vec![100, 200]);
+}
+
+#[test]
+fn test_integer_maps() {
+    let map = serde_json::from_value(json!({
+        "name": "tap0",
+        "type": "internal",
+        "statistics": ["map", [["rx_packets", 10], ["tx_packets", 20]]],
+    }))
+    .unwrap();
+
+    let iface = Interface::from_map(&map).unwrap();
+    assert_eq!(iface.r#type, "internal");
+    assert_eq!(iface.statistics["rx_packets"], 10);
+
+    let mut qos = QoS::new();
+    qos.r#type = "linux-htb".to_string();
+    qos.queues.insert(0, Uuid::parse_str(QUEUE_UUID).unwrap());
+    assert_eq!(
+        qos.to_map()["queues"],
+        json!(["map", [[0, ["uuid", QUEUE_UUID]]]])
+    );
+}
@@ -0,0 +1,123 @@
+//! Benchmarks of the decoding of large monitor updates, run with
+//! `cargo bench -p ovsdb-client [filter]`.
+//!
+//! The decoding of the notifications of monitors is compared with and
+//! without the `simd-json` feature by running the benchmarks of the monitors
+//! twice:
+//!
+//! ```bash
+//! cargo bench -p ovsdb-client --bench codec -- --save-baseline serde_json decode/monitor
+//! cargo bench -p ovsdb-client --bench codec --features simd-json -- --baseline serde_json decode/monitor
+//! ```
+//!
+//! Criterion keeps the results of the last run in `target/criterion`, and
+//! reports the change of every benchmark against them.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use futures_util::StreamExt;
+use jsonrpsee::core::client::{Subscription, SubscriptionClientT};
+use ovsdb_client::{
+    rpc::ConnectOptions,
+    schema::UpdateNotification,
+    transports::{codec::JsonCodec, listener::framed},
+};
+use serde_json::{Value, json};
+use std::{collections::HashMap, hint::black_box};
+use tokio::{
+    io::{AsyncWriteExt, DuplexStream},
+    runtime::Runtime,
+};
+use tokio_util::codec::FramedRead;
+
+/// Returns an "update" notification of Logical_Flow rows, as sent when a
+/// monitor of OVN_Southbound starts or during network-wide churn.
+fn update(rows: usize) -> Vec<u8> {
+    let rows: serde_json::Map<String, Value> = (0..rows)
+        .map(|n| {
+            let row = json!({
+                "logical_datapath": ["uuid", format!("{:08x}-0000-4000-8000-000000000000", n % 64)],
+                "pipeline": "ingress",
+                "table_id": 23,
+                "priority": 100,
+                "match": format!("inport == \"lsp{n}\" && eth.src == {{00:00:00:00:00:01}} && ip4.src == {{10.0.0.1, 10.0.0.2}}"),
+                "actions": "reg0[0] = 1; reg9[2] = lookup_arp(inport, ip4.src, eth.src); next;",
+                "external_ids": ["map", [["source", "northd.c:5432"], ["stage-name", "ls_in_port_sec_ip"]]],
+            });
+            (
+                format!("{n:08x}-0000-4000-8000-000000000000"),
+                json!({"new": row}),
+            )
+        })
+        .collect();
+
+    let notification = json!({
+        "id": null,
+        "method": "update",
+        "params": [null, {"Logical_Flow": rows}],
+    });
+    serde_json::to_vec(&notification).unwrap()
+}
+
+/// Read the request from the other end of the stream and reply with the
+/// result.
+async fn reply(stream: &mut DuplexStream, result: Value) {
+    let mut requests = FramedRead::new(stream, JsonCodec::default());
+    let request: Value = serde_json::from_slice(&requests.next().await.unwrap().unwrap()).unwrap();
+    let reply = json!({"id": request["id"], "result": result, "error": null});
+    send(requests.into_inner(), &serde_json::to_vec(&reply).unwrap()).await;
+}
+
+/// Write the bytes to the other end of the stream.
+async fn send(stream: &mut DuplexStream, bytes: &[u8]) {
+    stream.write_all(bytes).await.unwrap();
+}
+
+fn decode(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("decode");
+
+    for rows in [100, 10_000] {
+        let update = update(rows);
+        group.throughput(Throughput::Bytes(update.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("framed", rows), &update, |b, update| {
+            b.to_async(&runtime).iter(|| async {
+                let (mut client, server) = tokio::io::duplex(64 * 1024);
+                let (_, mut receiver) = framed(server);
+                let (message, ()) = tokio::join!(receiver.receive(), send(&mut client, update));
+                black_box(message.unwrap().unwrap());
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("client", rows), &update, |b, update| {
+            b.to_async(&runtime).iter(|| async {
+                let (connection, mut server) = tokio::io::duplex(64 * 1024);
+                let client = ConnectOptions::new().connect_stream(connection);
+                let mut updates: Subscription<UpdateNotification<Value>> =
+                    client.subscribe_to_method("update").await.unwrap();
+                let (notification, ()) = tokio::join!(updates.next(), send(&mut server, update));
+                black_box(notification.unwrap().unwrap());
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("monitor", rows), &update, |b, update| {
+            b.to_async(&runtime).iter(|| async {
+                let (connection, mut server) = tokio::io::duplex(64 * 1024);
+                let client = ConnectOptions::new().connect_stream(connection);
+                let monitor =
+                    client.monitor_updates::<Value>("OVN_Southbound", Value::Null, HashMap::new());
+                let ((_, mut updates), ()) = tokio::join!(
+                    async { monitor.await.unwrap() },
+                    reply(&mut server, json!({}))
+                );
+                let (notification, ()) = tokio::join!(updates.next(), send(&mut server, update));
+                black_box(notification.unwrap().unwrap());
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, decode);
+criterion_main!(benches);
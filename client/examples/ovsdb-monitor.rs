@@ -43,7 +43,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         },
     );
 
-    let initial = client.monitor("OVN_Northbound", None, requests).await?;
+    let initial = client
+        .monitor("OVN_Northbound", serde_json::Value::Null, requests)
+        .await?;
     println!("Initial state: {:?}", initial);
 
     let mut stream: Subscription<UpdateNotification<serde_json::Value>> = client.subscribe_to_method("update").await?;
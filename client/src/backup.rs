@@ -0,0 +1,236 @@
+//! Backups of databases, in the format of `ovsdb-client backup`.
+//!
+//! A backup is a standalone database file holding the schema of the
+//! database and a single transaction inserting every row, so that it can
+//! also be read with [`Database`] or served by `ovsdb-server` as is.
+
+use crate::{
+    Error,
+    file::{self, Database, FileError, Row, Transaction},
+    schema::DatabaseSchema,
+    trace,
+    transact::{Operation, TransactClient},
+};
+use jsonrpsee::{core::client::ClientT, rpc_params};
+use ovsdb_schema::OvsdbConversionError;
+use serde_json::{Map, Value, json};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use uuid::Uuid;
+
+/// The schema and the rows of a database at a point in time
+#[derive(Clone, Debug, PartialEq)]
+pub struct Backup {
+    /// The schema, as returned by `get_schema`
+    pub schema: Value,
+
+    /// Rows of every table, keyed by their UUID
+    pub tables: HashMap<String, BTreeMap<Uuid, Row>>,
+}
+
+impl Backup {
+    /// Read a backup, or any standalone database file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FileError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Read a backup, replaying the transactions after the schema.
+    pub fn read(reader: impl BufRead) -> Result<Self, FileError> {
+        let mut records = file::records(reader);
+
+        let record = records.next().ok_or(FileError::MissingSchema)??;
+        let schema =
+            serde_json::from_value(record.data.clone()).map_err(|source| FileError::Json {
+                offset: record.offset,
+                source,
+            })?;
+
+        let mut database = Database::new(schema);
+        for record in records {
+            database.apply(&Transaction::from_json(&record?.data)?)?;
+        }
+
+        Ok(Backup {
+            schema: record.data,
+            tables: database.tables,
+        })
+    }
+
+    /// Write the backup to a new file.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Write the schema and then the rows as a single transaction.
+    pub fn write(&self, mut writer: impl Write) -> std::io::Result<()> {
+        let mut transaction: Map<String, Value> = self
+            .tables
+            .iter()
+            .filter(|(_, rows)| !rows.is_empty())
+            .map(|(table, rows)| {
+                let rows = rows
+                    .iter()
+                    .map(|(uuid, row)| (uuid.to_string(), Value::Object(row.clone())))
+                    .collect();
+                (table.clone(), Value::Object(rows))
+            })
+            .collect();
+        let date = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        transaction.insert("_date".to_owned(), json!(date));
+        transaction.insert("_comment".to_owned(), json!("produced by dump_database"));
+
+        file::write_record(&mut writer, &self.schema)?;
+        file::write_record(&mut writer, &Value::Object(transaction))
+    }
+
+    /// The operations replacing the rows of the database with those of the
+    /// backup.
+    ///
+    /// Every row of the tables of the schema is deleted, and the rows of the
+    /// backup are inserted with new UUIDs, their references to each other
+    /// being kept through named UUIDs.
+    pub fn restore_operations(&self) -> Vec<Operation> {
+        let uuids: HashSet<Uuid> = self
+            .tables
+            .values()
+            .flat_map(BTreeMap::keys)
+            .copied()
+            .collect();
+
+        let mut tables: Vec<&str> = self
+            .schema
+            .get("tables")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(Map::keys)
+            .map(String::as_str)
+            .collect();
+        tables.sort_unstable();
+
+        let deletes = tables.into_iter().map(|table| Operation::Delete {
+            table: table.to_owned(),
+            conditions: Vec::new(),
+        });
+        let inserts = self.tables.iter().flat_map(|(table, rows)| {
+            rows.iter().map(|(uuid, row)| Operation::Insert {
+                table: table.clone(),
+                row: row
+                    .iter()
+                    .map(|(column, value)| (column.clone(), named(value, &uuids)))
+                    .collect(),
+                uuid_name: Some(name(uuid)),
+            })
+        });
+
+        deletes.chain(inserts).collect()
+    }
+}
+
+/// Returns the name of the row with the UUID within a restore.
+fn name(uuid: &Uuid) -> String {
+    format!("row{}", uuid.simple())
+}
+
+/// Returns the value with its references to the rows of the backup replaced
+/// by their named UUIDs.
+fn named(value: &Value, uuids: &HashSet<Uuid>) -> Value {
+    match value {
+        Value::Array(array) => match array.as_slice() {
+            [Value::String(tag), Value::String(uuid)] if tag == "uuid" => {
+                match Uuid::parse_str(uuid) {
+                    Ok(uuid) if uuids.contains(&uuid) => json!(["named-uuid", name(&uuid)]),
+                    _ => value.clone(),
+                }
+            }
+            array => Value::Array(array.iter().map(|value| named(value, uuids)).collect()),
+        },
+        value => value.clone(),
+    }
+}
+
+/// Take a consistent backup of the database, selecting every row of every
+/// table of its schema within a single transaction.
+pub async fn dump_database(client: &(impl ClientT + Sync), db_name: &str) -> Result<Backup, Error> {
+    let request = client.request("get_schema", rpc_params![db_name]);
+    let schema: Value = trace::request("get_schema", Some(db_name), request).await?;
+    let parsed: DatabaseSchema = serde_json::from_value(schema.clone())?;
+
+    let mut tables: Vec<String> = parsed.tables.into_keys().collect();
+    tables.sort_unstable();
+    let operations = tables
+        .iter()
+        .map(|table| Operation::Select {
+            table: table.clone(),
+            conditions: Vec::new(),
+            columns: None,
+        })
+        .collect();
+    let results = client.transact(db_name, operations).await?;
+    if results.len() != tables.len() {
+        return Err(Error::UnexpectedResponse(format!(
+            "{} results for {} selects",
+            results.len(),
+            tables.len()
+        )));
+    }
+
+    let mut backup = Backup {
+        schema,
+        tables: HashMap::new(),
+    };
+    for (table, result) in tables.into_iter().zip(results) {
+        let mut rows = BTreeMap::new();
+        for mut row in result.rows.unwrap_or_default() {
+            let uuid = row
+                .remove("_uuid")
+                .as_ref()
+                .and_then(ovsdb_schema::extract_uuid)
+                .ok_or_else(|| {
+                    Error::UnexpectedResponse(format!("row of {table} without a UUID"))
+                })?;
+            row.remove("_version");
+            rows.insert(uuid, row.into_iter().collect());
+        }
+        backup.tables.insert(table, rows);
+    }
+
+    Ok(backup)
+}
+
+/// Replace the rows of the database with those of the backup, within a
+/// single transaction.
+///
+/// The schema of the database is not changed, the backup has to be of the
+/// same database and its columns have to exist in its schema.
+pub async fn restore(
+    client: &impl TransactClient,
+    db_name: &str,
+    backup: &Backup,
+) -> Result<(), Error> {
+    let name = backup.schema.get("name").and_then(Value::as_str);
+    if name != Some(db_name) {
+        return Err(Error::InvalidSchema(
+            OvsdbConversionError::new(
+                format!("a backup of {db_name}"),
+                backup.schema.get("name").cloned().unwrap_or_default(),
+            )
+            .at_column("name"),
+        ));
+    }
+
+    client
+        .transact(db_name, backup.restore_operations())
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,279 @@
+mod watch;
+
+use clap::{Parser, Subcommand};
+use jsonrpsee::{
+    core::client::{ClientT, SubscriptionClientT},
+    rpc_params,
+};
+use ovsdb_client::{
+    dump::{DumpTable, Table, datum},
+    rpc::{self, RpcClient},
+    schema::{MonitorRequest, TableUpdate, UpdateNotification},
+    transact::Condition,
+};
+use serde_json::{Value, json};
+use std::{collections::HashMap, error::Error, time::Duration};
+
+/// Query and monitor an OVSDB server.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Server to connect to, such as `tcp:127.0.0.1:6641` or
+    /// `unix:/var/run/openvswitch/db.sock`
+    #[arg(long, default_value = "unix:/var/run/openvswitch/db.sock")]
+    remote: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List the databases of the server
+    ListDbs,
+
+    /// Print the schema of a database as JSON
+    GetSchema { database: String },
+
+    /// Print the rows of a table as they change, until interrupted
+    Monitor {
+        database: String,
+
+        table: String,
+
+        /// Columns to print, all of them by default
+        columns: Vec<String>,
+    },
+
+    /// Run a transaction given as `["<database>", <operation>...]`, printing
+    /// its results as JSON
+    Transact { transaction: String },
+
+    /// Print the rows of a table
+    Dump {
+        database: String,
+
+        table: String,
+
+        /// Columns to print, all of them by default
+        columns: Vec<String>,
+    },
+
+    /// Show the rows of a table and the rate of changes made to it,
+    /// refreshed until interrupted
+    Watch {
+        database: String,
+
+        table: String,
+
+        /// Columns to show, all of them by default
+        columns: Vec<String>,
+
+        /// Only watch the rows matching a condition such as `chassis==ch1`
+        /// or `tunnel_key>=10`, can be repeated
+        #[arg(long = "where", value_parser = watch::parse_condition)]
+        conditions: Vec<Condition>,
+
+        /// Seconds between refreshes
+        #[arg(long, default_value_t = 1.0)]
+        interval: f64,
+
+        /// Maximum number of rows shown
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+
+    run(rpc::connect(&args.remote).await?, args.command).await
+}
+
+async fn run(client: impl SubscriptionClientT + Send + Sync, command: Command) -> Result<()> {
+    match command {
+        Command::ListDbs => {
+            for database in client.list_databases().await? {
+                println!("{database}");
+            }
+        }
+        Command::GetSchema { database } => {
+            let schema: Value = client.request("get_schema", rpc_params![database]).await?;
+            println!("{}", serde_json::to_string_pretty(&schema)?);
+        }
+        Command::Monitor {
+            database,
+            table,
+            columns,
+        } => monitor(&client, &database, &table, columns).await?,
+        Command::Transact { transaction } => {
+            let transaction: Vec<Value> = serde_json::from_str(&transaction)?;
+            let results: Value = client.request("transact", transaction).await?;
+            println!("{}", serde_json::to_string_pretty(&results)?);
+        }
+        Command::Dump {
+            database,
+            table,
+            columns,
+        } => dump(&client, &database, &table, columns).await?,
+        Command::Watch {
+            database,
+            table,
+            columns,
+            conditions,
+            interval,
+            limit,
+        } => {
+            let interval = Duration::try_from_secs_f64(interval)?;
+            watch::watch(
+                &client, &database, &table, columns, conditions, interval, limit,
+            )
+            .await?
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the given columns, or every column of the table sorted by name.
+async fn table_columns(
+    client: &(impl ClientT + Sync),
+    database: &str,
+    table: &str,
+    columns: Vec<String>,
+) -> Result<Vec<String>> {
+    if !columns.is_empty() {
+        return Ok(columns);
+    }
+
+    let schema = client.get_schema(database).await?;
+    let mut columns: Vec<String> = schema
+        .tables
+        .get(table)
+        .ok_or_else(|| format!("unknown table {table:?}"))?
+        .columns
+        .keys()
+        .cloned()
+        .collect();
+    columns.sort();
+
+    Ok(columns)
+}
+
+async fn dump(
+    client: &(impl ClientT + Sync),
+    database: &str,
+    table: &str,
+    columns: Vec<String>,
+) -> Result<()> {
+    let schema = client.get_schema(database).await?;
+    let table_schema = schema
+        .tables
+        .get(table)
+        .ok_or_else(|| format!("unknown table {table:?}"))?;
+    let columns = match columns.is_empty() {
+        true => {
+            let mut columns: Vec<String> = table_schema.columns.keys().cloned().collect();
+            columns.sort();
+            columns
+        }
+        false => columns,
+    };
+    let selected: Vec<&str> = std::iter::once("_uuid")
+        .chain(columns.iter().map(String::as_str))
+        .collect();
+    let select = json!({"op": "select", "table": table, "where": [], "columns": selected});
+
+    let results: Vec<Value> = client
+        .request("transact", rpc_params![database, select])
+        .await?;
+    let result = results.first().ok_or("empty transaction results")?;
+    if let Some(error) = result.get("error") {
+        return Err(format!("{error}: {}", result["details"]).into());
+    }
+
+    let rows: Vec<HashMap<String, Value>> =
+        serde_json::from_value(result["rows"].clone()).unwrap_or_default();
+    print!(
+        "{}",
+        DumpTable::from_rows(table, table_schema, &columns, &rows)
+    );
+
+    Ok(())
+}
+
+async fn monitor(
+    client: &(impl SubscriptionClientT + Sync),
+    database: &str,
+    table: &str,
+    columns: Vec<String>,
+) -> Result<()> {
+    let columns = table_columns(client, database, table, columns).await?;
+
+    let mut updates = client
+        .subscribe_to_method::<UpdateNotification<Value>>("update")
+        .await?;
+    let request = MonitorRequest {
+        columns: Some(columns.clone()),
+        ..Default::default()
+    };
+    let initial = client
+        .monitor(
+            database,
+            Value::Null,
+            HashMap::from([(table.to_owned(), request)]),
+        )
+        .await?;
+    print_updates(&columns, &initial, true);
+
+    while let Some(update) = updates.next().await {
+        print_updates(&columns, &update?.message, false);
+    }
+
+    Ok(())
+}
+
+/// Print the updated rows as `ovsdb-client monitor` does, with the old and
+/// new values of a modified row on separate lines.
+fn print_updates(columns: &[String], updates: &TableUpdate<Value>, initial: bool) {
+    let mut output = Table::new(
+        ["row", "action"]
+            .into_iter()
+            .chain(columns.iter().map(String::as_str)),
+    );
+    let line = |uuid: String, action: &str, row: &Value| {
+        [uuid, action.to_owned()]
+            .into_iter()
+            .chain(
+                columns
+                    .iter()
+                    .map(|column| row.get(column).map(datum).unwrap_or_default()),
+            )
+            .collect()
+    };
+
+    for (uuid, update) in updates.values().flatten() {
+        match (&update.old, &update.new) {
+            (None, Some(new)) => {
+                output.push(line(
+                    uuid.to_string(),
+                    if initial { "initial" } else { "insert" },
+                    new,
+                ));
+            }
+            (Some(old), None) => output.push(line(uuid.to_string(), "delete", old)),
+            (Some(old), Some(new)) => {
+                output.push(line(uuid.to_string(), "old", old));
+                output.push(line(String::new(), "new", new));
+            }
+            (None, None) => {}
+        }
+    }
+
+    if !output.is_empty() {
+        print!("{output}");
+    }
+}
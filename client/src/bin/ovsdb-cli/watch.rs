@@ -0,0 +1,196 @@
+use crate::Result;
+use jsonrpsee::core::client::SubscriptionClientT;
+use ovsdb_client::{
+    dump::{Table, datum},
+    file::apply_diff,
+    rpc::RpcClient,
+    schema::{MonitorCondRequest, RowUpdate2, TableSchema, TableUpdate2, Update2Notification},
+    transact::{Condition, Function},
+};
+use serde_json::{Map, Value, json};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+/// Parse a condition such as `name==ls0` or `tunnel_key>=10`, the value being
+/// a string unless it is valid JSON.
+pub fn parse_condition(condition: &str) -> std::result::Result<Condition, String> {
+    const FUNCTIONS: [(&str, Function); 6] = [
+        ("==", Function::Equal),
+        ("!=", Function::NotEqual),
+        ("<=", Function::LessThanOrEqual),
+        (">=", Function::GreaterThanOrEqual),
+        ("<", Function::LessThan),
+        (">", Function::GreaterThan),
+    ];
+
+    let (index, operator, function) = FUNCTIONS
+        .iter()
+        .filter_map(|(operator, function)| {
+            condition
+                .find(operator)
+                .map(|index| (index, *operator, *function))
+        })
+        .min_by_key(|(index, operator, _)| (*index, Reverse(operator.len())))
+        .ok_or_else(|| format!("expected <column><operator><value>, got {condition:?}"))?;
+
+    let (column, value) = (&condition[..index], &condition[index + operator.len()..]);
+    let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_owned()));
+
+    Ok(Condition::new(column, function, value))
+}
+
+/// The rows of the watched table and the changes counted since the last
+/// refresh
+#[derive(Default)]
+struct View {
+    rows: BTreeMap<Uuid, Map<String, Value>>,
+
+    inserts: u64,
+
+    modifies: u64,
+
+    deletes: u64,
+}
+
+impl View {
+    fn apply(&mut self, schema: &TableSchema, updates: TableUpdate2<Value>) {
+        for (uuid, update) in updates.into_values().flatten() {
+            match update {
+                RowUpdate2::Initial(row) => {
+                    self.rows.insert(uuid, object(row));
+                }
+                RowUpdate2::Insert(row) => {
+                    self.inserts += 1;
+                    self.rows.insert(uuid, object(row));
+                }
+                RowUpdate2::Delete => {
+                    self.deletes += 1;
+                    self.rows.remove(&uuid);
+                }
+                RowUpdate2::Modify(diff) => {
+                    self.modifies += 1;
+                    let Some(row) = self.rows.get_mut(&uuid) else {
+                        continue;
+                    };
+                    for (column, diff) in object(diff) {
+                        let Some(column_schema) = schema.columns.get(&column) else {
+                            continue;
+                        };
+                        let r#type = &column_schema.r#type;
+                        let value =
+                            apply_diff(r#type, row.get(&column), &diff).unwrap_or_else(|| {
+                                json!([if r#type.is_map() { "map" } else { "set" }, []])
+                            });
+                        row.insert(column, value);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Redraw the terminal, then start counting changes again.
+    fn render(&mut self, title: &str, columns: &[String], limit: usize, elapsed: Duration) {
+        let rate = |count: u64| count as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+
+        let mut output =
+            Table::new(std::iter::once("_uuid").chain(columns.iter().map(String::as_str)));
+        for (uuid, row) in self.rows.iter().take(limit) {
+            output.push(
+                std::iter::once(uuid.to_string())
+                    .chain(
+                        columns
+                            .iter()
+                            .map(|column| row.get(column).map(datum).unwrap_or_default()),
+                    )
+                    .collect(),
+            );
+        }
+
+        print!("\x1b[2J\x1b[H");
+        println!(
+            "{title}: {} rows, {:.1} inserts/s, {:.1} modifies/s, {:.1} deletes/s",
+            self.rows.len(),
+            rate(self.inserts),
+            rate(self.modifies),
+            rate(self.deletes),
+        );
+        println!();
+        print!("{output}");
+        if self.rows.len() > limit {
+            println!("... {} more rows", self.rows.len() - limit);
+        }
+
+        self.inserts = 0;
+        self.modifies = 0;
+        self.deletes = 0;
+    }
+}
+
+fn object(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(object) => object,
+        _ => Map::new(),
+    }
+}
+
+/// Show the rows of a table matching the conditions, refreshed with the rate
+/// of inserts, modifications and deletions at every interval until
+/// interrupted.
+pub async fn watch(
+    client: &(impl SubscriptionClientT + Sync),
+    database: &str,
+    table: &str,
+    mut columns: Vec<String>,
+    conditions: Vec<Condition>,
+    interval: Duration,
+    limit: usize,
+) -> Result<()> {
+    let schema = client.get_schema(database).await?;
+    let table_schema = schema
+        .tables
+        .get(table)
+        .ok_or_else(|| format!("unknown table {table:?}"))?;
+    if columns.is_empty() {
+        columns = table_schema.columns.keys().cloned().collect();
+        columns.sort();
+    }
+
+    let mut updates = client
+        .subscribe_to_method::<Update2Notification<Value>>("update2")
+        .await?;
+    let request = MonitorCondRequest {
+        columns: Some(columns.clone()),
+        conditions: (!conditions.is_empty()).then_some(conditions),
+        ..Default::default()
+    };
+    let initial = client
+        .monitor_cond(
+            database,
+            Value::Null,
+            HashMap::from([(table.to_owned(), request)]),
+        )
+        .await?;
+
+    let mut view = View::default();
+    view.apply(table_schema, initial);
+
+    let title = format!("{database} {table}");
+    let mut ticker = tokio::time::interval(interval);
+    let mut last = Instant::now();
+    loop {
+        tokio::select! {
+            update = updates.next() => match update {
+                Some(update) => view.apply(table_schema, update?.message),
+                None => return Err("connection closed by the server".into()),
+            },
+            _ = ticker.tick() => {
+                view.render(&title, &columns, limit, last.elapsed());
+                last = Instant::now();
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{
+    schema::{RowUpdate, TableUpdate},
+    transact::Condition,
+};
+use futures_util::{StreamExt, stream::BoxStream};
+use serde_json::{Value, json};
+use std::sync::Mutex;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use uuid::Uuid;
+
+/// An update of a row matching the conditions of a subscription
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowEvent {
+    pub table: String,
+
+    pub uuid: Uuid,
+
+    /// The row before the update, none if it was inserted
+    pub old: Option<Value>,
+
+    /// The row after the update, none if it was deleted
+    pub new: Option<Value>,
+}
+
+struct Subscriber {
+    table: String,
+
+    conditions: Vec<Condition>,
+
+    sender: UnboundedSender<RowEvent>,
+}
+
+impl Subscriber {
+    fn matches(&self, row: Option<&Value>) -> bool {
+        row.is_some_and(|row| {
+            self.conditions
+                .iter()
+                .all(|condition| condition.matches(row))
+        })
+    }
+}
+
+/// Fan-out of the row updates of monitors to the subscribers interested in
+/// them.
+///
+/// Each subscriber gives a table and conditions, such as
+/// `Port_Binding.chassis == X`, which are evaluated once per update as it is
+/// published, so that consumers do not each scan every update. An update is
+/// delivered when the row matched the conditions before it or matches them
+/// after it, so that a subscriber also sees the rows leaving its selection.
+#[derive(Default)]
+pub struct RowEventBus {
+    subscribers: Mutex<Vec<Subscriber>>,
+}
+
+impl RowEventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the stream of the updates of the rows of the table matching
+    /// every condition, all of them if there are none.
+    ///
+    /// The events are kept until the stream is polled, and the subscription
+    /// ends once the stream is dropped.
+    pub fn subscribe(
+        &self,
+        table: impl Into<String>,
+        conditions: Vec<Condition>,
+    ) -> BoxStream<'static, RowEvent> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.subscribers.lock().unwrap().push(Subscriber {
+            table: table.into(),
+            conditions,
+            sender,
+        });
+
+        futures_util::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|event| (event, receiver))
+        })
+        .boxed()
+    }
+
+    /// Returns the number of subscriptions whose stream is still alive.
+    pub fn subscribers(&self) -> usize {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|subscriber| !subscriber.sender.is_closed());
+        subscribers.len()
+    }
+
+    /// Deliver the rows of an update, such as the initial rows returned by
+    /// `monitor` or the message of an "update" notification, to the
+    /// subscribers they match.
+    ///
+    /// The old row of a modification only holds the columns which changed,
+    /// so the others are taken from the new row, and both rows are given
+    /// their `_uuid` column before they are evaluated.
+    pub fn publish(&self, update: &TableUpdate<Value>) {
+        let mut subscribers = self.subscribers.lock().unwrap();
+        subscribers.retain(|subscriber| !subscriber.sender.is_closed());
+
+        for (table, rows) in update {
+            let interested: Vec<&Subscriber> = subscribers
+                .iter()
+                .filter(|subscriber| subscriber.table == *table)
+                .collect();
+            if interested.is_empty() {
+                continue;
+            }
+
+            for (uuid, RowUpdate { old, new }) in rows {
+                let new = new.as_ref().map(|new| with_uuid(uuid, new.clone()));
+                let old = old.as_ref().map(|old| match (old, &new) {
+                    (Value::Object(old), Some(Value::Object(new))) => {
+                        let mut row = new.clone();
+                        row.extend(old.clone());
+                        Value::Object(row)
+                    }
+                    (old, _) => with_uuid(uuid, old.clone()),
+                });
+
+                for subscriber in &interested {
+                    if subscriber.matches(old.as_ref()) || subscriber.matches(new.as_ref()) {
+                        // NOTE(mnaser): Sending only fails when the stream has been
+                        //               dropped, which is forgotten on the next publish.
+                        let _ = subscriber.sender.send(RowEvent {
+                            table: table.clone(),
+                            uuid: *uuid,
+                            old: old.clone(),
+                            new: new.clone(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn with_uuid(uuid: &Uuid, mut row: Value) -> Value {
+    if let Value::Object(columns) = &mut row {
+        columns.insert("_uuid".to_owned(), json!(["uuid", uuid]));
+    }
+    row
+}
@@ -0,0 +1,131 @@
+use crate::{
+    Error,
+    rpc::RpcClient,
+    schema::DatabaseSchema,
+    transact::Operation,
+    validation::{validate_row, validate_update},
+};
+use jsonrpsee::core::client::ClientT;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::OnceCell;
+
+type Slot = Arc<OnceCell<Arc<DatabaseSchema>>>;
+
+/// Lazily fetched schemas of the databases of a server.
+///
+/// Each schema is downloaded with `get_schema` the first time it is needed
+/// and shared afterwards, concurrent callers waiting on the same request.
+/// Every `OvsdbClient` has one, from `OvsdbClient::schemas`, which it
+/// invalidates itself when the server cancels a monitor after
+/// `set_db_change_aware`, and which goes away with its connection. Other
+/// caches have to be invalidated when the connection is re-established or
+/// when the server reports a schema change, such as a `monitor_canceled`
+/// notification or a new checksum in the `_Server` database.
+#[derive(Debug, Default)]
+pub struct SchemaCache {
+    schemas: Mutex<HashMap<String, Slot>>,
+}
+
+impl SchemaCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn slot(&self, db_name: &str) -> Slot {
+        self.schemas
+            .lock()
+            .unwrap()
+            .entry(db_name.to_owned())
+            .or_default()
+            .clone()
+    }
+
+    /// Returns the schema of the database, fetching it if it is not cached.
+    pub async fn get<C: ClientT + Sync>(
+        &self,
+        client: &C,
+        db_name: &str,
+    ) -> Result<Arc<DatabaseSchema>, Error> {
+        self.slot(db_name)
+            .get_or_try_init(|| async { client.get_schema(db_name).await.map(Arc::new) })
+            .await
+            .cloned()
+    }
+
+    /// Returns the schema of the database if it has already been fetched.
+    pub fn cached(&self, db_name: &str) -> Option<Arc<DatabaseSchema>> {
+        self.schemas
+            .lock()
+            .unwrap()
+            .get(db_name)
+            .and_then(|slot| slot.get().cloned())
+    }
+
+    /// Returns the checksum of the cached schema of the database.
+    pub fn checksum(&self, db_name: &str) -> Option<String> {
+        self.cached(db_name)
+            .and_then(|schema| schema.checksum.clone())
+    }
+
+    /// Forget the schema of the database, so that it is fetched again.
+    pub fn invalidate(&self, db_name: &str) {
+        self.schemas.lock().unwrap().remove(db_name);
+    }
+
+    /// Forget every schema, typically after reconnecting.
+    pub fn clear(&self) {
+        self.schemas.lock().unwrap().clear();
+    }
+
+    /// Invalidate the schema of the database if the server reports a
+    /// different checksum for it, returning whether it was invalidated.
+    pub fn observe_checksum(&self, db_name: &str, checksum: &str) -> bool {
+        let mut schemas = self.schemas.lock().unwrap();
+        let changed = schemas
+            .get(db_name)
+            .and_then(|slot| slot.get())
+            .is_some_and(|schema| schema.checksum.as_deref() != Some(checksum));
+        if changed {
+            schemas.remove(db_name);
+        }
+        changed
+    }
+
+    /// Validate the rows of `insert` and `update` operations against the
+    /// schema of the database before sending them in a transaction.
+    pub async fn validate<C: ClientT + Sync>(
+        &self,
+        client: &C,
+        db_name: &str,
+        operations: &[Operation],
+    ) -> Result<(), Error> {
+        let schema = self.get(client, db_name).await?;
+
+        for operation in operations {
+            let (table, row, update) = match operation {
+                Operation::Insert { table, row, .. } => (table, row, false),
+                Operation::Update { table, row, .. } => (table, row, true),
+                _ => continue,
+            };
+            let table_schema = schema
+                .tables
+                .get(table)
+                .ok_or_else(|| Error::UnknownTable(table.clone()))?;
+
+            let result = if update {
+                validate_update(table_schema, row)
+            } else {
+                validate_row(table_schema, row)
+            };
+            result.map_err(|source| Error::InvalidRow {
+                table: table.clone(),
+                source,
+            })?;
+        }
+
+        Ok(())
+    }
+}
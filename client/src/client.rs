@@ -0,0 +1,540 @@
+use crate::{
+    Error,
+    cache::SchemaCache,
+    monitor::Updates,
+    rpc::RpcClient,
+    schema::{
+        DatabaseSchema, MonitorCondRequest, MonitorCondSinceReply, MonitorRequest, TableUpdate,
+        TableUpdate2, TableUpdateExt, Update2Notification, Update3Notification, UpdateNotification,
+    },
+    snapshot::Snapshot,
+    transact::{Operation, OperationResult, TransactClient},
+    transports::{self, Session},
+};
+use futures_util::{StreamExt, stream};
+use jsonrpsee::core::{
+    ClientError, async_trait,
+    client::{BatchResponse, ClientT, Subscription, SubscriptionClientT, async_client::Client},
+    params::BatchRequestBuilder,
+    rpc_params,
+    traits::ToRpcParams,
+};
+use ovsdb_schema::OvsdbTable;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    fmt,
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::Semaphore;
+use uuid::Uuid;
+
+/// A connection to an OVSDB server, made with the `connect_*` functions of
+/// the `rpc` module
+///
+/// It is a JSON-RPC client, so the methods of `RpcClient` and
+/// `TransactClient` can be used on it, which also keeps track of what the
+/// connection holds on the server in order to shut it down cleanly.
+///
+/// Requests can be made concurrently from as many tasks as needed, they are
+/// pipelined over the connection and their replies matched by id. Past the
+/// limit of requests in flight, they wait for their turn in the order they
+/// were made.
+pub struct OvsdbClient {
+    inner: Client,
+
+    session: Arc<Session>,
+
+    timeout: Duration,
+
+    in_flight: Semaphore,
+}
+
+impl OvsdbClient {
+    pub(crate) fn new(
+        inner: Client,
+        session: Arc<Session>,
+        timeout: Duration,
+        max_in_flight: usize,
+    ) -> Self {
+        OvsdbClient {
+            inner,
+            session,
+            timeout,
+            in_flight: Semaphore::new(max_in_flight),
+        }
+    }
+
+    /// Returns the client making its requests with the timeout, instead of
+    /// the one of the connection, such as for a transaction with a long
+    /// `wait` operation.
+    pub fn with_timeout(&self, timeout: Duration) -> TimedClient<'_> {
+        TimedClient {
+            client: self,
+            timeout,
+            deadline: None,
+        }
+    }
+
+    /// Returns the client making its requests within the timeout of the
+    /// connection, failing them with `Error::TimedOut` past the deadline.
+    pub fn with_deadline(&self, deadline: Instant) -> TimedClient<'_> {
+        TimedClient {
+            client: self,
+            timeout: self.timeout,
+            deadline: Some(deadline),
+        }
+    }
+
+    /// Returns whether the connection is still up.
+    pub fn is_connected(&self) -> bool {
+        self.inner.is_connected()
+    }
+
+    /// Wait for the connection to be closed or lost.
+    pub async fn on_disconnect(&self) {
+        self.inner.on_disconnect().await
+    }
+
+    /// Returns the schemas fetched over the connection, which are dropped
+    /// when the server cancels a monitor because a schema changed, or when
+    /// the client converts a database, and are not kept by the client of
+    /// the next connection.
+    pub fn schemas(&self) -> &SchemaCache {
+        &self.session.schemas
+    }
+
+    /// Returns the schema of the database, fetching it only if it is not in
+    /// the schemas of the connection.
+    pub async fn schema(&self, db_name: &str) -> Result<Arc<DatabaseSchema>, Error> {
+        self.session.schemas.get(self, db_name).await
+    }
+
+    /// Run the operations like `TransactClient::transact`, once the rows of
+    /// their `insert` and `update` operations are validated against the
+    /// schema of the database, failing with `Error::InvalidRow` otherwise.
+    pub async fn transact_validated(
+        &self,
+        db_name: &str,
+        operations: Vec<Operation>,
+    ) -> Result<Vec<OperationResult>, Error> {
+        self.session
+            .schemas
+            .validate(self, db_name, &operations)
+            .await?;
+        self.transact(db_name, operations).await
+    }
+
+    /// Create a monitor like `RpcClient::monitor`, returning its initial rows
+    /// along with the stream of its own notifications.
+    ///
+    /// Unlike the subscription to "update" notifications, which receives
+    /// those of every monitor, each monitor has its own stream, so that the
+    /// monitors of several databases can be held on the same connection. Their
+    /// notifications are no longer received by the subscription.
+    pub async fn monitor_updates<T>(
+        &self,
+        db_name: &str,
+        id: Value,
+        requests: HashMap<String, MonitorRequest>,
+    ) -> Result<(TableUpdate<Value>, Updates<UpdateNotification<T>>), Error>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let updates = self.updates(&id)?;
+        let initial = self
+            .monitor(db_name, id.clone(), requests)
+            .await
+            .inspect_err(|_| self.session.unroute(&id))?;
+
+        Ok((initial, updates))
+    }
+
+    /// Create a monitor like `RpcClient::monitor_cond`, returning its initial
+    /// rows along with the stream of its own "update2" notifications.
+    pub async fn monitor_cond_updates<T>(
+        &self,
+        db_name: &str,
+        id: Value,
+        requests: HashMap<String, MonitorCondRequest>,
+    ) -> Result<(TableUpdate2<Value>, Updates<Update2Notification<T>>), Error>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let updates = self.updates(&id)?;
+        let initial = self
+            .monitor_cond(db_name, id.clone(), requests)
+            .await
+            .inspect_err(|_| self.session.unroute(&id))?;
+
+        Ok((initial, updates))
+    }
+
+    /// Create a monitor like `RpcClient::monitor_cond_since`, returning its
+    /// reply along with the stream of its own "update3" notifications.
+    pub async fn monitor_cond_since_updates<T>(
+        &self,
+        db_name: &str,
+        id: Value,
+        requests: HashMap<String, MonitorCondRequest>,
+        last_id: Option<Uuid>,
+    ) -> Result<
+        (
+            MonitorCondSinceReply<Value>,
+            Updates<Update3Notification<T>>,
+        ),
+        Error,
+    >
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let updates = self.updates(&id)?;
+        let reply = self
+            .monitor_cond_since(db_name, id.clone(), requests, last_id)
+            .await
+            .inspect_err(|_| self.session.unroute(&id))?;
+
+        Ok((reply, updates))
+    }
+
+    /// Monitor the rows of the table until one of them satisfies the
+    /// predicate, returning it, such as to wait for ovn-northd to catch up
+    /// with the `nb_cfg` of `NB_Global`.
+    ///
+    /// The rows are checked as they are when the monitor is created, and then
+    /// as they change, failing with `Error::TimedOut` once the timeout
+    /// elapsed. The monitor is canceled once done.
+    pub async fn wait_until<T>(
+        &self,
+        db_name: &str,
+        mut predicate: impl FnMut(&T) -> bool,
+        timeout: Duration,
+    ) -> Result<T, Error>
+    where
+        T: OvsdbTable,
+    {
+        static WAITS: AtomicU64 = AtomicU64::new(0);
+
+        let id = json!([
+            "wait_until",
+            T::TABLE,
+            WAITS.fetch_add(1, Ordering::Relaxed)
+        ]);
+        let requests = HashMap::from([(
+            T::TABLE.to_owned(),
+            MonitorRequest {
+                columns: Some(T::COLUMNS.iter().map(|&column| column.to_owned()).collect()),
+                ..Default::default()
+            },
+        )]);
+
+        let found = tokio::time::timeout(timeout, async {
+            let (initial, mut updates) = self
+                .monitor_updates::<Value>(db_name, id.clone(), requests)
+                .await?;
+
+            let mut rows = initial.table::<T>(T::TABLE)?;
+            loop {
+                if let Some(row) = rows
+                    .into_values()
+                    .filter_map(|update| update.new)
+                    .find(|row| predicate(row))
+                {
+                    return Ok(row);
+                }
+
+                let notification = match updates.next().await {
+                    Some(notification) => notification?,
+                    None if self.is_connected() => {
+                        return Err(Error::UnexpectedResponse(
+                            "monitor canceled by the server".to_owned(),
+                        ));
+                    }
+                    None => return Err(Error::ConnectionClosed),
+                };
+                rows = notification.message.table::<T>(T::TABLE)?;
+            }
+        })
+        .await;
+
+        // NOTE(mnaser): The monitor is unknown to the server if its request failed or
+        //               timed out, so the error of its cancellation is ignored.
+        let _: Result<Value, _> = self.request("monitor_cancel", rpc_params![id]).await;
+
+        found.map_err(|_| Error::TimedOut)?
+    }
+
+    /// Returns the stream of the notifications of the monitor, routed to it
+    /// before its request is made so that none is missed.
+    fn updates<N>(&self, id: &Value) -> Result<Updates<N>, Error>
+    where
+        N: DeserializeOwned + Send + 'static,
+    {
+        let notifications = self
+            .session
+            .route(id.clone())
+            .ok_or_else(|| Error::DuplicateMonitor(id.clone()))?;
+
+        Ok(
+            stream::unfold(notifications, |mut notifications| async move {
+                let params = notifications.recv().await?;
+                let notification = transports::decode(&params).map_err(Error::Protocol);
+                Some((notification, notifications))
+            })
+            .boxed(),
+        )
+    }
+
+    /// Create a monitor like `RpcClient::monitor`, returning its initial rows
+    /// undecoded so that they can be loaded one at a time, such as those of
+    /// a large OVN_Southbound database.
+    pub async fn monitor_snapshot(
+        &self,
+        db_name: &str,
+        id: Value,
+        requests: HashMap<String, MonitorRequest>,
+    ) -> Result<Snapshot, Error> {
+        self.snapshot(id.clone(), self.monitor(db_name, id, requests))
+            .await
+    }
+
+    /// Create a monitor like `RpcClient::monitor_cond`, returning its initial
+    /// rows undecoded so that they can be loaded one at a time.
+    pub async fn monitor_cond_snapshot(
+        &self,
+        db_name: &str,
+        id: Value,
+        requests: HashMap<String, MonitorCondRequest>,
+    ) -> Result<Snapshot, Error> {
+        self.snapshot(id.clone(), self.monitor_cond(db_name, id, requests))
+            .await
+    }
+
+    /// Make the request creating the monitor, with the transport handing over
+    /// the rows of its reply.
+    async fn snapshot<T>(
+        &self,
+        id: Value,
+        request: impl Future<Output = Result<T, Error>>,
+    ) -> Result<Snapshot, Error> {
+        let rows = self.session.expect_snapshot(id.clone());
+        if let Err(error) = request.await {
+            self.session.forget_snapshot(&id);
+            return Err(error);
+        }
+
+        rows.await
+            .map(Snapshot::new)
+            .map_err(|_| Error::UnexpectedResponse("monitor reply without rows".to_owned()))
+    }
+
+    /// Returns the names of the locks requested and not released.
+    pub(crate) fn locks(&self) -> Vec<String> {
+        self.session.locks.lock().unwrap().clone()
+    }
+
+    /// Request the lock again, as a session does after reconnecting,
+    /// returning whether it was granted at once. Otherwise the transport
+    /// reports it once the server sends its "locked" notification.
+    pub(crate) async fn reacquire(&self, lock: &str) -> Result<bool, Error> {
+        self.session
+            .reacquiring
+            .lock()
+            .unwrap()
+            .push(lock.to_owned());
+
+        let reply = self.request::<Value, _>("lock", rpc_params![lock]).await;
+        match reply {
+            Ok(reply) if reply["locked"] == true => Ok(self.session.reacquired(lock)),
+            Ok(_) => Ok(false),
+            Err(error) => {
+                self.session.reacquired(lock);
+                Err(error.into())
+            }
+        }
+    }
+
+    /// Close the connection, once the monitors are canceled, the locks
+    /// released and the replies to every pending request received.
+    ///
+    /// It resolves once the connection is closed, every request made
+    /// afterwards failing with `Error::ConnectionClosed`. Requests which are
+    /// not replied to within the timeout of the connection are given up on.
+    pub async fn shutdown(&self) -> Result<(), Error> {
+        // NOTE(mnaser): Monitors and locks which the server refused are unknown to it,
+        //               so the errors of their cancellation are ignored.
+        let monitors = self.session.monitors.lock().unwrap().clone();
+        for id in monitors {
+            let _: Result<Value, _> = self.request("monitor_cancel", rpc_params![id]).await;
+        }
+        let locks = self.session.locks.lock().unwrap().clone();
+        for lock in locks {
+            let _: Result<Value, _> = self.request("unlock", rpc_params![lock]).await;
+        }
+
+        let mut pending = self.session.pending.subscribe();
+        let flushed = tokio::time::timeout(self.timeout, pending.wait_for(|pending| *pending == 0));
+        tokio::select! {
+            _ = flushed => {}
+            _ = self.inner.on_disconnect() => {}
+        }
+
+        self.session.closed.send_replace(true);
+        self.inner.on_disconnect().await;
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for OvsdbClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OvsdbClient")
+            .field("connected", &self.is_connected())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl ClientT for OvsdbClient {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), ClientError>
+    where
+        Params: ToRpcParams + Send,
+    {
+        self.with_timeout(self.timeout)
+            .notification(method, params)
+            .await
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, ClientError>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        self.with_timeout(self.timeout)
+            .request(method, params)
+            .await
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, ClientError>
+    where
+        R: DeserializeOwned + fmt::Debug + 'a,
+    {
+        self.with_timeout(self.timeout).batch_request(batch).await
+    }
+}
+
+#[async_trait]
+impl SubscriptionClientT for OvsdbClient {
+    async fn subscribe<'a, Notif, Params>(
+        &self,
+        subscribe_method: &'a str,
+        params: Params,
+        unsubscribe_method: &'a str,
+    ) -> Result<Subscription<Notif>, ClientError>
+    where
+        Params: ToRpcParams + Send,
+        Notif: DeserializeOwned,
+    {
+        let subscription = self
+            .inner
+            .subscribe(subscribe_method, params, unsubscribe_method);
+        self.with_timeout(self.timeout).limit(subscription).await
+    }
+
+    async fn subscribe_to_method<'a, Notif>(
+        &self,
+        method: &'a str,
+    ) -> Result<Subscription<Notif>, ClientError>
+    where
+        Notif: DeserializeOwned,
+    {
+        let subscription = self.inner.subscribe_to_method(method);
+        self.with_timeout(self.timeout).limit(subscription).await
+    }
+}
+
+/// A client whose requests fail with `Error::TimedOut` after a timeout or a
+/// deadline of their own, returned by `OvsdbClient::with_timeout` and
+/// `OvsdbClient::with_deadline`
+///
+/// The timeout applies to each request, from when it is made.
+#[derive(Debug)]
+pub struct TimedClient<'a> {
+    client: &'a OvsdbClient,
+
+    timeout: Duration,
+
+    deadline: Option<Instant>,
+}
+
+impl TimedClient<'_> {
+    /// Also fail the requests past the deadline.
+    pub fn with_deadline(self, deadline: Instant) -> Self {
+        TimedClient {
+            deadline: Some(self.deadline.map_or(deadline, |d| d.min(deadline))),
+            ..self
+        }
+    }
+
+    /// Wait for the turn of the request and then for its reply, until the
+    /// timeout elapses or the deadline passes, whichever comes first.
+    async fn limit<T>(
+        &self,
+        request: impl Future<Output = Result<T, ClientError>>,
+    ) -> Result<T, ClientError> {
+        let after = tokio::time::Instant::now() + self.timeout;
+        let deadline = self
+            .deadline
+            .map_or(after, |deadline| after.min(deadline.into()));
+
+        let request = async {
+            // NOTE(mnaser): The semaphore is never closed, and it queues the requests
+            //               fairly so that none waits behind newer ones.
+            let _permit = self.client.in_flight.acquire().await;
+            request.await
+        };
+        tokio::time::timeout_at(deadline, request)
+            .await
+            .unwrap_or(Err(ClientError::RequestTimeout))
+    }
+}
+
+#[async_trait]
+impl ClientT for TimedClient<'_> {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), ClientError>
+    where
+        Params: ToRpcParams + Send,
+    {
+        let notification = self.client.inner.notification(method, params);
+        self.limit(notification).await
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, ClientError>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        let request = self.client.inner.request(method, params);
+        self.limit(request).await
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, ClientError>
+    where
+        R: DeserializeOwned + fmt::Debug + 'a,
+    {
+        let request = self.client.inner.batch_request(batch);
+        self.limit(request).await
+    }
+}
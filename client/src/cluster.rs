@@ -0,0 +1,280 @@
+//! The health of the databases of a server, and of the Raft clusters they
+//! belong to.
+//!
+//! A running server reports the state of each of its databases in the
+//! `Database` table of its `_Server` database: whether it is clustered,
+//! connected to the majority of its cluster, the leader, and the last log
+//! index it applied. The term, the log and the members of a cluster are
+//! only found in the database file itself, read with
+//! [`ClusteredDatabase`].
+
+use crate::{
+    Error,
+    file::raft::ClusteredDatabase,
+    rpc::ConnectOptions,
+    transact::{Condition, Function, Operation, TransactClient},
+};
+use ovsdb_schema::{OvsdbConversionError, extract_uuid};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+/// The name of the database of the server itself
+pub const SERVER_DATABASE: &str = "_Server";
+
+/// How a server hosts a database
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Model {
+    Standalone,
+
+    /// A member of a Raft cluster
+    Clustered,
+
+    /// A read-only copy of the database of another server
+    Relay,
+}
+
+/// A row of the `Database` table of `_Server`
+#[derive(Clone, Debug, PartialEq)]
+pub struct DatabaseStatus {
+    pub name: String,
+
+    pub model: Model,
+
+    /// Whether the database can be read and written, which for a clustered
+    /// database requires the server to be connected to the majority of the
+    /// cluster
+    pub connected: bool,
+
+    /// Whether the server is the leader of the cluster, always true for a
+    /// standalone database
+    pub leader: bool,
+
+    /// The schema of the database, none while a server joins a cluster
+    pub schema: Option<String>,
+
+    /// The id of the cluster, for a clustered database
+    pub cluster_id: Option<Uuid>,
+
+    /// The id of the server in the cluster, for a clustered database
+    pub server_id: Option<Uuid>,
+
+    /// The index of the last log entry applied, for a clustered database
+    pub index: Option<u64>,
+}
+
+impl DatabaseStatus {
+    /// Convert a row selected from the `Database` table.
+    pub fn from_row(row: &HashMap<String, Value>) -> Result<Self, OvsdbConversionError> {
+        let column = |name: &str| {
+            row.get(name)
+                .ok_or_else(|| OvsdbConversionError::missing_column(name))
+        };
+        let mismatch = |name: &str, expected: &str, found: &Value| {
+            OvsdbConversionError::new(expected, found.clone()).at_column(name)
+        };
+        let boolean = |name: &str| {
+            let value = column(name)?;
+            value
+                .as_bool()
+                .ok_or_else(|| mismatch(name, "boolean", value))
+        };
+
+        let model = match column("model")? {
+            Value::String(model) if model == "standalone" => Model::Standalone,
+            Value::String(model) if model == "clustered" => Model::Clustered,
+            Value::String(model) if model == "relay" => Model::Relay,
+            model => {
+                return Err(mismatch(
+                    "model",
+                    "one of standalone, clustered or relay",
+                    model,
+                ));
+            }
+        };
+        let name = column("name")?;
+
+        Ok(DatabaseStatus {
+            name: name
+                .as_str()
+                .ok_or_else(|| mismatch("name", "string", name))?
+                .to_owned(),
+            model,
+            connected: boolean("connected")?,
+            leader: boolean("leader")?,
+            schema: optional(row.get("schema")).and_then(|schema| schema.as_str().map(Into::into)),
+            cluster_id: optional(row.get("cid")).and_then(extract_uuid),
+            server_id: optional(row.get("sid")).and_then(extract_uuid),
+            index: optional(row.get("index")).and_then(Value::as_u64),
+        })
+    }
+
+    /// Returns whether the database can be used through this server, a
+    /// clustered one only while it is connected to the majority of its
+    /// cluster.
+    pub fn is_healthy(&self) -> bool {
+        self.connected
+    }
+}
+
+/// Returns the value of an optional column, which is an empty set if unset.
+fn optional(value: Option<&Value>) -> Option<&Value> {
+    match value? {
+        Value::Array(array) if array.first().and_then(Value::as_str) == Some("set") => array
+            .get(1)
+            .and_then(Value::as_array)
+            .and_then(|set| set.first()),
+        value => Some(value),
+    }
+}
+
+/// Returns the status of every database of the server.
+pub async fn database_statuses(client: &impl TransactClient) -> Result<Vec<DatabaseStatus>, Error> {
+    select(client, Vec::new()).await
+}
+
+/// Returns the status of the database.
+pub async fn database_status(
+    client: &impl TransactClient,
+    db_name: &str,
+) -> Result<DatabaseStatus, Error> {
+    let condition = Condition::new("name", Function::Equal, json!(db_name));
+    select(client, vec![condition])
+        .await?
+        .pop()
+        .ok_or_else(|| Error::UnexpectedResponse(format!("{SERVER_DATABASE} has no {db_name}")))
+}
+
+async fn select(
+    client: &impl TransactClient,
+    conditions: Vec<Condition>,
+) -> Result<Vec<DatabaseStatus>, Error> {
+    let operation = Operation::Select {
+        table: "Database".to_owned(),
+        conditions,
+        columns: None,
+    };
+
+    client
+        .transact(SERVER_DATABASE, vec![operation])
+        .await?
+        .into_iter()
+        .next()
+        .and_then(|result| result.rows)
+        .ok_or_else(|| Error::UnexpectedResponse("select returned no rows".to_owned()))?
+        .iter()
+        .map(|row| DatabaseStatus::from_row(row).map_err(Error::Conversion))
+        .collect()
+}
+
+/// The status of a database as reported by one of the servers of its
+/// cluster
+#[derive(Debug)]
+pub struct MemberStatus {
+    /// The remote of the server, such as `tcp:10.0.0.1:6641`
+    pub remote: String,
+
+    /// The status of the database, or the error connecting to the server or
+    /// fetching it
+    pub status: Result<DatabaseStatus, Error>,
+}
+
+/// Fetch the status of the database from each of the servers of its
+/// cluster, connecting to them with the options.
+///
+/// The members are returned in the order of the remotes, the ones which
+/// cannot be reached with an error.
+pub async fn probe(
+    options: &ConnectOptions,
+    remotes: impl IntoIterator<Item = impl Into<String>>,
+    db_name: &str,
+) -> Vec<MemberStatus> {
+    let members = remotes.into_iter().map(|remote| async move {
+        let remote = remote.into();
+        let status = match options.connect(&remote).await {
+            Ok(client) => database_status(&client, db_name).await,
+            Err(error) => Err(error),
+        };
+
+        MemberStatus { remote, status }
+    });
+
+    futures_util::future::join_all(members).await
+}
+
+/// Returns the remote of the leader of the cluster, among the members which
+/// are connected to it.
+pub fn leader(members: &[MemberStatus]) -> Option<&str> {
+    members
+        .iter()
+        .find(|member| {
+            member
+                .status
+                .as_ref()
+                .is_ok_and(|status| status.connected && status.leader)
+        })
+        .map(|member| member.remote.as_str())
+}
+
+/// The state of a server of a cluster, as recorded in its database file
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClusterStatus {
+    /// The id of the server owning the file
+    pub server_id: Uuid,
+
+    /// The id of the cluster, unknown until the server has joined it
+    pub cluster_id: Option<Uuid>,
+
+    /// The latest term the server took part in
+    pub term: u64,
+
+    /// The leader of the latest term, if known
+    pub leader: Option<Uuid>,
+
+    /// The latest index known to be committed
+    pub commit_index: Option<u64>,
+
+    /// The index of the last entry in the log
+    pub last_index: u64,
+
+    /// The servers of the cluster with their Raft address, as of the last
+    /// change of its membership
+    pub servers: BTreeMap<Uuid, String>,
+}
+
+impl ClusterStatus {
+    /// Returns whether the server was the leader of its latest term.
+    pub fn is_leader(&self) -> bool {
+        self.leader == Some(self.server_id)
+    }
+}
+
+impl From<&ClusteredDatabase> for ClusterStatus {
+    fn from(cluster: &ClusteredDatabase) -> Self {
+        // NOTE(mnaser): The servers are only recorded when they change, so the latest
+        //               entry changing them wins over the snapshot.
+        let servers = cluster
+            .entries
+            .iter()
+            .rev()
+            .find_map(|entry| entry.servers.as_ref())
+            .or(cluster.header.snapshot.as_ref().map(|s| &s.servers))
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter_map(|(sid, address)| {
+                Some((Uuid::parse_str(sid).ok()?, address.as_str()?.to_owned()))
+            })
+            .collect();
+
+        ClusterStatus {
+            server_id: cluster.header.server_id,
+            cluster_id: cluster.header.cluster_id,
+            term: cluster.term,
+            leader: cluster.leader,
+            commit_index: cluster.commit_index,
+            last_index: cluster.last_index(),
+            servers,
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Online conversion of a database to a new schema, as when upgrading OVN to
+//! a version whose schema changed.
+//!
+//! [`convert_database`] compares the schema of the database against the
+//! target one, converts the database only if they differ, and checks the
+//! schema reported afterwards, each step being reported as it is reached.
+
+use crate::{
+    Error,
+    rpc::RpcClient,
+    schema::{DatabaseSchema, SchemaDiff},
+};
+use ovsdb_schema::OvsdbConversionError;
+use serde_json::{Value, json};
+
+/// A step of [`convert_database`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConversionStep {
+    /// The schema of the database was fetched, with its version
+    Fetched { version: String },
+
+    /// The database already has the target schema, so it is not converted
+    UpToDate,
+
+    /// The conversion is sent, the schemas differing by the tables and
+    /// columns of the diff
+    Converting {
+        from: String,
+        to: String,
+        diff: SchemaDiff,
+    },
+
+    /// The server converted the database
+    Converted,
+
+    /// The schema fetched again is the target one
+    Verified,
+}
+
+/// Convert the database to the schema, given as it is read from an
+/// `.ovsschema` file, reporting each step to `progress`, and returning the
+/// differences between the schemas.
+///
+/// The database is converted if the schemas differ in their version or in
+/// their tables and columns. The server cancels the monitors of the database
+/// and closes the connections which are not aware of database changes, so
+/// the connection is made aware of them first, and then used to check the
+/// schema of the converted database.
+pub async fn convert_database(
+    client: &impl RpcClient,
+    db_name: &str,
+    schema: &Value,
+    mut progress: impl FnMut(ConversionStep),
+) -> Result<SchemaDiff, Error> {
+    let target: DatabaseSchema = serde_json::from_value(schema.clone())?;
+    if target.name != db_name {
+        return Err(Error::InvalidSchema(
+            OvsdbConversionError::new(format!("a schema of {db_name}"), json!(target.name))
+                .at_column("name"),
+        ));
+    }
+
+    let current = client.get_schema(db_name).await?;
+    progress(ConversionStep::Fetched {
+        version: current.version.clone(),
+    });
+
+    let diff = current.diff(&target);
+    if diff.is_empty() && current.version == target.version {
+        progress(ConversionStep::UpToDate);
+        return Ok(diff);
+    }
+
+    progress(ConversionStep::Converting {
+        from: current.version,
+        to: target.version.clone(),
+        diff: diff.clone(),
+    });
+    client.set_db_change_aware(true).await?;
+    client.convert(db_name, schema).await?;
+    progress(ConversionStep::Converted);
+
+    let converted = client.get_schema(db_name).await?;
+    if converted.version != target.version || !converted.diff(&target).is_empty() {
+        return Err(Error::UnexpectedResponse(format!(
+            "{db_name} has schema {} after its conversion to {}",
+            converted.version, target.version
+        )));
+    }
+    progress(ConversionStep::Verified);
+
+    Ok(diff)
+}
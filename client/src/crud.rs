@@ -0,0 +1,121 @@
+//! Typed create, read, update and delete helpers for table structs.
+//!
+//! These are used by the methods `ovsdb-derive` generates for tables with
+//! the `crud` option, but can also be called directly.
+
+use crate::{
+    Error,
+    transact::{Condition, Operation, OperationResult, TransactClient},
+};
+use ovsdb_schema::OvsdbTable;
+use serde_json::Value;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Run a single operation, returning its result.
+async fn transact_one(
+    client: &impl TransactClient,
+    db_name: &str,
+    operation: Operation,
+) -> Result<OperationResult, Error> {
+    client
+        .transact(db_name, vec![operation])
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::UnexpectedResponse("missing operation result".to_owned()))
+}
+
+fn decode_rows<T: OvsdbTable>(result: OperationResult) -> Result<Vec<T>, Error> {
+    result
+        .rows
+        .ok_or_else(|| Error::UnexpectedResponse("select returned no rows".to_owned()))?
+        .iter()
+        .map(|row| T::from_map(row).map_err(Error::Conversion))
+        .collect()
+}
+
+/// Fetch the row with the given UUID.
+pub async fn get<T: OvsdbTable>(
+    client: &impl TransactClient,
+    db_name: &str,
+    uuid: Uuid,
+) -> Result<Option<T>, Error> {
+    let operation = Operation::Select {
+        table: T::TABLE.to_owned(),
+        conditions: vec![Condition::uuid(uuid)],
+        columns: None,
+    };
+    let result = transact_one(client, db_name, operation).await?;
+
+    Ok(decode_rows(result)?.into_iter().next())
+}
+
+/// Fetch every row of the table.
+pub async fn list<T: OvsdbTable>(
+    client: &impl TransactClient,
+    db_name: &str,
+) -> Result<Vec<T>, Error> {
+    let operation = Operation::Select {
+        table: T::TABLE.to_owned(),
+        conditions: Vec::new(),
+        columns: None,
+    };
+
+    decode_rows(transact_one(client, db_name, operation).await?)
+}
+
+/// Insert a row, returning the UUID assigned by the server.
+pub async fn insert<T: OvsdbTable>(
+    client: &impl TransactClient,
+    db_name: &str,
+    row: &T,
+) -> Result<Uuid, Error> {
+    let operation = Operation::Insert {
+        table: T::TABLE.to_owned(),
+        row: row.try_to_map().map_err(Error::Conversion)?,
+        uuid_name: None,
+    };
+
+    transact_one(client, db_name, operation)
+        .await?
+        .uuid()
+        .ok_or_else(|| Error::UnexpectedResponse("insert returned no uuid".to_owned()))
+}
+
+/// Update the given columns of the row with the given UUID, returning the
+/// number of rows which matched.
+pub async fn update<T: OvsdbTable>(
+    client: &impl TransactClient,
+    db_name: &str,
+    uuid: Uuid,
+    row: HashMap<String, Value>,
+) -> Result<u64, Error> {
+    let operation = Operation::Update {
+        table: T::TABLE.to_owned(),
+        conditions: vec![Condition::uuid(uuid)],
+        row,
+    };
+
+    Ok(transact_one(client, db_name, operation)
+        .await?
+        .count
+        .unwrap_or_default())
+}
+
+/// Delete the row with the given UUID, returning the number of rows deleted.
+pub async fn delete<T: OvsdbTable>(
+    client: &impl TransactClient,
+    db_name: &str,
+    uuid: Uuid,
+) -> Result<u64, Error> {
+    let operation = Operation::Delete {
+        table: T::TABLE.to_owned(),
+        conditions: vec![Condition::uuid(uuid)],
+    };
+
+    Ok(transact_one(client, db_name, operation)
+        .await?
+        .count
+        .unwrap_or_default())
+}
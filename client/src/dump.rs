@@ -0,0 +1,359 @@
+//! The text tables printed by `ovsdb-client dump`, so that rows can be
+//! exported in the same format as the tools of Open vSwitch and parsed back.
+//!
+//! Each table is printed as its name, the headings of its columns, a line
+//! of dashes as wide as each column and then a line per row, the columns
+//! being separated by a space:
+//!
+//! ```text
+//! Logical_Switch table
+//! _uuid                                name  ports
+//! ------------------------------------ ----- --------------------------------------
+//! 0b4ad97e-5a8e-4b6c-9b1b-3c1f5e7a9d01 "ls0" [5f0c4d2e-8b7a-4f3e-a1d2-6e9b8c7a5d02]
+//! ```
+
+use crate::schema::{AtomicType, BaseType, ColumnType, TableSchema};
+use ovsdb_schema::{OvsdbAtom, OvsdbValue, json_to_ovsdb_value};
+use serde_json::{Map, Value, json};
+use std::{collections::HashMap, fmt};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DumpError {
+    #[error("Line {line}: {reason}")]
+    Syntax { line: usize, reason: String },
+
+    #[error("Column {0:?} does not exist in the table")]
+    UnknownColumn(String),
+
+    #[error("Invalid value {text:?} for column {column:?}: {reason}")]
+    InvalidDatum {
+        column: String,
+        text: String,
+        reason: String,
+    },
+}
+
+/// Rows printed as aligned columns under their headings
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Table {
+    pub headings: Vec<String>,
+
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<I, S>(headings: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Table {
+            headings: headings.into_iter().map(Into::into).collect(),
+            rows: vec![],
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+impl fmt::Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let widths: Vec<usize> = self
+            .headings
+            .iter()
+            .enumerate()
+            .map(|(i, heading)| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(i))
+                    .chain([heading])
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let line = |f: &mut fmt::Formatter<'_>, cells: &[String]| {
+            let line = widths
+                .iter()
+                .zip(cells)
+                .map(|(width, cell)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{}", line.trim_end())
+        };
+
+        line(f, &self.headings)?;
+        line(
+            f,
+            &widths
+                .iter()
+                .map(|width| "-".repeat(*width))
+                .collect::<Vec<_>>(),
+        )?;
+        for row in &self.rows {
+            line(f, row)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A table of a dump, with the text of each cell
+#[derive(Clone, Debug, PartialEq)]
+pub struct DumpTable {
+    pub name: String,
+
+    pub table: Table,
+}
+
+impl DumpTable {
+    /// Format the columns of the rows, as returned by a `select`, `_uuid`
+    /// being printed first as `ovsdb-client dump` does.
+    pub fn from_rows(
+        name: &str,
+        schema: &TableSchema,
+        columns: &[String],
+        rows: &[HashMap<String, Value>],
+    ) -> Self {
+        let columns: Vec<&str> = std::iter::once("_uuid")
+            .chain(columns.iter().map(String::as_str).filter(|c| *c != "_uuid"))
+            .collect();
+
+        let mut table = Table::new(columns.iter().copied());
+        for row in rows {
+            table.push(
+                columns
+                    .iter()
+                    .map(|column| {
+                        let value = row.get(*column).unwrap_or(&Value::Null);
+                        match schema.columns.get(*column) {
+                            Some(column) => format_datum(value, &column.r#type),
+                            None => datum(value),
+                        }
+                    })
+                    .collect(),
+            );
+        }
+
+        DumpTable {
+            name: name.to_owned(),
+            table,
+        }
+    }
+
+    /// Parse the cells back to rows in the JSON format of OVSDB, with the
+    /// types of the columns of the schema.
+    pub fn rows(&self, schema: &TableSchema) -> Result<Vec<Map<String, Value>>, DumpError> {
+        let uuid = ColumnType {
+            key: AtomicType::Uuid.into(),
+            value: None,
+            min: 1,
+            max: Some(1),
+        };
+        let types = self
+            .table
+            .headings
+            .iter()
+            .map(|column| match schema.columns.get(column) {
+                Some(schema) => Ok(&schema.r#type),
+                None if column == "_uuid" || column == "_version" => Ok(&uuid),
+                None => Err(DumpError::UnknownColumn(column.clone())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.table
+            .rows
+            .iter()
+            .map(|row| {
+                self.table
+                    .headings
+                    .iter()
+                    .zip(&types)
+                    .zip(row)
+                    .map(|((column, column_type), text)| {
+                        parse_datum(text, column_type)
+                            .map(|value| (column.clone(), value))
+                            .map_err(|reason| DumpError::InvalidDatum {
+                                column: column.clone(),
+                                text: text.clone(),
+                                reason,
+                            })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for DumpTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} table", self.name)?;
+        write!(f, "{}", self.table)
+    }
+}
+
+/// Parse the tables of a dump, separated by empty lines.
+pub fn parse_dump(text: &str) -> Result<Vec<DumpTable>, DumpError> {
+    let mut tables = Vec::new();
+    let mut lines = text.lines().enumerate().peekable();
+
+    while let Some((number, line)) = lines.next() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let syntax = |line: usize, reason: &str| DumpError::Syntax {
+            line: line + 1,
+            reason: reason.to_owned(),
+        };
+
+        let name = line
+            .strip_suffix(" table")
+            .ok_or_else(|| syntax(number, "expected the name of a table"))?;
+        let (number, headings) = lines
+            .next()
+            .ok_or_else(|| syntax(number + 1, "missing headings"))?;
+        let (number, dashes) = lines
+            .next()
+            .ok_or_else(|| syntax(number + 1, "missing the line of dashes"))?;
+
+        // NOTE(mnaser): The cells are found by the positions of the dashes, as they
+        //               may hold spaces themselves.
+        let mut spans = Vec::new();
+        let mut start = 0;
+        for dashes in dashes.split(' ') {
+            if dashes.is_empty() || dashes.chars().any(|c| c != '-') {
+                return Err(syntax(number, "expected dashes separated by a space"));
+            }
+            let width = dashes.chars().count();
+            spans.push((start, width));
+            start += width + 1;
+        }
+        let cells = |line: &str| -> Vec<String> {
+            let chars: Vec<char> = line.chars().collect();
+            spans
+                .iter()
+                .enumerate()
+                .map(|(i, &(start, width))| {
+                    let end = match i + 1 == spans.len() {
+                        true => chars.len(),
+                        false => (start + width).min(chars.len()),
+                    };
+                    chars
+                        .get(start..end)
+                        .map(|cell| cell.iter().collect::<String>().trim_end().to_owned())
+                        .unwrap_or_default()
+                })
+                .collect()
+        };
+
+        let mut table = Table::new(cells(headings));
+        while let Some((_, line)) = lines.next_if(|(_, line)| !line.trim().is_empty()) {
+            table.push(cells(line));
+        }
+
+        tables.push(DumpTable {
+            name: name.to_owned(),
+            table,
+        });
+    }
+
+    Ok(tables)
+}
+
+/// Format a datum as `ovsdb-client` does, such as `[a, b]` for a set and
+/// `{key=value}` for a map.
+///
+/// Without the type of its column, a set of a single element cannot be told
+/// apart from an atom, so it is printed without brackets.
+pub fn datum(value: &Value) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+
+    json_to_ovsdb_value(value).map_or_else(|_| value.to_string(), |value| value.to_string())
+}
+
+/// Format a datum of a column, sets and optional atoms always being printed
+/// within brackets and maps within braces.
+pub fn format_datum(value: &Value, column_type: &ColumnType) -> String {
+    let datum = match json_to_ovsdb_value(value) {
+        Ok(OvsdbValue::Map(pairs)) => OvsdbValue::Map(pairs),
+        _ if column_type.is_map() => OvsdbValue::Map(vec![]),
+        Ok(OvsdbValue::Atom(atom)) if !column_type.is_scalar() => OvsdbValue::Set(vec![atom]),
+        Ok(datum) => datum,
+        Err(_) => return value.to_string(),
+    };
+
+    datum.to_string()
+}
+
+/// Parse a datum as printed by `format_datum`, or by `datum` for a set of a
+/// single element, to its JSON format.
+pub fn parse_datum(text: &str, column_type: &ColumnType) -> Result<Value, String> {
+    // NOTE(mnaser): An empty set may be printed as nothing at all, which is
+    //               not a value of the textual syntax.
+    if text.trim().is_empty() && !column_type.is_map() && !column_type.is_scalar() {
+        return Ok(json!(["set", []]));
+    }
+
+    let datum = text.parse::<OvsdbValue>().map_err(|e| e.to_string())?;
+    let value_type = column_type.value.as_ref().unwrap_or(&column_type.key);
+    match datum {
+        OvsdbValue::Map(pairs) if column_type.is_map() => {
+            let pairs = pairs
+                .into_iter()
+                .map(|(key, value)| {
+                    Ok(json!([
+                        atom(key, &column_type.key)?,
+                        atom(value, value_type)?
+                    ]))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(json!(["map", pairs]))
+        }
+        _ if column_type.is_map() => Err("expected a map".to_owned()),
+        OvsdbValue::Atom(key) if column_type.is_scalar() => atom(key, &column_type.key),
+        _ if column_type.is_scalar() => Err("expected an atom".to_owned()),
+        OvsdbValue::Atom(key) => Ok(json!(["set", [atom(key, &column_type.key)?]])),
+        OvsdbValue::Set(atoms) => {
+            let atoms = atoms
+                .into_iter()
+                .map(|key| atom(key, &column_type.key))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(json!(["set", atoms]))
+        }
+        OvsdbValue::Map(_) => Err("expected a set".to_owned()),
+    }
+}
+
+/// Convert a parsed atom to the JSON format of the type of its column.
+fn atom(atom: OvsdbAtom, base: &BaseType) -> Result<Value, String> {
+    match (&base.r#type, atom) {
+        (AtomicType::Integer, OvsdbAtom::Integer(integer)) => Ok(json!(integer)),
+        (AtomicType::Real, OvsdbAtom::Real(real)) => Ok(json!(real)),
+        (AtomicType::Real, OvsdbAtom::Integer(integer)) => Ok(json!(integer as f64)),
+        (AtomicType::Boolean, OvsdbAtom::Boolean(boolean)) => Ok(json!(boolean)),
+        (AtomicType::String, OvsdbAtom::String(string)) => Ok(json!(string)),
+        (AtomicType::Uuid, OvsdbAtom::Uuid(uuid)) => Ok(json!(["uuid", uuid])),
+        (AtomicType::Uuid, OvsdbAtom::NamedUuid(name)) => Ok(json!(["named-uuid", name])),
+        (r#type, atom) => Err(format!("{atom} is not {}", article(r#type))),
+    }
+}
+
+fn article(r#type: &AtomicType) -> &'static str {
+    match r#type {
+        AtomicType::Integer => "an integer",
+        AtomicType::Real => "a real",
+        AtomicType::Boolean => "a boolean",
+        AtomicType::String => "a string",
+        AtomicType::Uuid => "a UUID",
+    }
+}
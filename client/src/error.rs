@@ -0,0 +1,270 @@
+use crate::validation::ValidationError;
+use jsonrpsee::core::ClientError;
+use ovsdb_schema::OvsdbConversionError;
+use serde_json::Value;
+use std::time::Duration;
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Errors returned by the client
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The connection could not be established, or failed while in use
+    #[error("Transport error: {0}")]
+    Transport(#[from] std::io::Error),
+
+    /// A remote is not of the form `tcp:<host>:<port>` or `unix:<path>`
+    #[error("Invalid remote {0:?}, expected tcp:<host>:<port> or unix:<path>")]
+    InvalidRemote(String),
+
+    #[error("Connection closed")]
+    ConnectionClosed,
+
+    /// The notifications of a monitor are already received by another stream
+    #[error("Monitor {0} already has a stream")]
+    DuplicateMonitor(Value),
+
+    /// The server did not send anything, not even the reply to an inactivity
+    /// probe, for the given time
+    #[error("No message received from the server for {0:?}")]
+    Inactive(Duration),
+
+    /// A message could not be framed, encoded or decoded
+    #[error("Protocol error: {0}")]
+    Protocol(#[from] serde_json::Error),
+
+    /// The server replied with something else than expected
+    #[error("Unexpected response: {0}")]
+    UnexpectedResponse(String),
+
+    /// The server failed a request, or an operation of a transaction
+    #[error("OVSDB error: {0}")]
+    Rpc(#[from] OvsdbError),
+
+    /// No reply was received before the timeout or the deadline of the
+    /// request
+    #[error("Request timed out")]
+    TimedOut,
+
+    /// The JSON-RPC client failed, such as when too many requests are made
+    /// at once
+    #[error("Client error: {0}")]
+    Client(#[source] ClientError),
+
+    /// A schema cannot be used for the database, such as one of another
+    /// database given to a conversion
+    #[error("Invalid schema: {0}")]
+    InvalidSchema(#[source] OvsdbConversionError),
+
+    /// A table is missing from the schema of the database
+    #[error("Table {0:?} does not exist in the schema")]
+    UnknownTable(String),
+
+    /// A row does not match the schema of its table
+    #[error("Invalid row for table {table:?}: {source}")]
+    InvalidRow {
+        table: String,
+        #[source]
+        source: ValidationError,
+    },
+
+    /// A row could not be converted to its Rust type
+    #[error("Conversion error: {0}")]
+    Conversion(#[source] OvsdbConversionError),
+
+    /// A row of a table update could not be converted to its Rust type
+    #[error("Invalid row {uuid} of table {table:?}: {source}")]
+    InvalidRowUpdate {
+        table: String,
+        uuid: Uuid,
+        #[source]
+        source: Box<OvsdbConversionError>,
+    },
+}
+
+impl Error {
+    /// Build the error for an `{"error": ..., "details": ...}` object, as
+    /// found in the replies and operation results of the server.
+    pub(crate) fn from_json(value: &Value) -> Self {
+        Error::Rpc(OvsdbError::new(
+            value["error"]
+                .as_str()
+                .or(value.as_str())
+                .unwrap_or("unknown error"),
+            value["details"].as_str().map(str::to_owned),
+            value["syntax"].as_str().map(str::to_owned),
+        ))
+    }
+
+    /// Returns the kind of error reported by the server, if any.
+    pub fn ovsdb_kind(&self) -> Option<&OvsdbErrorKind> {
+        match self {
+            Error::Rpc(error) => Some(&error.kind),
+            _ => None,
+        }
+    }
+}
+
+/// An error reported by the server, as described in section 3.1 of RFC 7047
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("{}{}", kind.as_str(), details.as_ref().map(|d| format!(" ({d})")).unwrap_or_default())]
+pub struct OvsdbError {
+    /// The `error` member, telling which kind of error happened
+    pub kind: OvsdbErrorKind,
+
+    /// The `details` member, a human-readable description of the error
+    pub details: Option<String>,
+
+    /// The `syntax` member, holding the JSON which could not be parsed
+    pub syntax: Option<String>,
+}
+
+impl OvsdbError {
+    pub fn new(error: &str, details: Option<String>, syntax: Option<String>) -> Self {
+        OvsdbError {
+            kind: OvsdbErrorKind::from(error),
+            details,
+            syntax,
+        }
+    }
+
+    /// Returns the `error` member as sent by the server.
+    pub fn error(&self) -> &str {
+        self.kind.as_str()
+    }
+}
+
+/// The well-known values of the `error` member, the others being kept as is
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum OvsdbErrorKind {
+    /// A row or a column value does not satisfy the constraints of the
+    /// schema, such as an index or the number of rows of a table
+    ConstraintViolation,
+
+    /// A strong reference points to a row which does not exist
+    ReferentialIntegrityViolation,
+
+    /// The server ran out of memory or of another resource
+    ResourcesExhausted,
+
+    /// The request could not be parsed, the `syntax` member showing where
+    SyntaxError,
+
+    /// The database could not be written to disk
+    IoError,
+
+    /// The same `uuid-name` was used by several `insert` operations
+    DuplicateUuidName,
+
+    /// A value is outside of the domain of the function applied to it
+    DomainError,
+
+    /// A value is outside of the range allowed by its type
+    RangeError,
+
+    /// A `wait` operation timed out
+    TimedOut,
+
+    /// The operation is not supported by the server
+    NotSupported,
+
+    /// The transaction was aborted by an `abort` operation
+    Aborted,
+
+    /// The operation was made on a row or a lock owned by another client
+    NotOwner,
+
+    /// The database of the request is not served
+    UnknownDatabase,
+
+    /// The method of the request is not implemented by the server, such as
+    /// an extension of RFC 7047 newer than the server
+    UnknownMethod,
+
+    /// Any other error, such as those of the lock and monitor requests
+    Other(String),
+}
+
+const KINDS: [(&str, OvsdbErrorKind); 14] = [
+    ("constraint violation", OvsdbErrorKind::ConstraintViolation),
+    (
+        "referential integrity violation",
+        OvsdbErrorKind::ReferentialIntegrityViolation,
+    ),
+    ("resources exhausted", OvsdbErrorKind::ResourcesExhausted),
+    ("syntax error", OvsdbErrorKind::SyntaxError),
+    ("I/O error", OvsdbErrorKind::IoError),
+    ("duplicate uuid-name", OvsdbErrorKind::DuplicateUuidName),
+    ("domain error", OvsdbErrorKind::DomainError),
+    ("range error", OvsdbErrorKind::RangeError),
+    ("timed out", OvsdbErrorKind::TimedOut),
+    ("not supported", OvsdbErrorKind::NotSupported),
+    ("aborted", OvsdbErrorKind::Aborted),
+    ("not owner", OvsdbErrorKind::NotOwner),
+    ("unknown database", OvsdbErrorKind::UnknownDatabase),
+    ("unknown method", OvsdbErrorKind::UnknownMethod),
+];
+
+impl OvsdbErrorKind {
+    /// Returns the `error` member for this kind of error.
+    pub fn as_str(&self) -> &str {
+        match self {
+            OvsdbErrorKind::Other(error) => error,
+            kind => KINDS
+                .iter()
+                .find(|(_, known)| known == kind)
+                .map(|(error, _)| *error)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&str> for OvsdbErrorKind {
+    fn from(error: &str) -> Self {
+        KINDS
+            .iter()
+            .find(|(known, _)| *known == error)
+            .map(|(_, kind)| kind.clone())
+            .unwrap_or_else(|| OvsdbErrorKind::Other(error.to_owned()))
+    }
+}
+
+impl From<ClientError> for Error {
+    fn from(error: ClientError) -> Self {
+        match error {
+            // NOTE(mnaser): The transport keeps the error object of the server as the data
+            //               of the JSON-RPC error.
+            ClientError::Call(object) => {
+                let data = object
+                    .data()
+                    .and_then(|data| serde_json::from_str(data.get()).ok());
+                Error::from_json(&data.unwrap_or_else(|| Value::from(object.message())))
+            }
+            ClientError::ParseError(error) => Error::Protocol(error),
+            ClientError::RequestTimeout => Error::TimedOut,
+            // NOTE(mnaser): Errors of the transport are boxed by jsonrpsee, they are
+            //               unboxed so that callers can match on them.
+            ClientError::Transport(error) => match error.downcast::<Error>() {
+                Ok(error) => *error,
+                Err(error) => Error::Client(ClientError::Transport(error)),
+            },
+            // NOTE(mnaser): Requests made after the connection was lost fail with the
+            //               error which stopped the client.
+            ClientError::RestartNeeded(reason) => match &*reason {
+                ClientError::Transport(error) => match error.downcast_ref::<Error>() {
+                    Some(Error::ConnectionClosed) => Error::ConnectionClosed,
+                    Some(Error::Inactive(duration)) => Error::Inactive(*duration),
+                    Some(Error::Transport(error)) => {
+                        Error::Transport(std::io::Error::new(error.kind(), error.to_string()))
+                    }
+                    Some(Error::Protocol(error)) => {
+                        Error::Protocol(serde::de::Error::custom(error))
+                    }
+                    _ => Error::Client(ClientError::RestartNeeded(reason)),
+                },
+                _ => Error::Client(ClientError::RestartNeeded(reason)),
+            },
+            error => Error::Client(error),
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use crate::schema::{ColumnType, DatabaseSchema};
+use serde_json::{Map, Value, json};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{BufRead, BufReader, Read, Write},
+    path::Path,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub mod raft;
+
+#[derive(Debug, Error)]
+pub enum FileError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid record header at offset {offset}: {header:?}")]
+    InvalidHeader { offset: u64, header: String },
+
+    #[error("Truncated record at offset {0}")]
+    Truncated(u64),
+
+    #[error("Checksum mismatch for the record at offset {0}")]
+    Checksum(u64),
+
+    #[error("Invalid JSON in the record at offset {offset}: {source}")]
+    Json {
+        offset: u64,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("Invalid record at offset {offset}: {reason}")]
+    InvalidRecord { offset: u64, reason: String },
+
+    #[error("The file does not contain a schema")]
+    MissingSchema,
+
+    #[error("Invalid transaction: {0}")]
+    InvalidTransaction(String),
+
+    #[error("Table {0:?} does not exist in the schema")]
+    UnknownTable(String),
+
+    #[error("Column {column:?} does not exist in table {table:?}")]
+    UnknownColumn { table: String, column: String },
+}
+
+/// A record of a database file
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    /// Position of the record header in the file
+    pub offset: u64,
+
+    pub data: Value,
+}
+
+/// Iterator over the records of a database file, as returned by [`records`]
+/// and [`raft::records`]
+pub struct Records<R> {
+    reader: R,
+
+    magic: &'static str,
+
+    offset: u64,
+}
+
+/// Read the records of a standalone database file.
+///
+/// Each record is a `OVSDB JSON <length> <sha1>` header line followed by the
+/// JSON text it describes. The first record holds the schema and every other
+/// one a committed transaction.
+pub fn records<R: BufRead>(reader: R) -> Records<R> {
+    Records::new(reader, "OVSDB JSON")
+}
+
+impl<R: BufRead> Records<R> {
+    fn new(reader: R, magic: &'static str) -> Self {
+        Records {
+            reader,
+            magic,
+            offset: 0,
+        }
+    }
+
+    fn read_record(&mut self) -> Result<Option<Record>, FileError> {
+        let offset = self.offset;
+
+        let mut header = String::new();
+        let n = self.reader.read_line(&mut header)?;
+        if n == 0 {
+            return Ok(None);
+        }
+        if !header.ends_with('\n') {
+            return Err(FileError::Truncated(offset));
+        }
+
+        let invalid = || FileError::InvalidHeader {
+            offset,
+            header: header.trim_end().to_owned(),
+        };
+        let (length, checksum) = header
+            .trim_end()
+            .strip_prefix(self.magic)
+            .and_then(|rest| rest.strip_prefix(' '))
+            .and_then(|rest| rest.split_once(' '))
+            .ok_or_else(invalid)?;
+        let length: u64 = length.parse().map_err(|_| invalid())?;
+
+        // NOTE(mnaser): The length comes from the file, so the record is read as it
+        //               arrives rather than into a buffer of that length up front.
+        let mut data = Vec::new();
+        (&mut self.reader).take(length).read_to_end(&mut data)?;
+        if (data.len() as u64) < length {
+            return Err(FileError::Truncated(offset));
+        }
+        if sha1_smol::Sha1::from(&data).digest().to_string() != checksum {
+            return Err(FileError::Checksum(offset));
+        }
+
+        // NOTE(mnaser): ovsdb-server ends each record with a newline which isn't part of
+        //               the length in the header.
+        let mut consumed = n + data.len();
+        if self.reader.fill_buf()?.first() == Some(&b'\n') {
+            self.reader.consume(1);
+            consumed += 1;
+        }
+        self.offset += consumed as u64;
+
+        let data =
+            serde_json::from_slice(&data).map_err(|source| FileError::Json { offset, source })?;
+
+        Ok(Some(Record { offset, data }))
+    }
+}
+
+impl<R: BufRead> Iterator for Records<R> {
+    type Item = Result<Record, FileError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+/// Write a record of a standalone database file, as read by [`records`].
+pub fn write_record(mut writer: impl Write, data: &Value) -> std::io::Result<()> {
+    let data = data.to_string();
+    write!(
+        writer,
+        "OVSDB JSON {} {}\n{data}\n",
+        data.len(),
+        sha1_smol::Sha1::from(&data).digest()
+    )
+}
+
+/// Columns of a row, without `_uuid`
+pub type Row = Map<String, Value>;
+
+/// A transaction committed to a database file
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Transaction {
+    /// Commit time, in milliseconds since the epoch
+    pub date: Option<u64>,
+
+    pub comment: Option<String>,
+
+    /// Whether the columns of modified rows hold differences from the old
+    /// values instead of the new values
+    pub is_diff: bool,
+
+    /// Changed rows by table and UUID, `None` for deleted rows
+    pub tables: HashMap<String, HashMap<Uuid, Option<Row>>>,
+}
+
+impl Transaction {
+    pub fn from_json(value: &Value) -> Result<Self, FileError> {
+        let invalid = |reason: &str| FileError::InvalidTransaction(reason.to_owned());
+        let object = value.as_object().ok_or_else(|| invalid("not an object"))?;
+
+        let mut transaction = Transaction {
+            date: object.get("_date").and_then(Value::as_u64),
+            comment: object
+                .get("_comment")
+                .and_then(Value::as_str)
+                .map(str::to_owned),
+            is_diff: object
+                .get("_is_diff")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            tables: HashMap::new(),
+        };
+
+        for (table, rows) in object.iter().filter(|(key, _)| !key.starts_with('_')) {
+            let rows = rows
+                .as_object()
+                .ok_or_else(|| invalid("table changes are not an object"))?;
+
+            let mut changes = HashMap::new();
+            for (uuid, row) in rows {
+                let uuid = Uuid::parse_str(uuid).map_err(|_| invalid("invalid row UUID"))?;
+                let row = match row {
+                    Value::Null => None,
+                    Value::Object(row) => Some(row.clone()),
+                    _ => return Err(invalid("row is neither an object nor null")),
+                };
+                changes.insert(uuid, row);
+            }
+            transaction.tables.insert(table.clone(), changes);
+        }
+
+        Ok(transaction)
+    }
+}
+
+/// The contents of a database reconstructed from its file
+///
+/// Columns holding their default value are left out of the rows, as they
+/// are in the file.
+#[derive(Debug)]
+pub struct Database {
+    pub schema: DatabaseSchema,
+
+    /// Rows of every table, keyed by their UUID
+    pub tables: HashMap<String, BTreeMap<Uuid, Row>>,
+}
+
+impl Database {
+    pub fn new(schema: DatabaseSchema) -> Self {
+        let tables = schema
+            .tables
+            .keys()
+            .map(|name| (name.clone(), BTreeMap::new()))
+            .collect();
+
+        Database { schema, tables }
+    }
+
+    /// Read a standalone database file, such as the `conf.db` of Open vSwitch.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FileError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Read a standalone database file, replaying its transactions.
+    pub fn read(reader: impl BufRead) -> Result<Self, FileError> {
+        let mut records = records(reader);
+
+        let record = records.next().ok_or(FileError::MissingSchema)??;
+        let schema = serde_json::from_value(record.data).map_err(|source| FileError::Json {
+            offset: record.offset,
+            source,
+        })?;
+
+        let mut database = Database::new(schema);
+        for record in records {
+            database.apply(&Transaction::from_json(&record?.data)?)?;
+        }
+
+        Ok(database)
+    }
+
+    /// Apply a committed transaction to the rows.
+    pub fn apply(&mut self, transaction: &Transaction) -> Result<(), FileError> {
+        for (table, changes) in &transaction.tables {
+            let table_schema = self
+                .schema
+                .tables
+                .get(table)
+                .ok_or_else(|| FileError::UnknownTable(table.clone()))?;
+            let rows = self.tables.entry(table.clone()).or_default();
+
+            for (uuid, change) in changes {
+                let Some(change) = change else {
+                    rows.remove(uuid);
+                    continue;
+                };
+
+                let existing = rows.contains_key(uuid);
+                let row = rows.entry(*uuid).or_default();
+                for (column, value) in change {
+                    let column_type = &table_schema
+                        .columns
+                        .get(column)
+                        .ok_or_else(|| FileError::UnknownColumn {
+                            table: table.clone(),
+                            column: column.clone(),
+                        })?
+                        .r#type;
+
+                    let value = if transaction.is_diff && existing {
+                        apply_diff(column_type, row.get(column), value)
+                    } else {
+                        Some(value.clone())
+                    };
+                    match value {
+                        Some(value) => row.insert(column.clone(), value),
+                        None => row.remove(column),
+                    };
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Apply the difference stored for a column to its old value, returning
+/// `None` if the result is an empty set or map.
+///
+/// Elements of a set are toggled. Entries of a map are added if their key is
+/// missing, removed if they match the old entry and replaced otherwise.
+/// Other columns hold their new value. The `modify` updates of `update2`
+/// notifications use the same format.
+pub fn apply_diff(column_type: &ColumnType, old: Option<&Value>, diff: &Value) -> Option<Value> {
+    if column_type.is_scalar() {
+        return Some(diff.clone());
+    }
+
+    if column_type.is_map() {
+        let mut pairs = old.map(map_pairs).unwrap_or_default();
+        for (key, value) in map_pairs(diff) {
+            match pairs.iter().position(|(k, _)| *k == key) {
+                Some(index) if pairs[index].1 == value => {
+                    pairs.remove(index);
+                }
+                Some(index) => pairs[index].1 = value,
+                None => pairs.push((key, value)),
+            }
+        }
+
+        return (!pairs.is_empty()).then(|| {
+            let pairs = pairs
+                .into_iter()
+                .map(|(key, value)| json!([key, value]))
+                .collect::<Vec<_>>();
+            json!(["map", pairs])
+        });
+    }
+
+    let mut atoms = old.map(set_atoms).unwrap_or_default();
+    for atom in set_atoms(diff) {
+        match atoms.iter().position(|a| *a == atom) {
+            Some(index) => {
+                atoms.remove(index);
+            }
+            None => atoms.push(atom),
+        }
+    }
+
+    match atoms.len() {
+        0 => None,
+        1 => atoms.pop(),
+        _ => Some(json!(["set", atoms])),
+    }
+}
+
+/// Returns the atoms of a set, which is a bare atom if it has one element.
+fn set_atoms(value: &Value) -> Vec<Value> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([kind, Value::Array(atoms)]) if kind == "set" => atoms.clone(),
+        _ => vec![value.clone()],
+    }
+}
+
+fn map_pairs(value: &Value) -> Vec<(Value, Value)> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([kind, Value::Array(pairs)]) if kind == "map" => pairs
+            .iter()
+            .filter_map(|pair| match pair.as_array().map(Vec::as_slice) {
+                Some([key, value]) => Some((key.clone(), value.clone())),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
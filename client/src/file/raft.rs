@@ -0,0 +1,363 @@
+use crate::file::{Database, FileError, Record, Records, Transaction};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+use uuid::Uuid;
+
+/// Read the records of a clustered database file.
+///
+/// The records are framed as in standalone files, with a `CLUSTER` magic
+/// instead of `OVSDB JSON`. The first record is the [`ClusterHeader`] and
+/// every other one a [`RaftRecord`].
+pub fn records<R: BufRead>(reader: R) -> Records<R> {
+    Records::new(reader, "CLUSTER")
+}
+
+/// The first record of a clustered database file
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClusterHeader {
+    /// Name of the database
+    pub name: String,
+
+    /// Identity of the server owning the file
+    pub server_id: Uuid,
+
+    /// Identity of the cluster, unknown until the server has joined it
+    pub cluster_id: Option<Uuid>,
+
+    /// Address the server listens on for Raft traffic, such as `tcp:10.0.0.1:6643`
+    pub local_address: String,
+
+    /// Addresses of the servers to join, if the server is still joining
+    pub remote_addresses: Vec<String>,
+
+    /// The snapshot the log starts from, if the server has joined
+    pub snapshot: Option<Snapshot>,
+}
+
+/// The state of the database covering every entry up to a log index
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    /// Term of the last entry included in the snapshot
+    pub term: u64,
+
+    /// Index of the last entry included in the snapshot
+    pub index: u64,
+
+    /// Servers of the cluster, keyed by server id with their address
+    pub servers: Value,
+
+    /// Election timer in milliseconds
+    pub election_timer: Option<u64>,
+
+    /// Entry id of the last entry included in the snapshot
+    pub eid: Option<Uuid>,
+
+    /// Contents of the database, as an entry data
+    pub data: Option<Value>,
+}
+
+/// An entry of the Raft log
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogEntry {
+    pub term: u64,
+
+    pub index: u64,
+
+    /// The payload, `[<schema> or null, <transaction> or null]` for OVSDB
+    /// databases
+    pub data: Option<Value>,
+
+    /// Entry id, used to detect whether a transaction was committed
+    pub eid: Option<Uuid>,
+
+    /// New cluster configuration, for membership changes
+    pub servers: Option<Value>,
+
+    /// New election timer in milliseconds
+    pub election_timer: Option<u64>,
+}
+
+/// A record following the header of a clustered database file
+#[derive(Clone, Debug, PartialEq)]
+pub enum RaftRecord {
+    /// An entry appended to the log
+    Entry(LogEntry),
+
+    /// The server started a new term
+    Term { term: u64 },
+
+    /// The server voted in an election
+    Vote { term: u64, vote: Uuid },
+
+    /// The server learned who the leader of a term is
+    Leader { term: u64, leader: Uuid },
+
+    /// Entries up to this index are committed
+    CommitIndex(u64),
+
+    /// A note for humans, such as `transfer leadership`
+    Note(String),
+}
+
+impl RaftRecord {
+    /// Parse a record following the header.
+    pub fn from_record(record: &Record) -> Result<Self, FileError> {
+        let data = &record.data;
+        let invalid = |reason: &str| FileError::InvalidRecord {
+            offset: record.offset,
+            reason: reason.to_owned(),
+        };
+        let term = || data["term"].as_u64().ok_or_else(|| invalid("missing term"));
+
+        // NOTE(mnaser): The records are not tagged with their type, so it is guessed from
+        //               their fields in the same order as ovsdb-server does.
+        if let Some(note) = data.get("note") {
+            return Ok(RaftRecord::Note(
+                note.as_str()
+                    .ok_or_else(|| invalid("invalid note"))?
+                    .to_owned(),
+            ));
+        }
+        if let Some(commit_index) = data.get("commit_index") {
+            return Ok(RaftRecord::CommitIndex(
+                commit_index
+                    .as_u64()
+                    .ok_or_else(|| invalid("invalid commit index"))?,
+            ));
+        }
+        if let Some(index) = data.get("index") {
+            return Ok(RaftRecord::Entry(LogEntry {
+                term: term()?,
+                index: index.as_u64().ok_or_else(|| invalid("invalid index"))?,
+                data: data.get("data").cloned(),
+                eid: optional_uuid(data, "eid").map_err(|_| invalid("invalid eid"))?,
+                servers: data.get("servers").cloned(),
+                election_timer: data["election_timer"].as_u64(),
+            }));
+        }
+        if data.get("leader").is_some() {
+            return Ok(RaftRecord::Leader {
+                term: term()?,
+                leader: required_uuid(data, "leader").ok_or_else(|| invalid("invalid leader"))?,
+            });
+        }
+        if data.get("vote").is_some() {
+            return Ok(RaftRecord::Vote {
+                term: term()?,
+                vote: required_uuid(data, "vote").ok_or_else(|| invalid("invalid vote"))?,
+            });
+        }
+
+        Ok(RaftRecord::Term { term: term()? })
+    }
+}
+
+impl ClusterHeader {
+    /// Parse the first record of a file.
+    pub fn from_record(record: &Record) -> Result<Self, FileError> {
+        let data = &record.data;
+        let invalid = |reason: &str| FileError::InvalidRecord {
+            offset: record.offset,
+            reason: reason.to_owned(),
+        };
+        let string = |key: &str| {
+            data[key]
+                .as_str()
+                .map(str::to_owned)
+                .ok_or_else(|| invalid(&format!("missing {key}")))
+        };
+
+        let snapshot = match data.get("prev_index") {
+            None => None,
+            Some(index) => Some(Snapshot {
+                term: data["prev_term"]
+                    .as_u64()
+                    .ok_or_else(|| invalid("missing prev_term"))?,
+                index: index
+                    .as_u64()
+                    .ok_or_else(|| invalid("invalid prev_index"))?,
+                servers: data["prev_servers"].clone(),
+                election_timer: data["prev_election_timer"].as_u64(),
+                eid: optional_uuid(data, "prev_eid").map_err(|_| invalid("invalid prev_eid"))?,
+                data: data.get("prev_data").cloned(),
+            }),
+        };
+
+        Ok(ClusterHeader {
+            name: string("name")?,
+            server_id: required_uuid(data, "server_id")
+                .ok_or_else(|| invalid("invalid server_id"))?,
+            cluster_id: optional_uuid(data, "cluster_id")
+                .map_err(|_| invalid("invalid cluster_id"))?,
+            local_address: string("local_address")?,
+            remote_addresses: data["remote_addresses"]
+                .as_array()
+                .map(|addresses| {
+                    addresses
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_owned)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            snapshot,
+        })
+    }
+}
+
+fn required_uuid(data: &Value, key: &str) -> Option<Uuid> {
+    optional_uuid(data, key).ok().flatten()
+}
+
+fn optional_uuid(data: &Value, key: &str) -> Result<Option<Uuid>, uuid::Error> {
+    data.get(key)
+        .and_then(Value::as_str)
+        .map(Uuid::parse_str)
+        .transpose()
+}
+
+/// The contents of a clustered database reconstructed from its file
+#[derive(Debug)]
+pub struct ClusteredDatabase {
+    pub header: ClusterHeader,
+
+    /// The latest term the server took part in
+    pub term: u64,
+
+    /// The server this server voted for in the latest term
+    pub vote: Option<Uuid>,
+
+    /// The leader of the latest term, if known
+    pub leader: Option<Uuid>,
+
+    /// The latest index known to be committed
+    pub commit_index: Option<u64>,
+
+    /// Entries after the snapshot, in log order
+    ///
+    /// Entries replaced by a new leader are dropped, as they are when
+    /// `ovsdb-server` reads the file.
+    pub entries: Vec<LogEntry>,
+
+    /// The database after applying the snapshot and every entry, `None` if
+    /// the server has not received a schema yet
+    pub database: Option<Database>,
+}
+
+impl ClusteredDatabase {
+    /// Read a clustered database file, such as the `ovnnb_db.db` of an OVN
+    /// Raft cluster.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FileError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Read a clustered database file, replaying the log after the snapshot.
+    ///
+    /// Every entry in the log is applied, including those past
+    /// [`commit_index`](Self::commit_index) which may not be committed yet.
+    pub fn read(reader: impl BufRead) -> Result<Self, FileError> {
+        let mut records = records(reader);
+
+        let record = records.next().ok_or(FileError::MissingSchema)??;
+        let header = ClusterHeader::from_record(&record)?;
+
+        let mut cluster = ClusteredDatabase {
+            term: header.snapshot.as_ref().map_or(0, |s| s.term),
+            header,
+            vote: None,
+            leader: None,
+            commit_index: None,
+            entries: vec![],
+            database: None,
+        };
+
+        let mut entries = BTreeMap::new();
+        for record in records {
+            match RaftRecord::from_record(&record?)? {
+                RaftRecord::Entry(entry) => {
+                    // NOTE(mnaser): An entry written for an index which is already in the
+                    //               log replaces it along with every entry after it.
+                    entries.split_off(&entry.index);
+                    cluster.term = cluster.term.max(entry.term);
+                    entries.insert(entry.index, entry);
+                }
+                RaftRecord::Term { term } => cluster.new_term(term),
+                RaftRecord::Vote { term, vote } => {
+                    cluster.new_term(term);
+                    cluster.vote = Some(vote);
+                }
+                RaftRecord::Leader { term, leader } => {
+                    cluster.new_term(term);
+                    cluster.leader = Some(leader);
+                }
+                RaftRecord::CommitIndex(index) => {
+                    cluster.commit_index = cluster.commit_index.max(Some(index));
+                }
+                RaftRecord::Note(_) => {}
+            }
+        }
+        cluster.entries = entries.into_values().collect();
+
+        if let Some(data) = cluster
+            .header
+            .snapshot
+            .as_ref()
+            .and_then(|s| s.data.as_ref())
+        {
+            apply_data(&mut cluster.database, data)?;
+        }
+        for entry in &cluster.entries {
+            if let Some(data) = &entry.data {
+                apply_data(&mut cluster.database, data)?;
+            }
+        }
+
+        Ok(cluster)
+    }
+
+    fn new_term(&mut self, term: u64) {
+        if term > self.term {
+            self.term = term;
+            self.vote = None;
+            self.leader = None;
+        }
+    }
+
+    /// Returns the index of the last entry in the log.
+    pub fn last_index(&self) -> u64 {
+        self.entries.last().map_or_else(
+            || self.header.snapshot.as_ref().map_or(0, |s| s.index),
+            |entry| entry.index,
+        )
+    }
+}
+
+/// Apply the `[<schema> or null, <transaction> or null]` data of an entry,
+/// a schema replacing the whole database as after a conversion.
+fn apply_data(database: &mut Option<Database>, data: &Value) -> Result<(), FileError> {
+    let invalid = |reason: &str| FileError::InvalidTransaction(reason.to_owned());
+    let [schema, transaction] = data
+        .as_array()
+        .map(Vec::as_slice)
+        .and_then(|data| <&[Value; 2]>::try_from(data).ok())
+        .ok_or_else(|| invalid("entry data is not a [schema, transaction] pair"))?;
+
+    if !schema.is_null() {
+        let schema = serde_json::from_value(schema.clone())
+            .map_err(|e| invalid(&format!("invalid schema: {e}")))?;
+        *database = Some(Database::new(schema));
+    }
+    if !transaction.is_null() {
+        database
+            .as_mut()
+            .ok_or(FileError::MissingSchema)?
+            .apply(&Transaction::from_json(transaction)?)?;
+    }
+
+    Ok(())
+}
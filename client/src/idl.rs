@@ -0,0 +1,611 @@
+//! A replica of the rows of a database kept up to date by a monitor, as the
+//! IDL of Open vSwitch does, with its references resolved and its orphan
+//! rows collected.
+
+use crate::{
+    Error, OvsdbClient,
+    file::{self, FileError, Row},
+    monitor::Updates,
+    schema::{
+        AtomicType, ColumnType, DatabaseSchema, MonitorCondRequest, RowUpdate2, TableSchema,
+        TableUpdate, TableUpdate2, Update2Notification, Update3Notification,
+    },
+};
+use ovsdb_schema::{OvsdbTable, WeakRef};
+use serde_json::{Map, Value, json};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::Path,
+    sync::Arc,
+};
+use uuid::Uuid;
+
+/// A row inserted, modified or deleted by an update of a replica
+///
+/// The rows are shared with the replica, which copies a row on its next
+/// modification only while a change or the application still holds it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowChange {
+    pub table: String,
+
+    pub uuid: Uuid,
+
+    /// The row before the update, none if it was inserted
+    pub old: Option<Arc<Row>>,
+
+    /// The row after the update, none if it was deleted
+    pub new: Option<Arc<Row>>,
+}
+
+/// The rows of the tables of a database, by table and UUID, holding every
+/// column of their table
+///
+/// The rows come from the initial state and the notifications of a monitor
+/// of every column of the replicated tables, created with `monitor`. Those
+/// of the tables which are not roots in the schema are dropped once no row
+/// reaches them through strong references anymore, as the server does.
+///
+/// The rows are reference counted, so that handing them out, and the changes
+/// of the updates, does not copy them.
+#[derive(Debug)]
+pub struct Replica {
+    schema: Arc<DatabaseSchema>,
+
+    tables: HashMap<String, HashMap<Uuid, Arc<Row>>>,
+
+    /// The tables whose rows are dropped once nothing references them
+    collectable: HashSet<String>,
+
+    /// The id of the last transaction applied, for `monitor_since`
+    last_id: Option<Uuid>,
+}
+
+impl Replica {
+    /// Returns an empty replica of every table of the database.
+    pub fn new(schema: Arc<DatabaseSchema>) -> Self {
+        let tables = schema.tables.keys().cloned().collect::<Vec<_>>();
+        Self::with_tables(schema, tables)
+    }
+
+    /// Returns an empty replica of some tables of the database.
+    ///
+    /// The rows of a table which is not a root are only collected when every
+    /// table referencing it strongly is replicated too, as they could be
+    /// referenced by rows the replica does not know about.
+    ///
+    /// # Panics
+    ///
+    /// If a table is not in the schema.
+    pub fn with_tables(
+        schema: Arc<DatabaseSchema>,
+        tables: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let tables: HashMap<String, HashMap<Uuid, Arc<Row>>> = tables
+            .into_iter()
+            .map(|table| (table.into(), HashMap::new()))
+            .collect();
+        for table in tables.keys() {
+            assert!(
+                schema.tables.contains_key(table),
+                "table {table:?} is not in the schema of {}",
+                schema.name
+            );
+        }
+
+        // NOTE(mnaser): Every table is a root in a schema where none of them sets
+        //               isRoot, as in RFC 7047, so none of them is collected.
+        let rootless = schema
+            .tables
+            .values()
+            .all(|table| !table.is_root.unwrap_or(false));
+        let collectable = tables
+            .keys()
+            .filter(|_| !rootless)
+            .filter(|table| !schema.tables[*table].is_root.unwrap_or(false))
+            .filter(|table| {
+                schema
+                    .tables
+                    .iter()
+                    .filter(|(_, referrer)| strongly_references(referrer, table))
+                    .all(|(referrer, _)| tables.contains_key(referrer))
+            })
+            .cloned()
+            .collect();
+
+        Replica {
+            schema,
+            tables,
+            collectable,
+            last_id: None,
+        }
+    }
+
+    pub fn schema(&self) -> &DatabaseSchema {
+        &self.schema
+    }
+
+    /// Returns the rows of the table, none if it is not replicated.
+    pub fn table(&self, table: &str) -> Option<&HashMap<Uuid, Arc<Row>>> {
+        self.tables.get(table)
+    }
+
+    /// Returns the row of the table with the UUID, which can be cloned to
+    /// keep it after the replica is updated.
+    pub fn row(&self, table: &str, uuid: &Uuid) -> Option<&Arc<Row>> {
+        self.tables.get(table)?.get(uuid)
+    }
+
+    /// Returns the row with the UUID, converted to its table struct.
+    pub fn get<T: OvsdbTable>(&self, uuid: &Uuid) -> Result<Option<T>, Error> {
+        self.row(T::TABLE, uuid)
+            .map(|row| typed(T::TABLE, uuid, row))
+            .transpose()
+    }
+
+    /// Returns the rows of the table of `T`, converted to it.
+    pub fn rows<T: OvsdbTable>(&self) -> Result<HashMap<Uuid, T>, Error> {
+        let rows = self
+            .tables
+            .get(T::TABLE)
+            .ok_or_else(|| Error::UnknownTable(T::TABLE.to_owned()))?;
+
+        rows.iter()
+            .map(|(uuid, row)| Ok((*uuid, typed(T::TABLE, uuid, row)?)))
+            .collect()
+    }
+
+    /// Returns the row a strong or weak reference points to, none if it does
+    /// not exist, as for a weak reference to a deleted row.
+    pub fn resolve<T: OvsdbTable>(
+        &self,
+        reference: impl Into<WeakRef<T>>,
+    ) -> Result<Option<T>, Error> {
+        self.get(&reference.into().uuid())
+    }
+
+    /// Returns the requests of a monitor of every column of the replicated
+    /// tables, for `RpcClient::monitor_cond`.
+    pub fn requests(&self) -> HashMap<String, MonitorCondRequest> {
+        self.tables
+            .keys()
+            .map(|table| (table.clone(), MonitorCondRequest::default()))
+            .collect()
+    }
+
+    /// Returns the id of the last transaction applied, from which
+    /// `monitor_since` resumes.
+    pub fn last_id(&self) -> Option<Uuid> {
+        self.last_id
+    }
+
+    /// Drop every row, such as before loading the initial state of a new
+    /// monitor.
+    pub fn clear(&mut self) {
+        self.tables.values_mut().for_each(HashMap::clear);
+        self.last_id = None;
+    }
+
+    /// Replace the rows with the initial state of a monitor of the replicated
+    /// tables, made over the client, returning the stream of its
+    /// notifications, to be given to `apply_update2`.
+    pub async fn monitor(
+        &mut self,
+        client: &OvsdbClient,
+        id: Value,
+    ) -> Result<Updates<Update2Notification<Value>>, Error> {
+        let (initial, updates) = client
+            .monitor_cond_updates(&self.schema.name, id, self.requests())
+            .await?;
+
+        self.clear();
+        self.apply_update2(&initial)?;
+
+        Ok(updates)
+    }
+
+    /// Bring the rows up to date with a monitor of the replicated tables made
+    /// over the client with `monitor_cond_since`, returning the stream of its
+    /// notifications, to be given to `apply_update3`.
+    ///
+    /// The server only sends the changes made after the last transaction of
+    /// the replica if it still has them, such as after a reconnection or for
+    /// a replica loaded from disk, and otherwise the whole initial state,
+    /// which replaces the rows. Servers older than Open vSwitch 2.12 do not
+    /// implement the method, as `RpcClient::server_supports` tells, and
+    /// need `monitor`.
+    pub async fn monitor_since(
+        &mut self,
+        client: &OvsdbClient,
+        id: Value,
+    ) -> Result<Updates<Update3Notification<Value>>, Error> {
+        let (reply, updates) = client
+            .monitor_cond_since_updates(&self.schema.name, id, self.requests(), self.last_id)
+            .await?;
+
+        if !reply.found {
+            self.clear();
+        }
+        self.apply_update3(reply.last_id, &reply.updates)?;
+
+        Ok(updates)
+    }
+
+    /// Apply the rows of an "update3" notification, made by the transaction
+    /// with the id, returning the rows it changed.
+    pub fn apply_update3(
+        &mut self,
+        last_id: Uuid,
+        update: &TableUpdate2<Value>,
+    ) -> Result<Vec<RowChange>, Error> {
+        let changes = self.apply_update2(update)?;
+        // NOTE(mnaser): Servers which do not track transactions, such as those of
+        //               standalone databases, send the nil UUID.
+        self.last_id = Some(last_id).filter(|id| !id.is_nil());
+
+        Ok(changes)
+    }
+
+    /// Write the rows and the id of the last transaction to the file, so that
+    /// a restarted process can `load` them and resume with `monitor_since`
+    /// instead of downloading every row again.
+    ///
+    /// The file is written next to the path and then renamed over it, so
+    /// that it is never left half written.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let tables: Map<String, Value> = self
+            .tables
+            .iter()
+            .map(|(table, rows)| {
+                let rows = rows
+                    .iter()
+                    .map(|(uuid, row)| (uuid.to_string(), Value::Object(Row::clone(row))))
+                    .collect();
+                (table.clone(), Value::Object(rows))
+            })
+            .collect();
+        let data = json!({
+            "name": self.schema.name,
+            "cksum": self.schema.checksum,
+            "last_id": self.last_id,
+            "tables": tables,
+        });
+
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+        file::write_record(&mut writer, &data)?;
+        writer.into_inner()?.sync_all()?;
+
+        fs::rename(temporary, path)
+    }
+
+    /// Read the replica saved to the file, of the tables it was saved with.
+    ///
+    /// # Errors
+    ///
+    /// `FileError::InvalidRecord` if it was saved with another schema, by
+    /// its checksum, in which case a new replica has to be filled instead.
+    pub fn load(schema: Arc<DatabaseSchema>, path: impl AsRef<Path>) -> Result<Self, FileError> {
+        let mut records = file::records(BufReader::new(File::open(path)?));
+        let record = records.next().ok_or(FileError::MissingSchema)??;
+        let invalid = |reason: String| FileError::InvalidRecord {
+            offset: record.offset,
+            reason,
+        };
+
+        let data = &record.data;
+        if data["name"] != schema.name.as_str() || data["cksum"] != json!(schema.checksum) {
+            return Err(invalid(format!(
+                "saved for {} {}, not {} {}",
+                data["name"],
+                data["cksum"],
+                schema.name,
+                schema.checksum.as_deref().unwrap_or("without checksum")
+            )));
+        }
+        let tables = data["tables"]
+            .as_object()
+            .ok_or_else(|| invalid("tables are not an object".to_owned()))?;
+        for table in tables.keys() {
+            if !schema.tables.contains_key(table) {
+                return Err(FileError::UnknownTable(table.clone()));
+            }
+        }
+
+        let mut replica = Replica::with_tables(schema.clone(), tables.keys().cloned());
+        for (table, rows) in tables {
+            let columns = &schema.tables[table].columns;
+            let rows = rows
+                .as_object()
+                .ok_or_else(|| invalid(format!("rows of {table} are not an object")))?;
+            for (uuid, row) in rows {
+                let uuid = Uuid::parse_str(uuid)
+                    .map_err(|_| invalid(format!("invalid row UUID {uuid:?}")))?;
+                let row = row
+                    .as_object()
+                    .ok_or_else(|| invalid(format!("row {uuid} of {table} is not an object")))?;
+                if let Some(column) = row.keys().find(|column| !columns.contains_key(*column)) {
+                    return Err(FileError::UnknownColumn {
+                        table: table.clone(),
+                        column: column.clone(),
+                    });
+                }
+                replica
+                    .tables
+                    .get_mut(table)
+                    .unwrap()
+                    .insert(uuid, Arc::new(row.clone()));
+            }
+        }
+        replica.last_id = serde_json::from_value(data["last_id"].clone())
+            .map_err(|_| invalid("invalid last transaction id".to_owned()))?;
+
+        Ok(replica)
+    }
+
+    /// Apply the rows of an "update" notification, or the initial state of a
+    /// "monitor" request, returning the rows it changed.
+    pub fn apply_update(&mut self, update: &TableUpdate<Value>) -> Result<Vec<RowChange>, Error> {
+        let mut changes = Vec::new();
+        for (table, rows) in update {
+            for (uuid, row) in rows {
+                let new = match &row.new {
+                    Some(new) => Some(Arc::new(self.complete(table, uuid, new)?)),
+                    None => None,
+                };
+                changes.extend(self.replace(table, uuid, new)?);
+            }
+        }
+        self.collect_garbage(&mut changes);
+
+        Ok(changes)
+    }
+
+    /// Apply the rows of an "update2" notification, or the initial state of
+    /// a "monitor_cond" request, returning the rows it changed.
+    pub fn apply_update2(&mut self, update: &TableUpdate2<Value>) -> Result<Vec<RowChange>, Error> {
+        let mut changes = Vec::new();
+        for (table, rows) in update {
+            for (uuid, row) in rows {
+                let change = match row {
+                    RowUpdate2::Initial(new) | RowUpdate2::Insert(new) => {
+                        let row = self.complete(table, uuid, new)?;
+                        self.replace(table, uuid, Some(Arc::new(row)))?
+                    }
+                    RowUpdate2::Delete => self.replace(table, uuid, None)?,
+                    RowUpdate2::Modify(diff) => Some(self.modify(table, uuid, diff)?),
+                };
+                changes.extend(change);
+            }
+        }
+        self.collect_garbage(&mut changes);
+
+        Ok(changes)
+    }
+
+    /// Insert, replace or delete the row, returning the change, none for
+    /// the deletion of a row which does not exist.
+    fn replace(
+        &mut self,
+        table: &str,
+        uuid: &Uuid,
+        new: Option<Arc<Row>>,
+    ) -> Result<Option<RowChange>, Error> {
+        let rows = self.rows_mut(table)?;
+        let old = match &new {
+            Some(new) => rows.insert(*uuid, new.clone()),
+            None => rows.remove(uuid),
+        };
+        if old.is_none() && new.is_none() {
+            return Ok(None);
+        }
+
+        Ok(Some(RowChange {
+            table: table.to_owned(),
+            uuid: *uuid,
+            old,
+            new,
+        }))
+    }
+
+    fn rows_mut(&mut self, table: &str) -> Result<&mut HashMap<Uuid, Arc<Row>>, Error> {
+        self.tables
+            .get_mut(table)
+            .ok_or_else(|| Error::UnknownTable(table.to_owned()))
+    }
+
+    fn table_schema(&self, table: &str) -> Result<&TableSchema, Error> {
+        self.schema
+            .tables
+            .get(table)
+            .ok_or_else(|| Error::UnknownTable(table.to_owned()))
+    }
+
+    /// Returns the columns of an inserted row, with the default value of
+    /// those the server left out.
+    fn complete(&self, table: &str, uuid: &Uuid, row: &Value) -> Result<Row, Error> {
+        let columns = columns(table, uuid, row)?;
+        let table_schema = self.table_schema(table)?;
+
+        let mut row = Row::new();
+        for (column, schema) in &table_schema.columns {
+            let value = match columns.get(column) {
+                Some(value) => value.clone(),
+                None => default_value(&schema.r#type),
+            };
+            row.insert(column.clone(), value);
+        }
+
+        Ok(row)
+    }
+
+    /// Apply the changed columns of a "modify" update to the row, copying it
+    /// if it is still shared.
+    fn modify(&mut self, table: &str, uuid: &Uuid, diff: &Value) -> Result<RowChange, Error> {
+        let columns = columns(table, uuid, diff)?;
+        let schema = self.schema.clone();
+        let table_schema = schema
+            .tables
+            .get(table)
+            .ok_or_else(|| Error::UnknownTable(table.to_owned()))?;
+        let stored = self.rows_mut(table)?.get_mut(uuid).ok_or_else(|| {
+            Error::UnexpectedResponse(format!("modify of unknown row {uuid} of {table}"))
+        })?;
+        let old = stored.clone();
+        let row = Arc::make_mut(stored);
+
+        for (column, diff) in columns {
+            let column_type = &table_schema
+                .columns
+                .get(column)
+                .ok_or_else(|| {
+                    Error::UnexpectedResponse(format!("unknown column {column:?} of {table}"))
+                })?
+                .r#type;
+
+            let value = file::apply_diff(column_type, row.get(column), diff)
+                .unwrap_or_else(|| default_value(column_type));
+            row.insert(column.clone(), value);
+        }
+
+        Ok(RowChange {
+            table: table.to_owned(),
+            uuid: *uuid,
+            old: Some(old),
+            new: Some(stored.clone()),
+        })
+    }
+
+    /// Drop the rows of the collectable tables which no row of the other
+    /// tables reaches through strong references, as deleted by the update of
+    /// the changes.
+    fn collect_garbage(&mut self, changes: &mut Vec<RowChange>) {
+        if self.collectable.is_empty() {
+            return;
+        }
+
+        let mut reached: HashSet<(&str, Uuid)> = HashSet::new();
+        let mut pending: Vec<(&str, &Uuid, &Arc<Row>)> = self
+            .tables
+            .iter()
+            .filter(|(table, _)| !self.collectable.contains(*table))
+            .flat_map(|(table, rows)| rows.iter().map(move |(uuid, row)| (&**table, uuid, row)))
+            .collect();
+
+        while let Some((table, _, row)) = pending.pop() {
+            for (column, schema) in &self.schema.tables[table].columns {
+                let Some(value) = row.get(column) else {
+                    continue;
+                };
+                for (ref_table, uuid) in schema.r#type.strong_references(value) {
+                    let Some((ref_table, rows)) = self.tables.get_key_value(ref_table) else {
+                        continue;
+                    };
+                    if !self.collectable.contains(ref_table)
+                        || !reached.insert((ref_table.as_str(), uuid))
+                    {
+                        continue;
+                    }
+                    if let Some((uuid, row)) = rows.get_key_value(&uuid) {
+                        pending.push((ref_table, uuid, row));
+                    }
+                }
+            }
+        }
+
+        let reached: HashSet<(String, Uuid)> = reached
+            .into_iter()
+            .map(|(table, uuid)| (table.to_owned(), uuid))
+            .collect();
+        let mut collected = HashMap::new();
+        for table in &self.collectable {
+            if let Some(rows) = self.tables.get_mut(table) {
+                rows.retain(|uuid, row| {
+                    let reached = reached.contains(&(table.clone(), *uuid));
+                    if !reached {
+                        collected.insert((table.clone(), *uuid), row.clone());
+                    }
+                    reached
+                });
+            }
+        }
+
+        if collected.is_empty() {
+            return;
+        }
+
+        // NOTE(mnaser): A row inserted and collected by the same update was never
+        //               seen by the application, so it is left out of the changes.
+        for change in changes.iter_mut() {
+            if collected
+                .remove(&(change.table.clone(), change.uuid))
+                .is_some()
+            {
+                change.new = None;
+            }
+        }
+        changes.retain(|change| change.old.is_some() || change.new.is_some());
+        changes.extend(collected.into_iter().map(|((table, uuid), row)| RowChange {
+            table,
+            uuid,
+            old: Some(row),
+            new: None,
+        }));
+    }
+}
+
+/// Returns the columns of a row of an update.
+fn columns<'a>(
+    table: &str,
+    uuid: &Uuid,
+    row: &'a Value,
+) -> Result<&'a serde_json::Map<String, Value>, Error> {
+    row.as_object()
+        .ok_or_else(|| Error::UnexpectedResponse(format!("row {uuid} of {table} is not an object")))
+}
+
+/// Convert a row of the replica to its table struct.
+fn typed<T: OvsdbTable>(table: &str, uuid: &Uuid, row: &Row) -> Result<T, Error> {
+    let mut map: HashMap<String, Value> = row.clone().into_iter().collect();
+    map.insert("_uuid".to_owned(), json!(["uuid", uuid]));
+
+    T::from_map(&map).map_err(|source| Error::InvalidRowUpdate {
+        table: table.to_owned(),
+        uuid: *uuid,
+        source: Box::new(source),
+    })
+}
+
+/// Returns whether a column of the table holds strong references to the
+/// other table.
+fn strongly_references(table: &TableSchema, other: &str) -> bool {
+    table.columns.values().any(|column| {
+        let column_type = &column.r#type;
+        [Some(&column_type.key), column_type.value.as_ref()]
+            .into_iter()
+            .flatten()
+            .any(|base| base.is_strong() && base.ref_table.as_deref() == Some(other))
+    })
+}
+
+/// Returns the value of a column the server leaves out of inserted rows: an
+/// empty set or map, or the default atom of its type.
+fn default_value(column_type: &ColumnType) -> Value {
+    if column_type.is_map() {
+        return json!(["map", []]);
+    }
+    if column_type.min == 0 {
+        return json!(["set", []]);
+    }
+
+    match column_type.key.r#type {
+        AtomicType::Integer => json!(0),
+        AtomicType::Real => json!(0.0),
+        AtomicType::Boolean => json!(false),
+        AtomicType::String => json!(""),
+        AtomicType::Uuid => json!(["uuid", Uuid::nil()]),
+    }
+}
@@ -1,3 +1,27 @@
+pub mod backup;
+pub mod bus;
+pub mod cache;
+mod client;
+pub mod cluster;
+pub mod convert;
+pub mod crud;
+pub mod dump;
+mod error;
+pub mod file;
+pub mod idl;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod monitor;
+pub mod pool;
+pub mod registry;
 pub mod rpc;
 pub mod schema;
-mod transports;
+pub mod session;
+pub mod snapshot;
+mod trace;
+pub mod transact;
+pub mod transports;
+pub mod validation;
+
+pub use client::{OvsdbClient, TimedClient};
+pub use error::{Error, OvsdbError, OvsdbErrorKind};
@@ -0,0 +1,243 @@
+//! Counters and histograms shared by every client of the process, rendered
+//! in the Prometheus text format.
+//!
+//! The metrics are only gathered with the `metrics` feature, an application
+//! exposes them by serving [`render`] on its own endpoint, typically as
+//! `/metrics`.
+
+use std::{collections::BTreeMap, fmt::Write, sync::Mutex, time::Duration};
+
+/// The upper bounds of the request latency buckets, in seconds
+const BUCKETS: [f64; 12] = [
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+];
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+
+    count: u64,
+
+    sum: f64,
+}
+
+struct Registry {
+    requests: BTreeMap<&'static str, u64>,
+
+    request_errors: BTreeMap<&'static str, u64>,
+
+    request_duration: BTreeMap<&'static str, Histogram>,
+
+    sent_bytes: u64,
+
+    received_bytes: u64,
+
+    decode_errors: u64,
+
+    connections: u64,
+
+    connection_failures: u64,
+
+    disconnections: u64,
+
+    updates: BTreeMap<String, u64>,
+
+    updated_rows: BTreeMap<String, u64>,
+}
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    requests: BTreeMap::new(),
+    request_errors: BTreeMap::new(),
+    request_duration: BTreeMap::new(),
+    sent_bytes: 0,
+    received_bytes: 0,
+    decode_errors: 0,
+    connections: 0,
+    connection_failures: 0,
+    disconnections: 0,
+    updates: BTreeMap::new(),
+    updated_rows: BTreeMap::new(),
+});
+
+fn registry() -> std::sync::MutexGuard<'static, Registry> {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+pub(crate) fn request(method: &'static str, duration: Duration, failed: bool) {
+    let mut registry = registry();
+    *registry.requests.entry(method).or_default() += 1;
+    if failed {
+        *registry.request_errors.entry(method).or_default() += 1;
+    }
+
+    let seconds = duration.as_secs_f64();
+    let histogram = registry.request_duration.entry(method).or_default();
+    for (bucket, bound) in histogram.buckets.iter_mut().zip(BUCKETS) {
+        if seconds <= bound {
+            *bucket += 1;
+        }
+    }
+    histogram.count += 1;
+    histogram.sum += seconds;
+}
+
+pub(crate) fn sent(bytes: usize) {
+    registry().sent_bytes += bytes as u64;
+}
+
+pub(crate) fn received(bytes: usize) {
+    registry().received_bytes += bytes as u64;
+}
+
+pub(crate) fn decode_error() {
+    registry().decode_errors += 1;
+}
+
+pub(crate) fn connect(failed: bool) {
+    let mut registry = registry();
+    if failed {
+        registry.connection_failures += 1;
+    } else {
+        registry.connections += 1;
+    }
+}
+
+pub(crate) fn disconnected() {
+    registry().disconnections += 1;
+}
+
+pub(crate) fn update(table: &str, rows: usize) {
+    let mut registry = registry();
+    *registry.updates.entry(table.to_owned()).or_default() += 1;
+    *registry.updated_rows.entry(table.to_owned()).or_default() += rows as u64;
+}
+
+fn header(output: &mut String, name: &str, r#type: &str, help: &str) {
+    let _ = writeln!(output, "# HELP {name} {help}");
+    let _ = writeln!(output, "# TYPE {name} {type}");
+}
+
+fn counter(output: &mut String, name: &str, help: &str, value: u64) {
+    header(output, name, "counter", help);
+    let _ = writeln!(output, "{name} {value}");
+}
+
+// NOTE(mnaser): Label values are method and table names, which are identifiers
+//               and never need to be escaped.
+fn counters<K: AsRef<str>>(
+    output: &mut String,
+    name: &str,
+    help: &str,
+    label: &str,
+    values: &BTreeMap<K, u64>,
+) {
+    header(output, name, "counter", help);
+    for (key, value) in values {
+        let _ = writeln!(output, "{name}{{{label}=\"{}\"}} {value}", key.as_ref());
+    }
+}
+
+/// Render the metrics in the Prometheus text exposition format.
+pub fn render() -> String {
+    let registry = registry();
+    let mut output = String::new();
+
+    counters(
+        &mut output,
+        "ovsdb_client_requests_total",
+        "Requests sent, by method.",
+        "method",
+        &registry.requests,
+    );
+    counters(
+        &mut output,
+        "ovsdb_client_request_errors_total",
+        "Requests which failed, by method.",
+        "method",
+        &registry.request_errors,
+    );
+
+    let name = "ovsdb_client_request_duration_seconds";
+    header(
+        &mut output,
+        name,
+        "histogram",
+        "Time until the reply of a request was received, by method.",
+    );
+    for (method, histogram) in &registry.request_duration {
+        for (bound, count) in BUCKETS.iter().zip(histogram.buckets) {
+            let _ = writeln!(
+                output,
+                "{name}_bucket{{method=\"{method}\",le=\"{bound}\"}} {count}"
+            );
+        }
+        let _ = writeln!(
+            output,
+            "{name}_bucket{{method=\"{method}\",le=\"+Inf\"}} {}",
+            histogram.count
+        );
+        let _ = writeln!(
+            output,
+            "{name}_sum{{method=\"{method}\"}} {}",
+            histogram.sum
+        );
+        let _ = writeln!(
+            output,
+            "{name}_count{{method=\"{method}\"}} {}",
+            histogram.count
+        );
+    }
+
+    counter(
+        &mut output,
+        "ovsdb_client_sent_bytes_total",
+        "Bytes of the messages sent.",
+        registry.sent_bytes,
+    );
+    counter(
+        &mut output,
+        "ovsdb_client_received_bytes_total",
+        "Bytes of the messages received.",
+        registry.received_bytes,
+    );
+    counter(
+        &mut output,
+        "ovsdb_client_decode_errors_total",
+        "Messages received which were not valid JSON.",
+        registry.decode_errors,
+    );
+    counter(
+        &mut output,
+        "ovsdb_client_connections_total",
+        "Connections established, including reconnections.",
+        registry.connections,
+    );
+    counter(
+        &mut output,
+        "ovsdb_client_connection_failures_total",
+        "Connection attempts which failed.",
+        registry.connection_failures,
+    );
+    counter(
+        &mut output,
+        "ovsdb_client_disconnections_total",
+        "Connections which stopped, closed by the server or failed.",
+        registry.disconnections,
+    );
+    counters(
+        &mut output,
+        "ovsdb_client_updates_total",
+        "Update notifications received, by table.",
+        "table",
+        &registry.updates,
+    );
+    counters(
+        &mut output,
+        "ovsdb_client_updated_rows_total",
+        "Rows updated by notifications, by table.",
+        "table",
+        &registry.updated_rows,
+    );
+
+    output
+}
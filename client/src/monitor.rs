@@ -0,0 +1,193 @@
+use crate::{
+    Error,
+    schema::{RowUpdate, UpdateNotification},
+};
+use futures_util::{
+    Stream, StreamExt,
+    stream::{self, BoxStream},
+};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// The notifications of a single monitor, returned by
+/// `OvsdbClient::monitor_updates` and `OvsdbClient::monitor_cond_updates`
+///
+/// It ends once the monitor is canceled, by the client or the server, or the
+/// connection is lost.
+pub type Updates<N> = BoxStream<'static, Result<N, Error>>;
+
+/// Options of the streams of "update" notifications, such as the
+/// subscriptions made with `subscribe_to_method("update")`
+pub trait UpdateStreamExt<T, E>: Stream<Item = Result<UpdateNotification<T>, E>> {
+    /// Deliver the notifications at most once per period, merging those
+    /// received in between per monitor.
+    ///
+    /// The first notification after a quiet period is delivered at once,
+    /// those following it within the period are merged into one, in which
+    /// each row is updated once:
+    ///
+    /// * `new` is the row after the last of its updates
+    /// * `old` holds the columns of the row before the burst, as the `old`
+    ///   of a modification only holds those it changed, the columns of each
+    ///   update adding to those of the updates before it
+    /// * a row both inserted and deleted within the period is left out
+    ///
+    /// Errors are delivered in order, after the notifications merged before
+    /// them.
+    fn coalesce(self, period: Duration) -> BoxStream<'static, Result<UpdateNotification<T>, E>>
+    where
+        Self: Sized + Send + 'static,
+        T: OldRow + Send + 'static,
+        E: Send + 'static,
+    {
+        let state = Coalesce {
+            updates: self.boxed(),
+            period,
+            ready_at: Instant::now(),
+            merged: Vec::new(),
+            ready: VecDeque::new(),
+            done: false,
+        };
+
+        stream::unfold(state, Coalesce::next).boxed()
+    }
+}
+
+impl<S, T, E> UpdateStreamExt<T, E> for S where S: Stream<Item = Result<UpdateNotification<T>, E>> {}
+
+/// The rows of "update" notifications, whose `old` columns can be merged
+/// when coalescing them
+pub trait OldRow {
+    /// Add the columns of the `old` row of a later update which this one,
+    /// of an earlier update, does not hold.
+    fn merge_later(&mut self, later: Self);
+}
+
+impl OldRow for Value {
+    fn merge_later(&mut self, later: Self) {
+        if let (Value::Object(columns), Value::Object(later)) = (self, later) {
+            for (column, value) in later {
+                columns.entry(column).or_insert(value);
+            }
+        }
+    }
+}
+
+impl<V> OldRow for HashMap<String, V> {
+    fn merge_later(&mut self, later: Self) {
+        for (column, value) in later {
+            self.entry(column).or_insert(value);
+        }
+    }
+}
+
+struct Coalesce<T, E> {
+    updates: BoxStream<'static, Result<UpdateNotification<T>, E>>,
+
+    period: Duration,
+
+    /// When the next notification can be delivered
+    ready_at: Instant,
+
+    /// The notifications merged since the last delivery, one per monitor
+    merged: Vec<UpdateNotification<T>>,
+
+    /// What is left to deliver, in order
+    ready: VecDeque<Result<UpdateNotification<T>, E>>,
+
+    done: bool,
+}
+
+impl<T: OldRow, E> Coalesce<T, E> {
+    async fn next(mut self) -> Option<(Result<UpdateNotification<T>, E>, Self)> {
+        loop {
+            if let Some(item) = self.ready.pop_front() {
+                return Some((item, self));
+            }
+            if self.done {
+                return None;
+            }
+
+            let received = if self.merged.is_empty() {
+                self.updates.next().await
+            } else {
+                tokio::select! {
+                    received = self.updates.next() => received,
+                    _ = tokio::time::sleep_until(self.ready_at) => {
+                        self.flush();
+                        continue;
+                    }
+                }
+            };
+
+            match received {
+                Some(Ok(notification)) => {
+                    self.merge(notification);
+                    if Instant::now() >= self.ready_at {
+                        self.flush();
+                    }
+                }
+                Some(Err(error)) => {
+                    self.flush();
+                    self.ready.push_back(Err(error));
+                }
+                None => {
+                    self.flush();
+                    self.done = true;
+                }
+            }
+        }
+    }
+
+    fn merge(&mut self, notification: UpdateNotification<T>) {
+        let Some(merged) = self
+            .merged
+            .iter_mut()
+            .find(|merged| merged.id == notification.id)
+        else {
+            self.merged.push(notification);
+            return;
+        };
+
+        for (table, rows) in notification.message {
+            let merged = merged.message.entry(table).or_default();
+            for (uuid, update) in rows {
+                match merged.remove(&uuid) {
+                    Some(earlier) => {
+                        let old = match (earlier.old, update.old) {
+                            (Some(mut old), Some(later)) => {
+                                old.merge_later(later);
+                                Some(old)
+                            }
+                            (old, _) => old,
+                        };
+                        let update = RowUpdate {
+                            old,
+                            new: update.new,
+                        };
+                        if update.old.is_some() || update.new.is_some() {
+                            merged.insert(uuid, update);
+                        }
+                    }
+                    None => {
+                        merged.insert(uuid, update);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Deliver the merged notifications, starting a new period.
+    fn flush(&mut self) {
+        if self.merged.is_empty() {
+            return;
+        }
+
+        self.ready.extend(self.merged.drain(..).map(Ok));
+        self.ready_at = Instant::now() + self.period;
+    }
+}
@@ -0,0 +1,199 @@
+//! A pool of connections to the same servers, for tools making many
+//! independent requests, such as audit or reporting jobs.
+
+use crate::{
+    Error, OvsdbClient,
+    rpc::{ConnectOptions, RpcClient},
+};
+use std::{
+    fmt,
+    ops::Deref,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// A connection waiting in the pool to be checked out
+struct Idle {
+    client: OvsdbClient,
+
+    since: Instant,
+}
+
+/// The state of a pool shared by its clones and its checked out clients
+struct Shared {
+    idle: Mutex<Vec<Idle>>,
+
+    /// A permit per connection, so that at most `size` are checked out
+    slots: Arc<Semaphore>,
+
+    /// The remote the next connection is attempted to first
+    next: AtomicUsize,
+}
+
+/// Up to a number of connections to the same remotes, which are checked out
+/// for a while and then given back to the pool to be reused
+///
+/// The connections are made when checked out while none is idle, attempting
+/// each remote in turn. A connection which was lost, or which fails its
+/// health check, is dropped and replaced by a new one.
+#[derive(Clone)]
+pub struct OvsdbPool {
+    remotes: Arc<[String]>,
+
+    options: ConnectOptions,
+
+    health_check: Option<Duration>,
+
+    shared: Arc<Shared>,
+}
+
+impl OvsdbPool {
+    /// Returns a pool of up to `size` connections to the remotes, such as
+    /// `tcp:127.0.0.1:6641` or `unix:/var/run/ovn/ovnnb_db.sock`.
+    ///
+    /// # Panics
+    ///
+    /// If there is no remote, or if the size is zero.
+    pub fn new(remotes: impl IntoIterator<Item = impl Into<String>>, size: usize) -> Self {
+        let remotes: Arc<[String]> = remotes.into_iter().map(Into::into).collect();
+        assert!(!remotes.is_empty(), "a pool needs at least a remote");
+        assert!(size > 0, "a pool needs at least a connection");
+
+        OvsdbPool {
+            remotes,
+            options: ConnectOptions::default(),
+            health_check: None,
+            shared: Arc::new(Shared {
+                idle: Mutex::new(Vec::new()),
+                slots: Arc::new(Semaphore::new(size)),
+                next: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Make the connections with the options, which should not set a
+    /// reconnect backoff so that the other remotes get attempted.
+    pub fn with_options(self, options: ConnectOptions) -> Self {
+        OvsdbPool { options, ..self }
+    }
+
+    /// Send an "echo" request over the connections which were idle for the
+    /// interval before checking them out, replacing those which fail it.
+    pub fn with_health_check(self, interval: Duration) -> Self {
+        OvsdbPool {
+            health_check: Some(interval),
+            ..self
+        }
+    }
+
+    /// Check out a connection, waiting for one to be given back if all of
+    /// them are checked out.
+    pub async fn get(&self) -> Result<PooledClient, Error> {
+        // NOTE(mnaser): The semaphore is never closed, and it queues the callers fairly.
+        let permit = self.shared.slots.clone().acquire_owned().await.unwrap();
+
+        let client = match self.idle().await {
+            Some(client) => client,
+            None => self.connect().await?,
+        };
+
+        Ok(PooledClient {
+            client: Some(client),
+            shared: self.shared.clone(),
+            _permit: permit,
+        })
+    }
+
+    /// Returns the number of connections waiting in the pool.
+    pub fn idle_count(&self) -> usize {
+        self.shared.idle.lock().unwrap().len()
+    }
+
+    /// Returns the most recently used idle connection which is still alive.
+    async fn idle(&self) -> Option<OvsdbClient> {
+        loop {
+            let Idle { client, since } = self.shared.idle.lock().unwrap().pop()?;
+            if !client.is_connected() {
+                continue;
+            }
+
+            match self.health_check {
+                Some(interval) if since.elapsed() >= interval => {
+                    if client.echo(Vec::new()).await.is_ok() {
+                        return Some(client);
+                    }
+                }
+                _ => return Some(client),
+            }
+        }
+    }
+
+    /// Connect to the remotes in turn, starting after the one of the
+    /// previous connection, until one succeeds.
+    async fn connect(&self) -> Result<OvsdbClient, Error> {
+        let start = self.shared.next.fetch_add(1, Ordering::Relaxed);
+
+        let mut error = None;
+        for offset in 0..self.remotes.len() {
+            let remote = &self.remotes[(start + offset) % self.remotes.len()];
+            match self.options.connect(remote).await {
+                Ok(client) => return Ok(client),
+                Err(err) => error = Some(err),
+            }
+        }
+
+        Err(error.unwrap())
+    }
+}
+
+impl fmt::Debug for OvsdbPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OvsdbPool")
+            .field("remotes", &self.remotes)
+            .field("idle", &self.idle_count())
+            .finish()
+    }
+}
+
+/// A connection checked out of an `OvsdbPool`, given back to it when dropped
+/// unless it was lost
+pub struct PooledClient {
+    client: Option<OvsdbClient>,
+
+    shared: Arc<Shared>,
+
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Deref for PooledClient {
+    type Target = OvsdbClient;
+
+    fn deref(&self) -> &OvsdbClient {
+        self.client.as_ref().unwrap()
+    }
+}
+
+impl fmt::Debug for PooledClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PooledClient").field(&**self).finish()
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        let Some(client) = self.client.take() else {
+            return;
+        };
+
+        if client.is_connected() {
+            self.shared.idle.lock().unwrap().push(Idle {
+                client,
+                since: Instant::now(),
+            });
+        }
+    }
+}
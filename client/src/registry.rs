@@ -0,0 +1,81 @@
+use crate::{
+    Error,
+    schema::{MonitorRequest, TableUpdate, TableUpdateRows, typed_rows},
+};
+use ovsdb_schema::OvsdbTable;
+use serde_json::Value;
+use std::collections::HashMap;
+
+type Handler = Box<dyn FnMut(&str, &TableUpdateRows<Value>) -> Result<(), Error> + Send>;
+
+/// Handlers of the updates of tables, registered by table name.
+///
+/// Each handler receives the rows of its table converted to its
+/// `OvsdbTable`, so that the tables of interest can be decided at runtime,
+/// such as by plugins, while every update is still decoded into the right
+/// struct.
+#[derive(Default)]
+pub struct TableRegistry {
+    handlers: HashMap<String, Handler>,
+}
+
+impl TableRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the handler of the updates of the table of `T`, replacing
+    /// the one registered before for the same table.
+    pub fn register<T, F>(&mut self, mut handler: F)
+    where
+        T: OvsdbTable,
+        F: FnMut(TableUpdateRows<T>) + Send + 'static,
+    {
+        self.handlers.insert(
+            T::TABLE.to_owned(),
+            Box::new(move |table, rows| {
+                handler(typed_rows(table, rows)?);
+                Ok(())
+            }),
+        );
+    }
+
+    /// Forget the handler of the table, returning whether there was one.
+    pub fn unregister(&mut self, table: &str) -> bool {
+        self.handlers.remove(table).is_some()
+    }
+
+    /// Returns whether a handler is registered for the table.
+    pub fn contains(&self, table: &str) -> bool {
+        self.handlers.contains_key(table)
+    }
+
+    /// Returns the names of the registered tables, in no particular order.
+    pub fn tables(&self) -> impl Iterator<Item = &str> {
+        self.handlers.keys().map(String::as_str)
+    }
+
+    /// The requests monitoring every column of each registered table.
+    pub fn monitor_requests(&self) -> HashMap<String, MonitorRequest> {
+        self.handlers
+            .keys()
+            .map(|table| (table.clone(), MonitorRequest::default()))
+            .collect()
+    }
+
+    /// Hand the rows of each table of the update to its handler, the tables
+    /// without one being ignored.
+    ///
+    /// The first table whose rows cannot be converted fails the dispatch
+    /// with `Error::InvalidRowUpdate`, the handlers of the tables before it
+    /// having been called already.
+    pub fn dispatch(&mut self, update: &TableUpdate<Value>) -> Result<(), Error> {
+        for (table, rows) in update {
+            if let Some(handler) = self.handlers.get_mut(table) {
+                handler(table, rows)?;
+            }
+        }
+
+        Ok(())
+    }
+}
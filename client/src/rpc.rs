@@ -1,27 +1,55 @@
 use crate::{
-    schema::{DatabaseSchema, MonitorRequest, TableUpdate},
-    transports::{ipc, tcp},
+    Error, OvsdbClient, OvsdbErrorKind,
+    schema::{
+        DatabaseSchema, MonitorCondRequest, MonitorCondSinceReply, MonitorRequest, TableUpdate,
+        TableUpdate2,
+    },
+    session::OvsdbSession,
+    trace,
+    transports::{
+        self, OvsdbTransport, Transport,
+        ipc::UnixTransport,
+        stream,
+        tap::{Frame, Tap},
+        tcp::{TcpOptions, TcpTransport},
+    },
 };
-use jsonrpsee::{async_client::ClientBuilder, core::client::SubscriptionClientT, proc_macros::rpc};
-use std::{collections::HashMap, path::Path};
-use tokio::net::ToSocketAddrs;
+use futures_util::{StreamExt, stream::BoxStream};
+use jsonrpsee::{
+    async_client::ClientBuilder,
+    core::client::{ClientT, TransportReceiverT, TransportSenderT},
+    rpc_params,
+};
+use serde_json::Value;
+use socket2::TcpKeepalive;
+use std::{
+    collections::HashMap, future::Future, net::IpAddr, path::Path, sync::Arc, time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::ToSocketAddrs,
+    sync::broadcast::{self, error::RecvError},
+};
+use uuid::Uuid;
 
-#[rpc(client)]
-pub trait Rpc {
+/// The methods of RFC 7047 and its extensions, implemented for every
+/// JSON-RPC client
+pub trait RpcClient {
     /// 4.1.1.  List Databases
     ///
     /// This operation retrieves an array whose elements are the names of the
     /// databases that can be accessed over this management protocol
     /// connection.
-    #[method(name = "list_dbs")]
-    async fn list_databases(&self) -> Result<Vec<String>, ErrorObjectOwned>;
+    fn list_databases(&self) -> impl Future<Output = Result<Vec<String>, Error>> + Send;
 
     /// 4.1.2.  Get Schema
     ///
     /// This operation retrieves a <database-schema> that describes hosted
     /// database <db-name>.
-    #[method(name = "get_schema")]
-    async fn get_schema(&self, db_name: &str) -> Result<DatabaseSchema, ErrorObjectOwned>;
+    fn get_schema(
+        &self,
+        db_name: &str,
+    ) -> impl Future<Output = Result<DatabaseSchema, Error>> + Send;
 
     /// 4.1.5.  Monitor
     ///
@@ -29,38 +57,595 @@ pub trait Rpc {
     /// of tables within an OVSDB database by requesting notifications of
     /// changes to those tables and by receiving the complete initial state
     /// of a table or a subset of a table.
-    #[method(name = "monitor")]
-    async fn monitor(
+    ///
+    /// The id of the monitor can be any JSON value, such as an array telling
+    /// the database and the purpose of the monitor, and is the first
+    /// parameter of its notifications.
+    ///
+    /// The notifications of a monitor whose id is a string or a number are
+    /// taken for those of a JSON-RPC subscription by jsonrpsee, and never
+    /// reach `subscribe_to_method`. Such monitors need a null or array id, or
+    /// their own stream from `OvsdbClient::monitor_updates`.
+    fn monitor(
         &self,
         db_name: &str,
-        matcher: Option<&str>,
+        id: Value,
         requests: HashMap<String, MonitorRequest>,
-    ) -> Result<TableUpdate<serde_json::Value>, ErrorObjectOwned>;
+    ) -> impl Future<Output = Result<TableUpdate<Value>, Error>> + Send;
+
+    /// Monitor Cond (RFC 7047 extension)
+    ///
+    /// Like "monitor", but only the rows matching the conditions of each
+    /// table are reported, in "update2" notifications whose "modify" updates
+    /// hold the changed columns only.
+    fn monitor_cond(
+        &self,
+        db_name: &str,
+        id: Value,
+        requests: HashMap<String, MonitorCondRequest>,
+    ) -> impl Future<Output = Result<TableUpdate2<Value>, Error>> + Send;
+
+    /// Monitor Cond Change (RFC 7047 extension)
+    ///
+    /// Replace the conditions of the tables of a "monitor_cond" monitor,
+    /// which then reports the rows matching the new conditions only, and
+    /// give it a new id, which can be the same as the old one. Only the
+    /// conditions of the requests are used, the columns cannot change.
+    fn monitor_cond_change(
+        &self,
+        id: Value,
+        new_id: Value,
+        requests: HashMap<String, MonitorCondRequest>,
+    ) -> impl Future<Output = Result<Value, Error>> + Send;
+
+    /// Monitor Cond Since (RFC 7047 extension)
+    ///
+    /// Like "monitor_cond", but given the id of the last transaction a
+    /// client has seen, such as one restoring its replica, the server only
+    /// sends the changes made after it if it still has them. The monitor
+    /// sends "update3" notifications, which tell the id of their
+    /// transaction.
+    fn monitor_cond_since(
+        &self,
+        db_name: &str,
+        id: Value,
+        requests: HashMap<String, MonitorCondRequest>,
+        last_id: Option<Uuid>,
+    ) -> impl Future<Output = Result<MonitorCondSinceReply<Value>, Error>> + Send;
+
+    /// Set Database Change Awareness (RFC 7047 extension)
+    ///
+    /// With change awareness enabled, the server keeps the connection open
+    /// when a database schema is converted and cancels its monitors with a
+    /// "monitor_canceled" notification instead.
+    fn set_db_change_aware(&self, aware: bool)
+    -> impl Future<Output = Result<Value, Error>> + Send;
+
+    /// Convert (RFC 7047 extension)
+    ///
+    /// Convert the database to the schema, given as it is read from an
+    /// `.ovsschema` file, keeping the rows and the columns which still
+    /// exist. The server cancels the monitors of the database, and closes
+    /// the connections which are not aware of database changes.
+    fn convert(
+        &self,
+        db_name: &str,
+        schema: &Value,
+    ) -> impl Future<Output = Result<Value, Error>> + Send;
 
     /// 4.1.11.  Echo
     ///
     /// The "echo" method can be used by both clients and servers to verify
     /// the liveness of a database connection.  It MUST be implemented by
     /// both clients and servers.
-    #[method(name = "echo")]
-    async fn echo(
+    fn echo(&self, data: Vec<Value>) -> impl Future<Output = Result<Vec<Value>, Error>> + Send;
+
+    /// Returns whether the server hosts the database, as listed by
+    /// "list_dbs".
+    fn has_database(&self, db_name: &str) -> impl Future<Output = Result<bool, Error>> + Send;
+
+    /// Returns whether the server implements the method, such as to fall
+    /// back from "monitor_cond_since" to "monitor_cond" and then "monitor"
+    /// with older servers.
+    ///
+    /// The method is probed with a request without parameters, which the
+    /// server rejects either as invalid or, if it does not implement the
+    /// method, as unknown.
+    fn server_supports(&self, method: Method) -> impl Future<Output = Result<bool, Error>> + Send;
+}
+
+/// The monitor methods and the extensions of RFC 7047, which older servers
+/// may not implement, for `RpcClient::server_supports`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Method {
+    /// "monitor", of RFC 7047 itself
+    Monitor,
+
+    /// "monitor_cond", reporting the rows matching conditions only
+    MonitorCond,
+
+    /// "monitor_cond_change", changing the conditions of a monitor
+    MonitorCondChange,
+
+    /// "monitor_cond_since", resuming a monitor after a transaction
+    MonitorCondSince,
+
+    /// "set_db_change_aware", keeping connections across schema conversions
+    SetDbChangeAware,
+
+    /// "convert", converting a database to a new schema
+    Convert,
+}
+
+impl Method {
+    /// Returns the name of the method in requests.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Monitor => "monitor",
+            Method::MonitorCond => "monitor_cond",
+            Method::MonitorCondChange => "monitor_cond_change",
+            Method::MonitorCondSince => "monitor_cond_since",
+            Method::SetDbChangeAware => "set_db_change_aware",
+            Method::Convert => "convert",
+        }
+    }
+}
+
+impl<C: ClientT + Sync> RpcClient for C {
+    async fn list_databases(&self) -> Result<Vec<String>, Error> {
+        let request = self.request("list_dbs", rpc_params![]);
+        Ok(trace::request("list_dbs", None, request).await?)
+    }
+
+    async fn get_schema(&self, db_name: &str) -> Result<DatabaseSchema, Error> {
+        let request = self.request("get_schema", rpc_params![db_name]);
+        Ok(trace::request("get_schema", Some(db_name), request).await?)
+    }
+
+    async fn monitor(
+        &self,
+        db_name: &str,
+        id: Value,
+        requests: HashMap<String, MonitorRequest>,
+    ) -> Result<TableUpdate<Value>, Error> {
+        let request = self.request("monitor", rpc_params![db_name, id, requests]);
+        Ok(trace::request("monitor", Some(db_name), request).await?)
+    }
+
+    async fn monitor_cond(
+        &self,
+        db_name: &str,
+        id: Value,
+        requests: HashMap<String, MonitorCondRequest>,
+    ) -> Result<TableUpdate2<Value>, Error> {
+        let request = self.request("monitor_cond", rpc_params![db_name, id, requests]);
+        Ok(trace::request("monitor_cond", Some(db_name), request).await?)
+    }
+
+    async fn monitor_cond_change(
+        &self,
+        id: Value,
+        new_id: Value,
+        requests: HashMap<String, MonitorCondRequest>,
+    ) -> Result<Value, Error> {
+        let request = self.request("monitor_cond_change", rpc_params![id, new_id, requests]);
+        Ok(trace::request("monitor_cond_change", None, request).await?)
+    }
+
+    async fn monitor_cond_since(
+        &self,
+        db_name: &str,
+        id: Value,
+        requests: HashMap<String, MonitorCondRequest>,
+        last_id: Option<Uuid>,
+    ) -> Result<MonitorCondSinceReply<Value>, Error> {
+        // NOTE(mnaser): The nil UUID stands for no transaction, so that the server
+        //               sends the initial state of the monitor.
+        let last_id = last_id.unwrap_or_else(Uuid::nil);
+        let request = self.request(
+            "monitor_cond_since",
+            rpc_params![db_name, id, requests, last_id],
+        );
+        Ok(trace::request("monitor_cond_since", Some(db_name), request).await?)
+    }
+
+    async fn set_db_change_aware(&self, aware: bool) -> Result<Value, Error> {
+        let request = self.request("set_db_change_aware", rpc_params![aware]);
+        Ok(trace::request("set_db_change_aware", None, request).await?)
+    }
+
+    async fn convert(&self, db_name: &str, schema: &Value) -> Result<Value, Error> {
+        let request = self.request("convert", rpc_params![db_name, schema]);
+        Ok(trace::request("convert", Some(db_name), request).await?)
+    }
+
+    async fn echo(&self, data: Vec<Value>) -> Result<Vec<Value>, Error> {
+        let request = self.request("echo", rpc_params![data]);
+        Ok(trace::request("echo", None, request).await?)
+    }
+
+    async fn has_database(&self, db_name: &str) -> Result<bool, Error> {
+        let databases = self.list_databases().await?;
+        Ok(databases.iter().any(|database| database == db_name))
+    }
+
+    async fn server_supports(&self, method: Method) -> Result<bool, Error> {
+        let request = self.request::<Value, _>(method.as_str(), rpc_params![]);
+        match trace::request(method.as_str(), None, request).await {
+            Ok(_) => Ok(true),
+            Err(error) => match Error::from(error) {
+                error if error.ovsdb_kind() == Some(&OvsdbErrorKind::UnknownMethod) => Ok(false),
+                Error::Rpc(_) => Ok(true),
+                error => Err(error),
+            },
+        }
+    }
+}
+
+/// A change of the state of the connections made with a [`ConnectOptions`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// A connection is being attempted
+    Connecting,
+
+    /// The connection is established to the remote, such as
+    /// `tcp:127.0.0.1:6641`
+    Connected { remote: String },
+
+    /// The connection attempt failed, or the established connection was lost
+    Disconnected { reason: String },
+
+    /// The next connection attempt is made after the delay
+    BackingOff { delay: Duration },
+
+    /// The server canceled a monitor, as it does when the schema of its
+    /// database is converted for a client aware of database changes
+    SchemaChanged,
+
+    /// A lock held by the connection was lost along with it, or stolen by
+    /// another client
+    LockLost { lock: String },
+
+    /// A lock which a session requested again after reconnecting was granted
+    LockReacquired { lock: String },
+}
+
+/// The time after which requests fail with `Error::TimedOut`, unless the
+/// options set another one
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The number of requests of a client waiting for their reply at once,
+/// unless the options set another one
+pub const DEFAULT_MAX_IN_FLIGHT: usize = 256;
+
+/// Settings of a connection, for what the `connect_*` functions leave to
+/// their defaults
+///
+/// Cloning the options keeps sending the events of their connections to the
+/// same streams.
+#[derive(Clone)]
+pub struct ConnectOptions {
+    pub(crate) tap: Option<Tap>,
+
+    pub(crate) inactivity_probe: Option<Duration>,
+
+    pub(crate) max_message_size: Option<usize>,
+
+    pub(crate) tcp: TcpOptions,
+
+    request_timeout: Duration,
+
+    max_in_flight: usize,
+
+    pub(crate) reconnect_backoff: Option<(Duration, Duration)>,
+
+    events: broadcast::Sender<ConnectionEvent>,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        ConnectOptions {
+            tap: None,
+            inactivity_probe: None,
+            max_message_size: None,
+            tcp: TcpOptions::default(),
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            reconnect_backoff: None,
+            events: broadcast::channel(64).0,
+        }
+    }
+}
+
+impl ConnectOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call the function with every message written to or read from the
+    /// server, such as to print the traffic or record it for a bug report.
+    pub fn with_tap(self, tap: impl Fn(&Frame) + Send + Sync + 'static) -> Self {
+        ConnectOptions {
+            tap: Some(Arc::new(tap)),
+            ..self
+        }
+    }
+
+    /// Send an "echo" request once the server has been silent for the
+    /// interval, and fail the connection with `Error::Inactive` if it stays
+    /// silent for another interval, as the `inactivity_probe` of Open
+    /// vSwitch does.
+    ///
+    /// The client is then disconnected, and a session connects again.
+    pub fn with_inactivity_probe(self, interval: Duration) -> Self {
+        ConnectOptions {
+            inactivity_probe: Some(interval),
+            ..self
+        }
+    }
+
+    /// Fail the connection once the server sends a message longer than the
+    /// given number of bytes, rather than buffering it whatever its size.
+    ///
+    /// The initial rows of a monitor come in a single message, so the limit
+    /// must leave room for the largest database the client monitors.
+    pub fn with_max_message_size(self, bytes: usize) -> Self {
+        ConnectOptions {
+            max_message_size: Some(bytes),
+            ..self
+        }
+    }
+
+    /// Send TCP keepalives once a connection has been idle for `idle`, every
+    /// `interval` after that, and drop it after `retries` of them went
+    /// unanswered.
+    pub fn with_tcp_keepalive(self, idle: Duration, interval: Duration, retries: u32) -> Self {
+        let keepalive = TcpKeepalive::new()
+            .with_time(idle)
+            .with_interval(interval)
+            .with_retries(retries);
+        ConnectOptions {
+            tcp: TcpOptions {
+                keepalive: Some(keepalive),
+                ..self.tcp
+            },
+            ..self
+        }
+    }
+
+    /// Set `TCP_NODELAY` on TCP connections, sending the messages without
+    /// waiting to coalesce them.
+    pub fn with_tcp_nodelay(self, nodelay: bool) -> Self {
+        ConnectOptions {
+            tcp: TcpOptions {
+                nodelay: Some(nodelay),
+                ..self.tcp
+            },
+            ..self
+        }
+    }
+
+    /// Bind TCP connections to a source address, such as the one of a
+    /// management network, only connecting to addresses of its family.
+    pub fn with_bind_address(self, address: IpAddr) -> Self {
+        ConnectOptions {
+            tcp: TcpOptions {
+                bind_address: Some(address),
+                ..self.tcp
+            },
+            ..self
+        }
+    }
+
+    /// Mark the packets of TCP connections with the DSCP, as `ovsdb-server`
+    /// does with the `dscp` of its remotes.
+    ///
+    /// # Panics
+    ///
+    /// If the DSCP does not fit in its six bits.
+    pub fn with_dscp(self, dscp: u8) -> Self {
+        assert!(dscp < 64, "DSCP {dscp} is larger than 63");
+        ConnectOptions {
+            tcp: TcpOptions {
+                dscp: Some(dscp),
+                ..self.tcp
+            },
+            ..self
+        }
+    }
+
+    /// Fail the requests which are not replied to within the timeout with
+    /// `Error::TimedOut`, instead of after `DEFAULT_REQUEST_TIMEOUT`.
+    ///
+    /// It is the default of the client, which `OvsdbClient::with_timeout`
+    /// and `OvsdbClient::with_deadline` override for some requests.
+    pub fn with_request_timeout(self, timeout: Duration) -> Self {
+        ConnectOptions {
+            request_timeout: timeout,
+            ..self
+        }
+    }
+
+    /// Send at most the given number of requests before their replies are
+    /// received, the others waiting for their turn, so that the server is
+    /// not flooded by a busy client.
+    pub fn with_max_in_flight(self, max_in_flight: usize) -> Self {
+        ConnectOptions {
+            max_in_flight,
+            ..self
+        }
+    }
+
+    /// Keep attempting to connect until it succeeds, waiting for the initial
+    /// delay after the first failure and doubling it after every other one,
+    /// up to the maximum.
+    ///
+    /// It only applies to connecting: a client whose connection is lost stays
+    /// disconnected, unless it is the one of a session, which connects again
+    /// with this backoff.
+    pub fn with_reconnect_backoff(self, initial: Duration, max: Duration) -> Self {
+        ConnectOptions {
+            reconnect_backoff: Some((initial, max)),
+            ..self
+        }
+    }
+
+    /// Returns the stream of the events of the connections made from now on
+    /// with these options.
+    ///
+    /// The events are kept until the stream is polled, the oldest ones being
+    /// dropped if it falls too far behind.
+    pub fn events(&self) -> BoxStream<'static, ConnectionEvent> {
+        futures_util::stream::unfold(self.events.subscribe(), |mut events| async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => return Some((event, events)),
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed()
+    }
+
+    pub(crate) fn emit(&self, event: ConnectionEvent) {
+        // NOTE(mnaser): Sending only fails when nobody listens to the events.
+        let _ = self.events.send(event);
+    }
+
+    /// Run connection attempts until one succeeds, or until the first failure
+    /// without a reconnect backoff.
+    async fn attempt<T, F>(&self, mut connect: impl FnMut() -> F) -> Result<T, Error>
+    where
+        F: Future<Output = Result<T, Error>>,
+    {
+        let mut delay = self.reconnect_backoff.map(|(initial, _)| initial);
+
+        loop {
+            self.emit(ConnectionEvent::Connecting);
+            let error = match connect().await {
+                Ok(connection) => return Ok(connection),
+                Err(error) => error,
+            };
+            self.emit(ConnectionEvent::Disconnected {
+                reason: error.to_string(),
+            });
+
+            let (Some(current), Some((_, max))) = (delay, self.reconnect_backoff) else {
+                return Err(error);
+            };
+            self.emit(ConnectionEvent::BackingOff { delay: current });
+            tokio::time::sleep(current).await;
+            delay = Some((current * 2).min(max));
+        }
+    }
+
+    /// Connect to a remote as given to `ovs-vsctl --db`, such as
+    /// `tcp:127.0.0.1:6641` or `unix:/var/run/openvswitch/db.sock`.
+    pub async fn connect(&self, remote: &str) -> Result<OvsdbClient, Error> {
+        match remote.split_once(':') {
+            Some(("tcp", address)) => self.connect_tcp(address).await,
+            Some(("unix", path)) => self.connect_unix(path).await,
+            _ => Err(Error::InvalidRemote(remote.to_owned())),
+        }
+    }
+
+    pub async fn connect_tcp(
+        &self,
+        tcp: impl ToSocketAddrs + Clone + Send + Sync,
+    ) -> Result<OvsdbClient, Error> {
+        self.connect_transport(TcpTransport::new(tcp).with_options(self.tcp.clone()))
+            .await
+    }
+
+    pub async fn connect_unix(&self, socket_path: impl AsRef<Path>) -> Result<OvsdbClient, Error> {
+        self.connect_transport(UnixTransport::new(socket_path.as_ref()))
+            .await
+    }
+
+    /// Connect through a transport of another kind than TCP and Unix
+    /// sockets, which is asked for a new connection on every attempt.
+    pub async fn connect_transport(
         &self,
-        data: Vec<serde_json::Value>,
-    ) -> Result<Vec<serde_json::Value>, ErrorObjectOwned>;
+        transport: impl OvsdbTransport,
+    ) -> Result<OvsdbClient, Error> {
+        let transport = self
+            .attempt(|| transports::connect(&transport, self))
+            .await?;
+
+        Ok(self.client(transport))
+    }
+
+    /// Start a session to a remote as given to `connect`, which connects in
+    /// the background and then again whenever its connection is lost, with
+    /// the reconnect backoff of the options, or that of Open vSwitch if they
+    /// set none.
+    ///
+    /// # Errors
+    ///
+    /// `Error::InvalidRemote` if the remote is neither a `tcp:` nor a
+    /// `unix:` one.
+    ///
+    /// # Panics
+    ///
+    /// If called outside of a Tokio runtime.
+    pub fn session(&self, remote: &str) -> Result<OvsdbSession, Error> {
+        OvsdbSession::new(self, remote)
+    }
+
+    /// Use an already established connection, such as an in-memory duplex
+    /// stream in tests or a tunnel.
+    pub fn connect_stream(
+        &self,
+        connection: impl AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    ) -> OvsdbClient {
+        self.client(stream::connect(connection, self))
+    }
+
+    fn client<S, R>(&self, transport: Transport<S, R>) -> OvsdbClient
+    where
+        S: TransportSenderT + Send,
+        R: TransportReceiverT + Send,
+    {
+        // NOTE(mnaser): The timeouts are applied by the client, which allows requests to
+        //               wait for longer than its default, so the one of jsonrpsee is set
+        //               out of reach.
+        let inner = ClientBuilder::default()
+            .request_timeout(Duration::from_secs(u32::MAX.into()))
+            .max_concurrent_requests(self.max_in_flight)
+            .build_with_tokio(transport.sender, transport.receiver);
+
+        OvsdbClient::new(
+            inner,
+            transport.session,
+            self.request_timeout,
+            self.max_in_flight,
+        )
+    }
+}
+
+pub async fn connect(remote: &str) -> Result<OvsdbClient, Error> {
+    ConnectOptions::default().connect(remote).await
 }
 
 pub async fn connect_tcp(
-    tcp: impl ToSocketAddrs,
-) -> Result<impl SubscriptionClientT, std::io::Error> {
-    let (sender, receiver) = tcp::connect(tcp).await?;
+    tcp: impl ToSocketAddrs + Clone + Send + Sync,
+) -> Result<OvsdbClient, Error> {
+    ConnectOptions::default().connect_tcp(tcp).await
+}
 
-    Ok(ClientBuilder::default().build_with_tokio(sender, receiver))
+pub async fn connect_unix(socket_path: impl AsRef<Path>) -> Result<OvsdbClient, Error> {
+    ConnectOptions::default().connect_unix(socket_path).await
 }
 
-pub async fn connect_unix(
-    socket_path: impl AsRef<Path>,
-) -> Result<impl SubscriptionClientT, std::io::Error> {
-    let (sender, receiver) = ipc::connect(socket_path).await?;
+pub async fn connect_transport(transport: impl OvsdbTransport) -> Result<OvsdbClient, Error> {
+    ConnectOptions::default().connect_transport(transport).await
+}
 
-    Ok(ClientBuilder::default().build_with_tokio(sender, receiver))
+/// Use an already established connection, such as an in-memory duplex
+/// stream in tests or a tunnel.
+pub fn connect_stream(
+    connection: impl AsyncRead + AsyncWrite + Send + Unpin + 'static,
+) -> OvsdbClient {
+    ConnectOptions::default().connect_stream(connection)
 }
@@ -1,8 +1,12 @@
+use crate::{Error, transact::Condition};
+use ovsdb_schema::{OvsdbConversionError, OvsdbTable};
 use serde::de::{self, SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::marker::PhantomData;
+use uuid::Uuid;
 
 #[derive(Debug, Deserialize)]
 pub struct DatabaseSchema {
@@ -16,6 +20,80 @@ pub struct DatabaseSchema {
     pub tables: HashMap<String, TableSchema>,
 }
 
+impl DatabaseSchema {
+    /// Compare this schema against another one, typically a newer version of
+    /// the same database, and report the tables and columns which differ.
+    pub fn diff(&self, other: &DatabaseSchema) -> SchemaDiff {
+        let mut diff = SchemaDiff::default();
+
+        for (name, table) in &self.tables {
+            match other.tables.get(name) {
+                None => diff.removed_tables.push(name.clone()),
+                Some(other_table) => {
+                    let table_diff = table.diff(other_table);
+                    if !table_diff.is_empty() {
+                        diff.changed_tables.insert(name.clone(), table_diff);
+                    }
+                }
+            }
+        }
+
+        diff.added_tables = other
+            .tables
+            .keys()
+            .filter(|name| !self.tables.contains_key(*name))
+            .cloned()
+            .collect();
+
+        diff.added_tables.sort();
+        diff.removed_tables.sort();
+
+        diff
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemaDiff {
+    pub added_tables: Vec<String>,
+
+    pub removed_tables: Vec<String>,
+
+    pub changed_tables: BTreeMap<String, TableDiff>,
+}
+
+impl SchemaDiff {
+    /// Returns true if both schemas have the same tables, columns and column types.
+    pub fn is_empty(&self) -> bool {
+        self.added_tables.is_empty()
+            && self.removed_tables.is_empty()
+            && self.changed_tables.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableDiff {
+    pub added_columns: Vec<String>,
+
+    pub removed_columns: Vec<String>,
+
+    pub changed_columns: BTreeMap<String, ColumnTypeChange>,
+}
+
+impl TableDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_columns.is_empty()
+            && self.removed_columns.is_empty()
+            && self.changed_columns.is_empty()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnTypeChange {
+    pub old: ColumnType,
+
+    pub new: ColumnType,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TableSchema {
     pub columns: HashMap<String, ColumnSchema>,
@@ -30,9 +108,62 @@ pub struct TableSchema {
     pub indexes: Option<Vec<Vec<String>>>,
 }
 
+impl TableSchema {
+    /// Compare the columns of this table against another version of it.
+    pub fn diff(&self, other: &TableSchema) -> TableDiff {
+        let mut diff = TableDiff::default();
+
+        for (name, column) in &self.columns {
+            match other.columns.get(name) {
+                None => diff.removed_columns.push(name.clone()),
+                Some(other_column) if other_column.r#type != column.r#type => {
+                    diff.changed_columns.insert(
+                        name.clone(),
+                        ColumnTypeChange {
+                            old: column.r#type.clone(),
+                            new: other_column.r#type.clone(),
+                        },
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+
+        diff.added_columns = other
+            .columns
+            .keys()
+            .filter(|name| !self.columns.contains_key(*name))
+            .cloned()
+            .collect();
+
+        diff.added_columns.sort();
+        diff.removed_columns.sort();
+
+        diff
+    }
+
+    /// Keep only the columns which exist in this table, preserving their order.
+    ///
+    /// The implicit `_uuid` and `_version` columns are always kept since they
+    /// exist in every table even though they are not listed in the schema.
+    pub fn prune_columns<I, S>(&self, columns: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        columns
+            .into_iter()
+            .map(|column| column.as_ref().to_owned())
+            .filter(|column| {
+                column == "_uuid" || column == "_version" || self.columns.contains_key(column)
+            })
+            .collect()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ColumnSchema {
-    pub r#type: serde_json::Value,
+    pub r#type: ColumnType,
 
     #[serde(rename = "ephemeral")]
     pub ephemeral: Option<bool>,
@@ -41,6 +172,264 @@ pub struct ColumnSchema {
     pub mutable: Option<bool>,
 }
 
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(from = "RawColumnType")]
+pub struct ColumnType {
+    pub key: BaseType,
+
+    pub value: Option<BaseType>,
+
+    pub min: u64,
+
+    /// Maximum number of elements, `None` meaning "unlimited".
+    pub max: Option<u64>,
+}
+
+impl ColumnType {
+    /// Returns true if the column holds a map (it has a value type).
+    pub fn is_map(&self) -> bool {
+        self.value.is_some()
+    }
+
+    /// Returns true if the column holds a set with more than one element allowed.
+    pub fn is_set(&self) -> bool {
+        self.value.is_none() && self.max != Some(1)
+    }
+
+    /// Returns true if the column holds zero or one atom.
+    pub fn is_optional(&self) -> bool {
+        self.value.is_none() && self.min == 0 && self.max == Some(1)
+    }
+
+    /// Returns true if the column holds exactly one atom.
+    pub fn is_scalar(&self) -> bool {
+        self.value.is_none() && self.min == 1 && self.max == Some(1)
+    }
+
+    /// Returns the rows a value of the column references strongly, with
+    /// their table.
+    pub fn strong_references<'a>(&'a self, value: &Value) -> Vec<(&'a str, Uuid)> {
+        let atoms: Vec<(&BaseType, &Value)> = match value.as_array().map(Vec::as_slice) {
+            Some([kind, Value::Array(pairs)]) if kind == "map" => {
+                let Some(value_type) = &self.value else {
+                    return Vec::new();
+                };
+                pairs
+                    .iter()
+                    .filter_map(|pair| match pair.as_array().map(Vec::as_slice) {
+                        Some([key, value]) => Some([(&self.key, key), (value_type, value)]),
+                        _ => None,
+                    })
+                    .flatten()
+                    .collect()
+            }
+            Some([kind, Value::Array(atoms)]) if kind == "set" => {
+                atoms.iter().map(|atom| (&self.key, atom)).collect()
+            }
+            _ => vec![(&self.key, value)],
+        };
+
+        atoms
+            .into_iter()
+            .filter(|(base, _)| base.is_strong())
+            .filter_map(|(base, atom)| match atom.as_array().map(Vec::as_slice) {
+                Some([kind, Value::String(uuid)]) if kind == "uuid" => {
+                    Some((base.ref_table.as_deref()?, Uuid::parse_str(uuid).ok()?))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawColumnType {
+    Atomic(AtomicType),
+    Full(Box<RawColumnTypeObject>),
+}
+
+#[derive(Deserialize)]
+struct RawColumnTypeObject {
+    key: BaseType,
+    value: Option<BaseType>,
+    min: Option<u64>,
+    max: Option<RawMax>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawMax {
+    Limited(u64),
+    Unlimited(Unlimited),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Unlimited {
+    Unlimited,
+}
+
+impl From<RawColumnType> for ColumnType {
+    fn from(raw: RawColumnType) -> Self {
+        match raw {
+            RawColumnType::Atomic(atomic) => ColumnType {
+                key: atomic.into(),
+                value: None,
+                min: 1,
+                max: Some(1),
+            },
+            RawColumnType::Full(raw) => ColumnType {
+                key: raw.key,
+                value: raw.value,
+                min: raw.min.unwrap_or(1),
+                max: match raw.max {
+                    None => Some(1),
+                    Some(RawMax::Limited(max)) => Some(max),
+                    Some(RawMax::Unlimited(_)) => None,
+                },
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(from = "RawBaseType")]
+pub struct BaseType {
+    pub r#type: AtomicType,
+
+    /// Allowed values, already unwrapped from the `["set", [...]]` form.
+    pub r#enum: Option<Vec<serde_json::Value>>,
+
+    pub min_integer: Option<i64>,
+
+    pub max_integer: Option<i64>,
+
+    pub min_real: Option<f64>,
+
+    pub max_real: Option<f64>,
+
+    pub min_length: Option<u64>,
+
+    pub max_length: Option<u64>,
+
+    pub ref_table: Option<String>,
+
+    pub ref_type: Option<RefType>,
+}
+
+impl BaseType {
+    /// Returns true if the atoms are strong references to another table.
+    pub fn is_strong(&self) -> bool {
+        self.ref_table.is_some() && self.ref_type == Some(RefType::Strong)
+    }
+}
+
+impl From<AtomicType> for BaseType {
+    fn from(r#type: AtomicType) -> Self {
+        BaseType {
+            r#type,
+            r#enum: None,
+            min_integer: None,
+            max_integer: None,
+            min_real: None,
+            max_real: None,
+            min_length: None,
+            max_length: None,
+            ref_table: None,
+            ref_type: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawBaseType {
+    Atomic(AtomicType),
+    Full {
+        r#type: AtomicType,
+        r#enum: Option<serde_json::Value>,
+        #[serde(rename = "minInteger")]
+        min_integer: Option<i64>,
+        #[serde(rename = "maxInteger")]
+        max_integer: Option<i64>,
+        #[serde(rename = "minReal")]
+        min_real: Option<f64>,
+        #[serde(rename = "maxReal")]
+        max_real: Option<f64>,
+        #[serde(rename = "minLength")]
+        min_length: Option<u64>,
+        #[serde(rename = "maxLength")]
+        max_length: Option<u64>,
+        #[serde(rename = "refTable")]
+        ref_table: Option<String>,
+        #[serde(rename = "refType")]
+        ref_type: Option<RefType>,
+    },
+}
+
+impl From<RawBaseType> for BaseType {
+    fn from(raw: RawBaseType) -> Self {
+        match raw {
+            RawBaseType::Atomic(atomic) => atomic.into(),
+            RawBaseType::Full {
+                r#type,
+                r#enum,
+                min_integer,
+                max_integer,
+                min_real,
+                max_real,
+                min_length,
+                max_length,
+                ref_table,
+                ref_type,
+            } => {
+                // NOTE(mnaser): References default to "strong" when "refTable" is
+                //               present without an explicit "refType".
+                let ref_type = match (&ref_table, ref_type) {
+                    (Some(_), None) => Some(RefType::Strong),
+                    (_, ref_type) => ref_type,
+                };
+
+                BaseType {
+                    r#type,
+                    r#enum: r#enum.map(|value| match value {
+                        serde_json::Value::Array(arr) if arr.len() == 2 && arr[0] == "set" => {
+                            arr[1].as_array().cloned().unwrap_or_default()
+                        }
+                        value => vec![value],
+                    }),
+                    min_integer,
+                    max_integer,
+                    min_real,
+                    max_real,
+                    min_length,
+                    max_length,
+                    ref_table,
+                    ref_type,
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AtomicType {
+    Integer,
+    Real,
+    Boolean,
+    String,
+    Uuid,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RefType {
+    Strong,
+    Weak,
+}
+
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct MonitorRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -50,16 +439,117 @@ pub struct MonitorRequest {
     pub select: Option<MonitorRequestSelect>,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+impl MonitorRequest {
+    /// Create a monitor request for the given columns, dropping any which are
+    /// not present in the live table schema.
+    ///
+    /// This allows structs generated against a newer or older schema to be
+    /// monitored without the server rejecting unknown columns.
+    pub fn pruned<I, S>(table: &TableSchema, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        MonitorRequest {
+            columns: Some(table.prune_columns(columns)),
+            ..Default::default()
+        }
+    }
+}
+
+/// A `<monitor-select>`, telling which kinds of changes are reported, each
+/// of them being reported unless set to false
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct MonitorRequestSelect {
-    initial: Option<bool>,
-    insert: Option<bool>,
-    delete: Option<bool>,
-    modify: Option<bool>,
+    /// Whether the rows present when the monitor is created are reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial: Option<bool>,
+
+    /// Whether inserted rows are reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insert: Option<bool>,
+
+    /// Whether deleted rows are reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<bool>,
+
+    /// Whether modified rows are reported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modify: Option<bool>,
+}
+
+impl MonitorRequestSelect {
+    /// Create a selection reporting every kind of change, as the server does
+    /// without one.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report the changes made after the monitor is created, without the
+    /// initial rows.
+    pub fn changes_only() -> Self {
+        Self::new().initial(false)
+    }
+
+    /// Report the rows inserted after the monitor is created only.
+    pub fn inserts_only() -> Self {
+        Self::new().initial(false).delete(false).modify(false)
+    }
+
+    /// Set whether the rows present when the monitor is created are
+    /// reported.
+    pub fn initial(self, initial: bool) -> Self {
+        MonitorRequestSelect {
+            initial: Some(initial),
+            ..self
+        }
+    }
+
+    /// Set whether inserted rows are reported.
+    pub fn insert(self, insert: bool) -> Self {
+        MonitorRequestSelect {
+            insert: Some(insert),
+            ..self
+        }
+    }
+
+    /// Set whether deleted rows are reported.
+    pub fn delete(self, delete: bool) -> Self {
+        MonitorRequestSelect {
+            delete: Some(delete),
+            ..self
+        }
+    }
+
+    /// Set whether modified rows are reported.
+    pub fn modify(self, modify: bool) -> Self {
+        MonitorRequestSelect {
+            modify: Some(modify),
+            ..self
+        }
+    }
+}
+
+/// A `<monitor-cond-request>`, which only reports the rows matching its
+/// conditions
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct MonitorCondRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<String>>,
+
+    /// Conditions a row must match, all rows being reported if unset
+    #[serde(rename = "where", skip_serializing_if = "Option::is_none")]
+    pub conditions: Option<Vec<Condition>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub select: Option<MonitorRequestSelect>,
 }
 
 pub type TableUpdate<T> = HashMap<String, TableUpdateRows<T>>;
-pub type TableUpdateRows<T> = HashMap<String, RowUpdate<T>>;
+
+/// The updates of the rows of a table, by UUID, a key which is not a UUID
+/// failing the decoding of the whole update
+pub type TableUpdateRows<T> = HashMap<Uuid, RowUpdate<T>>;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RowUpdate<T> {
@@ -67,9 +557,100 @@ pub struct RowUpdate<T> {
     pub new: Option<T>,
 }
 
+/// Conversions of the rows of a `TableUpdate<Value>`, such as the initial
+/// state returned by `monitor`, to table structs keyed by UUID
+pub trait TableUpdateExt {
+    /// Convert the rows of every table to `T`, failing on the first row
+    /// which cannot be converted.
+    fn into_typed<T: OvsdbTable>(self) -> Result<TableUpdate<T>, Error>;
+
+    /// Convert the rows of the table to `T`, none if the table has no update.
+    fn table<T: OvsdbTable>(&self, table: &str) -> Result<TableUpdateRows<T>, Error>;
+}
+
+impl TableUpdateExt for TableUpdate<Value> {
+    fn into_typed<T: OvsdbTable>(self) -> Result<TableUpdate<T>, Error> {
+        self.iter()
+            .map(|(table, rows)| Ok((table.clone(), typed_rows(table, rows)?)))
+            .collect()
+    }
+
+    fn table<T: OvsdbTable>(&self, table: &str) -> Result<TableUpdateRows<T>, Error> {
+        self.get(table)
+            .map_or(Ok(HashMap::new()), |rows| typed_rows(table, rows))
+    }
+}
+
+pub(crate) fn typed_rows<T: OvsdbTable>(
+    table: &str,
+    rows: &TableUpdateRows<Value>,
+) -> Result<TableUpdateRows<T>, Error> {
+    rows.iter()
+        .map(|(uuid, update)| {
+            let invalid = |source| Error::InvalidRowUpdate {
+                table: table.to_owned(),
+                uuid: *uuid,
+                source: Box::new(source),
+            };
+
+            // NOTE(mnaser): The old row of a modification only holds the columns which
+            //               changed, so the others are taken from the new row.
+            let new = update.new.as_ref().map(|new| row_map(uuid, new, None));
+            let old = update
+                .old
+                .as_ref()
+                .map(|old| row_map(uuid, old, update.new.as_ref()));
+            let convert = |row: Option<Result<HashMap<String, Value>, OvsdbConversionError>>| {
+                row.map(|row| row.and_then(|row| T::from_map(&row)))
+                    .transpose()
+                    .map_err(invalid)
+            };
+
+            Ok((
+                *uuid,
+                RowUpdate {
+                    old: convert(old)?,
+                    new: convert(new)?,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Returns the columns of a row with its UUID, on top of those of the base
+/// row if any.
+fn row_map(
+    uuid: &Uuid,
+    row: &Value,
+    base: Option<&Value>,
+) -> Result<HashMap<String, Value>, OvsdbConversionError> {
+    let columns = |row: &Value| match row {
+        Value::Object(columns) => Ok(columns.clone()),
+        row => Err(OvsdbConversionError::new(
+            "an object of columns",
+            row.clone(),
+        )),
+    };
+
+    let mut map: HashMap<String, Value> = match base {
+        Some(base) => columns(base)?.into_iter().collect(),
+        None => HashMap::new(),
+    };
+    map.extend(columns(row)?);
+    map.insert(
+        "_uuid".to_owned(),
+        serde_json::json!(["uuid", uuid.to_string()]),
+    );
+
+    Ok(map)
+}
+
+/// The parameters of an `update` notification
 #[derive(Debug)]
 pub struct UpdateNotification<T> {
-    pub id: Option<String>,
+    /// The id of the monitor, as given to `monitor`
+    pub id: Value,
+
     pub message: TableUpdate<T>,
 }
 
@@ -93,15 +674,14 @@ where
             type Value = UpdateNotification<T>;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter
-                    .write_str("an array with two elements: Option<String> and a TableUpdate<T>")
+                formatter.write_str("an array with two elements: a monitor id and a TableUpdate<T>")
             }
 
             fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
             where
                 A: SeqAccess<'de>,
             {
-                let id: Option<String> = seq
+                let id: Value = seq
                     .next_element()?
                     .ok_or_else(|| de::Error::invalid_length(0, &self))?;
                 let message: TableUpdate<T> = seq
@@ -118,3 +698,112 @@ where
         })
     }
 }
+
+pub type TableUpdate2<T> = HashMap<String, TableUpdateRows2<T>>;
+
+/// The updates of the rows of a table, by UUID, as sent by `monitor_cond`
+pub type TableUpdateRows2<T> = HashMap<Uuid, RowUpdate2<T>>;
+
+/// A `<row-update2>`, as sent by `monitor_cond`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RowUpdate2<T> {
+    /// A row present when the monitor was created
+    Initial(T),
+
+    /// A row inserted afterwards
+    Insert(T),
+
+    /// A row deleted, or no longer matching the conditions
+    Delete,
+
+    /// The changed columns of a row, as a difference for sets and maps
+    Modify(T),
+}
+
+/// The parameters of an `update2` notification
+#[derive(Debug)]
+pub struct Update2Notification<T> {
+    /// The id of the monitor, as given to `monitor_cond`
+    pub id: Value,
+
+    pub message: TableUpdate2<T>,
+}
+
+impl<'de, T> Deserialize<'de> for Update2Notification<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (id, message) = <(Value, TableUpdate2<T>)>::deserialize(deserializer)?;
+
+        Ok(Update2Notification { id, message })
+    }
+}
+
+/// The parameters of an `update3` notification, as sent for the monitors
+/// created with `monitor_cond_since`
+#[derive(Debug)]
+pub struct Update3Notification<T> {
+    /// The id of the monitor, as given to `monitor_cond_since`
+    pub id: Value,
+
+    /// The id of the transaction which made the changes
+    pub last_id: Uuid,
+
+    pub message: TableUpdate2<T>,
+}
+
+impl<'de, T> Deserialize<'de> for Update3Notification<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (id, last_id, message) = <(Value, Uuid, TableUpdate2<T>)>::deserialize(deserializer)?;
+
+        Ok(Update3Notification {
+            id,
+            last_id,
+            message,
+        })
+    }
+}
+
+/// The reply to a `monitor_cond_since` request
+#[derive(Debug)]
+pub struct MonitorCondSinceReply<T> {
+    /// Whether the server still had the transaction given to the request,
+    /// in which case the updates are the changes made after it, and
+    /// otherwise the initial state of the monitor
+    pub found: bool,
+
+    /// The id of the last transaction of the database, the nil UUID if the
+    /// server does not track them, as for databases which are not clustered
+    pub last_id: Uuid,
+
+    pub updates: TableUpdate2<T>,
+}
+
+impl<'de, T> Deserialize<'de> for MonitorCondSinceReply<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (found, last_id, updates) = <(bool, Uuid, TableUpdate2<T>)>::deserialize(deserializer)?;
+
+        Ok(MonitorCondSinceReply {
+            found,
+            last_id,
+            updates,
+        })
+    }
+}
@@ -0,0 +1,264 @@
+//! A connection which is made again whenever it is lost, for daemons which
+//! stay connected to a server for their whole life, as the `jsonrpc_session`
+//! of Open vSwitch does.
+
+use crate::{
+    Error, OvsdbClient,
+    rpc::{ConnectOptions, ConnectionEvent},
+};
+use futures_util::{StreamExt, stream::BoxStream};
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{sync::watch, task::JoinHandle, time::Instant};
+
+/// The delay before the first reconnection attempt of a session whose
+/// options set no reconnect backoff, as in Open vSwitch
+pub const DEFAULT_RECONNECT_INITIAL: Duration = Duration::from_secs(1);
+
+/// The longest delay between the reconnection attempts of a session whose
+/// options set no reconnect backoff, as in Open vSwitch
+pub const DEFAULT_RECONNECT_MAX: Duration = Duration::from_secs(8);
+
+/// The state of a session shared by its clones
+struct Shared {
+    /// The client of the current connection, none while reconnecting
+    current: watch::Receiver<Option<Arc<OvsdbClient>>>,
+
+    /// Set once the session is shut down
+    stop: watch::Sender<bool>,
+
+    /// The task keeping the session connected, until it is shut down
+    task: Mutex<Option<JoinHandle<()>>>,
+
+    options: ConnectOptions,
+}
+
+impl Drop for Shared {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.get_mut().unwrap().take() {
+            task.abort();
+        }
+    }
+}
+
+/// A connection to a remote which is made again, with the reconnect backoff
+/// of its options, whenever it is lost, returned by `ConnectOptions::session`
+///
+/// Every connection has its own `OvsdbClient`, so what it holds on the
+/// server is lost along with it. The session requests its locks again on the
+/// next one, while the rest, such as its monitors, has to be requested again
+/// by the application, for which `connections` streams the clients.
+#[derive(Clone)]
+pub struct OvsdbSession {
+    shared: Arc<Shared>,
+}
+
+impl OvsdbSession {
+    /// Start connecting to the remote, which must be a `tcp:` or `unix:`
+    /// one, in a task of the runtime.
+    pub(crate) fn new(options: &ConnectOptions, remote: &str) -> Result<Self, Error> {
+        if !matches!(remote.split_once(':'), Some(("tcp" | "unix", _))) {
+            return Err(Error::InvalidRemote(remote.to_owned()));
+        }
+
+        let options = match options.reconnect_backoff {
+            Some(_) => options.clone(),
+            None => options
+                .clone()
+                .with_reconnect_backoff(DEFAULT_RECONNECT_INITIAL, DEFAULT_RECONNECT_MAX),
+        };
+        let (current, watched) = watch::channel(None);
+        let (stop, stopped) = watch::channel(false);
+        let task = tokio::spawn(run(options.clone(), remote.to_owned(), current, stopped));
+
+        Ok(OvsdbSession {
+            shared: Arc::new(Shared {
+                current: watched,
+                stop,
+                task: Mutex::new(Some(task)),
+                options,
+            }),
+        })
+    }
+
+    /// Returns the client of the current connection, waiting for the session
+    /// to connect if it is not, or `Error::ConnectionClosed` once it is shut
+    /// down.
+    pub async fn client(&self) -> Result<Arc<OvsdbClient>, Error> {
+        let mut current = self.shared.current.clone();
+        loop {
+            if let Some(client) = current
+                .borrow_and_update()
+                .clone()
+                .filter(|client| client.is_connected())
+            {
+                return Ok(client);
+            }
+            current
+                .changed()
+                .await
+                .map_err(|_| Error::ConnectionClosed)?;
+        }
+    }
+
+    /// Returns the client of the current connection, none while the session
+    /// is reconnecting.
+    pub fn current(&self) -> Option<Arc<OvsdbClient>> {
+        self.shared
+            .current
+            .borrow()
+            .clone()
+            .filter(|client| client.is_connected())
+    }
+
+    /// Returns whether the session is connected.
+    pub fn is_connected(&self) -> bool {
+        self.current().is_some()
+    }
+
+    /// Returns the stream of the clients of the connections, starting with
+    /// the current one, so that what the previous connection held can be
+    /// requested again, such as the monitors of a replica.
+    ///
+    /// It ends once the session is shut down.
+    pub fn connections(&self) -> BoxStream<'static, Arc<OvsdbClient>> {
+        let mut current = self.shared.current.clone();
+        current.mark_changed();
+
+        futures_util::stream::unfold(current, |mut current| async move {
+            loop {
+                current.changed().await.ok()?;
+                let client = current.borrow_and_update().clone();
+                if let Some(client) = client {
+                    return Some((client, current));
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// Returns the stream of the events of the connections of the session,
+    /// as `ConnectOptions::events` does.
+    pub fn events(&self) -> BoxStream<'static, ConnectionEvent> {
+        self.shared.options.events()
+    }
+
+    /// Stop reconnecting and shut the current connection down, as
+    /// `OvsdbClient::shutdown` does.
+    pub async fn shutdown(&self) -> Result<(), Error> {
+        self.shared.stop.send_replace(true);
+
+        let task = self.shared.task.lock().unwrap().take();
+        if let Some(task) = task {
+            // NOTE(mnaser): The task only fails when it panicked or was aborted, in
+            //               which case there is nothing left to shut down.
+            let _ = task.await;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for OvsdbSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OvsdbSession")
+            .field("connected", &self.is_connected())
+            .finish()
+    }
+}
+
+/// Keep connecting to the remote until the session is shut down.
+async fn run(
+    options: ConnectOptions,
+    remote: String,
+    current: watch::Sender<Option<Arc<OvsdbClient>>>,
+    mut stopped: watch::Receiver<bool>,
+) {
+    let (initial, _) = options
+        .reconnect_backoff
+        .unwrap_or((DEFAULT_RECONNECT_INITIAL, DEFAULT_RECONNECT_MAX));
+
+    // NOTE(mnaser): The locks are released by the server along with the connection,
+    //               so those held by the last one are requested again on the next.
+    let mut locks: Vec<String> = Vec::new();
+
+    loop {
+        let client = tokio::select! {
+            client = options.connect(&remote) => client,
+            _ = stopping(&mut stopped) => return,
+        };
+        // NOTE(mnaser): With a reconnect backoff, connecting only fails for an invalid
+        //               remote, which the session checked before starting.
+        let Ok(client) = client else {
+            return;
+        };
+
+        let client = Arc::new(client);
+        let connected = Instant::now();
+        let unsent = tokio::select! {
+            unsent = reacquire(&options, &client, std::mem::take(&mut locks)) => unsent,
+            _ = stopping(&mut stopped) => {
+                let _ = client.shutdown().await;
+                return;
+            }
+        };
+        current.send_replace(Some(client.clone()));
+
+        tokio::select! {
+            _ = client.on_disconnect() => {}
+            _ = stopping(&mut stopped) => {
+                let _ = client.shutdown().await;
+                current.send_replace(None);
+                return;
+            }
+        }
+        current.send_replace(None);
+
+        locks = client.locks();
+        for lock in unsent {
+            if !locks.contains(&lock) {
+                locks.push(lock);
+            }
+        }
+        for lock in &locks {
+            options.emit(ConnectionEvent::LockLost { lock: lock.clone() });
+        }
+
+        // NOTE(mnaser): A server which accepts connections and drops them right away
+        //               would otherwise be reconnected to in a busy loop.
+        if connected.elapsed() < initial {
+            options.emit(ConnectionEvent::BackingOff { delay: initial });
+            tokio::select! {
+                _ = tokio::time::sleep(initial) => {}
+                _ = stopping(&mut stopped) => return,
+            }
+        }
+    }
+}
+
+/// Request the locks again on the new connection, before it is handed out,
+/// returning those whose request could not be made.
+async fn reacquire(
+    options: &ConnectOptions,
+    client: &OvsdbClient,
+    locks: Vec<String>,
+) -> Vec<String> {
+    let mut unsent = Vec::new();
+    for lock in locks {
+        match client.reacquire(&lock).await {
+            Ok(true) => options.emit(ConnectionEvent::LockReacquired { lock }),
+            Ok(false) => {}
+            Err(_) => unsent.push(lock),
+        }
+    }
+    unsent
+}
+
+/// Wait for the session to be shut down.
+async fn stopping(stopped: &mut watch::Receiver<bool>) {
+    // NOTE(mnaser): The sender is held by the session, whose drop aborts the task.
+    let _ = stopped.wait_for(|stopped| *stopped).await;
+}
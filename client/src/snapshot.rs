@@ -0,0 +1,137 @@
+use crate::Error;
+use bytes::Bytes;
+use serde::{
+    Deserialize, Deserializer,
+    de::{DeserializeOwned, DeserializeSeed, MapAccess, Visitor},
+};
+use std::{fmt, marker::PhantomData};
+use uuid::Uuid;
+
+/// The initial rows of a monitor, as they were received, returned by
+/// `OvsdbClient::monitor_snapshot` and `OvsdbClient::monitor_cond_snapshot`
+///
+/// The rows are only decoded by [`Snapshot::for_each_row`], one at a time,
+/// so that only the reply and a single row are held in memory rather than
+/// every row of the database as values.
+#[derive(Clone, Debug)]
+pub struct Snapshot {
+    rows: Bytes,
+}
+
+impl Snapshot {
+    pub(crate) fn new(rows: Bytes) -> Self {
+        Snapshot { rows }
+    }
+
+    /// Returns the size of the reply holding the rows, in bytes.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns whether the reply is empty, which it never is once received.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Decode the rows table by table, calling the function with the name of
+    /// the table, the UUID of the row and its columns, as soon as each row is
+    /// decoded.
+    ///
+    /// It can be called several times, such as to load the rows of another
+    /// type, and fails on the first row which cannot be decoded.
+    pub fn for_each_row<T, F>(&self, mut f: F) -> Result<(), Error>
+    where
+        T: DeserializeOwned,
+        F: FnMut(&str, Uuid, T),
+    {
+        let mut deserializer = serde_json::Deserializer::from_slice(&self.rows);
+        deserializer.deserialize_map(Tables {
+            f: &mut f,
+            row: PhantomData,
+        })?;
+        deserializer.end()?;
+
+        Ok(())
+    }
+}
+
+/// A `<row-update>` of "monitor" or a `<row-update2>` of "monitor_cond",
+/// which only hold new rows in their initial state
+#[derive(Deserialize)]
+struct Initial<T> {
+    #[serde(alias = "initial")]
+    new: Option<T>,
+}
+
+struct Tables<'f, F, T> {
+    f: &'f mut F,
+
+    row: PhantomData<T>,
+}
+
+impl<'de, F, T> Visitor<'de> for Tables<'_, F, T>
+where
+    T: DeserializeOwned,
+    F: FnMut(&str, Uuid, T),
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a <table-updates> object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(table) = map.next_key::<String>()? {
+            map.next_value_seed(Rows {
+                table: &table,
+                f: &mut *self.f,
+                row: PhantomData,
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Rows<'a, F, T> {
+    table: &'a str,
+
+    f: &'a mut F,
+
+    row: PhantomData<T>,
+}
+
+impl<'de, F, T> DeserializeSeed<'de> for Rows<'_, F, T>
+where
+    T: DeserializeOwned,
+    F: FnMut(&str, Uuid, T),
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, F, T> Visitor<'de> for Rows<'_, F, T>
+where
+    T: DeserializeOwned,
+    F: FnMut(&str, Uuid, T),
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a <table-update> object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(uuid) = map.next_key::<Uuid>()? {
+            let row: Initial<T> = map.next_value()?;
+            if let Some(new) = row.new {
+                (self.f)(self.table, uuid, new);
+            }
+        }
+
+        Ok(())
+    }
+}
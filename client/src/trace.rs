@@ -0,0 +1,163 @@
+//! Hooks feeding the `tracing` and `metrics` features, which compile to
+//! nothing without them.
+
+use crate::transports::Incoming;
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+use serde::de::IgnoredAny;
+use serde_json::Value;
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+use serde_json::value::RawValue;
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+use std::collections::BTreeMap;
+use std::{fmt::Display, future::Future};
+
+/// Run a request within an `ovsdb_request` span, which lasts until the reply
+/// is received.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn request<F, T, E>(
+    method: &'static str,
+    db_name: Option<&str>,
+    future: F,
+) -> impl Future<Output = Result<T, E>>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    #[cfg(feature = "metrics")]
+    let future = async move {
+        let start = std::time::Instant::now();
+        let result = future.await;
+        crate::metrics::request(method, start.elapsed(), result.is_err());
+        result
+    };
+
+    #[cfg(feature = "tracing")]
+    let future = tracing::Instrument::instrument(
+        future,
+        tracing::debug_span!("ovsdb_request", method, db = db_name),
+    );
+
+    future
+}
+
+/// A message about to be written to the server, of the given size.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn sent(message: &Value, bytes: usize) {
+    #[cfg(feature = "metrics")]
+    crate::metrics::sent(bytes);
+
+    #[cfg(feature = "tracing")]
+    match message["method"].as_str() {
+        Some(method) => tracing::debug!(
+            method,
+            id = %message["id"],
+            db = message["params"][0].as_str(),
+            bytes,
+            "sent request"
+        ),
+        None => tracing::debug!(id = %message["id"], bytes, "sent reply"),
+    }
+}
+
+/// Returns the first parameter of a notification, which is the id of the
+/// monitor for the update ones, and the number of rows it updated per table.
+///
+/// The table updates are the last parameter of "update", "update2" and
+/// "update3" notifications, which are counted without parsing the rows.
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+fn updated_rows(params: Option<&RawValue>) -> (Option<&RawValue>, Vec<(&str, usize)>) {
+    let params: Vec<&RawValue> = params
+        .and_then(|params| serde_json::from_str(params.get()).ok())
+        .unwrap_or_default();
+    let tables: BTreeMap<&str, BTreeMap<&str, IgnoredAny>> = params
+        .last()
+        .and_then(|updates| serde_json::from_str(updates.get()).ok())
+        .unwrap_or_default();
+
+    (
+        params.first().copied(),
+        tables
+            .into_iter()
+            .map(|(table, rows)| (table, rows.len()))
+            .collect(),
+    )
+}
+
+/// A message read from the server, before it is rewritten for jsonrpsee.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn received(message: &Incoming) {
+    let Some(method) = message.method.as_deref() else {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            id = %message.id,
+            error = message.error.map(RawValue::get),
+            "received reply"
+        );
+        return;
+    };
+
+    #[cfg(any(feature = "tracing", feature = "metrics"))]
+    let (monitor, tables) = updated_rows(message.params);
+
+    #[cfg(feature = "metrics")]
+    if method.starts_with("update") {
+        for (table, rows) in &tables {
+            crate::metrics::update(table, *rows);
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        method,
+        monitor = monitor.map(RawValue::get),
+        tables = tables
+            .iter()
+            .map(|(table, rows)| format!("{table}={rows}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+        "received notification"
+    );
+}
+
+/// A message of the given size decoded by the codec.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn decoded(bytes: usize) {
+    #[cfg(feature = "metrics")]
+    crate::metrics::received(bytes);
+
+    #[cfg(feature = "tracing")]
+    tracing::trace!(bytes, "decoded message");
+}
+
+/// Bytes received which the codec failed to decode.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn decode_error(error: &serde_json::Error) {
+    #[cfg(feature = "metrics")]
+    crate::metrics::decode_error();
+
+    #[cfg(feature = "tracing")]
+    tracing::debug!(%error, "failed to decode message");
+}
+
+/// A connection attempt to the given remote, which failed with the error if
+/// any.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn connect(remote: impl Display, error: Option<&std::io::Error>) {
+    #[cfg(feature = "metrics")]
+    crate::metrics::connect(error.is_some());
+
+    #[cfg(feature = "tracing")]
+    match error {
+        Some(error) => tracing::warn!(%remote, %error, "connection failed"),
+        None => tracing::info!(%remote, "connected"),
+    }
+}
+
+/// The connection stopped, the client failing every request from now on.
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+pub(crate) fn disconnected(error: &crate::Error) {
+    #[cfg(feature = "metrics")]
+    crate::metrics::disconnected();
+
+    #[cfg(feature = "tracing")]
+    tracing::warn!(%error, "disconnected");
+}
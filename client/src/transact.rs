@@ -0,0 +1,202 @@
+use crate::{Error, OvsdbError, trace};
+use jsonrpsee::core::{client::ClientT, params::ArrayParams};
+use ovsdb_schema::{Mutation, extract_uuid};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, future::Future};
+use uuid::Uuid;
+
+/// Comparison functions usable in a `where` clause.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Function {
+    #[serde(rename = "<")]
+    LessThan,
+    #[serde(rename = "<=")]
+    LessThanOrEqual,
+    #[serde(rename = "==")]
+    Equal,
+    #[serde(rename = "!=")]
+    NotEqual,
+    #[serde(rename = ">=")]
+    GreaterThanOrEqual,
+    #[serde(rename = ">")]
+    GreaterThan,
+    #[serde(rename = "includes")]
+    Includes,
+    #[serde(rename = "excludes")]
+    Excludes,
+}
+
+/// A `[column, function, value]` condition of a `where` clause.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Condition(pub String, pub Function, pub Value);
+
+impl Condition {
+    pub fn new(column: impl Into<String>, function: Function, value: Value) -> Self {
+        Condition(column.into(), function, value)
+    }
+
+    /// Match the row with the given UUID.
+    pub fn uuid(uuid: Uuid) -> Self {
+        Condition::new("_uuid", Function::Equal, serde_json::json!(["uuid", uuid]))
+    }
+
+    /// Returns whether the row, an object of columns in the JSON format of
+    /// OVSDB, matches the condition as the server would evaluate it.
+    ///
+    /// A row without the column, or an ordering of values which are not
+    /// numbers, does not match.
+    pub fn matches(&self, row: &Value) -> bool {
+        let Condition(column, function, arg) = self;
+        let Some(value) = row.get(column) else {
+            return false;
+        };
+
+        let (value_atoms, arg_atoms) = (atoms(value), atoms(arg));
+        let compare = |f: fn(f64, f64) -> bool| match (value.as_f64(), arg.as_f64()) {
+            (Some(a), Some(b)) => f(a, b),
+            _ => false,
+        };
+
+        match function {
+            Function::Equal => value_atoms == arg_atoms,
+            Function::NotEqual => value_atoms != arg_atoms,
+            Function::LessThan => compare(|a, b| a < b),
+            Function::LessThanOrEqual => compare(|a, b| a <= b),
+            Function::GreaterThan => compare(|a, b| a > b),
+            Function::GreaterThanOrEqual => compare(|a, b| a >= b),
+            Function::Includes => arg_atoms.iter().all(|atom| value_atoms.contains(atom)),
+            Function::Excludes => arg_atoms.iter().all(|atom| !value_atoms.contains(atom)),
+        }
+    }
+}
+
+/// Returns the sorted atoms of a value, or its key/value pairs for a map, so
+/// that a set of one element equals the element itself.
+fn atoms(value: &Value) -> Vec<Value> {
+    let mut atoms = match value.as_array().map(Vec::as_slice) {
+        Some([Value::String(tag), Value::Array(items)]) if tag == "set" || tag == "map" => {
+            items.clone()
+        }
+        _ => vec![value.clone()],
+    };
+    atoms.sort_by_key(Value::to_string);
+    atoms.dedup();
+    atoms
+}
+
+/// 5.2.  Operations
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Operation {
+    Insert {
+        table: String,
+        row: HashMap<String, Value>,
+        #[serde(rename = "uuid-name", skip_serializing_if = "Option::is_none")]
+        uuid_name: Option<String>,
+    },
+    Select {
+        table: String,
+        #[serde(rename = "where")]
+        conditions: Vec<Condition>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        columns: Option<Vec<String>>,
+    },
+    Update {
+        table: String,
+        #[serde(rename = "where")]
+        conditions: Vec<Condition>,
+        row: HashMap<String, Value>,
+    },
+    Mutate {
+        table: String,
+        #[serde(rename = "where")]
+        conditions: Vec<Condition>,
+        mutations: Vec<Mutation>,
+    },
+    Delete {
+        table: String,
+        #[serde(rename = "where")]
+        conditions: Vec<Condition>,
+    },
+    Comment {
+        comment: String,
+    },
+}
+
+/// The result of a single operation, only containing the members relevant
+/// to that operation.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct OperationResult {
+    pub uuid: Option<Value>,
+
+    pub rows: Option<Vec<HashMap<String, Value>>>,
+
+    pub count: Option<u64>,
+
+    pub error: Option<String>,
+
+    pub details: Option<String>,
+
+    pub syntax: Option<String>,
+}
+
+impl OperationResult {
+    /// Returns the UUID of the row created by an `insert` operation.
+    pub fn uuid(&self) -> Option<Uuid> {
+        self.uuid.as_ref().and_then(extract_uuid)
+    }
+}
+
+/// Check the results of a transaction, failing with the first error.
+///
+/// The server replies with one result per operation, followed by an extra
+/// error result when the transaction fails to commit.
+pub fn check_results(results: Vec<Option<OperationResult>>) -> Result<Vec<OperationResult>, Error> {
+    results
+        .into_iter()
+        .map(|result| {
+            let result = result.unwrap_or_default();
+            match result.error {
+                Some(error) => Err(Error::Rpc(OvsdbError::new(
+                    &error,
+                    result.details,
+                    result.syntax,
+                ))),
+                None => Ok(result),
+            }
+        })
+        .collect()
+}
+
+/// 4.1.3.  Transact
+pub trait TransactClient {
+    /// Run the operations atomically against the database, returning their
+    /// results in order.
+    fn transact(
+        &self,
+        db_name: &str,
+        operations: Vec<Operation>,
+    ) -> impl Future<Output = Result<Vec<OperationResult>, Error>> + Send;
+}
+
+impl<C: ClientT + Sync> TransactClient for C {
+    async fn transact(
+        &self,
+        db_name: &str,
+        operations: Vec<Operation>,
+    ) -> Result<Vec<OperationResult>, Error> {
+        // NOTE(mnaser): The operations are positional parameters following the
+        //               database name, not a nested array.
+        let mut params = ArrayParams::new();
+        params.insert(db_name)?;
+        for operation in &operations {
+            params.insert(operation)?;
+        }
+
+        let request = self.request("transact", params);
+        let results: Vec<Option<OperationResult>> =
+            trace::request("transact", Some(db_name), request).await?;
+        check_results(results)
+    }
+}
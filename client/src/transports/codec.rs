@@ -1,37 +1,124 @@
-use bytes::{BufMut, BytesMut};
+use crate::trace;
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use serde_json::Value;
 use std::io;
 use tokio_util::codec::{Decoder, Encoder};
 
-pub struct JsonCodec;
+/// Frames the JSON messages exchanged with OVSDB, which are written back to
+/// back without any delimiter.
+#[derive(Default)]
+pub struct JsonCodec {
+    /// The number of bytes of the next message scanned so far
+    scanned: usize,
 
-impl Encoder<BytesMut> for JsonCodec {
+    /// The number of objects and arrays opened and not closed yet
+    depth: usize,
+
+    /// Whether the scan stopped within a string
+    in_string: bool,
+
+    /// Whether the scan stopped after a backslash within a string
+    escaped: bool,
+
+    /// The length in bytes past which a message is rejected
+    max_length: Option<usize>,
+}
+
+impl JsonCodec {
+    /// Reject the messages longer than the given number of bytes, instead
+    /// of buffering them whatever their size.
+    pub fn with_max_length(max_length: usize) -> Self {
+        JsonCodec {
+            max_length: Some(max_length),
+            ..Self::default()
+        }
+    }
+
+    fn check_length(&self, length: usize) -> Result<(), io::Error> {
+        match self.max_length {
+            Some(max_length) if length > max_length => {
+                Err(invalid(format!("message longer than {max_length} bytes")))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Serializes the messages straight into the write buffer of the connection,
+/// which is reused from one message to the next.
+impl Encoder<&Value> for JsonCodec {
     type Error = io::Error;
 
-    fn encode(&mut self, data: BytesMut, buf: &mut BytesMut) -> Result<(), io::Error> {
-        buf.reserve(data.len());
-        buf.put(data);
+    fn encode(&mut self, message: &Value, buf: &mut BytesMut) -> Result<(), io::Error> {
+        let start = buf.len();
+        serde_json::to_writer(buf.writer(), message)?;
+        trace::sent(message, buf.len() - start);
+
         Ok(())
     }
 }
 
+/// Splits the bytes read into a frame per message, leaving them to be
+/// parsed once framed.
+///
+/// The messages are objects or arrays, whose end is found by tracking the
+/// nesting of brackets outside of strings. The scan resumes where it stopped
+/// as more bytes are read, so large messages are scanned only once.
 impl Decoder for JsonCodec {
-    type Item = Value;
+    type Item = Bytes;
     type Error = io::Error;
 
-    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Value>, io::Error> {
-        if src.is_empty() {
-            return Ok(None);
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Bytes>, io::Error> {
+        if self.scanned == 0 {
+            let whitespace = src.iter().take_while(|b| b.is_ascii_whitespace()).count();
+            src.advance(whitespace);
+
+            match src.first() {
+                None => return Ok(None),
+                Some(b'{' | b'[') => {}
+                Some(_) => return Err(invalid("expected a JSON object or array")),
+            }
         }
 
-        match serde_json::from_slice::<Value>(src) {
-            Ok(val) => {
-                src.clear();
+        // NOTE(mnaser): The server can write several messages at once, such as a
+        //               reply followed by an update notification, so only the
+        //               first one is consumed from the buffer.
+        for (offset, &byte) in src.iter().enumerate().skip(self.scanned) {
+            if self.in_string {
+                match byte {
+                    _ if self.escaped => self.escaped = false,
+                    b'\\' => self.escaped = true,
+                    b'"' => self.in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+
+            match byte {
+                b'"' => self.in_string = true,
+                b'{' | b'[' => self.depth += 1,
+                b'}' | b']' => {
+                    self.depth -= 1;
+                    if self.depth == 0 {
+                        self.check_length(offset + 1)?;
+                        self.scanned = 0;
+                        trace::decoded(offset + 1);
 
-                Ok(Some(val))
+                        return Ok(Some(src.split_to(offset + 1).freeze()));
+                    }
+                }
+                _ => {}
             }
-            Err(ref e) if e.is_eof() => Ok(None),
-            Err(e) => Err(e.into()),
         }
+
+        self.check_length(src.len())?;
+        self.scanned = src.len();
+        Ok(None)
     }
 }
+
+fn invalid(message: impl std::fmt::Display) -> io::Error {
+    let error = serde::de::Error::custom(message);
+    trace::decode_error(&error);
+    error.into()
+}
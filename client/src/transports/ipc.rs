@@ -1,18 +1,38 @@
-use crate::transports::{Receiver, Sender, codec::JsonCodec};
-use futures_util::stream::StreamExt;
+use crate::{
+    Error,
+    rpc::ConnectOptions,
+    transports::{self, OvsdbTransport, Transport},
+};
 use jsonrpsee::core::client::{TransportReceiverT, TransportSenderT};
-use std::{io::Error, path::Path};
+use std::path::{Path, PathBuf};
 use tokio::net::UnixStream;
-use tokio_util::codec::Framed;
 
-pub async fn connect(
-    socket: impl AsRef<Path>,
-) -> Result<(impl TransportSenderT + Send, impl TransportReceiverT + Send), Error> {
-    let connection = UnixStream::connect(socket).await?;
-    let (sink, stream) = Framed::new(connection, JsonCodec).split();
+/// Connects to the Unix socket at a path
+pub struct UnixTransport {
+    path: PathBuf,
+}
+
+impl UnixTransport {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        UnixTransport { path: path.into() }
+    }
+}
 
-    let sender = Sender { inner: sink };
-    let receiver = Receiver { inner: stream };
+impl OvsdbTransport for UnixTransport {
+    type Connection = UnixStream;
 
-    Ok((sender, receiver))
+    fn remote(&self) -> String {
+        format!("unix:{}", self.path.display())
+    }
+
+    async fn connect(&self) -> Result<UnixStream, std::io::Error> {
+        UnixStream::connect(&self.path).await
+    }
+}
+
+pub async fn connect(
+    socket: impl AsRef<Path>,
+    options: &ConnectOptions,
+) -> Result<Transport<impl TransportSenderT + Send, impl TransportReceiverT + Send>, Error> {
+    transports::connect(&UnixTransport::new(socket.as_ref()), options).await
 }
@@ -0,0 +1,145 @@
+use crate::transports::{self, codec::JsonCodec};
+use futures_util::{SinkExt, stream::StreamExt};
+use serde_json::Value;
+use std::{io, net::IpAddr, path::Path};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadHalf, WriteHalf},
+    net::{TcpListener, UnixListener},
+};
+use tokio_util::codec::{FramedRead, FramedWrite};
+
+#[derive(Debug, Error)]
+pub enum ListenerError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("Invalid remote {0:?}, expected punix:<path> or ptcp:<port>[:<ip>]")]
+    InvalidRemote(String),
+}
+
+/// A connection accepted by a [`Listener`] or wrapped with [`framed`]
+pub trait Connection: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Connection for T {}
+
+/// Sends JSON-RPC messages to the peer as they are, without the JSON-RPC 2.0
+/// rewriting done for clients
+pub struct MessageSender {
+    inner: FramedWrite<WriteHalf<Box<dyn Connection>>, JsonCodec>,
+}
+
+impl MessageSender {
+    pub async fn send(&mut self, message: &Value) -> io::Result<()> {
+        self.inner.send(message).await
+    }
+
+    pub async fn close(&mut self) -> io::Result<()> {
+        SinkExt::<&Value>::close(&mut self.inner).await
+    }
+}
+
+/// Receives the JSON-RPC messages sent by the peer
+pub struct MessageReceiver {
+    inner: FramedRead<ReadHalf<Box<dyn Connection>>, JsonCodec>,
+}
+
+impl MessageReceiver {
+    /// Returns the next message, or `None` once the peer disconnected.
+    pub async fn receive(&mut self) -> Option<io::Result<Value>> {
+        let frame = self.inner.next().await?;
+        Some(frame.and_then(|frame| Ok(transports::decode(&frame)?)))
+    }
+}
+
+/// Split a connection into framed halves exchanging JSON-RPC messages.
+pub fn framed(connection: impl Connection + 'static) -> (MessageSender, MessageReceiver) {
+    let connection: Box<dyn Connection> = Box::new(connection);
+    let (read, write) = tokio::io::split(connection);
+
+    (
+        MessageSender {
+            inner: FramedWrite::new(write, JsonCodec::default()),
+        },
+        MessageReceiver {
+            inner: FramedRead::new(read, JsonCodec::default()),
+        },
+    )
+}
+
+/// Accepts incoming connections on a passive remote
+pub enum Listener {
+    Tcp(TcpListener),
+    Unix(UnixListener),
+}
+
+impl Listener {
+    /// Listen on a passive remote, as accepted by `ovsdb-server --remote`,
+    /// such as `punix:/var/run/openvswitch/db.sock`, `ptcp:6641` or
+    /// `ptcp:6641:127.0.0.1`.
+    pub async fn bind(remote: &str) -> Result<Self, ListenerError> {
+        let invalid = || ListenerError::InvalidRemote(remote.to_owned());
+
+        match remote.split_once(':').ok_or_else(invalid)? {
+            ("punix", path) if !path.is_empty() => Self::bind_unix(path),
+            ("ptcp", target) => {
+                let (port, ip) = target.split_once(':').unwrap_or((target, "0.0.0.0"));
+                let port: u16 = port.parse().map_err(|_| invalid())?;
+                let ip: IpAddr = ip
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .parse()
+                    .map_err(|_| invalid())?;
+
+                Ok(Listener::Tcp(TcpListener::bind((ip, port)).await?))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Listen on a Unix socket.
+    pub fn bind_unix(path: impl AsRef<Path>) -> Result<Self, ListenerError> {
+        Ok(Listener::Unix(UnixListener::bind(path)?))
+    }
+
+    /// Wait for the next connection, returning it with a description of the
+    /// peer such as `tcp:127.0.0.1:40312` or `unix:`.
+    pub async fn accept_connection(&self) -> io::Result<(Box<dyn Connection>, String)> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, addr) = listener.accept().await?;
+                Ok((Box::new(stream), format!("tcp:{addr}")))
+            }
+            Listener::Unix(listener) => {
+                let (stream, addr) = listener.accept().await?;
+                let path = addr
+                    .as_pathname()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default();
+                Ok((Box::new(stream), format!("unix:{path}")))
+            }
+        }
+    }
+
+    /// Wait for the next connection, returning its framed halves.
+    pub async fn accept(&self) -> io::Result<(MessageSender, MessageReceiver)> {
+        let (connection, _) = self.accept_connection().await?;
+        Ok(framed(connection))
+    }
+
+    /// Returns the remote to connect to this listener, such as
+    /// `tcp:127.0.0.1:6641`, which is useful after binding to port 0.
+    pub fn local_remote(&self) -> io::Result<String> {
+        match self {
+            Listener::Tcp(listener) => Ok(format!("tcp:{}", listener.local_addr()?)),
+            Listener::Unix(listener) => {
+                let addr = listener.local_addr()?;
+                let path = addr
+                    .as_pathname()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default();
+                Ok(format!("unix:{path}"))
+            }
+        }
+    }
+}
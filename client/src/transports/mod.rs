@@ -1,41 +1,450 @@
-mod codec;
+pub mod codec;
 pub mod ipc;
+pub mod listener;
+pub mod stream;
+pub mod tap;
 pub mod tcp;
+#[cfg(all(feature = "vsock", target_os = "linux"))]
+pub mod vsock;
 
-use bytes::BytesMut;
-use futures_util::{Sink, SinkExt, Stream, stream::StreamExt};
+use crate::{
+    Error,
+    cache::SchemaCache,
+    rpc::{ConnectOptions, ConnectionEvent},
+    trace,
+};
+use bytes::Bytes;
+use codec::JsonCodec;
+use futures_util::{SinkExt, stream::StreamExt};
 use jsonrpsee::core::{
     async_trait,
     client::{ReceivedMessage, TransportReceiverT, TransportSenderT},
 };
-use serde_json::{Value, json};
-use thiserror::Error;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json, value::RawValue};
+use std::{borrow::Cow, future::Future, sync::Arc};
+use tap::{Direction, Tap};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::{Mutex, mpsc, oneshot, watch},
+    time::timeout,
+};
+use tokio_util::codec::{FramedRead, FramedWrite};
+
+/// The JSON-RPC 2.0 error code given to the errors of the remote
+const SERVER_ERROR: i32 = -32000;
+
+/// The reply to a monitor request whose initial rows are handed over
+const NO_ROWS: &str = "{}";
+
+/// Returns the first parameter of a notification, such as the id of the
+/// monitor of an update or the name of a lock.
+fn first_param(params: &RawValue) -> Result<Value, serde_json::Error> {
+    let params: Vec<&RawValue> = serde_json::from_str(params.get())?;
+    params
+        .first()
+        .map_or(Ok(Value::Null), |id| serde_json::from_str(id.get()))
+}
+
+/// The id of the "echo" requests sent by the inactivity probe, as used by
+/// Open vSwitch
+const PROBE_ID: &str = "echo";
+
+/// The write half of a connection, shared by the sender with the receiver,
+/// which answers the "echo" requests of the server and sends the probes
+struct Writer {
+    inner: Mutex<FramedWrite<Box<dyn AsyncWrite + Send + Unpin>, JsonCodec>>,
+
+    tap: Option<Tap>,
+}
+
+impl Writer {
+    async fn write(&self, message: &Value) -> Result<(), Error> {
+        tap::record(&self.tap, Direction::Outbound, message);
+        self.inner.lock().await.send(message).await?;
+
+        Ok(())
+    }
+
+    async fn close(&self) -> Result<(), std::io::Error> {
+        SinkExt::<&Value>::close(&mut *self.inner.lock().await).await
+    }
+}
+
+/// Decode a payload cut from a message of the server by its [`Incoming`]
+/// envelope, such as the rows of an update notification.
+///
+/// With the `simd-json` feature, the payload is decoded by simd-json, falling
+/// back to serde_json for those it rejects so that their errors stay those
+/// of serde_json.
+pub(crate) fn decode<T: DeserializeOwned>(payload: &[u8]) -> Result<T, serde_json::Error> {
+    #[cfg(feature = "simd-json")]
+    if let Ok(value) = simd_json::serde::from_slice(&mut payload.to_vec()) {
+        return Ok(value);
+    }
+
+    serde_json::from_slice(payload)
+}
+
+/// A message read from the server, whose members are borrowed from its frame
+/// so that the payload is only parsed once, by jsonrpsee
+//
+// NOTE(mnaser): The envelope is always read by serde_json, as simd-json can not
+//               borrow members as `RawValue`s. It only skims the frame, the
+//               payloads it cuts being decoded with `decode`.
+#[derive(Deserialize)]
+pub(crate) struct Incoming<'a> {
+    #[serde(default)]
+    pub(crate) id: Value,
+
+    #[serde(borrow, default)]
+    pub(crate) method: Option<Cow<'a, str>>,
+
+    #[serde(borrow, default)]
+    pub(crate) params: Option<&'a RawValue>,
+
+    #[serde(borrow, default)]
+    pub(crate) result: Option<&'a RawValue>,
+
+    #[serde(borrow, default)]
+    pub(crate) error: Option<&'a RawValue>,
+}
+
+/// The JSON-RPC 2.0 rewriting of an incoming message, as expected by
+/// jsonrpsee
+#[derive(Serialize)]
+struct Rewritten<'a> {
+    jsonrpc: &'static str,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<&'a Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    method: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<&'a RawValue>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<&'a RawValue>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorObject<'a>>,
+}
+
+#[derive(Serialize)]
+struct ErrorObject<'a> {
+    code: i32,
+
+    message: &'a str,
+
+    data: &'a RawValue,
+}
+
+/// What a connection holds on the server, shared by its halves and its
+/// client
+#[derive(Default)]
+pub(crate) struct Session {
+    /// The ids of the monitors created, to cancel them on shutdown
+    pub(crate) monitors: std::sync::Mutex<Vec<Value>>,
+
+    /// The names of the locks requested, to release them on shutdown
+    pub(crate) locks: std::sync::Mutex<Vec<String>>,
+
+    /// The names of the locks a session requested again after reconnecting,
+    /// until they are granted
+    pub(crate) reacquiring: std::sync::Mutex<Vec<String>>,
+
+    /// The number of requests waiting for their reply
+    pub(crate) pending: watch::Sender<usize>,
+
+    /// Set once the client shuts the connection down
+    pub(crate) closed: watch::Sender<bool>,
+
+    /// The schemas fetched over the connection, dropped when they change
+    pub(crate) schemas: SchemaCache,
+
+    /// The monitors whose initial rows are handed over as they were received
+    snapshots: std::sync::Mutex<Vec<PendingSnapshot>>,
+
+    /// The monitors whose notifications are handed over to their own stream
+    /// rather than to the subscriptions of jsonrpsee, by id
+    routes: std::sync::Mutex<Vec<(Value, mpsc::UnboundedSender<Bytes>)>>,
+}
+
+/// A monitor whose initial rows are expected, along with its request once
+/// sent
+struct PendingSnapshot {
+    monitor: Value,
+
+    request: Option<Value>,
+
+    rows: oneshot::Sender<Bytes>,
+}
+
+impl Session {
+    fn sent(&self, message: &Value) {
+        let Some(method) = message["method"].as_str() else {
+            return;
+        };
+        if !message["id"].is_null() {
+            self.pending.send_modify(|pending| *pending += 1);
+        }
+
+        let params = &message["params"];
+        match method {
+            "monitor" | "monitor_cond" | "monitor_cond_since" => {
+                // NOTE(mnaser): Requests without a monitor id, such as the probes of
+                //               `RpcClient::server_supports`, create no monitor.
+                let Some(monitor) = params.get(1) else {
+                    return;
+                };
+
+                let mut monitors = self.monitors.lock().unwrap();
+                if !monitors.contains(monitor) {
+                    monitors.push(monitor.clone());
+                }
+
+                let mut snapshots = self.snapshots.lock().unwrap();
+                if let Some(snapshot) = snapshots
+                    .iter_mut()
+                    .find(|snapshot| snapshot.request.is_none() && snapshot.monitor == *monitor)
+                {
+                    snapshot.request = Some(message["id"].clone());
+                }
+            }
+            "monitor_cond_change" if params[0] != params[1] => {
+                let mut monitors = self.monitors.lock().unwrap();
+                if let Some(monitor) = monitors.iter_mut().find(|id| **id == params[0]) {
+                    *monitor = params[1].clone();
+                }
+
+                let mut routes = self.routes.lock().unwrap();
+                if let Some((monitor, _)) = routes.iter_mut().find(|(id, _)| *id == params[0]) {
+                    *monitor = params[1].clone();
+                }
+            }
+            "monitor_cancel" => {
+                self.monitors.lock().unwrap().retain(|id| *id != params[0]);
+                self.unroute(&params[0]);
+            }
+            "lock" | "steal" => {
+                let mut locks = self.locks.lock().unwrap();
+                if let Some(lock) = params[0]
+                    .as_str()
+                    .filter(|lock| !locks.iter().any(|l| l == lock))
+                {
+                    locks.push(lock.to_owned());
+                }
+            }
+            "unlock" => self.locks.lock().unwrap().retain(|lock| *lock != params[0]),
+            "convert" => {
+                if let Some(db_name) = params[0].as_str() {
+                    self.schemas.invalidate(db_name);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn replied(&self) {
+        self.pending
+            .send_modify(|pending| *pending = pending.saturating_sub(1));
+    }
+
+    /// Stop waiting for the lock to be granted again, returning whether it
+    /// was waited for.
+    pub(crate) fn reacquired(&self, lock: &str) -> bool {
+        let mut reacquiring = self.reacquiring.lock().unwrap();
+        let waited = reacquiring.iter().any(|l| l == lock);
+        reacquiring.retain(|l| l != lock);
+
+        waited
+    }
+
+    /// Returns the channel receiving the initial rows of the monitor, taken
+    /// out of the reply to its next request.
+    pub(crate) fn expect_snapshot(&self, monitor: Value) -> oneshot::Receiver<Bytes> {
+        let (rows, received) = oneshot::channel();
+        self.snapshots.lock().unwrap().push(PendingSnapshot {
+            monitor,
+            request: None,
+            rows,
+        });
+
+        received
+    }
+
+    /// Stop expecting the initial rows of the monitor, its request failed.
+    pub(crate) fn forget_snapshot(&self, monitor: &Value) {
+        self.snapshots
+            .lock()
+            .unwrap()
+            .retain(|snapshot| snapshot.monitor != *monitor);
+    }
+
+    /// Returns the channel receiving the parameters of the notifications of
+    /// the monitor, until it is canceled or the connection is lost, none if
+    /// another stream receives them.
+    pub(crate) fn route(&self, monitor: Value) -> Option<mpsc::UnboundedReceiver<Bytes>> {
+        let mut routes = self.routes.lock().unwrap();
+        routes.retain(|(_, notifications)| !notifications.is_closed());
+        if routes.iter().any(|(id, _)| *id == monitor) {
+            return None;
+        }
+
+        let (notifications, received) = mpsc::unbounded_channel();
+        routes.push((monitor, notifications));
+
+        Some(received)
+    }
+
+    /// Stop handing over the notifications of the monitor, ending its stream.
+    pub(crate) fn unroute(&self, monitor: &Value) {
+        self.routes.lock().unwrap().retain(|(id, _)| id != monitor);
+    }
+
+    /// Hand the parameters of an update notification over to the stream of
+    /// its monitor, returning whether it had one.
+    fn route_update(&self, frame: &Bytes, params: &RawValue) -> Result<bool, Error> {
+        let mut routes = self.routes.lock().unwrap();
+        if routes.is_empty() {
+            return Ok(false);
+        }
+
+        let monitor = first_param(params)?;
+        let Some(index) = routes.iter().position(|(id, _)| *id == monitor) else {
+            return Ok(false);
+        };
+
+        // NOTE(mnaser): The stream of a monitor which was dropped no longer needs its
+        //               notifications, which are dropped as well.
+        if routes[index]
+            .1
+            .send(frame.slice_ref(params.get().as_bytes()))
+            .is_err()
+        {
+            routes.swap_remove(index);
+        }
+
+        Ok(true)
+    }
+
+    fn snapshot(&self, request: &Value) -> Option<oneshot::Sender<Bytes>> {
+        let mut snapshots = self.snapshots.lock().unwrap();
+        let index = snapshots
+            .iter()
+            .position(|snapshot| snapshot.request.as_ref() == Some(request))?;
+
+        Some(snapshots.swap_remove(index).rows)
+    }
+}
+
+/// A way of reaching an OVSDB server, such as an SSH tunnel, an in-memory
+/// stream or a vsock, for `ConnectOptions::connect_transport`
+///
+/// The transport only establishes the stream of bytes, the framing of the
+/// messages, the inactivity probe and the session of the connection being
+/// handled by the client as for the transports of this crate.
+pub trait OvsdbTransport: Send + Sync {
+    /// The stream of an established connection
+    type Connection: AsyncRead + AsyncWrite + Send + Unpin + 'static;
 
-#[derive(Debug, Error)]
-enum TransportError {
-    #[error("Connection closed.")]
-    ConnectionClosed,
+    /// Describe the remote, such as `unix:/var/run/ovn/ovnnb_db.sock`, in
+    /// the connection events and traces.
+    fn remote(&self) -> String;
 
-    #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+    /// Establish a new connection, which is done again on every attempt to
+    /// reconnect.
+    fn connect(&self) -> impl Future<Output = Result<Self::Connection, std::io::Error>> + Send;
 
-    #[error("Unkown error: {0}")]
-    Unknown(String),
+    /// Describe the remote reached by the connection, which is the one of
+    /// the transport unless it knows better, such as the address a host
+    /// name resolved to.
+    fn connected(&self, connection: &Self::Connection) -> String {
+        let _ = connection;
+        self.remote()
+    }
+}
+
+/// Connect through the transport and frame the halves of the connection
+/// into those used by jsonrpsee.
+pub(crate) async fn connect<T: OvsdbTransport>(
+    transport: &T,
+    options: &ConnectOptions,
+) -> Result<Transport<impl TransportSenderT + Send, impl TransportReceiverT + Send>, Error> {
+    let connection = transport
+        .connect()
+        .await
+        .inspect_err(|error| trace::connect(transport.remote(), Some(error)))?;
+    let remote = transport.connected(&connection);
+    trace::connect(&remote, None);
+    options.emit(ConnectionEvent::Connected { remote });
+    let (read, write) = tokio::io::split(connection);
+
+    Ok(split(read, write, options))
+}
+
+/// A connection split into the halves driven by the JSON-RPC client
+pub struct Transport<S, R> {
+    /// Writes the requests of the client
+    pub sender: S,
+
+    /// Reads the replies and notifications of the server
+    pub receiver: R,
+
+    pub(crate) session: Arc<Session>,
+}
+
+/// Frame the halves of a connection into those used by jsonrpsee.
+fn split(
+    read: impl AsyncRead + Send + Unpin + 'static,
+    write: impl AsyncWrite + Send + Unpin + 'static,
+    options: &ConnectOptions,
+) -> Transport<impl TransportSenderT + Send, impl TransportReceiverT + Send> {
+    let read: Box<dyn AsyncRead + Send + Unpin> = Box::new(read);
+    let write: Box<dyn AsyncWrite + Send + Unpin> = Box::new(write);
+    let writer = Arc::new(Writer {
+        inner: Mutex::new(FramedWrite::new(write, JsonCodec::default())),
+        tap: options.tap.clone(),
+    });
+
+    let session = Arc::new(Session::default());
+
+    let sender = Sender {
+        writer: writer.clone(),
+        session: session.clone(),
+    };
+    let codec = match options.max_message_size {
+        Some(max_length) => JsonCodec::with_max_length(max_length),
+        None => JsonCodec::default(),
+    };
+    let receiver = Receiver {
+        inner: FramedRead::new(read, codec),
+        writer,
+        options: options.clone(),
+        session: session.clone(),
+    };
+
+    Transport {
+        sender,
+        receiver,
+        session,
+    }
 }
 
-struct Sender<T: Send + Sink<BytesMut>> {
-    inner: T,
+struct Sender {
+    writer: Arc<Writer>,
+
+    session: Arc<Session>,
 }
 
 #[async_trait]
-impl<T: Send + Sink<BytesMut, Error = impl std::error::Error> + Unpin + 'static> TransportSenderT
-    for Sender<T>
-{
-    type Error = TransportError;
+impl TransportSenderT for Sender {
+    type Error = Error;
 
     async fn send(&mut self, body: String) -> Result<(), Self::Error> {
-        let mut message: Value =
-            serde_json::from_str(&body).map_err(|e| TransportError::Unknown(e.to_string()))?;
+        let mut message: Value = serde_json::from_str(&body)?;
 
         // NOTE(mnaser): In order to be able to use the subscription client, we need to
         //               drop the subscription message for the "update" method, as the
@@ -55,61 +464,202 @@ impl<T: Send + Sink<BytesMut, Error = impl std::error::Error> + Unpin + 'static>
             message["params"] = json!([]);
         }
 
-        self.inner
-            .send(BytesMut::from(message.to_string().as_str()))
-            .await
-            .map_err(|e| TransportError::Unknown(e.to_string()))?;
-
-        Ok(())
+        self.session.sent(&message);
+        self.writer.write(&message).await
     }
 
     async fn close(&mut self) -> Result<(), Self::Error> {
-        self.inner
-            .close()
-            .await
-            .map_err(|e| TransportError::Unknown(e.to_string()))?;
+        self.writer.close().await?;
 
         Ok(())
     }
 }
 
-struct Receiver<T: Send + Stream> {
-    inner: T,
+struct Receiver {
+    inner: FramedRead<Box<dyn AsyncRead + Send + Unpin>, JsonCodec>,
+
+    writer: Arc<Writer>,
+
+    options: ConnectOptions,
+
+    session: Arc<Session>,
+}
+
+impl Receiver {
+    /// Returns the next message, probing the server with an "echo" request
+    /// once the connection has been idle for the inactivity probe interval,
+    /// and failing if it stays idle for another interval.
+    async fn next(&mut self) -> Result<Bytes, Error> {
+        let Some(interval) = self.options.inactivity_probe else {
+            return message(self.read().await);
+        };
+
+        if let Ok(next) = timeout(interval, self.read()).await {
+            return message(next);
+        }
+
+        let probe = json!({"id": PROBE_ID, "method": "echo", "params": []});
+        self.writer.write(&probe).await?;
+
+        match timeout(interval, self.read()).await {
+            Ok(next) => message(next),
+            Err(_) => Err(Error::Inactive(interval * 2)),
+        }
+    }
+
+    /// Returns the next message, or nothing once the client shut the
+    /// connection down.
+    async fn read(&mut self) -> Option<Result<Bytes, std::io::Error>> {
+        let mut closed = self.session.closed.subscribe();
+        tokio::select! {
+            next = self.inner.next() => return next,
+            _ = closed.wait_for(|closed| *closed) => {}
+        }
+
+        let _ = self.writer.close().await;
+        None
+    }
+}
+
+fn message(next: Option<Result<Bytes, std::io::Error>>) -> Result<Bytes, Error> {
+    match next {
+        None => Err(Error::ConnectionClosed),
+        Some(Ok(message)) => Ok(message),
+        Some(Err(e)) => Err(receive_error(e)),
+    }
 }
 
 #[async_trait]
-impl<T: Send + Stream<Item = Result<Value, std::io::Error>> + Unpin + 'static> TransportReceiverT
-    for Receiver<T>
-{
-    type Error = TransportError;
+impl TransportReceiverT for Receiver {
+    type Error = Error;
 
     async fn receive(&mut self) -> Result<ReceivedMessage, Self::Error> {
-        match self.inner.next().await {
-            None => Err(TransportError::ConnectionClosed),
-            Some(Ok(mut message)) => {
-                // NOTE(mnaser): jsonrpsee runs using JSON-RPC 2.0 only which the remote doesn't
-                //               support, so we intercept the message, add "jsonrpc" and then
-                //               send the message.
-                message
-                    .as_object_mut()
-                    .map(|obj| obj.insert("jsonrpc".to_string(), json!("2.0")));
-
-                // NOTE(mnaser): jsonrpsee expects no error field if there is a result, due to the
-                //               remote not supporting JSON-RPC 2.0, we need to remove the "error"
-                //               field if there is a "result" field.
-                if message.as_object().unwrap().contains_key("result") {
-                    message.as_object_mut().map(|obj| obj.remove("error"));
-                }
+        self.rewrite().await.inspect_err(|error| {
+            self.session.routes.lock().unwrap().clear();
+            trace::disconnected(error);
+            self.options.emit(ConnectionEvent::Disconnected {
+                reason: error.to_string(),
+            });
+        })
+    }
+}
+
+impl Receiver {
+    /// Read the next message and rewrite it for jsonrpsee, handling those
+    /// which are not meant for it.
+    async fn rewrite(&mut self) -> Result<ReceivedMessage, Error> {
+        let frame = self.next().await?;
+        tap::record_frame(&self.options.tap, Direction::Inbound, &frame);
+
+        let message: Incoming = serde_json::from_slice(&frame).inspect_err(trace::decode_error)?;
+        trace::received(&message);
 
-                // NOTE(mnaser): If a message comes in with it's "id" field set to null, then
-                //               we remove it.
-                if message.as_object().unwrap().contains_key("id") && message["id"] == json!(null) {
-                    message.as_object_mut().map(|obj| obj.remove("id"));
+        let method = message.method.as_deref();
+        if method == Some("monitor_canceled") {
+            // NOTE(mnaser): The notification does not tell which database changed, so
+            //               every schema is fetched again.
+            self.session.schemas.clear();
+            self.options.emit(ConnectionEvent::SchemaChanged);
+            if let Some(params) = message.params {
+                self.session.unroute(&first_param(params)?);
+            }
+        }
+        if let (Some("locked" | "stolen"), Some(params)) = (method, message.params) {
+            if let Some(lock) = first_param(params)?.as_str() {
+                if method == Some("stolen") {
+                    self.options.emit(ConnectionEvent::LockLost {
+                        lock: lock.to_owned(),
+                    });
+                } else if self.session.reacquired(lock) {
+                    self.options.emit(ConnectionEvent::LockReacquired {
+                        lock: lock.to_owned(),
+                    });
                 }
+            }
+        }
+        if let (Some("update" | "update2" | "update3"), Some(params)) = (method, message.params) {
+            if self.session.route_update(&frame, params)? {
+                return Ok(ReceivedMessage::Pong);
+            }
+        }
+
+        // NOTE(mnaser): The server probes idle clients with "echo" requests and drops
+        //               those which do not reply, while the replies to the probes of
+        //               the client have an id unknown to jsonrpsee, so both are handled
+        //               here and reported as pongs.
+        if method == Some("echo") && !message.id.is_null() {
+            let params = message.params.unwrap_or(RawValue::NULL);
+            let reply = json!({
+                "id": message.id,
+                "result": serde_json::from_str::<Value>(params.get())?,
+                "error": null,
+            });
+            self.writer.write(&reply).await?;
+            return Ok(ReceivedMessage::Pong);
+        }
+        if method.is_none() && message.id == PROBE_ID {
+            return Ok(ReceivedMessage::Pong);
+        }
+        if method.is_none() && !message.id.is_null() {
+            self.session.replied();
+        }
 
-                Ok(ReceivedMessage::Bytes(message.to_string().into_bytes()))
+        // NOTE(mnaser): jsonrpsee runs using JSON-RPC 2.0 only which the remote doesn't
+        //               support, so the message is rewritten with a "jsonrpc" member and
+        //               without the null "id" of notifications. Its payload is copied
+        //               as it is, for jsonrpsee to parse.
+        let mut rewritten = Rewritten {
+            jsonrpc: "2.0",
+            id: Some(&message.id).filter(|id| !id.is_null()),
+            method,
+            params: message.params,
+            result: None,
+            error: None,
+        };
+
+        // NOTE(mnaser): The remote sends both "result" and "error" in replies, while
+        //               jsonrpsee expects only one of them, with errors being objects
+        //               holding a "code" and a "message". The error of the remote is
+        //               kept as the "data" of the error.
+        let error = message
+            .error
+            .map(|data| serde_json::from_str::<Value>(data.get()))
+            .transpose()?;
+        if let (Some(error), Some(data)) = (&error, message.error) {
+            rewritten.error = Some(ErrorObject {
+                code: SERVER_ERROR,
+                message: error["error"]
+                    .as_str()
+                    .or(error.as_str())
+                    .unwrap_or("unknown error"),
+                data,
+            });
+        } else if method.is_none() {
+            rewritten.result = Some(message.result.unwrap_or(RawValue::NULL));
+        }
+
+        // NOTE(mnaser): The initial rows of a monitor can be too many to be held as
+        //               values, so those of the snapshots are handed over as they were
+        //               received, to be decoded one at a time, and the reply is left
+        //               without rows for jsonrpsee.
+        if let Some(rows) = self.session.snapshot(&message.id) {
+            if let (None, Some(result)) = (&error, message.result) {
+                let _ = rows.send(frame.slice_ref(result.get().as_bytes()));
+                rewritten.result = Some(serde_json::from_str(NO_ROWS)?);
             }
-            Some(Err(e)) => Err(TransportError::Io(e)),
         }
+
+        Ok(ReceivedMessage::Bytes(serde_json::to_vec(&rewritten)?))
+    }
+}
+
+/// Tell the messages the codec failed to parse apart from failures of the
+/// connection.
+fn receive_error(error: std::io::Error) -> Error {
+    match error.get_ref() {
+        Some(inner) if inner.is::<serde_json::Error>() => {
+            Error::Protocol(*error.into_inner().unwrap().downcast().unwrap())
+        }
+        _ => Error::Transport(error),
     }
 }
@@ -0,0 +1,18 @@
+use crate::{
+    rpc::{ConnectOptions, ConnectionEvent},
+    transports::{Transport, split},
+};
+use jsonrpsee::core::client::{TransportReceiverT, TransportSenderT};
+use tokio::io::{AsyncRead, AsyncWrite};
+
+pub fn connect(
+    connection: impl AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    options: &ConnectOptions,
+) -> Transport<impl TransportSenderT + Send, impl TransportReceiverT + Send> {
+    options.emit(ConnectionEvent::Connected {
+        remote: "stream:".to_owned(),
+    });
+    let (read, write) = tokio::io::split(connection);
+
+    split(read, write, options)
+}
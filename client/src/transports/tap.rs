@@ -0,0 +1,47 @@
+use serde_json::Value;
+use std::{sync::Arc, time::SystemTime};
+
+/// Which way a frame went over the connection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Written to the server
+    Outbound,
+
+    /// Read from the server
+    Inbound,
+}
+
+/// A message as it went over the connection, without the JSON-RPC 2.0
+/// rewriting done for jsonrpsee
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub timestamp: SystemTime,
+
+    pub direction: Direction,
+
+    pub message: Value,
+}
+
+/// Called with every frame of a connection, from its transport task, so it
+/// should hand the frame off rather than block
+pub type Tap = Arc<dyn Fn(&Frame) + Send + Sync>;
+
+pub(crate) fn record(tap: &Option<Tap>, direction: Direction, message: &Value) {
+    if let Some(tap) = tap {
+        tap(&Frame {
+            timestamp: SystemTime::now(),
+            direction,
+            message: message.clone(),
+        });
+    }
+}
+
+/// Record a frame as read, which is only parsed when there is a tap.
+pub(crate) fn record_frame(tap: &Option<Tap>, direction: Direction, frame: &[u8]) {
+    if let Some(message) = tap
+        .as_ref()
+        .and_then(|_| serde_json::from_slice(frame).ok())
+    {
+        record(tap, direction, &message);
+    }
+}
@@ -1,18 +1,123 @@
-use crate::transports::{Receiver, Sender, codec::JsonCodec};
-use futures_util::stream::StreamExt;
+use crate::{
+    Error,
+    rpc::ConnectOptions,
+    transports::{self, OvsdbTransport, Transport},
+};
 use jsonrpsee::core::client::{TransportReceiverT, TransportSenderT};
-use std::io::Error;
-use tokio::net::{TcpStream, ToSocketAddrs};
-use tokio_util::codec::Framed;
+use socket2::{SockRef, TcpKeepalive};
+use std::{
+    io,
+    net::{IpAddr, SocketAddr},
+};
+use tokio::net::{TcpSocket, TcpStream, ToSocketAddrs, lookup_host};
 
-pub async fn connect(
-    socket: impl ToSocketAddrs,
-) -> Result<(impl TransportSenderT + Send, impl TransportReceiverT + Send), Error> {
-    let connection = TcpStream::connect(socket).await?;
-    let (sink, stream) = Framed::new(connection, JsonCodec).split();
+/// The options of the sockets of TCP connections, left to the defaults of
+/// the system unless set
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TcpOptions {
+    pub(crate) keepalive: Option<TcpKeepalive>,
+
+    pub(crate) nodelay: Option<bool>,
+
+    pub(crate) bind_address: Option<IpAddr>,
+
+    pub(crate) dscp: Option<u8>,
+}
+
+impl TcpOptions {
+    /// Connect a socket set up with the options to the address.
+    async fn connect(&self, address: SocketAddr) -> io::Result<TcpStream> {
+        let socket = match address {
+            SocketAddr::V4(_) => TcpSocket::new_v4()?,
+            SocketAddr::V6(_) => TcpSocket::new_v6()?,
+        };
 
-    let sender = Sender { inner: sink };
-    let receiver = Receiver { inner: stream };
+        if let Some(keepalive) = &self.keepalive {
+            SockRef::from(&socket).set_tcp_keepalive(keepalive)?;
+        }
+        if let Some(nodelay) = self.nodelay {
+            socket.set_nodelay(nodelay)?;
+        }
+        // NOTE(mnaser): The DSCP takes the upper six bits of the traffic
+        //               class, the lower two being left to ECN.
+        if let Some(dscp) = self.dscp {
+            let class = u32::from(dscp) << 2;
+            match address {
+                SocketAddr::V4(_) => SockRef::from(&socket).set_tos(class)?,
+                SocketAddr::V6(_) => SockRef::from(&socket).set_tclass_v6(class)?,
+            }
+        }
+        if let Some(ip) = self.bind_address {
+            socket.bind(SocketAddr::new(ip, 0))?;
+        }
+
+        socket.connect(address).await
+    }
+}
+
+/// Connects to a TCP remote, whose address is resolved again on every
+/// attempt
+pub struct TcpTransport<A> {
+    address: A,
+
+    options: TcpOptions,
+}
 
-    Ok((sender, receiver))
+impl<A> TcpTransport<A> {
+    pub fn new(address: A) -> Self {
+        TcpTransport {
+            address,
+            options: TcpOptions::default(),
+        }
+    }
+
+    pub(crate) fn with_options(self, options: TcpOptions) -> Self {
+        TcpTransport { options, ..self }
+    }
+}
+
+impl<A: ToSocketAddrs + Clone + Send + Sync> OvsdbTransport for TcpTransport<A> {
+    type Connection = TcpStream;
+
+    fn remote(&self) -> String {
+        "tcp".to_owned()
+    }
+
+    /// Connect to the addresses the remote resolves to in turn, skipping
+    /// those of another family than the bind address.
+    async fn connect(&self) -> Result<TcpStream, io::Error> {
+        let mut last = None;
+        for address in lookup_host(self.address.clone()).await? {
+            if let Some(ip) = self.options.bind_address {
+                if ip.is_ipv4() != address.is_ipv4() {
+                    continue;
+                }
+            }
+            match self.options.connect(address).await {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last = Some(e),
+            }
+        }
+
+        Err(last.unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "could not resolve to any usable address",
+            )
+        }))
+    }
+
+    fn connected(&self, connection: &TcpStream) -> String {
+        connection
+            .peer_addr()
+            .map_or_else(|_| self.remote(), |address| format!("tcp:{address}"))
+    }
+}
+
+pub async fn connect(
+    socket: impl ToSocketAddrs + Clone + Send + Sync,
+    options: &ConnectOptions,
+) -> Result<Transport<impl TransportSenderT + Send, impl TransportReceiverT + Send>, Error> {
+    let transport = TcpTransport::new(socket).with_options(options.tcp.clone());
+    transports::connect(&transport, options).await
 }
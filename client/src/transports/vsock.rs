@@ -0,0 +1,106 @@
+//! Connections over `AF_VSOCK`, through which the agents of a guest reach an
+//! OVSDB server on its hypervisor without any IP networking.
+
+use crate::transports::OvsdbTransport;
+use socket2::{Domain, SockAddr, Socket, Type};
+use std::{
+    io::{self, Read, Write},
+    net::Shutdown,
+    pin::Pin,
+    task::{Context, Poll, ready},
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf, unix::AsyncFd};
+
+/// The context identifier of the hypervisor, as seen from its guests
+pub const VMADDR_CID_HOST: u32 = 2;
+
+/// Connects to a port of a virtual machine or of the host, given by its
+/// context identifier
+pub struct VsockTransport {
+    cid: u32,
+    port: u32,
+}
+
+impl VsockTransport {
+    pub fn new(cid: u32, port: u32) -> Self {
+        VsockTransport { cid, port }
+    }
+
+    /// Connect to a port of the hypervisor from one of its guests.
+    pub fn host(port: u32) -> Self {
+        Self::new(VMADDR_CID_HOST, port)
+    }
+}
+
+impl OvsdbTransport for VsockTransport {
+    type Connection = VsockStream;
+
+    fn remote(&self) -> String {
+        format!("vsock:{}:{}", self.cid, self.port)
+    }
+
+    async fn connect(&self) -> Result<VsockStream, io::Error> {
+        let address = SockAddr::vsock(self.cid, self.port);
+
+        // NOTE(mnaser): The connection is established in blocking mode, on a
+        //               thread of its own, since a vsock only ever connects to
+        //               the local hypervisor and so does it quickly.
+        let socket = tokio::task::spawn_blocking(move || {
+            let socket = Socket::new(Domain::VSOCK, Type::STREAM, None)?;
+            socket.connect(&address)?;
+            socket.set_nonblocking(true)?;
+            Ok::<_, io::Error>(socket)
+        })
+        .await
+        .map_err(io::Error::other)??;
+
+        Ok(VsockStream(AsyncFd::new(socket)?))
+    }
+}
+
+/// An established vsock connection
+pub struct VsockStream(AsyncFd<Socket>);
+
+impl AsyncRead for VsockStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            let mut guard = ready!(self.0.poll_read_ready(cx))?;
+            let unfilled = buf.initialize_unfilled();
+            match guard.try_io(|socket| socket.get_ref().read(unfilled)) {
+                Ok(result) => {
+                    buf.advance(result?);
+                    return Poll::Ready(Ok(()));
+                }
+                Err(_would_block) => continue,
+            }
+        }
+    }
+}
+
+impl AsyncWrite for VsockStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            let mut guard = ready!(self.0.poll_write_ready(cx))?;
+            match guard.try_io(|socket| socket.get_ref().write(buf)) {
+                Ok(result) => return Poll::Ready(result),
+                Err(_would_block) => continue,
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.0.get_ref().shutdown(Shutdown::Write))
+    }
+}
@@ -0,0 +1,257 @@
+use crate::schema::{AtomicType, BaseType, ColumnType, TableSchema};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ValidationError {
+    #[error("Column {column:?} does not exist in table")]
+    UnknownColumn { column: String },
+
+    #[error("Column {column:?} is immutable and cannot be updated")]
+    ImmutableColumn { column: String },
+
+    #[error("Column {column:?} expected {expected}, found {found}")]
+    TypeMismatch {
+        column: String,
+        expected: String,
+        found: Value,
+    },
+
+    #[error("Column {column:?} expected between {min} and {max} elements, found {found}")]
+    Cardinality {
+        column: String,
+        min: u64,
+        max: String,
+        found: usize,
+    },
+
+    #[error("Column {column:?} holds {value} more than once")]
+    DuplicateElement { column: String, value: Value },
+
+    #[error("Column {column:?} value {value} is not one of the allowed values")]
+    NotInEnum { column: String, value: Value },
+
+    #[error("Column {column:?} value {value} violates constraint: {constraint}")]
+    ConstraintViolation {
+        column: String,
+        value: Value,
+        constraint: String,
+    },
+}
+
+/// Validate a row destined for an `insert` operation against its table schema.
+///
+/// This checks that every column exists, that values have the right base
+/// types, that sets and maps respect their cardinality, hold each element or
+/// key once, and that constrained atoms (enums, integer/real ranges, string
+/// lengths) are within bounds.
+///
+/// Immutable columns can be given a value on insert, so they are only
+/// rejected by [`validate_update`].
+pub fn validate_row(
+    table: &TableSchema,
+    row: &HashMap<String, Value>,
+) -> Result<(), ValidationError> {
+    for (column, value) in row {
+        let schema = table
+            .columns
+            .get(column)
+            .ok_or_else(|| ValidationError::UnknownColumn {
+                column: column.clone(),
+            })?;
+
+        validate_value(column, &schema.r#type, value)?;
+    }
+
+    Ok(())
+}
+
+/// Validate a row destined for an `update` operation against its table schema.
+///
+/// In addition to the checks done by [`validate_row`], this rejects changes to
+/// columns which the schema marks as immutable.
+pub fn validate_update(
+    table: &TableSchema,
+    row: &HashMap<String, Value>,
+) -> Result<(), ValidationError> {
+    for column in row.keys() {
+        if let Some(schema) = table.columns.get(column) {
+            if schema.mutable == Some(false) {
+                return Err(ValidationError::ImmutableColumn {
+                    column: column.clone(),
+                });
+            }
+        }
+    }
+
+    validate_row(table, row)
+}
+
+/// Validate a single column value in OVSDB JSON notation against its type.
+pub fn validate_value(
+    column: &str,
+    r#type: &ColumnType,
+    value: &Value,
+) -> Result<(), ValidationError> {
+    let elements = elements(column, r#type, value)?;
+
+    let found = elements.len();
+    if (found as u64) < r#type.min || r#type.max.is_some_and(|max| found as u64 > max) {
+        return Err(ValidationError::Cardinality {
+            column: column.to_owned(),
+            min: r#type.min,
+            max: r#type
+                .max
+                .map_or_else(|| "unlimited".to_owned(), |max| max.to_string()),
+            found,
+        });
+    }
+
+    let mut keys = HashSet::with_capacity(found);
+    for (key, value) in elements {
+        validate_atom(column, &r#type.key, key)?;
+
+        if let (Some(base), Some(value)) = (&r#type.value, value) {
+            validate_atom(column, base, value)?;
+        }
+
+        // NOTE(mnaser): The server rejects sets holding an element twice and maps
+        //               holding a key twice, and the atoms are small enough to be
+        //               told apart by their JSON text.
+        if !keys.insert(key.to_string()) {
+            return Err(ValidationError::DuplicateElement {
+                column: column.to_owned(),
+                value: key.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Split a column value into its elements, each being a key and, for maps, a value.
+fn elements<'a>(
+    column: &str,
+    r#type: &ColumnType,
+    value: &'a Value,
+) -> Result<Vec<(&'a Value, Option<&'a Value>)>, ValidationError> {
+    let mismatch = |expected: &str| ValidationError::TypeMismatch {
+        column: column.to_owned(),
+        expected: expected.to_owned(),
+        found: value.clone(),
+    };
+
+    // NOTE(mnaser): An empty array is how empty sets (and empty optional values) are
+    //               serialized by ovsdb-schema, so accept it for both sets and maps.
+    if value.as_array().is_some_and(Vec::is_empty) {
+        return Ok(vec![]);
+    }
+
+    if r#type.is_map() {
+        let pairs = match tagged(value, "map") {
+            Some(Value::Array(pairs)) => pairs,
+            _ => return Err(mismatch("map")),
+        };
+
+        return pairs
+            .iter()
+            .map(|pair| match pair.as_array().map(Vec::as_slice) {
+                Some([key, value]) => Ok((key, Some(value))),
+                _ => Err(mismatch("map")),
+            })
+            .collect();
+    }
+
+    match tagged(value, "set") {
+        Some(Value::Array(atoms)) => Ok(atoms.iter().map(|atom| (atom, None)).collect()),
+        Some(_) => Err(mismatch("set")),
+        None => Ok(vec![(value, None)]),
+    }
+}
+
+/// Return the payload of a `["<tag>", <payload>]` tagged array.
+fn tagged<'a>(value: &'a Value, tag: &str) -> Option<&'a Value> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([Value::String(t), payload]) if t == tag => Some(payload),
+        _ => None,
+    }
+}
+
+fn validate_atom(column: &str, base: &BaseType, value: &Value) -> Result<(), ValidationError> {
+    let mismatch = || ValidationError::TypeMismatch {
+        column: column.to_owned(),
+        expected: atomic_type_name(base.r#type).to_owned(),
+        found: value.clone(),
+    };
+    let violation = |constraint: String| ValidationError::ConstraintViolation {
+        column: column.to_owned(),
+        value: value.clone(),
+        constraint,
+    };
+
+    match base.r#type {
+        AtomicType::Integer => {
+            let i = value.as_i64().ok_or_else(mismatch)?;
+            if let Some(min) = base.min_integer.filter(|min| i < *min) {
+                return Err(violation(format!("minInteger {}", min)));
+            }
+            if let Some(max) = base.max_integer.filter(|max| i > *max) {
+                return Err(violation(format!("maxInteger {}", max)));
+            }
+        }
+        AtomicType::Real => {
+            let r = value.as_f64().ok_or_else(mismatch)?;
+            if let Some(min) = base.min_real.filter(|min| r < *min) {
+                return Err(violation(format!("minReal {}", min)));
+            }
+            if let Some(max) = base.max_real.filter(|max| r > *max) {
+                return Err(violation(format!("maxReal {}", max)));
+            }
+        }
+        AtomicType::Boolean => {
+            value.as_bool().ok_or_else(mismatch)?;
+        }
+        AtomicType::String => {
+            let length = value.as_str().ok_or_else(mismatch)?.chars().count() as u64;
+            if let Some(min) = base.min_length.filter(|min| length < *min) {
+                return Err(violation(format!("minLength {}", min)));
+            }
+            if let Some(max) = base.max_length.filter(|max| length > *max) {
+                return Err(violation(format!("maxLength {}", max)));
+            }
+        }
+        AtomicType::Uuid => {
+            let valid = match (tagged(value, "uuid"), tagged(value, "named-uuid")) {
+                (Some(Value::String(uuid)), _) => Uuid::parse_str(uuid).is_ok(),
+                (_, Some(Value::String(_))) => true,
+                _ => false,
+            };
+            if !valid {
+                return Err(mismatch());
+            }
+        }
+    }
+
+    if let Some(allowed) = &base.r#enum {
+        if !allowed.contains(value) {
+            return Err(ValidationError::NotInEnum {
+                column: column.to_owned(),
+                value: value.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn atomic_type_name(r#type: AtomicType) -> &'static str {
+    match r#type {
+        AtomicType::Integer => "integer",
+        AtomicType::Real => "real",
+        AtomicType::Boolean => "boolean",
+        AtomicType::String => "string",
+        AtomicType::Uuid => "uuid",
+    }
+}
@@ -0,0 +1,120 @@
+use futures_util::{FutureExt, StreamExt};
+use ovsdb_client::{
+    bus::RowEventBus,
+    schema::TableUpdate,
+    transact::{Condition, Function},
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+const PB0: &str = "0b4ad97e-5a8e-4b6c-9b1b-3c1f5e7a9d01";
+const PB1: &str = "5f0c4d2e-8b7a-4f3e-a1d2-6e9b8c7a5d02";
+const CH0: &str = "6e9b8c7a-5d02-4f3e-a1d2-5f0c4d2e8b7a";
+const CH1: &str = "3c1f5e7a-9d01-4b6c-9b1b-0b4ad97e5a8e";
+
+fn update(value: Value) -> TableUpdate<Value> {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn test_condition_matches() {
+    let row = json!({
+        "logical_port": "lsp0",
+        "tunnel_key": 5,
+        "chassis": ["uuid", CH0],
+        "tag": ["set", []],
+        "mac": ["set", ["00:00:00:00:00:01", "00:00:00:00:00:02"]],
+        "options": ["map", [["requested-chassis", "ch0"]]],
+    });
+    let matches =
+        |column: &str, function, value| Condition::new(column, function, value).matches(&row);
+
+    assert!(matches("logical_port", Function::Equal, json!("lsp0")));
+    assert!(matches("logical_port", Function::NotEqual, json!("lsp1")));
+    assert!(matches(
+        "chassis",
+        Function::Equal,
+        json!(["set", [["uuid", CH0]]])
+    ));
+    assert!(!matches("chassis", Function::Equal, json!(["uuid", CH1])));
+    assert!(matches("tag", Function::Equal, json!(["set", []])));
+    assert!(matches("tunnel_key", Function::GreaterThan, json!(4)));
+    assert!(matches("tunnel_key", Function::LessThanOrEqual, json!(5)));
+    assert!(!matches("logical_port", Function::LessThan, json!(5)));
+    assert!(matches(
+        "mac",
+        Function::Includes,
+        json!("00:00:00:00:00:02")
+    ));
+    assert!(matches(
+        "mac",
+        Function::Excludes,
+        json!(["set", ["00:00:00:00:00:03"]])
+    ));
+    assert!(matches(
+        "options",
+        Function::Includes,
+        json!(["map", [["requested-chassis", "ch0"]]])
+    ));
+    assert!(!matches("up", Function::Equal, json!(true)));
+}
+
+#[tokio::test]
+async fn test_publish() {
+    let bus = RowEventBus::new();
+    let chassis = |uuid: &str| {
+        vec![Condition::new(
+            "chassis",
+            Function::Equal,
+            json!(["uuid", uuid]),
+        )]
+    };
+    let mut ch0 = bus.subscribe("Port_Binding", chassis(CH0));
+    let mut ch1 = bus.subscribe("Port_Binding", chassis(CH1));
+    let mut all = bus.subscribe("Port_Binding", vec![]);
+    assert_eq!(bus.subscribers(), 3);
+
+    bus.publish(&update(json!({
+        "Port_Binding": {
+            PB0: {"new": {"logical_port": "lsp0", "chassis": ["uuid", CH0]}},
+            PB1: {"new": {"logical_port": "lsp1", "chassis": ["set", []]}},
+        },
+        "Chassis": {CH0: {"new": {"name": "ch0"}}},
+    })));
+
+    let event = ch0.next().await.unwrap();
+    assert_eq!(event.table, "Port_Binding");
+    assert_eq!(event.uuid, Uuid::parse_str(PB0).unwrap());
+    assert_eq!(event.old, None);
+    assert_eq!(event.new.unwrap()["_uuid"], json!(["uuid", PB0]));
+    assert!(ch1.next().now_or_never().is_none());
+    assert_eq!(all.next().await.unwrap().table, "Port_Binding");
+    assert_eq!(all.next().await.unwrap().table, "Port_Binding");
+
+    // NOTE(mnaser): The port moves from ch0 to ch1, which both see.
+    bus.publish(&update(json!({
+        "Port_Binding": {
+            PB0: {
+                "old": {"chassis": ["uuid", CH0]},
+                "new": {"logical_port": "lsp0", "chassis": ["uuid", CH1]},
+            },
+        },
+    })));
+
+    let event = ch0.next().await.unwrap();
+    assert_eq!(event.old.unwrap()["logical_port"], "lsp0");
+    assert_eq!(event.new.unwrap()["chassis"], json!(["uuid", CH1]));
+    assert_eq!(
+        ch1.next().await.unwrap().uuid,
+        Uuid::parse_str(PB0).unwrap()
+    );
+
+    drop(all);
+    assert_eq!(bus.subscribers(), 2);
+
+    bus.publish(&update(json!({
+        "Port_Binding": {PB0: {"old": {"logical_port": "lsp0", "chassis": ["uuid", CH1]}}},
+    })));
+    assert!(ch0.next().now_or_never().is_none());
+    assert_eq!(ch1.next().await.unwrap().new, None);
+}
@@ -0,0 +1,188 @@
+mod common;
+
+use futures_util::StreamExt;
+use ovsdb_client::{
+    Error,
+    cache::SchemaCache,
+    rpc::{self, ConnectOptions, ConnectionEvent},
+    transact::Operation,
+    validation::ValidationError,
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+fn schema(checksum: &str) -> Value {
+    json!({
+        "name": "OVN_Northbound",
+        "version": "7.3.0",
+        "cksum": checksum,
+        "tables": {
+            "Logical_Switch": {
+                "columns": {
+                    "name": {"type": "string", "mutable": false},
+                    "other_config": {
+                        "type": {"key": "string", "value": "string",
+                                 "min": 0, "max": "unlimited"}}
+                },
+                "isRoot": true
+            }
+        }
+    })
+}
+
+#[tokio::test]
+async fn test_get_fetches_once() {
+    let (connection, mut served) = common::serve_duplex(common::results(vec![schema("1 1")]));
+    let client = rpc::connect_stream(connection);
+    let cache = SchemaCache::new();
+
+    assert!(cache.cached("OVN_Northbound").is_none());
+
+    let first = cache.get(&client, "OVN_Northbound").await.unwrap();
+    let second = cache.get(&client, "OVN_Northbound").await.unwrap();
+    assert!(std::sync::Arc::ptr_eq(&first, &second));
+    assert_eq!(cache.checksum("OVN_Northbound").as_deref(), Some("1 1"));
+
+    let request = served.requests.recv().await.unwrap();
+    assert_eq!(request["method"], json!("get_schema"));
+    assert_eq!(request["params"], json!(["OVN_Northbound"]));
+    assert!(served.requests.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_invalidation() {
+    let (connection, mut served) =
+        common::serve_duplex(common::results(vec![schema("1 1"), schema("2 2")]));
+    let client = rpc::connect_stream(connection);
+    let cache = SchemaCache::new();
+
+    cache.get(&client, "OVN_Northbound").await.unwrap();
+
+    // The same checksum keeps the cached schema
+    assert!(!cache.observe_checksum("OVN_Northbound", "1 1"));
+    assert!(cache.cached("OVN_Northbound").is_some());
+
+    assert!(cache.observe_checksum("OVN_Northbound", "2 2"));
+    assert!(cache.cached("OVN_Northbound").is_none());
+
+    let schema = cache.get(&client, "OVN_Northbound").await.unwrap();
+    assert_eq!(schema.checksum.as_deref(), Some("2 2"));
+
+    served.requests.recv().await.unwrap();
+    served.requests.recv().await.unwrap();
+
+    cache.clear();
+    assert!(cache.checksum("OVN_Northbound").is_none());
+}
+
+#[tokio::test]
+async fn test_validate() {
+    let (connection, _served) = common::serve_duplex(common::results(vec![schema("1 1")]));
+    let client = rpc::connect_stream(connection);
+    let cache = SchemaCache::new();
+
+    let insert = Operation::Insert {
+        table: "Logical_Switch".to_owned(),
+        row: HashMap::from([("name".to_owned(), json!("ls0"))]),
+        uuid_name: None,
+    };
+    cache
+        .validate(&client, "OVN_Northbound", &[insert])
+        .await
+        .unwrap();
+
+    let update = Operation::Update {
+        table: "Logical_Switch".to_owned(),
+        conditions: vec![],
+        row: HashMap::from([("name".to_owned(), json!("ls1"))]),
+    };
+    let err = cache
+        .validate(&client, "OVN_Northbound", &[update])
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        Error::InvalidRow {
+            source: ValidationError::ImmutableColumn { .. },
+            ..
+        }
+    ));
+
+    let delete = Operation::Delete {
+        table: "Logical_Router".to_owned(),
+        conditions: vec![],
+    };
+    let insert = Operation::Insert {
+        table: "Logical_Router".to_owned(),
+        row: HashMap::new(),
+        uuid_name: None,
+    };
+    let err = cache
+        .validate(&client, "OVN_Northbound", &[delete, insert])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::UnknownTable(table) if table == "Logical_Router"));
+}
+
+#[tokio::test]
+async fn test_client_schemas() {
+    let mut checksums = ["1 1", "2 2"].into_iter();
+    let (connection, mut served) = common::serve_duplex(move |request| {
+        Some(match request["method"].as_str() {
+            Some("get_schema") => common::result(schema(checksums.next().unwrap())),
+            _ => {
+                common::result(json!([{"uuid": ["uuid", "1d2c3b4a-5e6f-4a1b-8c2d-3e4f5a6b7c08"]}]))
+            }
+        })
+    });
+    let options = ConnectOptions::new();
+    let mut events = options.events();
+    let client = options.connect_stream(connection);
+    events.next().await.unwrap();
+
+    let first = client.schema("OVN_Northbound").await.unwrap();
+    let second = client.schema("OVN_Northbound").await.unwrap();
+    assert!(std::sync::Arc::ptr_eq(&first, &second));
+    assert_eq!(
+        client.schemas().checksum("OVN_Northbound").as_deref(),
+        Some("1 1")
+    );
+
+    // The rows are validated with the cached schema before being sent
+    let update = Operation::Update {
+        table: "Logical_Switch".to_owned(),
+        conditions: vec![],
+        row: HashMap::from([("name".to_owned(), json!("ls1"))]),
+    };
+    let err = client
+        .transact_validated("OVN_Northbound", vec![update])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidRow { .. }), "{err:?}");
+
+    let insert = Operation::Insert {
+        table: "Logical_Switch".to_owned(),
+        row: HashMap::from([("name".to_owned(), json!("ls0"))]),
+        uuid_name: None,
+    };
+    client
+        .transact_validated("OVN_Northbound", vec![insert])
+        .await
+        .unwrap();
+
+    // A monitor canceled by a schema change drops the cached schemas
+    served
+        .notify
+        .send(json!({"id": null, "method": "monitor_canceled", "params": [null]}))
+        .unwrap();
+    assert_eq!(events.next().await.unwrap(), ConnectionEvent::SchemaChanged);
+    assert!(client.schemas().cached("OVN_Northbound").is_none());
+
+    let schema = client.schema("OVN_Northbound").await.unwrap();
+    assert_eq!(schema.checksum.as_deref(), Some("2 2"));
+
+    for method in ["get_schema", "transact", "get_schema"] {
+        assert_eq!(served.requests.recv().await.unwrap()["method"], method);
+    }
+    assert!(served.requests.try_recv().is_err());
+}
@@ -0,0 +1,95 @@
+use ovsdb_client::{
+    Error,
+    cluster::{self, Model},
+    rpc::{self, ConnectOptions},
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+mod common;
+
+const CLUSTER: &str = "1d2c3b4a-5e6f-4a1b-8c2d-3e4f5a6b7c08";
+
+/// Returns the row of `_Server` for a member of the cluster of
+/// OVN_Northbound.
+fn row(server: u128, leader: bool, index: u64) -> Value {
+    json!({
+        "_uuid": ["uuid", Uuid::from_u128(server)],
+        "name": "OVN_Northbound",
+        "model": "clustered",
+        "connected": true,
+        "leader": leader,
+        "schema": "{}",
+        "cid": ["uuid", CLUSTER],
+        "sid": ["uuid", Uuid::from_u128(server)],
+        "index": index,
+    })
+}
+
+/// A server replying to every request with the rows, as if selected.
+async fn serve(rows: Vec<Value>) -> String {
+    let (remote, _) = common::serve_tcp(move |message| {
+        assert_eq!(message["params"][0], "_Server");
+        Some(common::result(json!([{"rows": rows}])))
+    })
+    .await;
+
+    remote
+}
+
+#[tokio::test]
+async fn test_database_status() {
+    let rows = json!([
+        {
+            "name": "_Server",
+            "model": "standalone",
+            "connected": true,
+            "leader": true,
+            "schema": "{}",
+            "cid": ["set", []],
+            "sid": ["set", []],
+            "index": ["set", []],
+        },
+        row(1, false, 42),
+    ]);
+    let (client, _served) =
+        common::serve_duplex(move |_| Some(common::result(json!([{"rows": rows}]))));
+    let client = rpc::connect_stream(client);
+
+    let statuses = cluster::database_statuses(&client).await.unwrap();
+    assert_eq!(statuses[0].model, Model::Standalone);
+    assert_eq!(statuses[0].cluster_id, None);
+    assert_eq!(statuses[0].index, None);
+
+    let status = &statuses[1];
+    assert_eq!(status.model, Model::Clustered);
+    assert!(status.is_healthy() && !status.leader);
+    assert_eq!(status.cluster_id, Some(Uuid::parse_str(CLUSTER).unwrap()));
+    assert_eq!(status.server_id, Some(Uuid::from_u128(1)));
+    assert_eq!(status.index, Some(42));
+}
+
+#[tokio::test]
+async fn test_probe() {
+    let follower = serve(vec![row(1, false, 41)]).await;
+    let leader = serve(vec![row(2, true, 42)]).await;
+    let missing = serve(Vec::new()).await;
+
+    let remotes = [
+        follower.clone(),
+        leader.clone(),
+        missing,
+        "tcp:127.0.0.1:1".to_owned(),
+    ];
+    let members = cluster::probe(&ConnectOptions::new(), remotes, "OVN_Northbound").await;
+
+    assert_eq!(members.len(), 4);
+    assert_eq!(members[0].remote, follower);
+    assert_eq!(members[0].status.as_ref().unwrap().index, Some(41));
+    assert!(matches!(
+        members[2].status,
+        Err(Error::UnexpectedResponse(_))
+    ));
+    assert!(members[3].status.is_err());
+    assert_eq!(cluster::leader(&members), Some(leader.as_str()));
+}
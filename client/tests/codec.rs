@@ -0,0 +1,79 @@
+use bytes::BytesMut;
+use ovsdb_client::{
+    rpc::{ConnectOptions, RpcClient},
+    transports::{codec::JsonCodec, listener::framed},
+};
+use serde_json::json;
+use tokio_util::codec::Decoder;
+
+/// Decode every message of the input, fed to the codec a chunk at a time
+fn decode(codec: &mut JsonCodec, input: &[u8], chunk: usize) -> Vec<String> {
+    let mut buf = BytesMut::new();
+    let mut messages = Vec::new();
+    for bytes in input.chunks(chunk) {
+        buf.extend_from_slice(bytes);
+        while let Some(frame) = codec.decode(&mut buf).unwrap() {
+            messages.push(String::from_utf8(frame.to_vec()).unwrap());
+        }
+    }
+    messages
+}
+
+#[test]
+fn test_decode() {
+    let input = br#" {"id":1,"result":["a]","b\"}"]} [1,[2]]
+{"method":"update","params":[null,{"t":{"\\":{}}}]}"#;
+
+    for chunk in [1, 2, 7, input.len()] {
+        assert_eq!(
+            decode(&mut JsonCodec::default(), input, chunk),
+            [
+                r#"{"id":1,"result":["a]","b\"}"]}"#,
+                "[1,[2]]",
+                r#"{"method":"update","params":[null,{"t":{"\\":{}}}]}"#,
+            ],
+            "chunks of {chunk} bytes"
+        );
+    }
+}
+
+#[test]
+fn test_decode_errors() {
+    let mut codec = JsonCodec::default();
+    assert!(codec.decode(&mut BytesMut::from(" \"a\"")).is_err());
+    assert!(codec.decode(&mut BytesMut::from("}")).is_err());
+    assert_eq!(codec.decode(&mut BytesMut::from("   ")).unwrap(), None);
+}
+
+#[test]
+fn test_max_length() {
+    let mut codec = JsonCodec::with_max_length(8);
+    assert_eq!(decode(&mut codec, b"  [1,2,3] [4]", 1), ["[1,2,3]", "[4]"]);
+
+    let err = JsonCodec::with_max_length(8)
+        .decode(&mut BytesMut::from("[1,2,3,4]"))
+        .unwrap_err();
+    assert_eq!(err.to_string(), "message longer than 8 bytes");
+    assert!(
+        JsonCodec::with_max_length(8)
+            .decode(&mut BytesMut::from("[[[[[[[[["))
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_max_message_size() {
+    let (connection, server) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(async move {
+        let (mut sender, mut receiver) = framed(server);
+        while let Some(Ok(message)) = receiver.receive().await {
+            let reply = json!({"id": message["id"], "result": ["x".repeat(1024)], "error": null});
+            sender.send(&reply).await.unwrap();
+        }
+    });
+
+    let client = ConnectOptions::new()
+        .with_max_message_size(512)
+        .connect_stream(connection);
+    assert!(client.list_databases().await.is_err());
+}
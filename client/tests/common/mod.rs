@@ -0,0 +1,181 @@
+//! A scripted server for the tests, reading the requests of the client as
+//! framed messages however they are split over the stream.
+
+#![allow(dead_code)]
+
+use futures_util::StreamExt;
+use ovsdb_client::transports::{codec::JsonCodec, listener::Connection};
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncWriteExt, DuplexStream, ReadHalf, WriteHalf},
+    net::TcpListener,
+    sync::mpsc,
+};
+use tokio_util::codec::FramedRead;
+
+/// The server end of a connection
+pub struct Peer {
+    reader: FramedRead<ReadHalf<Box<dyn Connection>>, JsonCodec>,
+
+    writer: WriteHalf<Box<dyn Connection>>,
+}
+
+impl Peer {
+    pub fn new(connection: impl Connection + 'static) -> Self {
+        let connection: Box<dyn Connection> = Box::new(connection);
+        let (read, writer) = tokio::io::split(connection);
+
+        Peer {
+            reader: FramedRead::new(read, JsonCodec::default()),
+            writer,
+        }
+    }
+
+    /// Returns the next message of the client, none once it disconnected.
+    pub async fn receive(&mut self) -> Option<Value> {
+        let frame = self.reader.next().await?.ok()?;
+        Some(serde_json::from_slice(&frame).unwrap())
+    }
+
+    pub async fn send(&mut self, message: &Value) {
+        self.write(&serde_json::to_vec(message).unwrap()).await;
+    }
+
+    /// Send the message a few bytes at a time, so that the client reads it
+    /// in several parts.
+    pub async fn send_split(&mut self, message: &Value, size: usize) {
+        for chunk in serde_json::to_vec(message).unwrap().chunks(size) {
+            self.write(chunk).await;
+            tokio::task::yield_now().await;
+        }
+    }
+
+    /// Write bytes as they are, such as a part of a message or garbage.
+    pub async fn write(&mut self, bytes: &[u8]) {
+        self.writer.write_all(bytes).await.unwrap();
+    }
+
+    /// Reply to the request, with its id set on the reply.
+    pub async fn reply(&mut self, request: &Value, mut reply: Value) {
+        reply["id"] = request["id"].clone();
+        self.send(&reply).await;
+    }
+
+    pub async fn close(&mut self) {
+        let _ = self.writer.shutdown().await;
+    }
+}
+
+/// Returns the reply of a successful request, without its id.
+pub fn result(result: Value) -> Value {
+    json!({"result": result, "error": null})
+}
+
+/// Returns the reply of a failed request, without its id.
+pub fn error(error: Value) -> Value {
+    json!({"result": null, "error": error})
+}
+
+/// Returns an in-memory connection, along with its server end.
+pub fn duplex() -> (DuplexStream, Peer) {
+    let (client, server) = tokio::io::duplex(64 * 1024);
+    (client, Peer::new(server))
+}
+
+/// A server answering the requests of a connection
+pub struct Served {
+    /// Every message of the client, once it has been replied to
+    pub requests: mpsc::UnboundedReceiver<Value>,
+
+    /// Messages to send to the client, such as notifications
+    pub notify: mpsc::UnboundedSender<Value>,
+}
+
+/// Reply to every request with what `reply` returns for it, or not at all
+/// for none, until the client disconnects.
+pub fn serve(
+    mut peer: Peer,
+    mut reply: impl FnMut(&Value) -> Option<Value> + Send + 'static,
+) -> Served {
+    let (requests, received) = mpsc::unbounded_channel();
+    let (notify, mut notifications) = mpsc::unbounded_channel::<Value>();
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                request = peer.receive() => {
+                    let Some(request) = request else {
+                        break;
+                    };
+                    if let Some(reply) = reply(&request) {
+                        peer.reply(&request, reply).await;
+                    }
+                    let _ = requests.send(request);
+                }
+                Some(notification) = notifications.recv() => peer.send(&notification).await,
+            }
+        }
+    });
+
+    Served {
+        requests: received,
+        notify,
+    }
+}
+
+/// Returns an in-memory connection to a server replying with `reply`.
+pub fn serve_duplex(
+    reply: impl FnMut(&Value) -> Option<Value> + Send + 'static,
+) -> (DuplexStream, Served) {
+    let (connection, peer) = duplex();
+    (connection, serve(peer, reply))
+}
+
+/// Listen on a TCP port of the loopback, returning its remote, such as
+/// `tcp:127.0.0.1:6641`, and the server end of every connection accepted.
+pub async fn listen() -> (String, mpsc::UnboundedReceiver<Peer>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let remote = format!("tcp:{}", listener.local_addr().unwrap());
+    let (peers, accepted) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            if peers.send(Peer::new(stream)).is_err() {
+                break;
+            }
+        }
+    });
+
+    (remote, accepted)
+}
+
+/// Listen on a TCP port of the loopback, replying to the requests of every
+/// connection with a clone of `reply`, and returning its remote along with
+/// the messages of all of them.
+pub async fn serve_tcp(
+    reply: impl FnMut(&Value) -> Option<Value> + Clone + Send + 'static,
+) -> (String, mpsc::UnboundedReceiver<Value>) {
+    let (remote, mut peers) = listen().await;
+    let (requests, received) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Some(peer) = peers.recv().await {
+            let mut served = serve(peer, reply.clone());
+            let requests = requests.clone();
+            tokio::spawn(async move {
+                while let Some(request) = served.requests.recv().await {
+                    let _ = requests.send(request);
+                }
+            });
+        }
+    });
+
+    (remote, received)
+}
+
+/// Returns a reply function answering each request with the next of the
+/// results, and then not at all.
+pub fn results(results: Vec<Value>) -> impl FnMut(&Value) -> Option<Value> + Clone + Send {
+    let mut results = results.into_iter();
+    move |_| results.next().map(result)
+}
@@ -0,0 +1,126 @@
+use ovsdb_client::{
+    Error,
+    convert::{ConversionStep, convert_database},
+    rpc,
+};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+
+mod common;
+
+fn schema(version: &str, columns: &[&str]) -> Value {
+    let columns: serde_json::Map<String, Value> = columns
+        .iter()
+        .map(|column| (column.to_string(), json!({"type": "string"})))
+        .collect();
+
+    json!({
+        "name": "OVN_Northbound",
+        "version": version,
+        "tables": {"Logical_Switch": {"columns": columns, "isRoot": true}},
+    })
+}
+
+/// A server hosting the database with the schema, which "convert" replaces
+/// unless it is rejecting conversions, returning the methods it received.
+fn serve(schema: Value, rejecting: bool) -> (tokio::io::DuplexStream, Arc<Mutex<Vec<String>>>) {
+    let methods = Arc::new(Mutex::new(Vec::new()));
+
+    let received = methods.clone();
+    let mut schema = schema;
+    let (client, _) = common::serve_duplex(move |message| {
+        let method = message["method"].as_str().unwrap().to_owned();
+        received.lock().unwrap().push(method.clone());
+
+        Some(match method.as_str() {
+            "get_schema" => common::result(schema.clone()),
+            "convert" if rejecting => {
+                common::error(json!({"error": "constraint violation", "details": "duplicate name"}))
+            }
+            "convert" => {
+                schema = message["params"][1].clone();
+                common::result(json!({}))
+            }
+            _ => common::result(json!({})),
+        })
+    });
+
+    (client, methods)
+}
+
+#[tokio::test]
+async fn test_convert() {
+    let (connection, methods) = serve(schema("7.3.0", &["name"]), false);
+    let client = rpc::connect_stream(connection);
+
+    let mut steps = Vec::new();
+    let target = schema("7.4.0", &["name", "other_config"]);
+    let diff = convert_database(&client, "OVN_Northbound", &target, |step| steps.push(step))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        diff.changed_tables["Logical_Switch"].added_columns,
+        vec!["other_config"]
+    );
+    assert_eq!(
+        steps,
+        vec![
+            ConversionStep::Fetched {
+                version: "7.3.0".to_owned()
+            },
+            ConversionStep::Converting {
+                from: "7.3.0".to_owned(),
+                to: "7.4.0".to_owned(),
+                diff,
+            },
+            ConversionStep::Converted,
+            ConversionStep::Verified,
+        ]
+    );
+    assert_eq!(
+        *methods.lock().unwrap(),
+        vec!["get_schema", "set_db_change_aware", "convert", "get_schema"]
+    );
+}
+
+#[tokio::test]
+async fn test_up_to_date() {
+    let (connection, methods) = serve(schema("7.3.0", &["name"]), false);
+    let client = rpc::connect_stream(connection);
+
+    let mut steps = Vec::new();
+    let target = schema("7.3.0", &["name"]);
+    let diff = convert_database(&client, "OVN_Northbound", &target, |step| steps.push(step))
+        .await
+        .unwrap();
+
+    assert!(diff.is_empty());
+    assert_eq!(steps.last(), Some(&ConversionStep::UpToDate));
+    assert_eq!(*methods.lock().unwrap(), vec!["get_schema"]);
+}
+
+#[tokio::test]
+async fn test_convert_errors() {
+    let (connection, methods) = serve(schema("7.3.0", &["name"]), true);
+    let client = rpc::connect_stream(connection);
+
+    let mut target = schema("7.4.0", &["name"]);
+    target["name"] = json!("OVN_Southbound");
+    let error = convert_database(&client, "OVN_Northbound", &target, |_| {})
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        Error::InvalidSchema(ref source) if source.found == json!("OVN_Southbound")
+    ));
+    assert!(methods.lock().unwrap().is_empty());
+
+    let mut steps = Vec::new();
+    let target = schema("7.4.0", &["name"]);
+    let error = convert_database(&client, "OVN_Northbound", &target, |step| steps.push(step))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::Rpc(_)));
+    assert_ne!(steps.last(), Some(&ConversionStep::Converted));
+}
@@ -0,0 +1,180 @@
+mod common;
+
+use ovsdb_client::{
+    Error, OvsdbErrorKind, rpc,
+    transact::{Condition, Function, Operation, TransactClient},
+};
+use ovsdb_derive::ovsdb_object;
+use ovsdb_schema::PathSegment;
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[ovsdb_object(table = "Logical_Switch", database = "OVN_Northbound", crud)]
+#[derive(Debug, PartialEq)]
+pub struct LogicalSwitch {
+    pub name: Option<String>,
+}
+
+#[ovsdb_object(table = "Meter", database = "OVN_Northbound", crud)]
+pub struct Meter {
+    pub name: String,
+    pub bands: Vec<u64>,
+}
+
+const SWITCH_UUID: &str = "36ed24e6-6ba4-4a37-a5e1-8e0ce1dbd89a";
+
+#[test]
+fn test_operation_serialization() {
+    let operation = Operation::Select {
+        table: "Logical_Switch".to_owned(),
+        conditions: vec![Condition::new("name", Function::Equal, json!("ls0"))],
+        columns: Some(vec!["name".to_owned()]),
+    };
+
+    assert_eq!(
+        serde_json::to_value(&operation).unwrap(),
+        json!({
+            "op": "select",
+            "table": "Logical_Switch",
+            "where": [["name", "==", "ls0"]],
+            "columns": ["name"],
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_transact_sends_positional_operations() {
+    let (connection, mut served) =
+        common::serve_duplex(common::results(vec![json!([{}, {"count": 1}])]));
+    let client = rpc::connect_stream(connection);
+
+    let results = client
+        .transact(
+            "OVN_Northbound",
+            vec![
+                Operation::Comment {
+                    comment: "test".to_owned(),
+                },
+                Operation::Delete {
+                    table: "Logical_Switch".to_owned(),
+                    conditions: Vec::new(),
+                },
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(results[1].count, Some(1));
+
+    let request = served.requests.recv().await.unwrap();
+    assert_eq!(request["method"], "transact");
+    assert_eq!(
+        request["params"],
+        json!([
+            "OVN_Northbound",
+            {"op": "comment", "comment": "test"},
+            {"op": "delete", "table": "Logical_Switch", "where": []},
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_transact_error() {
+    let (connection, _served) = common::serve_duplex(common::results(vec![json!([
+        {},
+        {"error": "constraint violation", "details": "duplicate name"},
+    ])]));
+    let client = rpc::connect_stream(connection);
+
+    let err = client
+        .transact(
+            "OVN_Northbound",
+            vec![Operation::Comment {
+                comment: "test".to_owned(),
+            }],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.ovsdb_kind(), Some(&OvsdbErrorKind::ConstraintViolation));
+    assert_eq!(
+        err.to_string(),
+        "OVSDB error: constraint violation (duplicate name)"
+    );
+}
+
+#[tokio::test]
+async fn test_generated_crud_methods() {
+    let (connection, mut served) = common::serve_duplex(common::results(vec![
+        json!([{"uuid": ["uuid", SWITCH_UUID]}]),
+        json!([{"rows": [{"_uuid": ["uuid", SWITCH_UUID], "name": "ls0"}]}]),
+        json!([{"rows": []}]),
+        json!([{"count": 1}]),
+        json!([{"count": 1}]),
+    ]));
+    let client = rpc::connect_stream(connection);
+    let uuid = Uuid::parse_str(SWITCH_UUID).unwrap();
+
+    let mut switch = LogicalSwitch::new();
+    switch.name = Some("ls0".to_owned());
+    assert_eq!(LogicalSwitch::insert(&client, &switch).await.unwrap(), uuid);
+    let request = served.requests.recv().await.unwrap();
+    assert_eq!(
+        request["params"][1],
+        json!({"op": "insert", "table": "Logical_Switch", "row": {"name": "ls0"}})
+    );
+
+    let fetched = LogicalSwitch::get(&client, uuid).await.unwrap().unwrap();
+    assert_eq!(fetched._uuid, Some(uuid));
+    assert_eq!(fetched.name.as_deref(), Some("ls0"));
+    let request = served.requests.recv().await.unwrap();
+    assert_eq!(
+        request["params"][1]["where"],
+        json!([["_uuid", "==", ["uuid", SWITCH_UUID]]])
+    );
+
+    assert_eq!(LogicalSwitch::list(&client).await.unwrap(), Vec::new());
+    served.requests.recv().await.unwrap();
+
+    let mut changes = LogicalSwitchUpdate::new();
+    changes.set_name(Some("ls1".to_owned()));
+    assert_eq!(
+        LogicalSwitch::update(&client, uuid, &changes)
+            .await
+            .unwrap(),
+        1
+    );
+    let request = served.requests.recv().await.unwrap();
+    assert_eq!(
+        request["params"][1]["row"],
+        serde_json::to_value(HashMap::from([("name", "ls1")])).unwrap()
+    );
+
+    assert_eq!(LogicalSwitch::delete(&client, uuid).await.unwrap(), 1);
+    let request = served.requests.recv().await.unwrap();
+    assert_eq!(request["params"][1]["op"], "delete");
+}
+
+#[tokio::test]
+async fn test_crud_conversion_error() {
+    let (connection, mut served) = common::serve_duplex(|_| None);
+    let client = rpc::connect_stream(connection);
+
+    // A row holding a value which cannot be sent fails before the
+    // transaction, rather than being inserted or updated without it
+    let mut meter = Meter::new();
+    meter.name = "meter0".to_owned();
+    meter.bands = vec![u64::MAX];
+    let err = Meter::insert(&client, &meter).await.unwrap_err();
+    assert!(
+        matches!(&err, Error::Conversion(err) if err.path[0] == PathSegment::Column("bands".to_owned()))
+    );
+
+    let mut changes = MeterUpdate::new();
+    changes.set_bands(vec![1, u64::MAX]);
+    let err = Meter::update(&client, Uuid::nil(), &changes)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Conversion(_)));
+
+    assert!(served.requests.try_recv().is_err());
+}
@@ -0,0 +1,178 @@
+use ovsdb_client::{
+    dump::{DumpError, DumpTable, Table, datum, format_datum, parse_datum, parse_dump},
+    schema::TableSchema,
+};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+const LS0: &str = "0b4ad97e-5a8e-4b6c-9b1b-3c1f5e7a9d01";
+const LSP0: &str = "5f0c4d2e-8b7a-4f3e-a1d2-6e9b8c7a5d02";
+
+fn schema() -> TableSchema {
+    serde_json::from_value(json!({
+        "columns": {
+            "name": {"type": "string"},
+            "ports": {"type": {"key": {"type": "uuid", "refTable": "Logical_Switch_Port"},
+                               "min": 0, "max": "unlimited"}},
+            "external_ids": {"type": {"key": "string", "value": "string",
+                                      "min": 0, "max": "unlimited"}},
+            "tag": {"type": {"key": "integer", "min": 0, "max": 1}},
+            "enabled": {"type": "boolean"}
+        }
+    }))
+    .unwrap()
+}
+
+fn rows() -> Vec<HashMap<String, Value>> {
+    vec![HashMap::from([
+        ("_uuid".to_owned(), json!(["uuid", LS0])),
+        ("name".to_owned(), json!("ls0")),
+        ("ports".to_owned(), json!(["uuid", LSP0])),
+        (
+            "external_ids".to_owned(),
+            json!(["map", [["neutron:network_name", "private net"]]]),
+        ),
+        ("tag".to_owned(), json!(["set", []])),
+        ("enabled".to_owned(), json!(true)),
+    ])]
+}
+
+#[test]
+fn test_table() {
+    let mut table = Table::new(["name", "tag"]);
+    table.push(vec!["sw0".to_owned(), "[]".to_owned()]);
+    table.push(vec!["switch".to_owned(), "[10]".to_owned()]);
+
+    assert_eq!(
+        table.to_string(),
+        "name   tag\n------ ----\nsw0    []\nswitch [10]\n"
+    );
+}
+
+#[test]
+fn test_datum() {
+    assert_eq!(datum(&json!("sw")), "sw");
+    assert_eq!(datum(&json!("ls0")), "\"ls0\"");
+    assert_eq!(datum(&json!("true")), "\"true\"");
+    assert_eq!(datum(&json!("")), "\"\"");
+    assert_eq!(datum(&json!(10)), "10");
+    assert_eq!(datum(&json!(["uuid", LS0])), LS0);
+    assert_eq!(datum(&json!(LS0)), format!("\"{LS0}\""));
+    assert_eq!(datum(&json!(["set", ["a", "b"]])), "[a, b]");
+    assert_eq!(datum(&json!(["map", [["k", "v"]]])), "{k=v}");
+}
+
+#[test]
+fn test_format_datum() {
+    let schema = schema();
+    let column = |name: &str| &schema.columns[name].r#type;
+
+    assert_eq!(format_datum(&json!("ls0"), column("name")), "\"ls0\"");
+    assert_eq!(
+        format_datum(&json!(["uuid", LSP0]), column("ports")),
+        format!("[{LSP0}]")
+    );
+    assert_eq!(format_datum(&json!(["set", []]), column("tag")), "[]");
+    assert_eq!(format_datum(&json!(10), column("tag")), "[10]");
+    assert_eq!(
+        format_datum(&json!(["map", []]), column("external_ids")),
+        "{}"
+    );
+}
+
+#[test]
+fn test_parse_datum() {
+    let schema = schema();
+    let column = |name: &str| &schema.columns[name].r#type;
+
+    assert_eq!(
+        parse_datum("\"ls0\"", column("name")).unwrap(),
+        json!("ls0")
+    );
+    assert_eq!(parse_datum("sw", column("name")).unwrap(), json!("sw"));
+    assert_eq!(
+        parse_datum(&format!("\"{LS0}\""), column("name")).unwrap(),
+        json!(LS0)
+    );
+    assert_eq!(
+        parse_datum(&format!("[{LSP0}]"), column("ports")).unwrap(),
+        json!(["set", [["uuid", LSP0]]])
+    );
+    assert_eq!(
+        parse_datum("[]", column("tag")).unwrap(),
+        json!(["set", []])
+    );
+    assert_eq!(
+        parse_datum("10", column("tag")).unwrap(),
+        json!(["set", [10]])
+    );
+    assert_eq!(
+        parse_datum("{a=b, \"c d\"=\"e,f\"}", column("external_ids")).unwrap(),
+        json!(["map", [["a", "b"], ["c d", "e,f"]]])
+    );
+    assert_eq!(
+        parse_datum("false", column("enabled")).unwrap(),
+        json!(false)
+    );
+
+    assert!(parse_datum("[a]", column("tag")).is_err());
+    assert!(parse_datum("\"x\"", column("tag")).is_err());
+    assert!(parse_datum("[1, 2", column("tag")).is_err());
+    assert!(parse_datum("a b", column("name")).is_err());
+}
+
+#[test]
+fn test_dump_table() {
+    let columns = ["name", "ports", "external_ids", "tag", "enabled"].map(String::from);
+    let table = DumpTable::from_rows("Logical_Switch", &schema(), &columns, &rows());
+
+    assert_eq!(
+        table.to_string(),
+        format!(
+            "Logical_Switch table\n\
+             _uuid                                name  ports                                  external_ids                           tag enabled\n\
+             ------------------------------------ ----- -------------------------------------- -------------------------------------- --- -------\n\
+             {LS0} \"ls0\" [{LSP0}] {{\"neutron:network_name\"=\"private net\"}} []  true\n"
+        )
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let schema = schema();
+    let columns = ["name", "ports", "external_ids", "tag", "enabled"].map(String::from);
+    let table = DumpTable::from_rows("Logical_Switch", &schema, &columns, &rows());
+
+    let tables = parse_dump(&format!("{table}\n{table}")).unwrap();
+    assert_eq!(tables, vec![table.clone(), table]);
+
+    let rows = tables[0].rows(&schema).unwrap();
+    let expected: Map<String, Value> = serde_json::from_value(json!({
+        "_uuid": ["uuid", LS0],
+        "name": "ls0",
+        "ports": ["set", [["uuid", LSP0]]],
+        "external_ids": ["map", [["neutron:network_name", "private net"]]],
+        "tag": ["set", []],
+        "enabled": true,
+    }))
+    .unwrap();
+    assert_eq!(rows, vec![expected]);
+}
+
+#[test]
+fn test_parse_dump_errors() {
+    assert!(matches!(
+        parse_dump("Logical_Switch\n"),
+        Err(DumpError::Syntax { line: 1, .. })
+    ));
+    assert!(matches!(
+        parse_dump("Logical_Switch table\nname\n-- -x\n"),
+        Err(DumpError::Syntax { line: 3, .. })
+    ));
+
+    let tables = parse_dump("Logical_Switch table\nfoo\n---\nbar\n").unwrap();
+    assert!(matches!(
+        tables[0].rows(&schema()),
+        Err(DumpError::UnknownColumn(column)) if column == "foo"
+    ));
+}
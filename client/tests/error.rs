@@ -0,0 +1,112 @@
+mod common;
+
+use common::Peer;
+use ovsdb_client::{
+    Error, OvsdbError, OvsdbErrorKind,
+    rpc::{self, RpcClient},
+};
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+
+/// Start a server answering the first request with the given reply, or the
+/// given bytes if it is a string, then closing the connection.
+async fn serve(reply: Value) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut peer = Peer::new(stream);
+
+        let request = peer.receive().await.unwrap();
+        match reply {
+            Value::String(bytes) => peer.write(bytes.as_bytes()).await,
+            reply => peer.reply(&request, reply).await,
+        }
+    });
+
+    address
+}
+
+#[tokio::test]
+async fn test_server_error() {
+    let address = serve(json!({
+        "result": null,
+        "error": {"error": "unknown database", "details": "OVN_Southbound"}
+    }))
+    .await;
+    let client = rpc::connect_tcp(address).await.unwrap();
+
+    let err = client.get_schema("OVN_Southbound").await.unwrap_err();
+    assert!(matches!(
+        err,
+        Error::Rpc(OvsdbError { kind: OvsdbErrorKind::UnknownDatabase, details: Some(ref details), .. })
+            if details == "OVN_Southbound"
+    ));
+    assert_eq!(
+        err.to_string(),
+        "OVSDB error: unknown database (OVN_Southbound)"
+    );
+}
+
+#[tokio::test]
+async fn test_connection_errors() {
+    let address = serve(json!({"result": ["OVN_Northbound"], "error": null})).await;
+    let client = rpc::connect_tcp(&address).await.unwrap();
+    assert_eq!(
+        client.list_databases().await.unwrap(),
+        vec!["OVN_Northbound"]
+    );
+
+    // The server closes the connection after its first reply
+    let err = client.list_databases().await.unwrap_err();
+    assert!(matches!(err, Error::ConnectionClosed), "{err:?}");
+
+    // Nothing listens on the port anymore
+    let err = rpc::connect_tcp(&address).await.err().unwrap();
+    assert!(matches!(err, Error::Transport(_)), "{err:?}");
+
+    // Replies which are not valid JSON
+    let address = serve(json!("{garbage}")).await;
+    let client = rpc::connect_tcp(address).await.unwrap();
+    let err = client.list_databases().await.unwrap_err();
+    assert!(matches!(err, Error::Protocol(_)), "{err:?}");
+
+    // Replies which do not match the expected type
+    let address = serve(json!({"result": {"unexpected": true}, "error": null})).await;
+    let client = rpc::connect_tcp(address).await.unwrap();
+    let err = client.list_databases().await.unwrap_err();
+    assert!(matches!(err, Error::Protocol(_)), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_syntax_error() {
+    let address = serve(json!({
+        "result": null,
+        "error": {
+            "error": "syntax error",
+            "details": "unknown method",
+            "syntax": "{\"method\":\"list_dbs\"}"
+        }
+    }))
+    .await;
+    let client = rpc::connect_tcp(address).await.unwrap();
+
+    let Error::Rpc(err) = client.list_databases().await.unwrap_err() else {
+        panic!("expected an OVSDB error");
+    };
+    assert_eq!(err.kind, OvsdbErrorKind::SyntaxError);
+    assert_eq!(err.error(), "syntax error");
+    assert_eq!(err.syntax.as_deref(), Some("{\"method\":\"list_dbs\"}"));
+}
+
+#[test]
+fn test_error_kinds() {
+    let err = OvsdbError::new("referential integrity violation", None, None);
+    assert_eq!(err.kind, OvsdbErrorKind::ReferentialIntegrityViolation);
+    assert_eq!(err.to_string(), "referential integrity violation");
+
+    let err = OvsdbError::new("not a leader", Some("raft".to_owned()), None);
+    assert_eq!(err.kind, OvsdbErrorKind::Other("not a leader".to_owned()));
+    assert_eq!(err.to_string(), "not a leader (raft)");
+}
@@ -0,0 +1,84 @@
+use futures_util::StreamExt;
+use ovsdb_client::{
+    rpc::{ConnectOptions, ConnectionEvent},
+    transports::listener::framed,
+};
+use serde_json::json;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn test_events() {
+    let options = ConnectOptions::new();
+    let mut events = options.events();
+
+    let (client, server) = tokio::io::duplex(64 * 1024);
+    let _client = options.connect_stream(client);
+    assert_eq!(
+        events.next().await.unwrap(),
+        ConnectionEvent::Connected {
+            remote: "stream:".to_owned()
+        }
+    );
+
+    let (mut sender, receiver) = framed(server);
+    sender
+        .send(&json!({"id": null, "method": "monitor_canceled", "params": [null]}))
+        .await
+        .unwrap();
+    assert_eq!(events.next().await.unwrap(), ConnectionEvent::SchemaChanged);
+
+    drop((sender, receiver));
+    assert_eq!(
+        events.next().await.unwrap(),
+        ConnectionEvent::Disconnected {
+            reason: "Connection closed".to_owned()
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_reconnect_backoff() {
+    // Find a free port, which nothing listens on until the client backed off
+    // twice
+    let address = TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let options = ConnectOptions::new()
+        .with_reconnect_backoff(Duration::from_millis(20), Duration::from_millis(30));
+    let mut events = options.events();
+    let connect = tokio::spawn({
+        let options = options.clone();
+        async move { options.connect_tcp(address).await.map(drop) }
+    });
+
+    for delay in [20, 30] {
+        assert_eq!(events.next().await.unwrap(), ConnectionEvent::Connecting);
+        assert!(matches!(
+            events.next().await.unwrap(),
+            ConnectionEvent::Disconnected { .. }
+        ));
+        assert_eq!(
+            events.next().await.unwrap(),
+            ConnectionEvent::BackingOff {
+                delay: Duration::from_millis(delay)
+            }
+        );
+    }
+
+    let listener = TcpListener::bind(address).await.unwrap();
+    let accept = tokio::spawn(async move { listener.accept().await.unwrap() });
+
+    assert_eq!(events.next().await.unwrap(), ConnectionEvent::Connecting);
+    assert_eq!(
+        events.next().await.unwrap(),
+        ConnectionEvent::Connected {
+            remote: format!("tcp:{address}")
+        }
+    );
+    connect.await.unwrap().unwrap();
+    accept.await.unwrap();
+}
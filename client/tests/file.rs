@@ -0,0 +1,157 @@
+use ovsdb_client::file::{Database, FileError, records};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+const LS0: &str = "0b4ad97e-5a8e-4b6c-9b1b-3c1f5e7a9d01";
+const LS1: &str = "5f0c4d2e-8b7a-4f3e-a1d2-6e9b8c7a5d02";
+
+fn record(data: &Value) -> String {
+    let data = data.to_string();
+    format!(
+        "OVSDB JSON {} {}\n{data}\n",
+        data.len(),
+        sha1_smol::Sha1::from(&data).digest()
+    )
+}
+
+fn file(transactions: &[Value]) -> String {
+    let schema = json!({
+        "name": "OVN_Northbound",
+        "version": "7.3.0",
+        "tables": {
+            "Logical_Switch": {
+                "columns": {
+                    "name": {"type": "string"},
+                    "ports": {"type": {"key": "string", "min": 0, "max": "unlimited"}},
+                    "external_ids": {
+                        "type": {"key": "string", "value": "string",
+                                 "min": 0, "max": "unlimited"}}
+                },
+                "isRoot": true
+            }
+        }
+    });
+
+    std::iter::once(&schema)
+        .chain(transactions)
+        .map(record)
+        .collect()
+}
+
+fn row(database: &Database, uuid: &str) -> Option<Value> {
+    database.tables["Logical_Switch"]
+        .get(&Uuid::parse_str(uuid).unwrap())
+        .map(|row| Value::Object(row.clone()))
+}
+
+#[test]
+fn test_records() {
+    let file = file(&[json!({"_date": 1700000000000u64, "_comment": "init"})]);
+    let records = records(file.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].offset, 0);
+    assert_eq!(records[0].data["name"], "OVN_Northbound");
+    assert_eq!(records[1].offset, file.rfind("OVSDB JSON").unwrap() as u64);
+    assert_eq!(records[1].data["_comment"], "init");
+}
+
+#[test]
+fn test_replay_transactions() {
+    let database = Database::read(
+        file(&[
+            json!({
+                "Logical_Switch": {
+                    LS0: {"name": "ls0", "ports": ["set", ["p1", "p2"]],
+                          "external_ids": ["map", [["owner", "a"]]]},
+                    LS1: {"name": "ls1"}
+                }
+            }),
+            json!({"Logical_Switch": {LS1: {"name": "ls2"}}}),
+            json!({
+                "_is_diff": true,
+                "Logical_Switch": {
+                    LS0: {"ports": ["set", ["p2", "p3"]],
+                          "external_ids": ["map", [["owner", "b"], ["zone", "z1"]]]}
+                }
+            }),
+            json!({
+                "_is_diff": true,
+                "Logical_Switch": {
+                    LS0: {"name": "ls3", "external_ids": ["map", [["owner", "b"], ["zone", "z1"]]]}
+                }
+            }),
+            json!({"Logical_Switch": {LS1: null}}),
+        ])
+        .as_bytes(),
+    )
+    .unwrap();
+
+    assert_eq!(database.schema.name, "OVN_Northbound");
+    assert_eq!(database.tables["Logical_Switch"].len(), 1);
+    assert_eq!(
+        row(&database, LS0),
+        Some(json!({"name": "ls3", "ports": ["set", ["p1", "p3"]]}))
+    );
+    assert_eq!(row(&database, LS1), None);
+}
+
+#[test]
+fn test_diff_single_element() {
+    let database = Database::read(
+        file(&[
+            json!({"Logical_Switch": {LS0: {"name": "ls0", "ports": ["set", ["p1", "p2"]]}}}),
+            json!({"_is_diff": true, "Logical_Switch": {LS0: {"ports": "p1"}}}),
+        ])
+        .as_bytes(),
+    )
+    .unwrap();
+
+    assert_eq!(
+        row(&database, LS0),
+        Some(json!({"name": "ls0", "ports": "p2"}))
+    );
+}
+
+#[test]
+fn test_corrupted_files() {
+    let valid = file(&[json!({"Logical_Switch": {LS0: {"name": "ls0"}}})]);
+    let offset = valid.rfind("OVSDB JSON").unwrap() as u64;
+
+    let truncated = &valid[..valid.len() - 10];
+    assert!(matches!(
+        Database::read(truncated.as_bytes()),
+        Err(FileError::Truncated(o)) if o == offset
+    ));
+
+    let oversized = format!("OVSDB JSON {} 0\n{{}}\n", u64::MAX);
+    assert!(matches!(
+        records(oversized.as_bytes()).next(),
+        Some(Err(FileError::Truncated(0)))
+    ));
+
+    let corrupted = valid.replace("ls0", "ls1");
+    assert!(matches!(
+        Database::read(corrupted.as_bytes()),
+        Err(FileError::Checksum(o)) if o == offset
+    ));
+
+    let garbage = format!("{valid}garbage\n");
+    assert!(matches!(
+        Database::read(garbage.as_bytes()),
+        Err(FileError::InvalidHeader { header, .. }) if header == "garbage"
+    ));
+
+    assert!(matches!(
+        Database::read(&b""[..]),
+        Err(FileError::MissingSchema)
+    ));
+
+    let unknown = file(&[json!({"Logical_Router": {LS0: {"name": "lr0"}}})]);
+    assert!(matches!(
+        Database::read(unknown.as_bytes()),
+        Err(FileError::UnknownTable(table)) if table == "Logical_Router"
+    ));
+}
@@ -0,0 +1,452 @@
+mod common;
+
+use futures_util::StreamExt;
+use ovsdb_client::{
+    Error,
+    file::FileError,
+    idl::Replica,
+    rpc,
+    schema::{DatabaseSchema, TableUpdate, TableUpdate2},
+};
+use ovsdb_derive::ovsdb_object;
+use ovsdb_schema::{Ref, WeakRef};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[ovsdb_object(table = "Logical_Switch", database = "OVN_Northbound")]
+#[derive(Debug, PartialEq)]
+pub struct LogicalSwitch {
+    pub name: String,
+    pub ports: Vec<Ref<LogicalSwitchPort>>,
+}
+
+#[ovsdb_object(table = "Logical_Switch_Port", database = "OVN_Northbound")]
+#[derive(Debug, PartialEq)]
+pub struct LogicalSwitchPort {
+    pub name: String,
+    pub peer: Option<WeakRef<LogicalSwitchPort>>,
+}
+
+fn schema() -> Arc<DatabaseSchema> {
+    Arc::new(serde_json::from_value(schema_json()).unwrap())
+}
+
+fn schema_json() -> Value {
+    let reference = |table: &str, ref_type: &str, max: Value| {
+        json!({
+            "type": {
+                "key": {"type": "uuid", "refTable": table, "refType": ref_type},
+                "min": 0,
+                "max": max,
+            }
+        })
+    };
+
+    json!({
+        "name": "OVN_Northbound",
+        "version": "7.3.0",
+        "tables": {
+            "Logical_Switch": {
+                "columns": {
+                    "name": {"type": "string"},
+                    "ports": reference("Logical_Switch_Port", "strong", json!("unlimited")),
+                },
+                "isRoot": true,
+            },
+            "Logical_Switch_Port": {
+                "columns": {
+                    "name": {"type": "string"},
+                    "peer": reference("Logical_Switch_Port", "weak", json!(1)),
+                },
+            },
+            "Logical_Router": {
+                "columns": {
+                    "ports": reference("Logical_Router_Port", "strong", json!("unlimited")),
+                },
+                "isRoot": true,
+            },
+            "Logical_Router_Port": {
+                "columns": {"name": {"type": "string"}},
+            },
+        },
+    })
+}
+
+fn uuid(n: u128) -> Uuid {
+    Uuid::from_u128(n)
+}
+
+fn uuids(ns: &[u128]) -> Value {
+    json!([
+        "set",
+        ns.iter()
+            .map(|n| json!(["uuid", uuid(*n)]))
+            .collect::<Vec<_>>()
+    ])
+}
+
+fn update2(update: Value) -> TableUpdate2<Value> {
+    serde_json::from_value(update).unwrap()
+}
+
+#[test]
+fn test_replica_references() {
+    let mut replica = Replica::new(schema());
+    replica
+        .apply_update2(&update2(json!({
+            "Logical_Switch": {
+                uuid(1).to_string(): {"initial": {"name": "ls0", "ports": uuids(&[10, 11])}},
+            },
+            "Logical_Switch_Port": {
+                uuid(10).to_string(): {"initial": {"name": "lsp0", "peer": ["uuid", uuid(11)]}},
+                uuid(11).to_string(): {"initial": {"name": "lsp1"}},
+                uuid(12).to_string(): {"initial": {"name": "orphan"}},
+            },
+        })))
+        .unwrap();
+
+    // The port no switch references is collected, and the columns the
+    // server left out hold their default
+    let switch: LogicalSwitch = replica.get(&uuid(1)).unwrap().unwrap();
+    assert_eq!(replica.table("Logical_Switch_Port").unwrap().len(), 2);
+    assert!(replica.row("Logical_Switch_Port", &uuid(12)).is_none());
+    assert_eq!(
+        replica.row("Logical_Switch_Port", &uuid(11)).unwrap()["peer"],
+        json!(["set", []])
+    );
+
+    let ports: Vec<LogicalSwitchPort> = switch
+        .ports
+        .iter()
+        .map(|port| replica.resolve(*port).unwrap().unwrap())
+        .collect();
+    assert_eq!(ports[0].name, "lsp0");
+    let peer = replica.resolve(ports[0].peer.unwrap()).unwrap().unwrap();
+    assert_eq!(peer.name, "lsp1");
+
+    // Removing the port from the switch collects it, leaving the weak
+    // reference to it dangling
+    replica
+        .apply_update2(&update2(json!({
+            "Logical_Switch": {uuid(1).to_string(): {"modify": {"ports": uuids(&[11])}}},
+        })))
+        .unwrap();
+    assert!(replica.row("Logical_Switch_Port", &uuid(11)).is_none());
+    let port: LogicalSwitchPort = replica.get(&uuid(10)).unwrap().unwrap();
+    assert_eq!(replica.resolve(port.peer.unwrap()).unwrap(), None);
+
+    replica
+        .apply_update2(&update2(json!({
+            "Logical_Switch": {uuid(1).to_string(): "delete"},
+        })))
+        .unwrap();
+    assert!(replica.table("Logical_Switch_Port").unwrap().is_empty());
+    assert!(replica.rows::<LogicalSwitch>().unwrap().is_empty());
+}
+
+#[test]
+fn test_replica_update() {
+    let mut replica = Replica::new(schema());
+    let update: TableUpdate<Value> = serde_json::from_value(json!({
+        "Logical_Router": {uuid(1).to_string(): {"new": {"ports": ["uuid", uuid(10)]}}},
+        "Logical_Router_Port": {uuid(10).to_string(): {"new": {"name": "lrp0"}}},
+    }))
+    .unwrap();
+    replica.apply_update(&update).unwrap();
+    assert_eq!(
+        replica.row("Logical_Router_Port", &uuid(10)).unwrap()["name"],
+        "lrp0"
+    );
+
+    let update: TableUpdate<Value> = serde_json::from_value(json!({
+        "Logical_Router": {uuid(1).to_string(): {"old": {"ports": ["uuid", uuid(10)]}}},
+    }))
+    .unwrap();
+    replica.apply_update(&update).unwrap();
+    assert!(replica.table("Logical_Router").unwrap().is_empty());
+    assert!(replica.table("Logical_Router_Port").unwrap().is_empty());
+}
+
+#[test]
+fn test_replica_without_roots() {
+    // No table of the schema sets isRoot, so every table is a root and the
+    // rows nothing references are kept
+    let mut schema = schema_json();
+    for table in schema["tables"].as_object_mut().unwrap().values_mut() {
+        table.as_object_mut().unwrap().remove("isRoot");
+    }
+    let mut replica = Replica::new(Arc::new(serde_json::from_value(schema).unwrap()));
+    replica
+        .apply_update2(&update2(json!({
+            "Logical_Switch": {uuid(1).to_string(): {"initial": {"name": "ls0"}}},
+            "Logical_Switch_Port": {uuid(10).to_string(): {"initial": {"name": "lsp0"}}},
+            "Logical_Router_Port": {uuid(20).to_string(): {"initial": {"name": "lrp0"}}},
+        })))
+        .unwrap();
+
+    assert!(replica.row("Logical_Switch", &uuid(1)).is_some());
+    assert!(replica.row("Logical_Switch_Port", &uuid(10)).is_some());
+    assert!(replica.row("Logical_Router_Port", &uuid(20)).is_some());
+}
+
+#[test]
+fn test_replica_partial() {
+    // The routers referencing the ports are not replicated, so the ports
+    // are kept whether or not they are referenced
+    let mut replica = Replica::with_tables(schema(), ["Logical_Router_Port"]);
+    replica
+        .apply_update2(&update2(json!({
+            "Logical_Router_Port": {uuid(10).to_string(): {"insert": {"name": "lrp0"}}},
+        })))
+        .unwrap();
+    assert!(replica.row("Logical_Router_Port", &uuid(10)).is_some());
+    assert_eq!(
+        replica.requests().keys().collect::<Vec<_>>(),
+        vec!["Logical_Router_Port"]
+    );
+
+    let err = replica
+        .apply_update2(&update2(json!({
+            "Logical_Router": {uuid(1).to_string(): {"insert": {}}},
+        })))
+        .unwrap_err();
+    assert!(matches!(err, Error::UnknownTable(table) if table == "Logical_Router"));
+}
+
+#[test]
+fn test_replica_changes() {
+    let mut replica = Replica::new(schema());
+    let changes = replica
+        .apply_update2(&update2(json!({
+            "Logical_Switch": {uuid(1).to_string(): {"insert": {"name": "ls0", "ports": uuids(&[10])}}},
+            "Logical_Switch_Port": {
+                uuid(10).to_string(): {"insert": {"name": "lsp0"}},
+                uuid(11).to_string(): {"insert": {"name": "orphan"}},
+            },
+        })))
+        .unwrap();
+
+    // The port collected by the update it was inserted in is left out
+    let mut inserted: Vec<Uuid> = changes.iter().map(|change| change.uuid).collect();
+    inserted.sort();
+    assert_eq!(inserted, vec![uuid(1), uuid(10)]);
+    assert!(changes.iter().all(|change| change.old.is_none()));
+
+    // The rows handed out are shared with the replica, and kept as they were
+    // when it modifies them
+    let switch = replica.row("Logical_Switch", &uuid(1)).unwrap().clone();
+    let port = replica
+        .row("Logical_Switch_Port", &uuid(10))
+        .unwrap()
+        .clone();
+    let changes = replica
+        .apply_update2(&update2(json!({
+            "Logical_Switch": {uuid(1).to_string(): {"modify": {"name": "ls1"}}},
+        })))
+        .unwrap();
+    assert_eq!(switch["name"], "ls0");
+    assert_eq!(
+        replica.row("Logical_Switch", &uuid(1)).unwrap()["name"],
+        "ls1"
+    );
+    assert!(Arc::ptr_eq(
+        &port,
+        replica.row("Logical_Switch_Port", &uuid(10)).unwrap()
+    ));
+    assert_eq!(changes.len(), 1);
+    assert!(Arc::ptr_eq(changes[0].old.as_ref().unwrap(), &switch));
+    assert!(Arc::ptr_eq(
+        changes[0].new.as_ref().unwrap(),
+        replica.row("Logical_Switch", &uuid(1)).unwrap()
+    ));
+
+    // Removing the port from the switch deletes it, while deleting a row the
+    // replica does not have changes nothing
+    let changes = replica
+        .apply_update2(&update2(json!({
+            "Logical_Switch": {uuid(1).to_string(): {"modify": {"ports": uuids(&[10])}}},
+            "Logical_Router": {uuid(2).to_string(): "delete"},
+        })))
+        .unwrap();
+    let collected = changes
+        .iter()
+        .find(|change| change.table == "Logical_Switch_Port")
+        .unwrap();
+    assert_eq!(changes.len(), 2);
+    assert!(Arc::ptr_eq(collected.old.as_ref().unwrap(), &port));
+    assert_eq!(collected.new, None);
+}
+
+#[tokio::test]
+async fn test_replica_monitor() {
+    let initial = json!({
+        "Logical_Switch": {uuid(1).to_string(): {"initial": {"name": "ls0"}}},
+    });
+    let (connection, mut served) = common::serve_duplex(common::results(vec![initial]));
+    let client = rpc::connect_stream(connection);
+
+    let mut replica = Replica::with_tables(schema(), ["Logical_Switch", "Logical_Switch_Port"]);
+    let mut updates = replica.monitor(&client, json!("replica")).await.unwrap();
+    assert_eq!(
+        replica.rows::<LogicalSwitch>().unwrap()[&uuid(1)].name,
+        "ls0"
+    );
+
+    let request = served.requests.recv().await.unwrap();
+    assert_eq!(request["method"], "monitor_cond");
+    assert_eq!(request["params"][1], "replica");
+    assert_eq!(
+        request["params"][2],
+        json!({"Logical_Switch": {}, "Logical_Switch_Port": {}})
+    );
+
+    served
+        .notify
+        .send(json!({
+            "id": null,
+            "method": "update2",
+            "params": [
+                "replica",
+                {
+                    "Logical_Switch": {uuid(1).to_string(): {"modify": {"ports": ["uuid", uuid(10)]}}},
+                    "Logical_Switch_Port": {uuid(10).to_string(): {"insert": {"name": "lsp0"}}},
+                },
+            ],
+        }))
+        .unwrap();
+    let notification = updates.next().await.unwrap().unwrap();
+    replica.apply_update2(&notification.message).unwrap();
+
+    let switch: LogicalSwitch = replica.get(&uuid(1)).unwrap().unwrap();
+    let port = replica.resolve(switch.ports[0]).unwrap().unwrap();
+    assert_eq!(port.name, "lsp0");
+}
+
+#[test]
+fn test_replica_save_load() {
+    let path = std::env::temp_dir().join(format!("ovsdb-replica-{}.db", std::process::id()));
+
+    let mut replica = Replica::with_tables(schema(), ["Logical_Switch", "Logical_Switch_Port"]);
+    replica
+        .apply_update3(
+            uuid(100),
+            &update2(json!({
+                "Logical_Switch": {uuid(1).to_string(): {"insert": {"name": "ls0", "ports": uuids(&[10])}}},
+                "Logical_Switch_Port": {uuid(10).to_string(): {"insert": {"name": "lsp0"}}},
+            })),
+        )
+        .unwrap();
+    replica.save(&path).unwrap();
+
+    let loaded = Replica::load(schema(), &path).unwrap();
+    assert_eq!(loaded.last_id(), Some(uuid(100)));
+    assert_eq!(
+        loaded.table("Logical_Switch"),
+        replica.table("Logical_Switch")
+    );
+    assert_eq!(
+        loaded.table("Logical_Switch_Port"),
+        replica.table("Logical_Switch_Port")
+    );
+    assert!(loaded.table("Logical_Router").is_none());
+
+    // A replica saved with another schema is not loaded
+    let mut other =
+        json!({"name": "OVN_Northbound", "version": "7.4.0", "cksum": "2 2", "tables": {}});
+    other["tables"]["Logical_Switch"] = json!({"columns": {"name": {"type": "string"}}});
+    let err = Replica::load(Arc::new(serde_json::from_value(other).unwrap()), &path).unwrap_err();
+    assert!(matches!(err, FileError::InvalidRecord { .. }), "{err:?}");
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn test_replica_monitor_since() {
+    let mut replies = vec![
+        json!([true, uuid(101), {
+            "Logical_Switch_Port": {uuid(11).to_string(): {"insert": {"name": "lsp1"}}},
+            "Logical_Switch": {uuid(1).to_string(): {"modify": {"ports": ["uuid", uuid(11)]}}},
+        }]),
+        json!([false, uuid(200), {
+            "Logical_Switch": {uuid(2).to_string(): {"initial": {"name": "ls1"}}},
+        }]),
+    ]
+    .into_iter();
+    let (connection, mut served) =
+        common::serve_duplex(move |_| replies.next().map(common::result));
+    let client = rpc::connect_stream(connection);
+
+    let mut replica = Replica::with_tables(schema(), ["Logical_Switch", "Logical_Switch_Port"]);
+    replica
+        .apply_update3(
+            uuid(100),
+            &update2(json!({
+                "Logical_Switch": {uuid(1).to_string(): {"insert": {"name": "ls0", "ports": uuids(&[10])}}},
+                "Logical_Switch_Port": {uuid(10).to_string(): {"insert": {"name": "lsp0"}}},
+            })),
+        )
+        .unwrap();
+
+    // The server still has the transaction, so only the changes made after
+    // it are applied
+    let mut updates = replica
+        .monitor_since(&client, json!(["replica", 1]))
+        .await
+        .unwrap();
+    let request = served.requests.recv().await.unwrap();
+    assert_eq!(request["method"], "monitor_cond_since");
+    assert_eq!(request["params"][3], json!(uuid(100)));
+    assert_eq!(replica.last_id(), Some(uuid(101)));
+    assert_eq!(
+        replica
+            .get::<LogicalSwitch>(&uuid(1))
+            .unwrap()
+            .unwrap()
+            .ports
+            .len(),
+        2
+    );
+
+    served
+        .notify
+        .send(json!({
+            "id": null,
+            "method": "update3",
+            "params": [
+                ["replica", 1],
+                uuid(102),
+                {"Logical_Switch_Port": {uuid(10).to_string(): {"modify": {"name": "lsp2"}}}},
+            ],
+        }))
+        .unwrap();
+    let notification = updates.next().await.unwrap().unwrap();
+    replica
+        .apply_update3(notification.last_id, &notification.message)
+        .unwrap();
+    assert_eq!(replica.last_id(), Some(uuid(102)));
+    assert_eq!(
+        replica
+            .get::<LogicalSwitchPort>(&uuid(10))
+            .unwrap()
+            .unwrap()
+            .name,
+        "lsp2"
+    );
+
+    // Otherwise the initial state replaces the rows
+    let _updates = replica
+        .monitor_since(&client, json!(["replica", 2]))
+        .await
+        .unwrap();
+    assert_eq!(replica.last_id(), Some(uuid(200)));
+    assert_eq!(
+        replica
+            .rows::<LogicalSwitch>()
+            .unwrap()
+            .keys()
+            .collect::<Vec<_>>(),
+        vec![&uuid(2)]
+    );
+    assert!(replica.table("Logical_Switch_Port").unwrap().is_empty());
+}
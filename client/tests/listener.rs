@@ -0,0 +1,68 @@
+use ovsdb_client::{
+    rpc::{self, RpcClient},
+    transports::listener::{Listener, ListenerError},
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_accept() {
+    let listener = Listener::bind("ptcp:0:127.0.0.1").await.unwrap();
+    let remote = listener.local_remote().unwrap();
+    let addr = remote.strip_prefix("tcp:").unwrap().to_owned();
+
+    let server = tokio::spawn(async move {
+        let (mut sender, mut receiver) = listener.accept().await.unwrap();
+
+        let request = receiver.receive().await.unwrap().unwrap();
+        assert_eq!(request["method"], "list_dbs");
+        assert_eq!(request["params"], json!([]));
+        assert!(request.get("jsonrpc").is_none());
+
+        sender
+            .send(&json!({"id": request["id"], "result": ["OVN_Northbound"], "error": null}))
+            .await
+            .unwrap();
+    });
+
+    let client = rpc::connect_tcp(addr).await.unwrap();
+    assert_eq!(
+        client.list_databases().await.unwrap(),
+        vec!["OVN_Northbound"]
+    );
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_unix() {
+    let path = std::env::temp_dir().join(format!("ovsdb-listener-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = Listener::bind(&format!("punix:{}", path.display()))
+        .await
+        .unwrap();
+    assert_eq!(
+        listener.local_remote().unwrap(),
+        format!("unix:{}", path.display())
+    );
+
+    let _client = rpc::connect_unix(&path).await.unwrap();
+    let (_, peer) = listener.accept_connection().await.unwrap();
+    assert!(peer.starts_with("unix:"));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_invalid_remotes() {
+    for remote in [
+        "tcp:127.0.0.1:6641",
+        "ptcp:port",
+        "ptcp:6641:host",
+        "punix:",
+        "punix",
+    ] {
+        assert!(
+            matches!(Listener::bind(remote).await, Err(ListenerError::InvalidRemote(r)) if r == remote),
+            "{remote}"
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use ovsdb_client::{
+    metrics,
+    rpc::{self, RpcClient},
+};
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+#[tokio::test]
+async fn test_metrics() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+
+    tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0; 65536];
+
+        let n = stream.read(&mut buf).await.unwrap();
+        let request: Value = serde_json::from_slice(&buf[..n]).unwrap();
+        let reply = json!({"id": request["id"], "result": ["OVN_Northbound"], "error": null});
+        let update = json!({
+            "id": null,
+            "method": "update",
+            "params": [null, {"Logical_Switch": {"u1": {}, "u2": {}}}]
+        });
+        stream
+            .write_all(format!("{reply}{update}").as_bytes())
+            .await
+            .unwrap();
+    });
+
+    let client = rpc::connect_tcp(&address).await.unwrap();
+    client.list_databases().await.unwrap();
+    client.list_databases().await.unwrap_err();
+
+    let output = metrics::render();
+    for line in [
+        "# TYPE ovsdb_client_requests_total counter",
+        "ovsdb_client_requests_total{method=\"list_dbs\"} 2",
+        "ovsdb_client_request_errors_total{method=\"list_dbs\"} 1",
+        "# TYPE ovsdb_client_request_duration_seconds histogram",
+        "ovsdb_client_request_duration_seconds_bucket{method=\"list_dbs\",le=\"+Inf\"} 2",
+        "ovsdb_client_request_duration_seconds_count{method=\"list_dbs\"} 2",
+        "ovsdb_client_connections_total 1",
+        "ovsdb_client_disconnections_total 1",
+        "ovsdb_client_updates_total{table=\"Logical_Switch\"} 1",
+        "ovsdb_client_updated_rows_total{table=\"Logical_Switch\"} 2",
+    ] {
+        assert!(output.lines().any(|l| l == line), "{line:?} in {output}");
+    }
+}
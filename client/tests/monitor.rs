@@ -0,0 +1,332 @@
+use futures_util::{StreamExt, stream};
+use jsonrpsee::core::client::{ClientT, SubscriptionClientT};
+use ovsdb_client::{
+    Error,
+    monitor::UpdateStreamExt,
+    rpc::{ConnectOptions, RpcClient},
+    schema::{
+        MonitorCondRequest, MonitorRequest, RowUpdate, Update2Notification, UpdateNotification,
+    },
+    transact::{Condition, Function},
+};
+use serde_json::{Value, json};
+use std::{collections::HashMap, time::Duration};
+use tokio::{sync::mpsc, time::Instant};
+use uuid::Uuid;
+
+mod common;
+
+type Item = Result<UpdateNotification<Value>, String>;
+
+/// Build the notification of a monitor updating the rows of "Port", given
+/// as their UUID, as a number, and their old and new values.
+fn notification(id: &str, rows: &[(u128, Option<Value>, Option<Value>)]) -> Item {
+    let rows = rows
+        .iter()
+        .map(|(uuid, old, new)| {
+            (
+                Uuid::from_u128(*uuid),
+                RowUpdate {
+                    old: old.clone(),
+                    new: new.clone(),
+                },
+            )
+        })
+        .collect();
+
+    Ok(UpdateNotification {
+        id: json!(id),
+        message: HashMap::from([("Port".to_owned(), rows)]),
+    })
+}
+
+/// Returns the rows of "Port" updated by a notification, sorted by UUID.
+fn rows(item: Item) -> Vec<(u128, Option<Value>, Option<Value>)> {
+    let mut notification = item.unwrap();
+    let mut rows: Vec<_> = notification
+        .message
+        .remove("Port")
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(uuid, update)| (uuid.as_u128(), update.old, update.new))
+        .collect();
+    rows.sort_by_key(|row| row.0);
+    rows
+}
+
+fn channel() -> (
+    mpsc::UnboundedSender<Item>,
+    impl futures_util::Stream<Item = Item>,
+) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let updates = stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|item| (item, receiver))
+    });
+
+    (sender, updates)
+}
+
+#[tokio::test]
+async fn test_coalesce_burst() {
+    let (sender, updates) = channel();
+    let mut updates = updates.coalesce(Duration::from_millis(100));
+
+    sender
+        .send(notification("ports", &[(1, None, Some(json!(1)))]))
+        .unwrap();
+    assert_eq!(
+        rows(updates.next().await.unwrap()),
+        vec![(1, None, Some(json!(1)))]
+    );
+
+    let start = Instant::now();
+    sender
+        .send(notification(
+            "ports",
+            &[(1, Some(json!(1)), Some(json!(2)))],
+        ))
+        .unwrap();
+    sender
+        .send(notification("ports", &[(2, None, Some(json!(1)))]))
+        .unwrap();
+    sender
+        .send(notification(
+            "ports",
+            &[
+                (1, Some(json!(2)), Some(json!(3))),
+                (2, Some(json!(1)), None),
+            ],
+        ))
+        .unwrap();
+    sender
+        .send(notification("ports", &[(3, Some(json!(1)), None)]))
+        .unwrap();
+
+    assert_eq!(
+        rows(updates.next().await.unwrap()),
+        vec![
+            (1, Some(json!(1)), Some(json!(3))),
+            (3, Some(json!(1)), None),
+        ]
+    );
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    drop(sender);
+    assert!(updates.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_coalesce_old_columns() {
+    let (sender, updates) = channel();
+    let mut updates = updates.coalesce(Duration::from_millis(100));
+
+    sender
+        .send(notification(
+            "ports",
+            &[(1, None, Some(json!({"a": 1, "b": 1})))],
+        ))
+        .unwrap();
+    updates.next().await.unwrap().unwrap();
+
+    // The old rows of modifications only hold the columns they changed, and
+    // those of deletions the whole row
+    sender
+        .send(notification(
+            "ports",
+            &[
+                (1, Some(json!({"a": 1})), Some(json!({"a": 2, "b": 1}))),
+                (2, Some(json!({"a": 1})), Some(json!({"a": 2, "b": 1}))),
+            ],
+        ))
+        .unwrap();
+    sender
+        .send(notification(
+            "ports",
+            &[
+                (1, Some(json!({"b": 1})), Some(json!({"a": 2, "b": 2}))),
+                (2, Some(json!({"a": 2, "b": 1})), None),
+            ],
+        ))
+        .unwrap();
+
+    assert_eq!(
+        rows(updates.next().await.unwrap()),
+        vec![
+            (
+                1,
+                Some(json!({"a": 1, "b": 1})),
+                Some(json!({"a": 2, "b": 2}))
+            ),
+            (2, Some(json!({"a": 1, "b": 1})), None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_coalesce_per_monitor() {
+    let (sender, updates) = channel();
+    let mut updates = updates.coalesce(Duration::from_millis(50));
+
+    sender
+        .send(notification("first", &[(1, None, Some(json!(1)))]))
+        .unwrap();
+    assert!(updates.next().await.unwrap().is_ok());
+
+    sender
+        .send(notification(
+            "first",
+            &[(1, Some(json!(1)), Some(json!(2)))],
+        ))
+        .unwrap();
+    sender
+        .send(notification("second", &[(1, None, Some(json!(1)))]))
+        .unwrap();
+    sender.send(Err("failed".to_owned())).unwrap();
+    sender
+        .send(notification("second", &[(2, None, Some(json!(1)))]))
+        .unwrap();
+    drop(sender);
+
+    let delivered: Vec<_> = updates.collect().await;
+    let delivered: Vec<_> = delivered
+        .into_iter()
+        .map(|item| item.map(|notification| notification.id))
+        .collect();
+    assert_eq!(
+        delivered,
+        vec![
+            Ok(json!("first")),
+            Ok(json!("second")),
+            Err("failed".to_owned()),
+            Ok(json!("second")),
+        ]
+    );
+}
+
+/// Start a server over an in-memory stream which replies to every request
+/// with an empty object.
+fn serve() -> (tokio::io::DuplexStream, common::Served) {
+    common::serve_duplex(|_| Some(common::result(json!({}))))
+}
+
+fn update(method: &str, id: Value, name: &str) -> Value {
+    let row = match method {
+        "update" => json!({"new": {"name": name}}),
+        _ => json!({"insert": {"name": name}}),
+    };
+
+    json!({
+        "id": null,
+        "method": method,
+        "params": [id, {"Database": {"6a4c7a4e-0000-4000-8000-000000000001": row}}],
+    })
+}
+
+#[tokio::test]
+async fn test_monitor_streams() {
+    let (connection, served) = serve();
+    let notify = served.notify;
+    let client = ConnectOptions::new().connect_stream(connection);
+
+    let nb = json!(["OVN_Northbound", "switches"]);
+    let server = json!(["_Server", "databases"]);
+    let requests = || HashMap::from([("Database".to_owned(), MonitorRequest::default())]);
+    let (_, mut nb_updates) = client
+        .monitor_updates::<Value>("OVN_Northbound", nb.clone(), requests())
+        .await
+        .unwrap();
+    let (_, mut server_updates) = client
+        .monitor_cond_updates::<Value>("_Server", server.clone(), HashMap::new())
+        .await
+        .unwrap();
+    let mut others = client
+        .subscribe_to_method::<UpdateNotification<Value>>("update")
+        .await
+        .unwrap();
+
+    notify
+        .send(update("update2", server.clone(), "_Server"))
+        .unwrap();
+    notify
+        .send(update("update", nb.clone(), "OVN_Northbound"))
+        .unwrap();
+    notify.send(update("update", Value::Null, "other")).unwrap();
+
+    let notification: Update2Notification<Value> = server_updates.next().await.unwrap().unwrap();
+    assert_eq!(notification.id, server);
+    let notification = nb_updates.next().await.unwrap().unwrap();
+    assert_eq!(notification.id, nb);
+    let notification = others.next().await.unwrap().unwrap();
+    assert_eq!(notification.id, Value::Null);
+
+    let duplicate = client
+        .monitor_updates::<Value>("OVN_Northbound", nb.clone(), requests())
+        .await;
+    assert!(matches!(duplicate, Err(Error::DuplicateMonitor(id)) if id == nb));
+
+    let _: Value = client
+        .request("monitor_cancel", jsonrpsee::rpc_params![nb.clone()])
+        .await
+        .unwrap();
+    assert!(nb_updates.next().await.is_none());
+
+    notify
+        .send(json!({"id": null, "method": "monitor_canceled", "params": [server]}))
+        .unwrap();
+    assert!(server_updates.next().await.is_none());
+
+    drop(server_updates);
+    let (_, mut nb_updates) = client
+        .monitor_updates::<Value>("OVN_Northbound", nb.clone(), requests())
+        .await
+        .unwrap();
+    notify.send(update("update", nb.clone(), "again")).unwrap();
+    assert!(nb_updates.next().await.unwrap().is_ok());
+
+    drop(notify);
+    client.shutdown().await.unwrap();
+    assert!(nb_updates.next().await.is_none());
+}
+
+#[tokio::test]
+async fn test_monitor_cond_change() {
+    let (connection, served) = serve();
+    let notify = served.notify;
+    let client = ConnectOptions::new().connect_stream(connection);
+
+    let old = json!(["_Server", 1]);
+    let new = json!(["_Server", 2]);
+    let (_, mut updates) = client
+        .monitor_cond_updates::<Value>("_Server", old.clone(), HashMap::new())
+        .await
+        .unwrap();
+
+    let requests = HashMap::from([(
+        "Database".to_owned(),
+        MonitorCondRequest {
+            conditions: Some(vec![Condition::new(
+                "name",
+                Function::Equal,
+                json!("OVN_Northbound"),
+            )]),
+            ..Default::default()
+        },
+    )]);
+    client
+        .monitor_cond_change(old.clone(), new.clone(), requests)
+        .await
+        .unwrap();
+
+    // The stream of the monitor follows it to its new id
+    notify
+        .send(update("update2", new.clone(), "OVN_Northbound"))
+        .unwrap();
+    let notification: Update2Notification<Value> = updates.next().await.unwrap().unwrap();
+    assert_eq!(notification.id, new);
+
+    let _: Value = client
+        .request("monitor_cancel", jsonrpsee::rpc_params![new])
+        .await
+        .unwrap();
+    assert!(updates.next().await.is_none());
+}
@@ -0,0 +1,70 @@
+use futures_util::future::join_all;
+use ovsdb_client::rpc::{ConnectOptions, RpcClient};
+use serde_json::json;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+mod common;
+
+/// Start a server over an in-memory stream which replies to the "echo"
+/// requests in the reverse order they were received, once it holds the
+/// given number of them or is idle, reporting how many it held at most.
+fn serve(batch: usize) -> (tokio::io::DuplexStream, oneshot::Receiver<usize>) {
+    let (client, mut peer) = common::duplex();
+    let (report, held) = oneshot::channel();
+
+    tokio::spawn(async move {
+        let mut pending = Vec::new();
+        let mut most = 0;
+        loop {
+            match tokio::time::timeout(Duration::from_millis(20), peer.receive()).await {
+                Ok(Some(message)) => {
+                    pending.push(message);
+                    most = most.max(pending.len());
+                    if pending.len() < batch {
+                        continue;
+                    }
+                }
+                Ok(_) => break,
+                Err(_) => {}
+            }
+            for message in pending.drain(..).rev() {
+                let reply = common::result(message["params"].clone());
+                peer.reply(&message, reply).await;
+            }
+        }
+        report.send(most).unwrap();
+    });
+
+    (client, held)
+}
+
+#[tokio::test]
+async fn test_pipelining() {
+    let (connection, held) = serve(8);
+    let client = ConnectOptions::new().connect_stream(connection);
+
+    let replies = join_all((0..64).map(|i| client.echo(vec![json!(i)]))).await;
+    for (i, reply) in replies.into_iter().enumerate() {
+        assert_eq!(reply.unwrap(), vec![json!([i])]);
+    }
+
+    drop(client);
+    assert_eq!(held.await.unwrap(), 8);
+}
+
+#[tokio::test]
+async fn test_max_in_flight() {
+    let (connection, held) = serve(usize::MAX);
+    let client = ConnectOptions::new()
+        .with_max_in_flight(4)
+        .connect_stream(connection);
+
+    let replies = join_all((0..32).map(|i| client.echo(vec![json!(i)]))).await;
+    for (i, reply) in replies.into_iter().enumerate() {
+        assert_eq!(reply.unwrap(), vec![json!([i])]);
+    }
+
+    drop(client);
+    assert_eq!(held.await.unwrap(), 4);
+}
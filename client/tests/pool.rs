@@ -0,0 +1,121 @@
+use ovsdb_client::{
+    Error,
+    pool::OvsdbPool,
+    rpc::{self, RpcClient},
+    transports::listener::Listener,
+};
+use serde_json::json;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+/// A server replying to the "echo" requests, unless silenced, and counting
+/// the connections it accepted
+struct Server {
+    remote: String,
+
+    connections: Arc<AtomicUsize>,
+
+    silent: Arc<AtomicBool>,
+}
+
+async fn serve() -> Server {
+    let listener = Listener::bind("ptcp:0:127.0.0.1").await.unwrap();
+    let remote = listener.local_remote().unwrap();
+    let connections = Arc::new(AtomicUsize::new(0));
+    let silent = Arc::new(AtomicBool::new(false));
+
+    let (accepted, silenced) = (connections.clone(), silent.clone());
+    tokio::spawn(async move {
+        while let Ok((mut sender, mut receiver)) = listener.accept().await {
+            accepted.fetch_add(1, Ordering::SeqCst);
+            let silenced = silenced.clone();
+            tokio::spawn(async move {
+                while let Some(Ok(message)) = receiver.receive().await {
+                    if !silenced.load(Ordering::SeqCst) {
+                        let reply = json!({"id": message["id"], "result": [], "error": null});
+                        sender.send(&reply).await.unwrap();
+                    }
+                }
+            });
+        }
+    });
+
+    Server {
+        remote,
+        connections,
+        silent,
+    }
+}
+
+#[tokio::test]
+async fn test_reuse() {
+    let server = serve().await;
+    let pool = OvsdbPool::new([server.remote.clone()], 2);
+
+    for _ in 0..3 {
+        let client = pool.get().await.unwrap();
+        client.echo(Vec::new()).await.unwrap();
+    }
+    assert_eq!(server.connections.load(Ordering::SeqCst), 1);
+    assert_eq!(pool.idle_count(), 1);
+
+    let (first, second) = (pool.get().await.unwrap(), pool.get().await.unwrap());
+    first.echo(Vec::new()).await.unwrap();
+    second.echo(Vec::new()).await.unwrap();
+    assert_eq!(server.connections.load(Ordering::SeqCst), 2);
+
+    // Both connections are checked out, so the next checkout waits
+    let third = tokio::time::timeout(Duration::from_millis(50), pool.get()).await;
+    assert!(third.is_err());
+
+    drop(first);
+    let third = pool.get().await.unwrap();
+    third.echo(Vec::new()).await.unwrap();
+    assert_eq!(server.connections.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_health_check() {
+    let server = serve().await;
+    let pool = OvsdbPool::new([server.remote.clone()], 1)
+        .with_options(rpc::ConnectOptions::new().with_request_timeout(Duration::from_millis(50)))
+        .with_health_check(Duration::ZERO);
+
+    pool.get().await.unwrap().echo(Vec::new()).await.unwrap();
+    assert_eq!(server.connections.load(Ordering::SeqCst), 1);
+
+    // The idle connection stops answering, so it gets replaced
+    server.silent.store(true, Ordering::SeqCst);
+    let client = pool.get().await.unwrap();
+    server.silent.store(false, Ordering::SeqCst);
+    client.echo(Vec::new()).await.unwrap();
+    assert_eq!(server.connections.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_remotes() {
+    let server = serve().await;
+
+    // Nothing listens on the port of a closed listener
+    let closed = Listener::bind("ptcp:0:127.0.0.1").await.unwrap();
+    let unreachable = closed.local_remote().unwrap();
+    drop(closed);
+
+    let pool = OvsdbPool::new([unreachable.clone(), server.remote.clone()], 2);
+    let (first, second) = (pool.get().await.unwrap(), pool.get().await.unwrap());
+    first.echo(Vec::new()).await.unwrap();
+    second.echo(Vec::new()).await.unwrap();
+    assert_eq!(server.connections.load(Ordering::SeqCst), 2);
+
+    let pool = OvsdbPool::new([unreachable], 1);
+    let err = pool.get().await.unwrap_err();
+    assert!(matches!(err, Error::Transport(_)), "{err:?}");
+
+    let err = rpc::connect("ssl:127.0.0.1:6641").await.unwrap_err();
+    assert!(matches!(err, Error::InvalidRemote(_)), "{err:?}");
+}
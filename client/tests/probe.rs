@@ -0,0 +1,64 @@
+use ovsdb_client::{
+    Error,
+    rpc::{ConnectOptions, RpcClient},
+};
+use serde_json::{Value, json};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+mod common;
+
+/// Start a server over an in-memory stream which passes the messages it
+/// receives to the channel, answering the "echo" requests if asked to.
+fn serve(answer: bool) -> (tokio::io::DuplexStream, mpsc::UnboundedReceiver<Value>) {
+    let (connection, served) = common::serve_duplex(move |message| {
+        (answer && message["method"] == "echo").then(|| common::result(message["params"].clone()))
+    });
+
+    (connection, served.requests)
+}
+
+#[tokio::test]
+async fn test_inactivity_probe() {
+    let (connection, mut received) = serve(true);
+    let client = ConnectOptions::new()
+        .with_inactivity_probe(Duration::from_millis(50))
+        .connect_stream(connection);
+
+    // The probes are answered, so the idle connection stays up
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    client.echo(vec![json!(1)]).await.unwrap();
+
+    let probe = received.recv().await.unwrap();
+    assert_eq!(probe, json!({"id": "echo", "method": "echo", "params": []}));
+}
+
+#[tokio::test]
+async fn test_inactivity_probe_timeout() {
+    let (connection, mut received) = serve(false);
+    let client = ConnectOptions::new()
+        .with_inactivity_probe(Duration::from_millis(50))
+        .connect_stream(connection);
+
+    let err = client.list_databases().await.unwrap_err();
+    assert!(
+        matches!(err, Error::Inactive(duration) if duration == Duration::from_millis(100)),
+        "{err:?}"
+    );
+
+    assert_eq!(received.recv().await.unwrap()["method"], "list_dbs");
+    assert_eq!(received.recv().await.unwrap()["id"], "echo");
+}
+
+#[tokio::test]
+async fn test_server_echo() {
+    let (client, server) = tokio::io::duplex(64 * 1024);
+    let _client = ConnectOptions::new().connect_stream(client);
+
+    let mut peer = common::Peer::new(server);
+    peer.send(&json!({"id": "echo", "method": "echo", "params": []}))
+        .await;
+
+    let reply = peer.receive().await.unwrap();
+    assert_eq!(reply, json!({"id": "echo", "result": [], "error": null}));
+}
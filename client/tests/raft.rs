@@ -0,0 +1,241 @@
+use ovsdb_client::{
+    cluster::ClusterStatus,
+    file::{
+        FileError,
+        raft::{ClusterHeader, ClusteredDatabase, LogEntry, RaftRecord, records},
+    },
+};
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+const SERVER: &str = "6f4bd0b4-0b4c-4b1e-9d5e-5a1c2b3d4e01";
+const PEER: &str = "6f4bd0b4-0b4c-4b1e-9d5e-5a1c2b3d4e02";
+const CLUSTER: &str = "1d2c3b4a-5e6f-4a1b-8c2d-3e4f5a6b7c08";
+const LS0: &str = "0b4ad97e-5a8e-4b6c-9b1b-3c1f5e7a9d01";
+const LS1: &str = "5f0c4d2e-8b7a-4f3e-a1d2-6e9b8c7a5d02";
+
+fn record(data: &Value) -> String {
+    let data = data.to_string();
+    format!(
+        "CLUSTER {} {}\n{data}\n",
+        data.len(),
+        sha1_smol::Sha1::from(&data).digest()
+    )
+}
+
+fn schema(version: &str) -> Value {
+    json!({
+        "name": "OVN_Northbound",
+        "version": version,
+        "tables": {
+            "Logical_Switch": {
+                "columns": {"name": {"type": "string"}},
+                "isRoot": true
+            }
+        }
+    })
+}
+
+fn header() -> Value {
+    json!({
+        "name": "OVN_Northbound",
+        "server_id": SERVER,
+        "cluster_id": CLUSTER,
+        "local_address": "tcp:10.0.0.1:6643",
+        "prev_term": 2,
+        "prev_index": 10,
+        "prev_servers": {SERVER: "tcp:10.0.0.1:6643", PEER: "tcp:10.0.0.2:6643"},
+        "prev_election_timer": 1000,
+        "prev_data": [schema("7.3.0"), {"Logical_Switch": {LS0: {"name": "ls0"}}}]
+    })
+}
+
+fn file(records: &[Value]) -> String {
+    std::iter::once(&header())
+        .chain(records)
+        .map(record)
+        .collect()
+}
+
+fn names(cluster: &ClusteredDatabase) -> Vec<String> {
+    cluster.database.as_ref().unwrap().tables["Logical_Switch"]
+        .values()
+        .map(|row| row["name"].as_str().unwrap().to_owned())
+        .collect()
+}
+
+#[test]
+fn test_records() {
+    let file = file(&[
+        json!({"term": 3}),
+        json!({"term": 3, "vote": PEER}),
+        json!({"term": 3, "leader": PEER}),
+        json!({"term": 3, "index": 11, "data": [null, {}], "eid": LS1}),
+        json!({"commit_index": 11}),
+        json!({"note": "transfer leadership"}),
+    ]);
+    let mut records = records(file.as_bytes());
+
+    let header = ClusterHeader::from_record(&records.next().unwrap().unwrap()).unwrap();
+    assert_eq!(header.local_address, "tcp:10.0.0.1:6643");
+
+    let records = records
+        .map(|record| RaftRecord::from_record(&record.unwrap()).unwrap())
+        .collect::<Vec<_>>();
+    let peer = Uuid::parse_str(PEER).unwrap();
+    assert_eq!(
+        records,
+        vec![
+            RaftRecord::Term { term: 3 },
+            RaftRecord::Vote {
+                term: 3,
+                vote: peer
+            },
+            RaftRecord::Leader {
+                term: 3,
+                leader: peer
+            },
+            RaftRecord::Entry(LogEntry {
+                term: 3,
+                index: 11,
+                data: Some(json!([null, {}])),
+                eid: Some(Uuid::parse_str(LS1).unwrap()),
+                servers: None,
+                election_timer: None,
+            }),
+            RaftRecord::CommitIndex(11),
+            RaftRecord::Note("transfer leadership".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn test_read_cluster() {
+    let cluster = ClusteredDatabase::read(
+        file(&[
+            json!({"term": 3, "vote": SERVER}),
+            json!({"term": 3, "index": 11,
+                   "data": [null, {"Logical_Switch": {LS1: {"name": "ls1"}}}]}),
+            json!({"term": 3, "leader": SERVER}),
+            json!({"commit_index": 11}),
+            json!({"term": 3, "index": 12,
+                   "data": [null, {"Logical_Switch": {LS0: null}}]}),
+        ])
+        .as_bytes(),
+    )
+    .unwrap();
+
+    let header = &cluster.header;
+    assert_eq!(header.name, "OVN_Northbound");
+    assert_eq!(header.server_id, Uuid::parse_str(SERVER).unwrap());
+    assert_eq!(header.cluster_id, Some(Uuid::parse_str(CLUSTER).unwrap()));
+    let snapshot = header.snapshot.as_ref().unwrap();
+    assert_eq!((snapshot.term, snapshot.index), (2, 10));
+    assert_eq!(snapshot.servers[PEER], "tcp:10.0.0.2:6643");
+
+    assert_eq!(cluster.term, 3);
+    assert_eq!(cluster.vote, Some(Uuid::parse_str(SERVER).unwrap()));
+    assert_eq!(cluster.leader, Some(Uuid::parse_str(SERVER).unwrap()));
+    assert_eq!(cluster.commit_index, Some(11));
+    assert_eq!(cluster.last_index(), 12);
+    assert_eq!(names(&cluster), vec!["ls1"]);
+}
+
+#[test]
+fn test_cluster_status() {
+    const JOINED: &str = "6f4bd0b4-0b4c-4b1e-9d5e-5a1c2b3d4e03";
+
+    let cluster = ClusteredDatabase::read(
+        file(&[
+            json!({"term": 3, "leader": PEER}),
+            json!({"term": 3, "index": 11, "servers": {
+                SERVER: "tcp:10.0.0.1:6643",
+                PEER: "tcp:10.0.0.2:6643",
+                JOINED: "tcp:10.0.0.3:6643",
+            }}),
+            json!({"commit_index": 11}),
+        ])
+        .as_bytes(),
+    )
+    .unwrap();
+
+    let status = ClusterStatus::from(&cluster);
+    assert_eq!(status.server_id, Uuid::parse_str(SERVER).unwrap());
+    assert_eq!(status.cluster_id, Some(Uuid::parse_str(CLUSTER).unwrap()));
+    assert_eq!(status.term, 3);
+    assert_eq!(status.leader, Some(Uuid::parse_str(PEER).unwrap()));
+    assert!(!status.is_leader());
+    assert_eq!((status.commit_index, status.last_index), (Some(11), 11));
+    assert_eq!(status.servers.len(), 3);
+    assert_eq!(
+        status.servers[&Uuid::parse_str(JOINED).unwrap()],
+        "tcp:10.0.0.3:6643"
+    );
+
+    // Without a change of membership, the servers are those of the snapshot
+    let cluster =
+        ClusteredDatabase::read(file(&[json!({"term": 3, "leader": SERVER})]).as_bytes()).unwrap();
+    let status = ClusterStatus::from(&cluster);
+    assert!(status.is_leader());
+    assert_eq!(status.last_index, 10);
+    assert_eq!(status.servers.len(), 2);
+}
+
+#[test]
+fn test_replaced_entries() {
+    // The entry written at index 11 in term 3 was never committed and is
+    // overwritten by the leader of term 4, which also converts the database.
+    let cluster = ClusteredDatabase::read(
+        file(&[
+            json!({"term": 3, "index": 11,
+                   "data": [null, {"Logical_Switch": {LS1: {"name": "lost"}}}]}),
+            json!({"term": 3, "index": 12, "data": [null, {}]}),
+            json!({"term": 4}),
+            json!({"term": 4, "index": 11,
+                   "data": [schema("7.4.0"), {"Logical_Switch": {LS1: {"name": "ls1"}}}]}),
+        ])
+        .as_bytes(),
+    )
+    .unwrap();
+
+    assert_eq!(cluster.term, 4);
+    assert_eq!(cluster.vote, None);
+    assert_eq!(cluster.entries.len(), 1);
+    assert_eq!(cluster.last_index(), 11);
+    assert_eq!(cluster.database.as_ref().unwrap().schema.version, "7.4.0");
+    assert_eq!(names(&cluster), vec!["ls1"]);
+}
+
+#[test]
+fn test_joining_server() {
+    let header = json!({
+        "name": "OVN_Northbound",
+        "server_id": SERVER,
+        "local_address": "tcp:10.0.0.1:6643",
+        "remote_addresses": ["tcp:10.0.0.2:6643"]
+    });
+    let cluster = ClusteredDatabase::read(record(&header).as_bytes()).unwrap();
+
+    assert_eq!(cluster.header.cluster_id, None);
+    assert_eq!(cluster.header.remote_addresses, vec!["tcp:10.0.0.2:6643"]);
+    assert!(cluster.header.snapshot.is_none());
+    assert!(cluster.database.is_none());
+}
+
+#[test]
+fn test_invalid_records() {
+    let file = file(&[json!({"term": 3, "vote": "nobody"})]);
+    let offset = file.rfind("CLUSTER").unwrap() as u64;
+
+    assert!(matches!(
+        ClusteredDatabase::read(file.as_bytes()),
+        Err(FileError::InvalidRecord { offset: o, .. }) if o == offset
+    ));
+
+    // Standalone files have a different magic
+    let standalone = record(&header()).replace("CLUSTER", "OVSDB JSON");
+    assert!(matches!(
+        ClusteredDatabase::read(standalone.as_bytes()),
+        Err(FileError::InvalidHeader { offset: 0, .. })
+    ));
+}
@@ -0,0 +1,103 @@
+use ovsdb_client::{
+    Error,
+    registry::TableRegistry,
+    schema::{TableUpdate, TableUpdateRows},
+};
+use ovsdb_derive::ovsdb_object;
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+#[ovsdb_object(table = "Logical_Switch")]
+#[derive(Debug, PartialEq)]
+pub struct LogicalSwitch {
+    pub name: String,
+}
+
+#[ovsdb_object(table = "Logical_Router")]
+#[derive(Debug, PartialEq)]
+pub struct LogicalRouter {
+    pub name: String,
+    pub enabled: Option<bool>,
+}
+
+const LS0: &str = "0b4ad97e-5a8e-4b6c-9b1b-3c1f5e7a9d01";
+const LR0: &str = "5f0c4d2e-8b7a-4f3e-a1d2-6e9b8c7a5d02";
+
+#[test]
+fn test_dispatch() {
+    let switches = Arc::new(Mutex::new(Vec::new()));
+    let routers = Arc::new(Mutex::new(Vec::new()));
+
+    let mut registry = TableRegistry::new();
+    registry.register({
+        let switches = switches.clone();
+        move |rows: TableUpdateRows<LogicalSwitch>| {
+            switches
+                .lock()
+                .unwrap()
+                .extend(rows.into_values().filter_map(|update| update.new))
+        }
+    });
+    registry.register({
+        let routers = routers.clone();
+        move |rows: TableUpdateRows<LogicalRouter>| {
+            routers
+                .lock()
+                .unwrap()
+                .extend(rows.into_values().filter_map(|update| update.old))
+        }
+    });
+
+    let mut tables: Vec<&str> = registry.tables().collect();
+    tables.sort_unstable();
+    assert_eq!(tables, ["Logical_Router", "Logical_Switch"]);
+    assert!(registry.monitor_requests().contains_key("Logical_Switch"));
+
+    let update: TableUpdate<Value> = serde_json::from_value(json!({
+        "Logical_Switch": {LS0: {"new": {"name": "ls0"}}},
+        "Logical_Router": {LR0: {"old": {"name": "lr0", "enabled": true}}},
+        "Logical_Switch_Port": {LS0: {"new": {"name": 1}}},
+    }))
+    .unwrap();
+    registry.dispatch(&update).unwrap();
+
+    let switches = switches.lock().unwrap();
+    assert_eq!(switches.len(), 1);
+    assert_eq!(switches[0].name, "ls0");
+    assert_eq!(switches[0]._uuid, Some(Uuid::parse_str(LS0).unwrap()));
+    let routers = routers.lock().unwrap();
+    assert_eq!(routers.len(), 1);
+    assert_eq!(routers[0].enabled, Some(true));
+}
+
+#[test]
+fn test_unregister() {
+    let mut registry = TableRegistry::new();
+    registry.register(|_: TableUpdateRows<LogicalSwitch>| panic!("unregistered"));
+
+    assert!(registry.contains("Logical_Switch"));
+    assert!(registry.unregister("Logical_Switch"));
+    assert!(!registry.unregister("Logical_Switch"));
+
+    let update: TableUpdate<Value> = serde_json::from_value(json!({
+        "Logical_Switch": {LS0: {"new": {"name": "ls0"}}},
+    }))
+    .unwrap();
+    registry.dispatch(&update).unwrap();
+}
+
+#[test]
+fn test_dispatch_errors() {
+    let mut registry = TableRegistry::new();
+    registry.register(|_: TableUpdateRows<LogicalSwitch>| {});
+
+    let update: TableUpdate<Value> = serde_json::from_value(json!({
+        "Logical_Switch": {LS0: {"new": {"name": 1}}},
+    }))
+    .unwrap();
+    assert!(matches!(
+        registry.dispatch(&update),
+        Err(Error::InvalidRowUpdate { ref table, .. }) if table == "Logical_Switch"
+    ));
+}
@@ -0,0 +1,353 @@
+use ovsdb_client::{
+    Error,
+    schema::{
+        AtomicType, DatabaseSchema, MonitorCondRequest, MonitorRequest, MonitorRequestSelect,
+        RefType, RowUpdate2, TableUpdate, TableUpdateExt, Update2Notification, UpdateNotification,
+    },
+    transact::{Condition, Function},
+};
+use ovsdb_derive::ovsdb_object;
+use ovsdb_schema::PathSegment;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+#[ovsdb_object(table = "NB_Global")]
+#[derive(Debug, PartialEq)]
+pub struct NbGlobal {
+    pub name: String,
+    pub nb_cfg: Option<i64>,
+}
+
+const SCHEMA: &str = r#"{
+    "name": "OVN_Northbound",
+    "version": "7.3.0",
+    "cksum": "1212223030 36532",
+    "tables": {
+        "NB_Global": {
+            "columns": {
+                "name": {"type": "string"},
+                "nb_cfg": {"type": {"key": "integer"}},
+                "external_ids": {
+                    "type": {"key": "string", "value": "string",
+                             "min": 0, "max": "unlimited"}},
+                "connections": {
+                    "type": {"key": {"type": "uuid",
+                                     "refTable": "Connection"},
+                             "min": 0,
+                             "max": "unlimited"}},
+                "ipsec": {"type": "boolean"}
+            },
+            "maxRows": 1,
+            "isRoot": true
+        },
+        "Logical_Switch_Port": {
+            "columns": {
+                "type": {"type": "string"},
+                "tag": {
+                    "type": {"key": {"type": "integer",
+                                     "minInteger": 1,
+                                     "maxInteger": 4095},
+                             "min": 0, "max": 1}},
+                "dynamic_addresses": {"type": {"key": "string",
+                                               "min": 0, "max": 1}},
+                "ha_chassis_group": {
+                    "type": {"key": {"type": "uuid",
+                                     "refTable": "HA_Chassis_Group",
+                                     "refType": "weak"},
+                             "min": 0, "max": 1}},
+                "mode": {
+                    "type": {"key": {"type": "string",
+                                     "enum": ["set", ["client", "server"]]},
+                             "min": 0, "max": 1}},
+                "action": {
+                    "type": {"key": {"type": "string",
+                                     "enum": "allow"}}}
+            },
+            "isRoot": false
+        }
+    }
+}"#;
+
+#[test]
+fn test_parse_atomic_column_type() {
+    let schema: DatabaseSchema = serde_json::from_str(SCHEMA).unwrap();
+    let nb_global = &schema.tables["NB_Global"];
+
+    let name = &nb_global.columns["name"].r#type;
+    assert_eq!(name.key.r#type, AtomicType::String);
+    assert!(name.value.is_none());
+    assert_eq!(name.min, 1);
+    assert_eq!(name.max, Some(1));
+    assert!(name.is_scalar());
+
+    let nb_cfg = &nb_global.columns["nb_cfg"].r#type;
+    assert_eq!(nb_cfg.key.r#type, AtomicType::Integer);
+    assert!(nb_cfg.is_scalar());
+}
+
+#[test]
+fn test_parse_set_and_map_column_types() {
+    let schema: DatabaseSchema = serde_json::from_str(SCHEMA).unwrap();
+    let nb_global = &schema.tables["NB_Global"];
+
+    let external_ids = &nb_global.columns["external_ids"].r#type;
+    assert!(external_ids.is_map());
+    assert_eq!(external_ids.min, 0);
+    assert_eq!(external_ids.max, None);
+    assert_eq!(
+        external_ids.value.as_ref().unwrap().r#type,
+        AtomicType::String
+    );
+
+    let connections = &nb_global.columns["connections"].r#type;
+    assert!(connections.is_set());
+    assert_eq!(connections.key.ref_table.as_deref(), Some("Connection"));
+    assert_eq!(connections.key.ref_type, Some(RefType::Strong));
+}
+
+#[test]
+fn test_parse_base_type_constraints() {
+    let schema: DatabaseSchema = serde_json::from_str(SCHEMA).unwrap();
+    let lsp = &schema.tables["Logical_Switch_Port"];
+
+    let tag = &lsp.columns["tag"].r#type;
+    assert!(tag.is_optional());
+    assert_eq!(tag.key.min_integer, Some(1));
+    assert_eq!(tag.key.max_integer, Some(4095));
+
+    let ha_chassis_group = &lsp.columns["ha_chassis_group"].r#type;
+    assert_eq!(ha_chassis_group.key.ref_type, Some(RefType::Weak));
+
+    let mode = &lsp.columns["mode"].r#type;
+    assert_eq!(
+        mode.key.r#enum,
+        Some(vec![
+            serde_json::json!("client"),
+            serde_json::json!("server")
+        ])
+    );
+
+    let action = &lsp.columns["action"].r#type;
+    assert_eq!(action.key.r#enum, Some(vec![serde_json::json!("allow")]));
+}
+
+#[test]
+fn test_reject_unknown_atomic_type() {
+    let result = serde_json::from_str::<DatabaseSchema>(
+        r#"{"name": "x", "version": "1.0.0", "tables": {
+            "T": {"columns": {"c": {"type": "float"}}}
+        }}"#,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_prune_columns() {
+    let schema: DatabaseSchema = serde_json::from_str(SCHEMA).unwrap();
+    let nb_global = &schema.tables["NB_Global"];
+
+    let columns = nb_global.prune_columns(["_uuid", "name", "hv_cfg", "nb_cfg", "_version"]);
+    assert_eq!(columns, vec!["_uuid", "name", "nb_cfg", "_version"]);
+
+    let request = MonitorRequest::pruned(nb_global, ["name", "ipsec", "options"]);
+    assert_eq!(
+        request.columns,
+        Some(vec!["name".to_owned(), "ipsec".to_owned()])
+    );
+    assert!(request.select.is_none());
+}
+
+#[test]
+fn test_schema_diff() {
+    let old: DatabaseSchema = serde_json::from_str(SCHEMA).unwrap();
+    let new: DatabaseSchema = serde_json::from_value(serde_json::json!({
+        "name": "OVN_Northbound",
+        "version": "7.4.0",
+        "tables": {
+            "NB_Global": {
+                "columns": {
+                    "name": {"type": "string"},
+                    "nb_cfg": {"type": {"key": "integer"}},
+                    "external_ids": {
+                        "type": {"key": "string", "value": "string",
+                                 "min": 0, "max": "unlimited"}},
+                    "connections": {
+                        "type": {"key": {"type": "uuid",
+                                         "refTable": "Connection",
+                                         "refType": "weak"},
+                                 "min": 0,
+                                 "max": "unlimited"}},
+                    "ipsec": {"type": "boolean"},
+                    "options": {
+                        "type": {"key": "string", "value": "string",
+                                 "min": 0, "max": "unlimited"}}
+                }
+            },
+            "Logical_Router": {
+                "columns": {"name": {"type": "string"}}
+            }
+        }
+    }))
+    .unwrap();
+
+    assert!(old.diff(&old).is_empty());
+
+    let diff = old.diff(&new);
+    assert_eq!(diff.added_tables, vec!["Logical_Router"]);
+    assert_eq!(diff.removed_tables, vec!["Logical_Switch_Port"]);
+
+    let nb_global = &diff.changed_tables["NB_Global"];
+    assert_eq!(nb_global.added_columns, vec!["options"]);
+    assert!(nb_global.removed_columns.is_empty());
+
+    let connections = &nb_global.changed_columns["connections"];
+    assert_eq!(connections.old.key.ref_type, Some(RefType::Strong));
+    assert_eq!(connections.new.key.ref_type, Some(RefType::Weak));
+}
+
+#[test]
+fn test_monitor_select() {
+    let request = MonitorRequest {
+        columns: Some(vec!["name".to_owned()]),
+        select: Some(MonitorRequestSelect::new().initial(false).modify(true)),
+    };
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        json!({"columns": ["name"], "select": {"initial": false, "modify": true}})
+    );
+
+    assert_eq!(
+        serde_json::to_value(MonitorRequestSelect::changes_only()).unwrap(),
+        json!({"initial": false})
+    );
+    assert_eq!(
+        serde_json::to_value(MonitorRequestSelect::inserts_only()).unwrap(),
+        json!({"initial": false, "delete": false, "modify": false})
+    );
+    assert_eq!(
+        serde_json::to_value(MonitorRequestSelect::new()).unwrap(),
+        json!({})
+    );
+
+    let select: MonitorRequestSelect = serde_json::from_value(json!({"insert": false})).unwrap();
+    assert_eq!(select, MonitorRequestSelect::new().insert(false));
+    assert_eq!(select.insert, Some(false));
+}
+
+#[test]
+fn test_structured_monitor_id() {
+    let notification: UpdateNotification<Value> = serde_json::from_value(json!([
+        ["OVN_Northbound", "switches"],
+        {"Logical_Switch": {}},
+    ]))
+    .unwrap();
+    assert_eq!(notification.id, json!(["OVN_Northbound", "switches"]));
+
+    let notification: Update2Notification<Value> =
+        serde_json::from_value(json!([{"db": "_Server"}, {}])).unwrap();
+    assert_eq!(notification.id, json!({"db": "_Server"}));
+}
+
+#[test]
+fn test_monitor_cond() {
+    let request = MonitorCondRequest {
+        columns: Some(vec!["name".to_owned()]),
+        conditions: Some(vec![Condition::new(
+            "name",
+            Function::Equal,
+            serde_json::json!("ls0"),
+        )]),
+        ..Default::default()
+    };
+    assert_eq!(
+        serde_json::to_value(&request).unwrap(),
+        serde_json::json!({"columns": ["name"], "where": [["name", "==", "ls0"]]})
+    );
+
+    let notification: Update2Notification<serde_json::Value> =
+        serde_json::from_value(serde_json::json!([null, {"Logical_Switch": {
+            "0b4ad97e-5a8e-4b6c-9b1b-3c1f5e7a9d01": {"insert": {"name": "ls0"}},
+            "5f0c4d2e-8b7a-4f3e-a1d2-6e9b8c7a5d02": {"modify": {"ports": "p1"}},
+            "6f4bd0b4-0b4c-4b1e-9d5e-5a1c2b3d4e01": {"delete": null}
+        }}]))
+        .unwrap();
+    assert_eq!(notification.id, Value::Null);
+
+    let rows = &notification.message["Logical_Switch"];
+    assert!(matches!(
+        &rows[&Uuid::parse_str("0b4ad97e-5a8e-4b6c-9b1b-3c1f5e7a9d01").unwrap()],
+        RowUpdate2::Insert(row) if row["name"] == "ls0"
+    ));
+    assert!(matches!(
+        &rows[&Uuid::parse_str("5f0c4d2e-8b7a-4f3e-a1d2-6e9b8c7a5d02").unwrap()],
+        RowUpdate2::Modify(diff) if diff["ports"] == "p1"
+    ));
+    assert!(matches!(
+        rows[&Uuid::parse_str("6f4bd0b4-0b4c-4b1e-9d5e-5a1c2b3d4e01").unwrap()],
+        RowUpdate2::Delete
+    ));
+}
+
+const GLOBAL_UUID: &str = "0b4ad97e-5a8e-4b6c-9b1b-3c1f5e7a9d01";
+
+#[test]
+fn test_typed_table_update() {
+    let update: TableUpdate<Value> = serde_json::from_value(json!({
+        "NB_Global": {
+            GLOBAL_UUID: {
+                "old": {"nb_cfg": 1},
+                "new": {"name": "global", "nb_cfg": 2},
+            },
+        },
+        "Logical_Switch": {},
+    }))
+    .unwrap();
+    let uuid = Uuid::parse_str(GLOBAL_UUID).unwrap();
+
+    let rows = update.table::<NbGlobal>("NB_Global").unwrap();
+    let old = rows[&uuid].old.as_ref().unwrap();
+    let new = rows[&uuid].new.as_ref().unwrap();
+    assert_eq!((old.name.as_str(), old.nb_cfg), ("global", Some(1)));
+    assert_eq!((new.name.as_str(), new.nb_cfg), ("global", Some(2)));
+    assert_eq!(new._uuid, Some(uuid));
+    assert!(update.table::<NbGlobal>("NB_Sequence").unwrap().is_empty());
+
+    let tables = update.into_typed::<NbGlobal>().unwrap();
+    assert_eq!(tables.len(), 2);
+    assert_eq!(
+        tables["NB_Global"][&uuid].new.as_ref().unwrap().nb_cfg,
+        Some(2)
+    );
+    assert!(tables["Logical_Switch"].is_empty());
+}
+
+#[test]
+fn test_typed_table_update_errors() {
+    let update: TableUpdate<Value> = serde_json::from_value(json!({
+        "NB_Global": {GLOBAL_UUID: {"new": {"nb_cfg": 1}}},
+    }))
+    .unwrap();
+    let error = update.table::<NbGlobal>("NB_Global").unwrap_err();
+    assert!(matches!(
+        error,
+        Error::InvalidRowUpdate { ref table, uuid, ref source }
+            if table == "NB_Global"
+                && uuid.to_string() == GLOBAL_UUID
+                && source.path == [PathSegment::Column("name".to_owned())]
+    ));
+}
+
+#[test]
+fn test_invalid_row_key() {
+    let error = serde_json::from_value::<TableUpdate<Value>>(json!({
+        "NB_Global": {"global": {"new": {"name": "global"}}},
+    }))
+    .unwrap_err();
+    assert!(error.to_string().contains("UUID"), "{error}");
+
+    let error = serde_json::from_value::<Update2Notification<Value>>(json!([null, {
+        "NB_Global": {"global": {"initial": {"name": "global"}}},
+    }]))
+    .unwrap_err();
+    assert!(error.to_string().contains("UUID"), "{error}");
+}
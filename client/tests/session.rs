@@ -0,0 +1,218 @@
+use futures_util::StreamExt;
+use jsonrpsee::{core::client::ClientT, rpc_params};
+use ovsdb_client::{
+    Error,
+    rpc::{ConnectOptions, ConnectionEvent, RpcClient},
+};
+use serde_json::{Value, json};
+use std::{sync::Arc, time::Duration};
+
+mod common;
+
+fn options() -> ConnectOptions {
+    ConnectOptions::new()
+        .with_reconnect_backoff(Duration::from_millis(10), Duration::from_millis(20))
+}
+
+#[tokio::test]
+async fn test_session_reconnects() {
+    let (remote, mut peers) = common::listen().await;
+    let options = options();
+    let mut events = options.events();
+    let session = options.session(&remote).unwrap();
+    let mut connections = session.connections();
+
+    let first = session.client().await.unwrap();
+    assert!(Arc::ptr_eq(&first, &connections.next().await.unwrap()));
+    assert_eq!(events.next().await.unwrap(), ConnectionEvent::Connecting);
+    assert_eq!(
+        events.next().await.unwrap(),
+        ConnectionEvent::Connected {
+            remote: remote.clone()
+        }
+    );
+
+    // The server drops the connection, which the session makes again
+    let mut peer = peers.recv().await.unwrap();
+    peer.close().await;
+    drop(peer);
+    assert!(matches!(
+        events.next().await.unwrap(),
+        ConnectionEvent::Disconnected { .. }
+    ));
+
+    let second = connections.next().await.unwrap();
+    assert!(!Arc::ptr_eq(&first, &second));
+    assert!(!first.is_connected());
+
+    let _served = common::serve(
+        peers.recv().await.unwrap(),
+        common::results(vec![json!(["OVN_Northbound"])]),
+    );
+    let client = session.client().await.unwrap();
+    assert!(Arc::ptr_eq(&client, &second));
+    assert_eq!(
+        client.list_databases().await.unwrap(),
+        vec!["OVN_Northbound"]
+    );
+}
+
+#[tokio::test]
+async fn test_session_inactive() {
+    let (remote, mut peers) = common::listen().await;
+    let options = options().with_inactivity_probe(Duration::from_millis(50));
+    let mut events = options.events();
+    let session = options.session(&remote).unwrap();
+    let mut connections = session.connections();
+
+    // The first server never answers the probes, so the session gives up on
+    // it and connects again
+    let first = connections.next().await.unwrap();
+    let _silent = peers.recv().await.unwrap();
+
+    let reason = loop {
+        if let ConnectionEvent::Disconnected { reason } = events.next().await.unwrap() {
+            break reason;
+        }
+    };
+    assert_eq!(reason, "No message received from the server for 100ms");
+
+    let second = connections.next().await.unwrap();
+    assert!(!Arc::ptr_eq(&first, &second));
+    assert!(!first.is_connected());
+
+    let _served = common::serve(peers.recv().await.unwrap(), |request| {
+        (request["method"] == "echo").then(|| common::result(request["params"].clone()))
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(second.is_connected());
+}
+
+#[tokio::test]
+async fn test_session_shutdown() {
+    let (remote, mut peers) = common::listen().await;
+    let session = options().session(&remote).unwrap();
+    let mut connections = session.connections();
+
+    let client = connections.next().await.unwrap();
+    let mut requests = common::serve(peers.recv().await.unwrap(), |_| None).requests;
+
+    session.shutdown().await.unwrap();
+    assert!(!client.is_connected());
+    assert!(!session.is_connected());
+    assert!(connections.next().await.is_none());
+    assert!(matches!(
+        session.client().await,
+        Err(Error::ConnectionClosed)
+    ));
+
+    // The connection is not made again once the session is shut down
+    assert!(requests.recv().await.is_none());
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(peers.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn test_session_invalid_remote() {
+    let err = ConnectOptions::new()
+        .session("ssl:127.0.0.1:6641")
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidRemote(remote) if remote == "ssl:127.0.0.1:6641"));
+}
+
+#[tokio::test]
+async fn test_session_reacquires_locks() {
+    let (remote, mut peers) = common::listen().await;
+    let options = options();
+    let mut events = options.events();
+    let session = options.session(&remote).unwrap();
+
+    let client = session.client().await.unwrap();
+    let mut peer = peers.recv().await.unwrap();
+    let locking = tokio::spawn(async move {
+        let _: Value = client
+            .request("lock", rpc_params!["ovn_northd"])
+            .await
+            .unwrap();
+        let _: Value = client.request("lock", rpc_params!["ovn_ic"]).await.unwrap();
+    });
+    for _ in 0..2 {
+        let request = peer.receive().await.unwrap();
+        peer.reply(&request, common::result(json!({"locked": true})))
+            .await;
+    }
+    locking.await.unwrap();
+    peer.close().await;
+    drop(peer);
+
+    // Both locks go along with the connection, and are requested again on the
+    // next one, where the server grants one now and the other later
+    let mut served = common::serve(peers.recv().await.unwrap(), |request| {
+        let locked = request["params"][0] == "ovn_northd";
+        Some(common::result(json!({ "locked": locked })))
+    });
+    let mut lost = Vec::new();
+    let event = loop {
+        match events.next().await.unwrap() {
+            ConnectionEvent::LockLost { lock } => lost.push(lock),
+            ConnectionEvent::LockReacquired { lock } => break lock,
+            _ => {}
+        }
+    };
+    assert_eq!(lost, vec!["ovn_northd", "ovn_ic"]);
+    assert_eq!(event, "ovn_northd");
+
+    let client = session.client().await.unwrap();
+    for lock in ["ovn_northd", "ovn_ic"] {
+        let request = served.requests.recv().await.unwrap();
+        assert_eq!(request["method"], "lock");
+        assert_eq!(request["params"], json!([lock]));
+    }
+
+    served
+        .notify
+        .send(json!({"id": null, "method": "locked", "params": ["ovn_ic"]}))
+        .unwrap();
+    let event = loop {
+        if let ConnectionEvent::LockReacquired { lock } = events.next().await.unwrap() {
+            break lock;
+        }
+    };
+    assert_eq!(event, "ovn_ic");
+
+    // A lock released is not requested again after the next reconnection
+    let _: Value = client
+        .request("unlock", rpc_params!["ovn_ic"])
+        .await
+        .unwrap();
+    assert_eq!(served.requests.recv().await.unwrap()["method"], "unlock");
+}
+
+#[tokio::test]
+async fn test_session_lock_stolen() {
+    let (remote, mut peers) = common::listen().await;
+    let options = options();
+    let mut events = options.events();
+    let session = options.session(&remote).unwrap();
+
+    let client = session.client().await.unwrap();
+    let served = common::serve(peers.recv().await.unwrap(), |_| {
+        Some(common::result(json!({"locked": true})))
+    });
+    let _: Value = client
+        .request("lock", rpc_params!["ovn_northd"])
+        .await
+        .unwrap();
+
+    served
+        .notify
+        .send(json!({"id": null, "method": "stolen", "params": ["ovn_northd"]}))
+        .unwrap();
+    let event = loop {
+        if let ConnectionEvent::LockLost { lock } = events.next().await.unwrap() {
+            break lock;
+        }
+    };
+    assert_eq!(event, "ovn_northd");
+    assert!(client.is_connected());
+}
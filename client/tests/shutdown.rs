@@ -0,0 +1,84 @@
+use jsonrpsee::{core::client::ClientT, rpc_params};
+use ovsdb_client::{
+    Error,
+    rpc::{self, RpcClient},
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+mod common;
+
+/// Start a server over an in-memory stream which replies to every request,
+/// returning the messages it receives until the client disconnects.
+fn serve() -> (tokio::io::DuplexStream, mpsc::UnboundedReceiver<Value>) {
+    let (connection, served) = common::serve_duplex(|message| match message["method"].as_str() {
+        Some("lock") => Some(common::result(json!({"locked": true}))),
+        _ => Some(common::result(json!({}))),
+    });
+
+    (connection, served.requests)
+}
+
+/// Returns the method and parameters of every message, which ends once the
+/// server has seen the client disconnect.
+async fn methods(mut received: mpsc::UnboundedReceiver<Value>) -> Vec<Value> {
+    let mut methods = Vec::new();
+    while let Some(message) = received.recv().await {
+        methods.push(json!([message["method"], message["params"]]));
+    }
+    methods
+}
+
+#[tokio::test]
+async fn test_shutdown() {
+    let (connection, received) = serve();
+    let client = rpc::connect_stream(connection);
+
+    client
+        .monitor("OVN_Northbound", json!("nb"), HashMap::new())
+        .await
+        .unwrap();
+    let _: Value = client.request("lock", rpc_params!["leader"]).await.unwrap();
+
+    client.shutdown().await.unwrap();
+    assert!(!client.is_connected());
+
+    assert_eq!(
+        methods(received).await,
+        vec![
+            json!(["monitor", ["OVN_Northbound", "nb", {}]]),
+            json!(["lock", ["leader"]]),
+            json!(["monitor_cancel", ["nb"]]),
+            json!(["unlock", ["leader"]]),
+        ]
+    );
+
+    let err = client.list_databases().await.unwrap_err();
+    assert!(matches!(err, Error::ConnectionClosed), "{err:?}");
+}
+
+#[tokio::test]
+async fn test_shutdown_canceled_monitor() {
+    let (connection, received) = serve();
+    let client = rpc::connect_stream(connection);
+
+    client
+        .monitor("OVN_Northbound", json!("nb"), HashMap::new())
+        .await
+        .unwrap();
+    let _: Value = client
+        .request("monitor_cancel", rpc_params!["nb"])
+        .await
+        .unwrap();
+
+    client.shutdown().await.unwrap();
+
+    assert_eq!(
+        methods(received).await,
+        vec![
+            json!(["monitor", ["OVN_Northbound", "nb", {}]]),
+            json!(["monitor_cancel", ["nb"]]),
+        ]
+    );
+}
@@ -0,0 +1,191 @@
+mod common;
+
+use ovsdb_client::{
+    Error, OvsdbErrorKind,
+    rpc::{ConnectOptions, RpcClient},
+    schema::{MonitorCondRequest, MonitorRequest},
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use tokio::io::DuplexStream;
+
+/// Start a server over an in-memory stream which replies to each request
+/// with the next of the replies, writing them a few bytes at a time so that
+/// they are split across reads.
+fn serve(replies: Vec<Value>) -> DuplexStream {
+    let (client, mut peer) = common::duplex();
+
+    tokio::spawn(async move {
+        for mut reply in replies {
+            let Some(request) = peer.receive().await else {
+                return;
+            };
+            reply["id"] = request["id"].clone();
+            peer.send_split(&reply, 7).await;
+        }
+        peer.receive().await;
+    });
+
+    client
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Flow {
+    #[serde(rename = "match")]
+    match_: String,
+
+    priority: i64,
+}
+
+fn requests() -> HashMap<String, MonitorRequest> {
+    HashMap::from([("Logical_Flow".to_owned(), MonitorRequest::default())])
+}
+
+#[tokio::test]
+async fn test_monitor_snapshot() {
+    let connection = serve(vec![
+        json!({
+            "result": {
+                "Logical_Flow": {
+                    "6a4c7a4e-0000-4000-8000-000000000001": {
+                        "new": {"match": "inport == \"[lsp]\"", "priority": 100},
+                    },
+                    "6a4c7a4e-0000-4000-8000-000000000002": {
+                        "new": {"match": "ip4 && {a} \\ {b}", "priority": 50},
+                    },
+                },
+                "Chassis": {
+                    "6a4c7a4e-0000-4000-8000-000000000003": {
+                        "new": {"match": "", "priority": 0},
+                    },
+                },
+            },
+            "error": null,
+        }),
+        json!({"result": ["after"], "error": null}),
+    ]);
+    let client = ConnectOptions::new().connect_stream(connection);
+
+    let snapshot = client
+        .monitor_snapshot("OVN_Southbound", json!("flows"), requests())
+        .await
+        .unwrap();
+    assert!(!snapshot.is_empty());
+
+    let mut rows = Vec::new();
+    snapshot
+        .for_each_row(|table, uuid, row: Flow| rows.push((table.to_owned(), uuid.to_string(), row)))
+        .unwrap();
+    rows.sort_by(|a, b| a.1.cmp(&b.1));
+    assert_eq!(
+        rows,
+        vec![
+            (
+                "Logical_Flow".to_owned(),
+                "6a4c7a4e-0000-4000-8000-000000000001".to_owned(),
+                Flow {
+                    match_: "inport == \"[lsp]\"".to_owned(),
+                    priority: 100,
+                },
+            ),
+            (
+                "Logical_Flow".to_owned(),
+                "6a4c7a4e-0000-4000-8000-000000000002".to_owned(),
+                Flow {
+                    match_: "ip4 && {a} \\ {b}".to_owned(),
+                    priority: 50,
+                },
+            ),
+            (
+                "Chassis".to_owned(),
+                "6a4c7a4e-0000-4000-8000-000000000003".to_owned(),
+                Flow {
+                    match_: String::new(),
+                    priority: 0,
+                },
+            ),
+        ]
+    );
+
+    assert_eq!(
+        client.echo(vec![json!("after")]).await.unwrap(),
+        vec![json!("after")]
+    );
+}
+
+#[tokio::test]
+async fn test_monitor_cond_snapshot() {
+    let connection = serve(vec![json!({
+        "result": {
+            "Logical_Flow": {
+                "6a4c7a4e-0000-4000-8000-000000000001": {
+                    "initial": {"match": "1", "priority": 1},
+                },
+            },
+        },
+        "error": null,
+    })]);
+    let client = ConnectOptions::new().connect_stream(connection);
+
+    let requests = HashMap::from([("Logical_Flow".to_owned(), MonitorCondRequest::default())]);
+    let snapshot = client
+        .monitor_cond_snapshot("OVN_Southbound", json!(["OVN_Southbound", 1]), requests)
+        .await
+        .unwrap();
+
+    let mut rows = Vec::new();
+    snapshot
+        .for_each_row(|_, _, row: Flow| rows.push(row))
+        .unwrap();
+    assert_eq!(
+        rows,
+        vec![Flow {
+            match_: "1".to_owned(),
+            priority: 1,
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_monitor_snapshot_error() {
+    let connection = serve(vec![
+        json!({"result": null, "error": {"error": "unknown database"}}),
+        json!({"result": {"Logical_Flow": {}}, "error": null}),
+    ]);
+    let client = ConnectOptions::new().connect_stream(connection);
+
+    let error = client
+        .monitor_snapshot("OVN_Southbound", json!("flows"), requests())
+        .await
+        .unwrap_err();
+    assert_eq!(error.ovsdb_kind(), Some(&OvsdbErrorKind::UnknownDatabase));
+
+    let snapshot = client
+        .monitor_snapshot("OVN_Southbound", json!("flows"), requests())
+        .await
+        .unwrap();
+    let mut rows = 0;
+    snapshot.for_each_row(|_, _, _: Value| rows += 1).unwrap();
+    assert_eq!(rows, 0);
+}
+
+#[tokio::test]
+async fn test_snapshot_invalid_row() {
+    let connection = serve(vec![json!({
+        "result": {
+            "Logical_Flow": {
+                "6a4c7a4e-0000-4000-8000-000000000001": {"new": {"priority": 1}},
+            },
+        },
+        "error": null,
+    })]);
+    let client = ConnectOptions::new().connect_stream(connection);
+
+    let snapshot = client
+        .monitor_snapshot("OVN_Southbound", json!("flows"), requests())
+        .await
+        .unwrap();
+    let error = snapshot.for_each_row(|_, _, _: Flow| {}).unwrap_err();
+    assert!(matches!(error, Error::Protocol(_)));
+}
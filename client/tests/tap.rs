@@ -0,0 +1,53 @@
+use ovsdb_client::{
+    rpc::{ConnectOptions, RpcClient},
+    transports::{
+        listener::framed,
+        tap::{Direction, Frame},
+    },
+};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+#[tokio::test]
+async fn test_tap() {
+    let (client, server) = tokio::io::duplex(64 * 1024);
+    tokio::spawn(async move {
+        let (mut sender, mut receiver) = framed(server);
+        while let Some(Ok(request)) = receiver.receive().await {
+            let reply = json!({"id": request["id"], "result": ["OVN_Northbound"], "error": null});
+            sender.send(&reply).await.unwrap();
+        }
+    });
+
+    let frames: Arc<Mutex<Vec<Frame>>> = Arc::default();
+    let tapped = frames.clone();
+    let client = ConnectOptions::new()
+        .with_tap(move |frame| tapped.lock().unwrap().push(frame.clone()))
+        .connect_stream(client);
+
+    assert_eq!(
+        client.list_databases().await.unwrap(),
+        vec!["OVN_Northbound"]
+    );
+
+    // The frames are the messages as exchanged with the server
+    let frames = frames.lock().unwrap();
+    let messages: Vec<_> = frames
+        .iter()
+        .map(|frame| (frame.direction, frame.message.clone()))
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            (
+                Direction::Outbound,
+                json!({"id": 0, "method": "list_dbs", "params": []})
+            ),
+            (
+                Direction::Inbound,
+                json!({"id": 0, "result": ["OVN_Northbound"], "error": null})
+            ),
+        ]
+    );
+    assert!(frames[0].timestamp <= frames[1].timestamp);
+}
@@ -0,0 +1,69 @@
+use ovsdb_client::{
+    Error,
+    rpc::{ConnectOptions, RpcClient},
+};
+use serde_json::json;
+use std::time::{Duration, Instant};
+
+mod common;
+
+/// Start a server over an in-memory stream which only replies to the "echo"
+/// requests, after the delay.
+fn serve(delay: Duration) -> tokio::io::DuplexStream {
+    let (client, mut peer) = common::duplex();
+
+    tokio::spawn(async move {
+        while let Some(message) = peer.receive().await {
+            if message["method"] == "echo" {
+                tokio::time::sleep(delay).await;
+                let reply = common::result(message["params"].clone());
+                peer.reply(&message, reply).await;
+            }
+        }
+    });
+
+    client
+}
+
+#[tokio::test]
+async fn test_request_timeout() {
+    let client = ConnectOptions::new()
+        .with_request_timeout(Duration::from_millis(50))
+        .connect_stream(serve(Duration::ZERO));
+
+    let err = client.get_schema("OVN_Northbound").await.unwrap_err();
+    assert!(matches!(err, Error::TimedOut), "{err:?}");
+
+    // The connection is still usable after a request timed out
+    client.echo(vec![json!(1)]).await.unwrap();
+    assert!(client.is_connected());
+}
+
+#[tokio::test]
+async fn test_with_timeout() {
+    let client = ConnectOptions::new()
+        .with_request_timeout(Duration::from_millis(50))
+        .connect_stream(serve(Duration::from_millis(100)));
+
+    let err = client.echo(vec![json!(1)]).await.unwrap_err();
+    assert!(matches!(err, Error::TimedOut), "{err:?}");
+
+    client
+        .with_timeout(Duration::from_secs(5))
+        .echo(vec![json!(1)])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_with_deadline() {
+    let client = ConnectOptions::new().connect_stream(serve(Duration::from_millis(100)));
+
+    let deadline = Instant::now() + Duration::from_millis(50);
+    let err = client
+        .with_deadline(deadline)
+        .echo(vec![json!(1)])
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::TimedOut), "{err:?}");
+}
@@ -0,0 +1,120 @@
+use futures_util::StreamExt;
+use ovsdb_client::{
+    rpc::{ConnectOptions, ConnectionEvent, RpcClient},
+    transports::{OvsdbTransport, listener::framed},
+};
+use serde_json::json;
+use std::{
+    io,
+    net::{Ipv4Addr, Ipv6Addr},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+use tokio::{io::DuplexStream, net::TcpListener};
+
+/// A transport over in-memory streams, served by a task answering
+/// `list_dbs`, whose first attempts are refused
+struct Memory {
+    refused: usize,
+    attempts: AtomicUsize,
+}
+
+impl OvsdbTransport for Memory {
+    type Connection = DuplexStream;
+
+    fn remote(&self) -> String {
+        "memory:".to_owned()
+    }
+
+    async fn connect(&self) -> Result<DuplexStream, io::Error> {
+        let attempt = self.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        if attempt <= self.refused {
+            return Err(io::ErrorKind::ConnectionRefused.into());
+        }
+
+        let (connection, server) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            let (mut sender, mut receiver) = framed(server);
+            while let Some(Ok(message)) = receiver.receive().await {
+                let reply = json!({"id": message["id"], "result": ["Open_vSwitch"], "error": null});
+                sender.send(&reply).await.unwrap();
+            }
+        });
+        Ok(connection)
+    }
+
+    fn connected(&self, _: &DuplexStream) -> String {
+        format!("memory:{}", self.attempts.load(Ordering::SeqCst))
+    }
+}
+
+#[tokio::test]
+async fn test_connect_transport() {
+    let options = ConnectOptions::new()
+        .with_reconnect_backoff(Duration::from_millis(10), Duration::from_millis(10));
+    let mut events = options.events();
+
+    let client = options
+        .connect_transport(Memory {
+            refused: 2,
+            attempts: AtomicUsize::new(0),
+        })
+        .await
+        .unwrap();
+
+    for _ in 0..2 {
+        assert_eq!(events.next().await.unwrap(), ConnectionEvent::Connecting);
+        assert!(matches!(
+            events.next().await.unwrap(),
+            ConnectionEvent::Disconnected { .. }
+        ));
+        assert!(matches!(
+            events.next().await.unwrap(),
+            ConnectionEvent::BackingOff { .. }
+        ));
+    }
+    assert_eq!(events.next().await.unwrap(), ConnectionEvent::Connecting);
+    assert_eq!(
+        events.next().await.unwrap(),
+        ConnectionEvent::Connected {
+            remote: "memory:3".to_owned()
+        }
+    );
+
+    assert_eq!(client.list_databases().await.unwrap(), ["Open_vSwitch"]);
+}
+
+#[tokio::test]
+async fn test_tcp_options() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let accept = tokio::spawn(async move { listener.accept().await.unwrap() });
+
+    let _client = ConnectOptions::new()
+        .with_tcp_keepalive(Duration::from_secs(10), Duration::from_secs(2), 3)
+        .with_tcp_nodelay(true)
+        .with_bind_address(Ipv4Addr::new(127, 0, 0, 2).into())
+        .with_dscp(48)
+        .connect_tcp(address)
+        .await
+        .unwrap();
+
+    let (_, peer) = accept.await.unwrap();
+    assert_eq!(peer.ip(), Ipv4Addr::new(127, 0, 0, 2));
+}
+
+#[tokio::test]
+async fn test_bind_address_family() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let result = ConnectOptions::new()
+        .with_bind_address(Ipv6Addr::LOCALHOST.into())
+        .connect_tcp(listener.local_addr().unwrap())
+        .await;
+    assert!(result.is_err());
+}
+
+#[test]
+#[should_panic(expected = "DSCP 64 is larger than 63")]
+fn test_dscp_range() {
+    let _ = ConnectOptions::new().with_dscp(64);
+}
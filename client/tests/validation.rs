@@ -0,0 +1,161 @@
+use ovsdb_client::{
+    schema::TableSchema,
+    validation::{ValidationError, validate_row, validate_update},
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+fn table() -> TableSchema {
+    serde_json::from_value(json!({
+        "columns": {
+            "name": {"type": "string", "mutable": false},
+            "type": {"type": {"key": {"type": "string",
+                                      "enum": ["set", ["", "router", "localnet"]]}}},
+            "tag": {"type": {"key": {"type": "integer",
+                                     "minInteger": 1, "maxInteger": 4095},
+                             "min": 0, "max": 1}},
+            "addresses": {"type": {"key": "string", "min": 0, "max": "unlimited"}},
+            "options": {"type": {"key": "string", "value": "string",
+                                 "min": 0, "max": "unlimited"}},
+            "ha_chassis_group": {"type": {"key": {"type": "uuid",
+                                                  "refTable": "HA_Chassis_Group"},
+                                          "min": 0, "max": 1}}
+        }
+    }))
+    .unwrap()
+}
+
+fn row(value: Value) -> HashMap<String, Value> {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn test_valid_row() {
+    let row = row(json!({
+        "name": "lsp0",
+        "type": "router",
+        "tag": 100,
+        "addresses": ["set", ["00:00:00:00:00:01", "00:00:00:00:00:02"]],
+        "options": ["map", [["router-port", "lrp0"]]],
+        "ha_chassis_group": ["uuid", "601c7161-97df-42ae-b377-3baf21830d8f"]
+    }));
+
+    assert_eq!(validate_row(&table(), &row), Ok(()));
+}
+
+#[test]
+fn test_empty_optional_values() {
+    let row = row(json!({"tag": [], "addresses": [], "options": ["map", []]}));
+
+    assert_eq!(validate_row(&table(), &row), Ok(()));
+}
+
+#[test]
+fn test_unknown_column() {
+    let row = row(json!({"nmae": "lsp0"}));
+
+    assert_eq!(
+        validate_row(&table(), &row),
+        Err(ValidationError::UnknownColumn {
+            column: "nmae".to_owned()
+        })
+    );
+}
+
+#[test]
+fn test_type_mismatch() {
+    let row = row(json!({"tag": "100"}));
+
+    assert!(matches!(
+        validate_row(&table(), &row),
+        Err(ValidationError::TypeMismatch { column, .. }) if column == "tag"
+    ));
+
+    let row = self::row(json!({"ha_chassis_group": ["uuid", "not-a-uuid"]}));
+
+    assert!(matches!(
+        validate_row(&table(), &row),
+        Err(ValidationError::TypeMismatch { column, .. }) if column == "ha_chassis_group"
+    ));
+}
+
+#[test]
+fn test_cardinality() {
+    let row = row(json!({"tag": ["set", [1, 2]]}));
+
+    assert_eq!(
+        validate_row(&table(), &row),
+        Err(ValidationError::Cardinality {
+            column: "tag".to_owned(),
+            min: 0,
+            max: "1".to_owned(),
+            found: 2,
+        })
+    );
+
+    let row = self::row(json!({"type": ["set", []]}));
+
+    assert!(matches!(
+        validate_row(&table(), &row),
+        Err(ValidationError::Cardinality { found: 0, .. })
+    ));
+}
+
+#[test]
+fn test_duplicate_elements() {
+    let row = row(json!({"addresses": ["set", ["router", "router"]]}));
+
+    assert_eq!(
+        validate_row(&table(), &row),
+        Err(ValidationError::DuplicateElement {
+            column: "addresses".to_owned(),
+            value: json!("router"),
+        })
+    );
+
+    let row = self::row(json!({"options": ["map", [["a", "1"], ["a", "2"]]]}));
+
+    assert!(matches!(
+        validate_row(&table(), &row),
+        Err(ValidationError::DuplicateElement { value, .. }) if value == json!("a")
+    ));
+
+    let row = self::row(json!({"options": ["map", [["a", "1"], ["b", "1"]]]}));
+    assert_eq!(validate_row(&table(), &row), Ok(()));
+}
+
+#[test]
+fn test_enum_membership() {
+    let row = row(json!({"type": "switch"}));
+
+    assert_eq!(
+        validate_row(&table(), &row),
+        Err(ValidationError::NotInEnum {
+            column: "type".to_owned(),
+            value: json!("switch"),
+        })
+    );
+}
+
+#[test]
+fn test_integer_range() {
+    let row = row(json!({"tag": 4096}));
+
+    assert!(matches!(
+        validate_row(&table(), &row),
+        Err(ValidationError::ConstraintViolation { column, .. }) if column == "tag"
+    ));
+}
+
+#[test]
+fn test_immutable_column() {
+    let row = row(json!({"name": "lsp1"}));
+
+    assert_eq!(validate_row(&table(), &row), Ok(()));
+    assert_eq!(
+        validate_update(&table(), &row),
+        Err(ValidationError::ImmutableColumn {
+            column: "name".to_owned()
+        })
+    );
+}
@@ -0,0 +1,32 @@
+use futures_util::StreamExt;
+use ovsdb_client::{
+    rpc::{ConnectOptions, ConnectionEvent},
+    transports::{
+        OvsdbTransport,
+        vsock::{VMADDR_CID_HOST, VsockTransport},
+    },
+};
+
+#[test]
+fn test_remote() {
+    assert_eq!(VsockTransport::new(3, 6641).remote(), "vsock:3:6641");
+    assert_eq!(
+        VsockTransport::host(6640).remote(),
+        format!("vsock:{VMADDR_CID_HOST}:6640")
+    );
+}
+
+#[tokio::test]
+async fn test_connect_refused() {
+    // Nothing listens on the port of the local context, when there is one
+    let options = ConnectOptions::new();
+    let mut events = options.events();
+    let result = options.connect_transport(VsockTransport::new(1, 1)).await;
+    assert!(result.is_err());
+
+    assert_eq!(events.next().await.unwrap(), ConnectionEvent::Connecting);
+    assert!(matches!(
+        events.next().await.unwrap(),
+        ConnectionEvent::Disconnected { .. }
+    ));
+}
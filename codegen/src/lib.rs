@@ -0,0 +1,420 @@
+//! Generate Rust bindings from OVSDB schemas
+//!
+//! The generated code uses `ovsdb-derive`, turning every table into an
+//! `#[ovsdb_object]` struct and every string column constrained by an `enum`
+//! into an `OvsdbEnum`. This is used by the `ovsdb_table!` macro and the
+//! `ovsdb-codegen` binary.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("schema has no tables")]
+    MissingTables,
+
+    #[error("table {0} is not in the schema")]
+    UnknownTable(String),
+
+    #[error("{table}.{column}: {reason}")]
+    InvalidColumn {
+        table: String,
+        column: String,
+        reason: String,
+    },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Base type of a column, after resolving `enum` and `refTable` constraints
+enum BaseType {
+    Atomic(String),
+    Enum(Vec<String>),
+    Ref(String),
+}
+
+struct ColumnType {
+    key: BaseType,
+    value: Option<BaseType>,
+    min: u64,
+    max: Option<u64>,
+}
+
+/// Convert an OVSDB table or column name into a Rust type name, so that
+/// `Logical_Switch_Port` becomes `LogicalSwitchPort` and `ACL` becomes `Acl`
+pub fn type_name(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().unwrap();
+            let rest = chars.as_str();
+            // Acronyms such as `ACL`, `NB` or the `DHCP` of `DHCPv6` are
+            // written as words, while `vSwitch` only gets capitalized
+            let acronym = if first.is_ascii_uppercase() {
+                rest.find(|c: char| !c.is_ascii_uppercase())
+                    .unwrap_or(rest.len())
+            } else {
+                0
+            };
+            format!(
+                "{}{}{}",
+                first.to_ascii_uppercase(),
+                rest[..acronym].to_ascii_lowercase(),
+                &rest[acronym..]
+            )
+        })
+        .collect()
+}
+
+/// Convert a column name into a field identifier, using raw identifiers for
+/// keywords and returning the column name when it must be given explicitly
+fn field_ident(column: &str) -> (Ident, bool) {
+    let mut name: String = column
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+
+    if let Ok(ident) = syn::parse_str::<Ident>(&name) {
+        return (ident, name != column);
+    }
+    match syn::parse_str::<Ident>(&format!("r#{}", name)) {
+        Ok(ident) => (ident, name != column),
+        // `self`, `super`, `crate` and `Self` can't be raw identifiers
+        Err(_) => (format_ident!("{}_", name), true),
+    }
+}
+
+fn parse_base_type(value: &Value) -> Result<BaseType, String> {
+    let object = match value {
+        Value::String(atomic) => return Ok(BaseType::Atomic(atomic.clone())),
+        Value::Object(object) => object,
+        _ => return Err(format!("invalid base type {}", value)),
+    };
+
+    let atomic = object
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("base type without a type: {}", value))?;
+
+    if let Some(table) = object.get("refTable").and_then(Value::as_str) {
+        return Ok(BaseType::Ref(table.to_owned()));
+    }
+
+    if let (Some(values), "string") = (object.get("enum"), atomic) {
+        // A single value is given as an atom, several as ["set", [...]]
+        let values = match values {
+            Value::Array(arr) if arr.len() == 2 && arr[0] == "set" => {
+                arr[1].as_array().cloned().unwrap_or_default()
+            }
+            value => vec![value.clone()],
+        };
+        return Ok(BaseType::Enum(
+            values
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_owned))
+                .collect(),
+        ));
+    }
+
+    Ok(BaseType::Atomic(atomic.to_owned()))
+}
+
+fn parse_column_type(value: &Value) -> Result<ColumnType, String> {
+    let object = match value {
+        Value::Object(object) => object,
+        atomic => {
+            return Ok(ColumnType {
+                key: parse_base_type(atomic)?,
+                value: None,
+                min: 1,
+                max: Some(1),
+            });
+        }
+    };
+
+    let key = object
+        .get("key")
+        .ok_or_else(|| format!("column type without a key: {}", value))?;
+
+    Ok(ColumnType {
+        key: parse_base_type(key)?,
+        value: object.get("value").map(parse_base_type).transpose()?,
+        min: object.get("min").and_then(Value::as_u64).unwrap_or(1),
+        max: match object.get("max") {
+            None => Some(1),
+            Some(Value::String(s)) if s == "unlimited" => None,
+            Some(max) => Some(max.as_u64().ok_or_else(|| format!("invalid max {}", max))?),
+        },
+    })
+}
+
+/// State shared while generating the tables of a single invocation
+struct Generator<'a> {
+    /// Tables being generated, mapped to their struct names
+    structs: &'a BTreeMap<String, Ident>,
+    /// Enums generated for the columns, emitted next to the structs
+    enums: Vec<TokenStream>,
+}
+
+impl Generator<'_> {
+    fn atomic_type(atomic: &str) -> Result<TokenStream, String> {
+        match atomic {
+            "integer" => Ok(quote! { i64 }),
+            "real" => Ok(quote! { f64 }),
+            "boolean" => Ok(quote! { bool }),
+            "string" => Ok(quote! { ::std::string::String }),
+            "uuid" => Ok(quote! { ::ovsdb_schema::__private::uuid::Uuid }),
+            _ => Err(format!("unknown atomic type {:?}", atomic)),
+        }
+    }
+
+    fn base_type(&mut self, base: &BaseType, enum_name: &str) -> Result<TokenStream, String> {
+        match base {
+            BaseType::Atomic(atomic) => Self::atomic_type(atomic),
+            BaseType::Ref(_) => Self::atomic_type("uuid"),
+            BaseType::Enum(values) => {
+                let ident = format_ident!("{}", enum_name);
+                self.enums.push(expand_enum(&ident, values));
+                Ok(quote! { #ident })
+            }
+        }
+    }
+
+    fn column(
+        &mut self,
+        struct_name: &Ident,
+        column: &str,
+        column_type: &ColumnType,
+    ) -> Result<TokenStream, String> {
+        let (ident, renamed) = field_ident(column);
+        let enum_name = format!("{}{}", struct_name, type_name(column));
+
+        let mut attrs = Vec::new();
+        if renamed {
+            attrs.push(quote! { column = #column });
+        }
+
+        let ty = match &column_type.value {
+            Some(value) => {
+                if matches!(&column_type.key, BaseType::Atomic(a) if a == "real") {
+                    return Err("maps with real keys are not supported".to_owned());
+                }
+                let key = self.base_type(&column_type.key, &format!("{}Key", enum_name))?;
+                let value = self.base_type(value, &format!("{}Value", enum_name))?;
+                quote! { ::std::collections::HashMap<#key, #value> }
+            }
+            None => {
+                let key = self.base_type(&column_type.key, &enum_name)?;
+                let ty = match (column_type.min, column_type.max) {
+                    (1, Some(1)) => {
+                        // Partial rows in updates don't carry every column
+                        attrs.push(quote! { default });
+                        key
+                    }
+                    (0, Some(1)) => quote! { ::core::option::Option<#key> },
+                    _ => quote! { ::std::vec::Vec<#key> },
+                };
+
+                // Only references to tables generated alongside are typed
+                if let BaseType::Ref(table) = &column_type.key {
+                    if let Some(target) = self.structs.get(table) {
+                        let target = target.to_string();
+                        attrs.push(quote! { ref = #target });
+                    }
+                }
+
+                ty
+            }
+        };
+
+        let attrs = (!attrs.is_empty()).then(|| quote! { #[ovsdb(#(#attrs),*)] });
+        Ok(quote! {
+            #attrs
+            pub #ident: #ty,
+        })
+    }
+}
+
+/// Generate a string enum for a column constrained by a schema `enum`
+fn expand_enum(ident: &Ident, values: &[String]) -> TokenStream {
+    let mut seen = BTreeSet::new();
+    let variants: Vec<_> = values
+        .iter()
+        .map(|value| {
+            let mut name = match type_name(value) {
+                name if name.is_empty() => "Empty".to_owned(),
+                name if name.starts_with(|c: char| c.is_ascii_digit()) => format!("V{}", name),
+                name => name,
+            };
+            while !seen.insert(name.clone()) || name == "Other" {
+                name.push('_');
+            }
+            format_ident!("{}", name)
+        })
+        .collect();
+    let variants = variants
+        .iter()
+        .zip(values)
+        .enumerate()
+        .map(|(i, (variant, value))| {
+            let default = (i == 0).then(|| quote! { #[default] });
+            quote! {
+                #default
+                #[ovsdb(rename = #value)]
+                #variant,
+            }
+        });
+
+    quote! {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, ::ovsdb_derive::OvsdbEnum)]
+        pub enum #ident {
+            #(#variants)*
+            /// Value which is not part of the schema
+            Other(::std::string::String),
+        }
+    }
+}
+
+/// Generate the structs and enums for the given tables of a schema, or for
+/// every table when `tables` is empty.
+///
+/// References to tables which are generated alongside use
+/// `#[ovsdb(ref = "...")]`, other references are plain UUIDs.
+pub fn generate_tables(schema: &Value, tables: &[String]) -> Result<TokenStream, Error> {
+    let schema_tables = match schema.get("tables") {
+        Some(Value::Object(tables)) => tables,
+        _ => return Err(Error::MissingTables),
+    };
+
+    let names: Vec<String> = if tables.is_empty() {
+        schema_tables.keys().cloned().collect()
+    } else {
+        tables.to_vec()
+    };
+
+    let structs: BTreeMap<String, Ident> = names
+        .iter()
+        .map(|table| (table.clone(), format_ident!("{}", type_name(table))))
+        .collect();
+    let mut generator = Generator {
+        structs: &structs,
+        enums: Vec::new(),
+    };
+
+    let mut items = Vec::new();
+    for table in &names {
+        let columns = schema_tables
+            .get(table)
+            .and_then(|t| t.get("columns"))
+            .and_then(Value::as_object)
+            .ok_or_else(|| Error::UnknownTable(table.clone()))?;
+
+        let struct_name = &structs[table];
+        // Columns are sorted so that the generated code is deterministic
+        let columns: BTreeMap<_, _> = columns.iter().collect();
+        let mut fields = Vec::new();
+        for (column, column_schema) in columns {
+            let field = column_schema
+                .get("type")
+                .ok_or_else(|| "column has no type".to_owned())
+                .and_then(parse_column_type)
+                .and_then(|ty| generator.column(struct_name, column, &ty))
+                .map_err(|reason| Error::InvalidColumn {
+                    table: table.clone(),
+                    column: column.clone(),
+                    reason,
+                })?;
+            fields.push(field);
+        }
+
+        let doc = format!(" Row of the `{}` table", table);
+        items.push(quote! {
+            #[doc = #doc]
+            #[::ovsdb_derive::ovsdb_object(table = #table)]
+            #[derive(Debug, Clone, PartialEq)]
+            pub struct #struct_name {
+                #(#fields)*
+            }
+        });
+    }
+
+    let enums = generator.enums;
+    Ok(quote! {
+        #(#items)*
+
+        #(#enums)*
+    })
+}
+
+/// Generate a formatted Rust module with every table of a schema.
+pub fn generate_module(schema: &Value) -> Result<String, Error> {
+    let tokens = generate_tables(schema, &[])?;
+    let file: syn::File = syn::parse2(tokens).expect("generated code is valid Rust");
+
+    let name = schema
+        .get("name")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let version = schema
+        .get("version")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+
+    Ok(format!(
+        "// Generated by ovsdb-codegen from the {} schema version {}, do not edit.\n\n{}",
+        name,
+        version,
+        prettyplease::unparse(&file)
+    ))
+}
+
+/// Generate the bindings for a schema file from a build script, returning
+/// the path of the generated file.
+///
+/// The module is written to `<out_dir>/<schema file stem>.rs`, only when its
+/// content changed, and Cargo is told to run the build script again when the
+/// schema changes. The output only depends on the schema, so it can be
+/// included with:
+///
+/// ```rust,ignore
+/// include!(concat!(env!("OUT_DIR"), "/ovn-nb.rs"));
+/// ```
+pub fn generate(
+    schema_path: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+) -> Result<PathBuf, Error> {
+    let schema_path = schema_path.as_ref();
+    println!("cargo:rerun-if-changed={}", schema_path.display());
+
+    let schema: Value = serde_json::from_str(&std::fs::read_to_string(schema_path)?)?;
+    let module = generate_module(&schema)?;
+
+    let stem = schema_path.file_stem().unwrap_or(schema_path.as_os_str());
+    let output = out_dir
+        .as_ref()
+        .join(format!("{}.rs", stem.to_string_lossy()));
+    // Keep the file untouched when nothing changed, to avoid rebuilds
+    if std::fs::read_to_string(&output).ok().as_deref() != Some(module.as_str()) {
+        std::fs::write(&output, module)?;
+    }
+
+    Ok(output)
+}
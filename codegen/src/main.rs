@@ -0,0 +1,66 @@
+use clap::Parser;
+use jsonrpsee::{core::client::ClientT, rpc_params};
+use ovsdb_client::rpc;
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Generate Rust bindings for the tables of an OVSDB schema.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Schema file to generate the bindings from
+    #[arg(required_unless_present = "remote", conflicts_with = "remote")]
+    schema: Option<PathBuf>,
+
+    /// Fetch the schema from a server instead, such as `tcp:127.0.0.1:6641`
+    /// or `unix:/var/run/openvswitch/db.sock`
+    #[arg(long, requires = "database")]
+    remote: Option<String>,
+
+    /// Database to fetch the schema of when using `--remote`
+    #[arg(long)]
+    database: Option<String>,
+
+    /// File to write the bindings to, instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+async fn fetch_schema(remote: &str, database: &str) -> Result<Value, Box<dyn std::error::Error>> {
+    let params = rpc_params![database];
+
+    // NOTE(mnaser): The schema is requested as raw JSON, since the typed schema
+    //               from the client doesn't keep everything needed for codegen.
+    let schema = match remote.split_once(':') {
+        Some(("tcp", address)) => {
+            let client = rpc::connect_tcp(address).await?;
+            client.request("get_schema", params).await?
+        }
+        Some(("unix", path)) => {
+            let client = rpc::connect_unix(path).await?;
+            client.request("get_schema", params).await?
+        }
+        _ => return Err(format!("unsupported remote {remote:?}").into()),
+    };
+
+    Ok(schema)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+
+    let schema: Value = match (&args.schema, &args.remote, &args.database) {
+        (Some(path), _, _) => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+        (None, Some(remote), Some(database)) => fetch_schema(remote, database).await?,
+        _ => unreachable!("enforced by the argument parser"),
+    };
+
+    let module = ovsdb_codegen::generate_module(&schema)?;
+    match &args.output {
+        Some(path) => std::fs::write(path, module)?,
+        None => print!("{module}"),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use ovsdb_codegen::{Error, generate_module, generate_tables, type_name};
+use serde_json::{Value, json};
+
+fn schema() -> Value {
+    serde_json::from_str(include_str!("../../schema/tests/fixtures/ovn-nb.ovsschema")).unwrap()
+}
+
+#[test]
+fn test_type_name() {
+    assert_eq!(type_name("Logical_Switch_Port"), "LogicalSwitchPort");
+    assert_eq!(type_name("ACL"), "Acl");
+    assert_eq!(type_name("NB_Global"), "NbGlobal");
+    assert_eq!(type_name("Open_vSwitch"), "OpenVSwitch");
+    assert_eq!(type_name("DHCPv6_Options"), "Dhcpv6Options");
+    assert_eq!(type_name("QoS"), "QoS");
+    assert_eq!(type_name("allow-related"), "AllowRelated");
+}
+
+#[test]
+fn test_generate_module() {
+    let module = generate_module(&schema()).unwrap();
+
+    assert!(
+        module.starts_with(
+            "// Generated by ovsdb-codegen from the OVN_Northbound schema version 7.3.0"
+        )
+    );
+    assert!(module.contains("#[::ovsdb_derive::ovsdb_object(table = \"Logical_Switch\")]"));
+    assert!(module.contains("pub struct LogicalSwitchPort {"));
+    assert!(module.contains("pub r#match: ::std::string::String,"));
+    assert!(module.contains("pub enum AclAction {"));
+    assert!(module.contains("#[ovsdb(rename = \"allow-related\")]"));
+    assert!(module.contains("#[ovsdb(ref = \"Acl\")]"));
+
+    // The output only depends on the schema
+    assert_eq!(module, generate_module(&schema()).unwrap());
+}
+
+#[test]
+fn test_untyped_references() {
+    let tokens = generate_tables(&schema(), &["Logical_Switch".to_owned()])
+        .unwrap()
+        .to_string();
+
+    // The referenced tables are not generated, so the references are UUIDs
+    assert!(!tokens.contains("ref ="));
+    assert!(!tokens.contains("LogicalSwitchPort"));
+}
+
+#[test]
+fn test_errors() {
+    assert!(matches!(
+        generate_tables(&schema(), &["Bogus".to_owned()]),
+        Err(Error::UnknownTable(table)) if table == "Bogus"
+    ));
+    assert!(matches!(
+        generate_tables(&json!({}), &[]),
+        Err(Error::MissingTables)
+    ));
+
+    let schema = json!({"tables": {"T": {"columns": {"c": {"type": "float"}}}}});
+    assert_eq!(
+        generate_tables(&schema, &[]).unwrap_err().to_string(),
+        "T.c: unknown atomic type \"float\""
+    );
+}
+
+#[test]
+fn test_generate_file() {
+    let out_dir = std::env::temp_dir().join(format!("ovsdb-codegen-{}", std::process::id()));
+    std::fs::create_dir_all(&out_dir).unwrap();
+
+    let schema_path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../schema/tests/fixtures/ovn-nb.ovsschema"
+    );
+    let output = ovsdb_codegen::generate(schema_path, &out_dir).unwrap();
+    assert_eq!(output, out_dir.join("ovn-nb.rs"));
+    assert_eq!(
+        std::fs::read_to_string(&output).unwrap(),
+        generate_module(&schema()).unwrap()
+    );
+
+    // Regenerating doesn't touch the file
+    let modified = std::fs::metadata(&output).unwrap().modified().unwrap();
+    ovsdb_codegen::generate(schema_path, &out_dir).unwrap();
+    assert_eq!(
+        std::fs::metadata(&output).unwrap().modified().unwrap(),
+        modified
+    );
+
+    assert!(matches!(
+        ovsdb_codegen::generate(out_dir.join("missing.ovsschema"), &out_dir),
+        Err(Error::Io(_))
+    ));
+
+    std::fs::remove_dir_all(&out_dir).unwrap();
+}
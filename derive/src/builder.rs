@@ -0,0 +1,68 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::DeriveInput;
+
+use crate::table::TableField;
+
+/// Generate the `<Struct>Builder` with fluent setters for each column
+pub fn expand_builder(input: &DeriveInput, fields: &[&TableField]) -> TokenStream {
+    let struct_name = &input.ident;
+    let vis = &input.vis;
+    let builder_name = format_ident!("{}Builder", struct_name);
+
+    let field_names: Vec<_> = fields.iter().map(|f| f.ident).collect();
+    let field_types: Vec<_> = fields.iter().map(|f| f.ty).collect();
+    let columns: Vec<_> = fields.iter().map(|f| &f.column).collect();
+    let required: Vec<_> = fields.iter().map(|f| f.required).collect();
+
+    let struct_doc = format!("Builder for `{}` rows", struct_name);
+
+    quote! {
+        #[doc = #struct_doc]
+        #[allow(dead_code)]
+        #vis struct #builder_name {
+            #(
+                #field_names: ::core::option::Option<#field_types>,
+            )*
+        }
+
+        #[allow(dead_code)]
+        impl #builder_name {
+            #(
+                /// Set the column
+                pub fn #field_names(mut self, value: #field_types) -> Self {
+                    self.#field_names = Some(value);
+                    self
+                }
+            )*
+
+            /// Build the row, failing if a required column was not set
+            pub fn build(self) -> ::core::result::Result<#struct_name, ::std::string::String> {
+                let mut row = #struct_name::new();
+
+                #(
+                    match self.#field_names {
+                        Some(value) => row.#field_names = value,
+                        None if #required => {
+                            return Err(format!("Missing required column {}", #columns));
+                        }
+                        None => {}
+                    }
+                )*
+
+                Ok(row)
+            }
+        }
+
+        impl #struct_name {
+            /// Create a builder for a new row
+            pub fn builder() -> #builder_name {
+                #builder_name {
+                    #(
+                        #field_names: None,
+                    )*
+                }
+            }
+        }
+    }
+}
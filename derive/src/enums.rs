@@ -0,0 +1,186 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+/// How variant names are converted into OVSDB strings when not renamed
+pub enum RenameRule {
+    Lowercase,
+    SnakeCase,
+    KebabCase,
+}
+
+impl RenameRule {
+    pub fn apply(&self, ident: &str) -> String {
+        let chars: Vec<char> = ident.chars().collect();
+        let mut result = String::new();
+        for (i, &c) in chars.iter().enumerate() {
+            // Split before an uppercase letter starting a new word, keeping
+            // acronyms such as `ACL` or `HTTPServer` together
+            if c.is_uppercase() && i > 0 {
+                let prev = chars[i - 1];
+                let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+                if prev.is_lowercase()
+                    || prev.is_ascii_digit()
+                    || (prev.is_uppercase() && next_is_lower)
+                {
+                    match self {
+                        RenameRule::Lowercase => {}
+                        RenameRule::SnakeCase => result.push('_'),
+                        RenameRule::KebabCase => result.push('-'),
+                    }
+                }
+            }
+            result.extend(c.to_lowercase());
+        }
+        result
+    }
+}
+
+pub fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let enum_name = &input.ident;
+
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "OvsdbEnum can only be derived for enums",
+        ));
+    };
+
+    let mut rename_rule = RenameRule::SnakeCase;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("ovsdb")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                let rule: LitStr = meta.value()?.parse()?;
+                rename_rule = match rule.value().as_str() {
+                    "lowercase" => RenameRule::Lowercase,
+                    "snake_case" => RenameRule::SnakeCase,
+                    "kebab-case" => RenameRule::KebabCase,
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            rule,
+                            "expected \"lowercase\", \"snake_case\" or \"kebab-case\"",
+                        ))
+                    }
+                };
+                Ok(())
+            } else {
+                Err(meta.error("unsupported ovsdb attribute"))
+            }
+        })?;
+    }
+
+    let mut values = Vec::new();
+    let mut variants = Vec::new();
+    let mut other = None;
+
+    for variant in &data.variants {
+        let mut rename = None;
+        let mut is_other = false;
+
+        for attr in variant.attrs.iter().filter(|a| a.path().is_ident("ovsdb")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("other") {
+                    is_other = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported ovsdb attribute"))
+                }
+            })?;
+        }
+
+        let ident = &variant.ident;
+        let is_other = is_other
+            || (ident == "Other"
+                && matches!(variant.fields, Fields::Unnamed(ref f) if f.unnamed.len() == 1));
+
+        match &variant.fields {
+            Fields::Unit if !is_other => {
+                values.push(rename.unwrap_or_else(|| rename_rule.apply(&ident.to_string())));
+                variants.push(ident);
+            }
+            Fields::Unnamed(fields) if is_other && fields.unnamed.len() == 1 => {
+                if other.is_some() {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "only one fallback variant is allowed",
+                    ));
+                }
+                other = Some(ident);
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "OvsdbEnum variants must be unit variants, except for a single `Other(String)` fallback",
+                ))
+            }
+        }
+    }
+
+    let (as_str_other, from_str_other) = match other {
+        Some(other) => (
+            quote! { #enum_name::#other(value) => value.as_str(), },
+            quote! { value => Some(#enum_name::#other(value.to_owned())), },
+        ),
+        None => (quote! {}, quote! { _ => None, }),
+    };
+
+    let expected = format!("one of {:?}", values);
+
+    Ok(quote! {
+        impl ::ovsdb_schema::OvsdbEnum for #enum_name {
+            const VALUES: &'static [&'static str] = &[#(#values),*];
+
+            fn as_str(&self) -> &str {
+                match self {
+                    #(
+                        #enum_name::#variants => #values,
+                    )*
+                    #as_str_other
+                }
+            }
+
+            fn from_ovsdb_str(value: &str) -> Option<Self> {
+                match value {
+                    #(
+                        #values => Some(#enum_name::#variants),
+                    )*
+                    #from_str_other
+                }
+            }
+        }
+
+        impl ::core::fmt::Display for #enum_name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(::ovsdb_schema::OvsdbEnum::as_str(self))
+            }
+        }
+
+        impl ::ovsdb_schema::OvsdbSerializable for #enum_name {
+            fn to_ovsdb(&self) -> ::ovsdb_schema::OvsdbValue {
+                ::ovsdb_schema::OvsdbValue::Atom(::ovsdb_schema::OvsdbAtom::String(
+                    ::ovsdb_schema::OvsdbEnum::as_str(self).to_owned(),
+                ))
+            }
+
+            fn from_ovsdb(value: &::ovsdb_schema::OvsdbValue) -> Option<Self> {
+                Self::try_from_ovsdb(value).ok()
+            }
+
+            fn try_from_ovsdb(
+                value: &::ovsdb_schema::OvsdbValue,
+            ) -> Result<Self, ::ovsdb_schema::OvsdbConversionError> {
+                match value {
+                    ::ovsdb_schema::OvsdbValue::Atom(::ovsdb_schema::OvsdbAtom::String(s)) => {
+                        <Self as ::ovsdb_schema::OvsdbEnum>::from_ovsdb_str(s).ok_or_else(|| {
+                            ::ovsdb_schema::OvsdbConversionError::mismatch(#expected, value)
+                        })
+                    }
+                    _ => Err(::ovsdb_schema::OvsdbConversionError::mismatch("string", value)),
+                }
+            }
+        }
+    })
+}
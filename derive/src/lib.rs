@@ -1,13 +1,28 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields};
 
+mod builder;
+mod enums;
+mod reference;
+mod schema_file;
+mod table;
+mod update;
+
+use table::{expand_table, named_fields, strip_ovsdb_attrs, table_fields, TableOptions};
+
 /// Attribute macro for OVSDB table structs
 ///
 /// This macro automatically adds `_uuid` and `_version` fields to your struct
 /// and generates the necessary implementations for it to work with OVSDB.
+/// The table name defaults to the struct name and can be set with
+/// `#[ovsdb_object(table = "...")]`.
+///
+/// Fields which are not an `Option`, `Vec`, set or map are required columns,
+/// so `from_map` fails if they are missing unless `#[ovsdb(default)]` is set.
 ///
 /// # Example
 ///
@@ -15,7 +30,7 @@ use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields};
 /// use ovsdb_derive::ovsdb_object;
 /// use std::collections::HashMap;
 ///
-/// #[ovsdb_object]
+/// #[ovsdb_object(table = "NB_Global")]
 /// pub struct NbGlobal {
 ///     pub name: Option<String>,
 ///     pub nb_cfg: Option<i64>,
@@ -23,10 +38,26 @@ use syn::{parse_macro_input, parse_quote, Data, DeriveInput, Fields};
 /// }
 /// ```
 #[proc_macro_attribute]
-pub fn ovsdb_object(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn ovsdb_object(attr: TokenStream, item: TokenStream) -> TokenStream {
     // Parse the struct definition
     let mut input = parse_macro_input!(item as DeriveInput);
 
+    // Options can be given as macro arguments or as #[ovsdb(...)] on the struct
+    let mut options = match TableOptions::from_attrs(&input.attrs) {
+        Ok(options) => options,
+        Err(err) => return err.into_compile_error().into(),
+    };
+    let parser = syn::meta::parser(|meta| options.parse_meta(meta));
+    parse_macro_input!(attr with parser);
+
+    expand_object(&mut input, &options)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_object(input: &mut DeriveInput, options: &TableOptions) -> syn::Result<TokenStream2> {
+    named_fields(input, "ovsdb_object")?;
+
     // Add _uuid and _version fields if they don't exist
     if let Data::Struct(ref mut data_struct) = input.data {
         if let Fields::Named(ref mut fields) = data_struct.fields {
@@ -44,138 +75,40 @@ pub fn ovsdb_object(_attr: TokenStream, item: TokenStream) -> TokenStream {
             if !has_uuid {
                 // Add _uuid field
                 fields.named.push(parse_quote! {
-                    pub _uuid: Option<uuid::Uuid>
+                    pub _uuid: ::core::option::Option<::ovsdb_schema::__private::uuid::Uuid>
                 });
             }
             if !has_version {
                 // Add _version field
                 fields.named.push(parse_quote! {
-                    pub _version: Option<uuid::Uuid>
+                    pub _version: ::core::option::Option<::ovsdb_schema::__private::uuid::Uuid>
                 });
             }
         }
     }
 
-    // Get the name of the struct
-    let struct_name = &input.ident;
+    options.validate(input)?;
 
-    // Extract field names and types, excluding _uuid and _version
-    let mut field_names = Vec::new();
-    let mut field_types = Vec::new();
-
-    if let Data::Struct(ref data_struct) = input.data {
-        if let Fields::Named(ref fields) = data_struct.fields {
-            for field in &fields.named {
-                if let Some(ident) = &field.ident {
-                    if ident == "_uuid" || ident == "_version" {
-                        continue;
-                    }
-                    field_names.push(ident);
-                    field_types.push(&field.ty);
-                }
-            }
-        }
-    }
+    // Extract the columns before stripping the helper attributes, which are
+    // not allowed on the emitted struct
+    let fields = table_fields(named_fields(input, "ovsdb_object")?)?;
+    let implementation = expand_table(input, options, &fields);
+    strip_ovsdb_attrs(input);
 
-    // Generate implementations
-    let implementation = quote! {
-        // Re-export the input struct with the added fields
+    // Re-export the input struct with the added fields
+    Ok(quote! {
         #input
 
-        // Automatically import necessary items from ovsdb-schema
-        use ::ovsdb_schema::{extract_uuid, OvsdbSerializableExt};
-
-        impl #struct_name {
-            /// Create a new instance with default values
-            pub fn new() -> Self {
-                Self {
-                    #(
-                        #field_names: Default::default(),
-                    )*
-                    _uuid: None,
-                    _version: None,
-                }
-            }
-
-            /// Convert to a HashMap for OVSDB serialization
-            pub fn to_map(&self) -> std::collections::HashMap<String, serde_json::Value> {
-                let mut map = std::collections::HashMap::new();
-
-                #(
-                    // Skip None values
-                    let field_value = &self.#field_names;
-                    if let Some(value) = field_value.to_ovsdb_json() {
-                        map.insert(stringify!(#field_names).to_string(), value);
-                    }
-                )*
-
-                map
-            }
-
-            /// Create from a HashMap received from OVSDB
-            pub fn from_map(map: &std::collections::HashMap<String, serde_json::Value>) -> Result<Self, String> {
-                let mut result = Self::new();
-
-                // Extract UUID if present
-                if let Some(uuid_val) = map.get("_uuid") {
-                    if let Some(uuid) = extract_uuid(uuid_val) {
-                        result._uuid = Some(uuid);
-                    }
-                }
-
-                // Extract version if present
-                if let Some(version_val) = map.get("_version") {
-                    if let Some(version) = extract_uuid(version_val) {
-                        result._version = Some(version);
-                    }
-                }
-
-                // Extract other fields
-                #(
-                    if let Some(value) = map.get(stringify!(#field_names)) {
-                        result.#field_names = <#field_types>::from_ovsdb_json(value)
-                            .ok_or_else(|| format!("Failed to parse field {}", stringify!(#field_names)))?;
-                    }
-                )*
-
-                Ok(result)
-            }
-        }
-
-        impl Default for #struct_name {
-            fn default() -> Self {
-                Self::new()
-            }
-        }
-
-        impl serde::Serialize for #struct_name {
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-            where
-                S: serde::Serializer
-            {
-                self.to_map().serialize(serializer)
-            }
-        }
-
-        impl<'de> serde::Deserialize<'de> for #struct_name {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-            where
-                D: serde::Deserializer<'de>
-            {
-                let map = std::collections::HashMap::<String, serde_json::Value>::deserialize(deserializer)?;
-                Self::from_map(&map).map_err(serde::de::Error::custom)
-            }
-        }
-    };
-
-    // Return the modified struct and implementations
-    TokenStream::from(implementation)
+        #implementation
+    })
 }
 
 /// Derive macro for OVSDB table structs (requires manual _uuid and _version fields)
 ///
 /// This macro generates the necessary implementations for a struct to work with OVSDB.
 /// The struct must have `_uuid` and `_version` fields of type `Option<uuid::Uuid>`.
+/// The table name defaults to the struct name and can be set with
+/// `#[ovsdb(table = "...")]`.
 ///
 /// # Example
 ///
@@ -185,6 +118,7 @@ pub fn ovsdb_object(_attr: TokenStream, item: TokenStream) -> TokenStream {
 /// use uuid::Uuid;
 ///
 /// #[derive(Debug, Clone, PartialEq, OVSDB)]
+/// #[ovsdb(table = "NB_Global")]
 /// pub struct NbGlobal {
 ///     pub name: Option<String>,
 ///     pub nb_cfg: Option<i64>,
@@ -195,125 +129,99 @@ pub fn ovsdb_object(_attr: TokenStream, item: TokenStream) -> TokenStream {
 ///     pub _version: Option<Uuid>,
 /// }
 /// ```
-#[proc_macro_derive(OVSDB)]
+#[proc_macro_derive(OVSDB, attributes(ovsdb))]
 pub fn ovsdb_derive(input: TokenStream) -> TokenStream {
     // Parse the input tokens into a syntax tree
     let input = parse_macro_input!(input as DeriveInput);
 
-    // Get the name of the struct
-    let struct_name = &input.ident;
-
-    // Check if the input is a struct
-    let fields = match &input.data {
-        Data::Struct(data_struct) => match &data_struct.fields {
-            Fields::Named(fields_named) => &fields_named.named,
-            _ => panic!("OVSDB can only be derived for structs with named fields"),
-        },
-        _ => panic!("OVSDB can only be derived for structs"),
-    };
-
-    // Extract field names and types, excluding _uuid and _version
-    let mut field_names = Vec::new();
-    let mut field_types = Vec::new();
+    expand_derive(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    for field in fields {
-        if let Some(ident) = &field.ident {
-            if ident == "_uuid" || ident == "_version" {
-                continue;
-            }
-            field_names.push(ident);
-            field_types.push(&field.ty);
+fn expand_derive(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = named_fields(input, "OVSDB")?;
+
+    // Unlike #[ovsdb_object], the derive can't add the row metadata fields
+    for required in ["_uuid", "_version"] {
+        if !fields
+            .iter()
+            .any(|f| f.ident.as_ref().is_some_and(|i| i == required))
+        {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                format!(
+                    "OVSDB requires a `{}: Option<uuid::Uuid>` field, or use #[ovsdb_object] to add it",
+                    required
+                ),
+            ));
         }
     }
 
-    // Generate code for the implementation
-    let expanded = quote! {
-        // Automatically import necessary items from ovsdb-schema
-        use ::ovsdb_schema::{extract_uuid, OvsdbSerializableExt};
-
-        impl #struct_name {
-            /// Create a new instance with default values
-            pub fn new() -> Self {
-                Self {
-                    #(
-                        #field_names: Default::default(),
-                    )*
-                    _uuid: None,
-                    _version: None,
-                }
-            }
-
-            /// Convert to a HashMap for OVSDB serialization
-            pub fn to_map(&self) -> std::collections::HashMap<String, serde_json::Value> {
-                let mut map = std::collections::HashMap::new();
-
-                #(
-                    // Skip None values
-                    let field_value = &self.#field_names;
-                    if let Some(value) = field_value.to_ovsdb_json() {
-                        map.insert(stringify!(#field_names).to_string(), value);
-                    }
-                )*
-
-                map
-            }
-
-            /// Create from a HashMap received from OVSDB
-            pub fn from_map(map: &std::collections::HashMap<String, serde_json::Value>) -> Result<Self, String> {
-                let mut result = Self::new();
-
-                // Extract UUID if present
-                if let Some(uuid_val) = map.get("_uuid") {
-                    if let Some(uuid) = extract_uuid(uuid_val) {
-                        result._uuid = Some(uuid);
-                    }
-                }
-
-                // Extract version if present
-                if let Some(version_val) = map.get("_version") {
-                    if let Some(version) = extract_uuid(version_val) {
-                        result._version = Some(version);
-                    }
-                }
-
-                // Extract other fields
-                #(
-                    if let Some(value) = map.get(stringify!(#field_names)) {
-                        result.#field_names = <#field_types>::from_ovsdb_json(value)
-                            .ok_or_else(|| format!("Failed to parse field {}", stringify!(#field_names)))?;
-                    }
-                )*
-
-                Ok(result)
-            }
-        }
+    let options = TableOptions::from_attrs(&input.attrs)?;
+    options.validate(input)?;
+    Ok(expand_table(input, &options, &table_fields(fields)?))
+}
 
-        impl Default for #struct_name {
-            fn default() -> Self {
-                Self::new()
-            }
-        }
+/// Derive macro for Rust enums stored in string columns constrained by a schema `enum`
+///
+/// Unit variants map to their snake_case name unless renamed with
+/// `#[ovsdb(rename = "...")]` or a container-level `#[ovsdb(rename_all = "...")]`
+/// (`"lowercase"`, `"snake_case"` or `"kebab-case"`). A single `Other(String)`
+/// variant (or one marked `#[ovsdb(other)]`) captures values that are not known.
+/// `Display` writes the stored value, so the enum can also be used as a map key.
+///
+/// # Example
+///
+/// ```rust
+/// use ovsdb_derive::OvsdbEnum;
+///
+/// #[derive(Debug, Clone, PartialEq, OvsdbEnum)]
+/// pub enum LogicalSwitchPortType {
+///     #[ovsdb(rename = "")]
+///     Normal,
+///     Router,
+///     Localnet,
+///     Other(String),
+/// }
+/// ```
+#[proc_macro_derive(OvsdbEnum, attributes(ovsdb))]
+pub fn ovsdb_enum_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
 
-        impl serde::Serialize for #struct_name {
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-            where
-                S: serde::Serializer
-            {
-                self.to_map().serialize(serializer)
-            }
-        }
+    enums::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-        impl<'de> serde::Deserialize<'de> for #struct_name {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-            where
-                D: serde::Deserializer<'de>
-            {
-                let map = std::collections::HashMap::<String, serde_json::Value>::deserialize(deserializer)?;
-                Self::from_map(&map).map_err(serde::de::Error::custom)
-            }
-        }
-    };
+/// Generate table structs from an OVSDB schema file
+///
+/// The first argument is the path of the `.ovsschema` file, relative to the
+/// crate root, followed by the names of the tables to generate. Every table
+/// is generated when no names are given.
+///
+/// Each table becomes an `#[ovsdb_object]` struct named after the table
+/// (`Logical_Switch_Port` becomes `LogicalSwitchPort`), with:
+///
+/// - scalar columns as plain fields, optional ones as `Option<T>`, sets as
+///   `Vec<T>` and maps as `HashMap<K, V>`
+/// - string columns constrained by an `enum` as a generated `OvsdbEnum`,
+///   named after the struct and the column (`AclAction`)
+/// - references as `Uuid`, with `#[ovsdb(ref = "...")]` accessors when the
+///   referenced table is generated by the same invocation
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use ovsdb_derive::ovsdb_table;
+///
+/// ovsdb_table!("ovn-nb.ovsschema", "Logical_Switch", "Logical_Switch_Port");
+/// ```
+#[proc_macro]
+pub fn ovsdb_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as schema_file::TableMacroInput);
 
-    // Return the generated code
-    TokenStream::from(expanded)
+    schema_file::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
@@ -0,0 +1,119 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{GenericArgument, Path, PathArguments, Type};
+
+use crate::table::TableField;
+
+/// Shapes of UUID fields which can be marked with `#[ovsdb(ref = "...")]`
+#[derive(Clone, Copy)]
+pub enum RefShape {
+    /// `Uuid`
+    Single,
+    /// `Option<Uuid>`
+    Optional,
+    /// `Vec<Uuid>`
+    List,
+    /// `Option<Vec<Uuid>>`
+    OptionalList,
+}
+
+/// Returns the single generic argument of `ty` if its last segment is `name`
+fn generic_argument<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != name {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_uuid(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.path.segments.last().is_some_and(|s| s.ident == "Uuid"))
+}
+
+impl RefShape {
+    /// Detect the shape of a field type, if it is supported
+    pub fn of(ty: &Type) -> Option<Self> {
+        if is_uuid(ty) {
+            return Some(RefShape::Single);
+        }
+        if let Some(inner) = generic_argument(ty, "Vec") {
+            return is_uuid(inner).then_some(RefShape::List);
+        }
+        let inner = generic_argument(ty, "Option")?;
+        if is_uuid(inner) {
+            return Some(RefShape::Optional);
+        }
+        generic_argument(inner, "Vec")
+            .filter(|ty| is_uuid(ty))
+            .map(|_| RefShape::OptionalList)
+    }
+}
+
+/// Generate the `Ref<T>`-typed accessors for fields marked with `#[ovsdb(ref = "...")]`
+pub fn expand_ref_accessors(fields: &[&TableField]) -> TokenStream {
+    let accessors = fields.iter().filter_map(|field| {
+        let (target, shape) = field.reference.as_ref()?;
+        Some(expand_accessor(field, target, *shape))
+    });
+
+    quote! {
+        #(#accessors)*
+    }
+}
+
+fn expand_accessor(field: &TableField, target: &Path, shape: RefShape) -> TokenStream {
+    let ident = field.ident;
+    let reference = quote! { ::ovsdb_schema::Ref<#target> };
+    let doc = format!(
+        "Typed references to `{}` rows stored in `{}`",
+        quote!(#target),
+        field.column
+    );
+
+    let (name, ty, body) = match shape {
+        RefShape::Single => (
+            format_ident!("{}_ref", ident.unraw()),
+            quote! { #reference },
+            quote! { ::ovsdb_schema::Ref::new(self.#ident) },
+        ),
+        RefShape::Optional => (
+            format_ident!("{}_ref", ident.unraw()),
+            quote! { ::core::option::Option<#reference> },
+            quote! { self.#ident.map(::ovsdb_schema::Ref::new) },
+        ),
+        RefShape::List => (
+            format_ident!("{}_refs", ident.unraw()),
+            quote! { ::std::vec::Vec<#reference> },
+            quote! { self.#ident.iter().copied().map(::ovsdb_schema::Ref::new).collect() },
+        ),
+        RefShape::OptionalList => (
+            format_ident!("{}_refs", ident.unraw()),
+            quote! { ::std::vec::Vec<#reference> },
+            quote! {
+                self.#ident
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .map(::ovsdb_schema::Ref::new)
+                    .collect()
+            },
+        ),
+    };
+
+    quote! {
+        #[doc = #doc]
+        pub fn #name(&self) -> #ty {
+            #body
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use serde_json::Value;
+use std::path::PathBuf;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{LitStr, Token};
+
+/// Arguments of `ovsdb_table!`: the schema file followed by the table names
+pub struct TableMacroInput {
+    path: LitStr,
+    tables: Vec<LitStr>,
+}
+
+impl Parse for TableMacroInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = Punctuated::<LitStr, Token![,]>::parse_terminated(input)?.into_iter();
+        let path = args
+            .next()
+            .ok_or_else(|| input.error("expected the path of a .ovsschema file"))?;
+
+        Ok(TableMacroInput {
+            path,
+            tables: args.collect(),
+        })
+    }
+}
+
+fn load_schema(path: &LitStr) -> syn::Result<(PathBuf, Value)> {
+    let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let full_path = PathBuf::from(manifest_dir).join(path.value());
+
+    let content = std::fs::read_to_string(&full_path).map_err(|e| {
+        syn::Error::new_spanned(
+            path,
+            format!("failed to read {}: {}", full_path.display(), e),
+        )
+    })?;
+    let schema: Value = serde_json::from_str(&content).map_err(|e| {
+        syn::Error::new_spanned(
+            path,
+            format!("failed to parse {}: {}", full_path.display(), e),
+        )
+    })?;
+
+    Ok((full_path, schema))
+}
+
+pub fn expand(input: TableMacroInput) -> syn::Result<TokenStream> {
+    let (full_path, schema) = load_schema(&input.path)?;
+    let tables: Vec<String> = input.tables.iter().map(LitStr::value).collect();
+
+    let items = ovsdb_codegen::generate_tables(&schema, &tables).map_err(|e| {
+        // Point at the table name when it is the cause of the error
+        let table = match &e {
+            ovsdb_codegen::Error::UnknownTable(table) => Some(table),
+            ovsdb_codegen::Error::InvalidColumn { table, .. } => Some(table),
+            _ => None,
+        };
+        let span = input
+            .tables
+            .iter()
+            .find(|t| Some(&t.value()) == table)
+            .unwrap_or(&input.path)
+            .span();
+        syn::Error::new(span, e)
+    })?;
+    let full_path = full_path.to_string_lossy().into_owned();
+
+    Ok(quote! {
+        // Rebuild when the schema changes
+        const _: &str = ::core::include_str!(#full_path);
+
+        #items
+    })
+}
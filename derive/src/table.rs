@@ -0,0 +1,691 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::builder::expand_builder;
+use crate::enums::RenameRule;
+use crate::reference::{expand_ref_accessors, RefShape};
+use crate::update::expand_update;
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::{
+    parse_quote, Attribute, Data, DeriveInput, Expr, Fields, Ident, LitStr, Path, Token, Type,
+};
+
+/// Struct-level options, from `#[ovsdb(...)]` or the `#[ovsdb_object(...)]` arguments
+#[derive(Default)]
+pub struct TableOptions {
+    /// Name of the OVSDB table, defaulting to the struct name
+    pub table: Option<String>,
+    /// Name of the database, used by the CRUD methods
+    pub database: Option<String>,
+    /// Generate async CRUD methods using `ovsdb-client`, for tables with a
+    /// `database`
+    pub crud: bool,
+    /// Generate `content_eq()`, comparing rows while ignoring `_version`
+    pub content_eq: bool,
+    /// Implement `PartialEq`, `Eq` and `Hash` using `_uuid`, with `uuid_key()`
+    pub hash_by_uuid: bool,
+}
+
+impl TableOptions {
+    /// Parse the `#[ovsdb(...)]` attributes of a struct
+    pub fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = TableOptions::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("ovsdb")) {
+            attr.parse_nested_meta(|meta| options.parse_meta(meta))?;
+        }
+        Ok(options)
+    }
+
+    /// Check the options which only make sense together
+    pub fn validate(&self, input: &DeriveInput) -> syn::Result<()> {
+        if self.crud && self.database.is_none() {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "the `crud` option requires a `database`",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parse a single `key = value` option
+    pub fn parse_meta(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("table") {
+            self.table = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else if meta.path.is_ident("database") {
+            self.database = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        } else if meta.path.is_ident("crud") {
+            self.crud = true;
+            Ok(())
+        } else if meta.path.is_ident("content_eq") {
+            self.content_eq = true;
+            Ok(())
+        } else if meta.path.is_ident("hash_by_uuid") {
+            self.hash_by_uuid = true;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported ovsdb attribute"))
+        }
+    }
+}
+
+/// A struct field mapped to an OVSDB column
+pub struct TableField<'a> {
+    pub ident: &'a Ident,
+    pub ty: &'a Type,
+    /// Name of the column on the wire
+    pub column: String,
+    /// Application-local field which is not mapped to a column
+    pub skip: bool,
+    /// Field collecting the columns which are not mapped by other fields
+    pub flatten_unknown: bool,
+    /// Value used by `new()` and for columns missing from a row
+    pub default: Option<Expr>,
+    /// Whether `from_map` fails when the column is missing from a row
+    pub required: bool,
+    /// Module providing `to_ovsdb` and `try_from_ovsdb` for this field
+    pub with: Option<Path>,
+    /// Table struct referenced by a UUID field, with the shape of the field
+    pub reference: Option<(Path, RefShape)>,
+}
+
+impl TableField<'_> {
+    /// Expression converting `value` (a reference to the field) into an
+    /// `Option<serde_json::Value>`
+    pub fn encode(&self, value: TokenStream) -> TokenStream {
+        match &self.with {
+            Some(with) => quote! {
+                ::ovsdb_schema::__private::encode_with(#with::to_ovsdb(#value))
+            },
+            None => quote! {
+                ::ovsdb_schema::OvsdbSerializableExt::to_ovsdb_json(#value)
+            },
+        }
+    }
+
+    /// Expression converting `value` (a reference to the field) into a
+    /// `Result<serde_json::Value, OvsdbConversionError>`
+    pub fn try_encode(&self, value: TokenStream) -> TokenStream {
+        match &self.with {
+            Some(with) => quote! {
+                ::ovsdb_schema::__private::try_encode_with(#with::to_ovsdb(#value))
+            },
+            None => quote! {
+                ::ovsdb_schema::OvsdbSerializableExt::try_to_ovsdb_json(#value)
+            },
+        }
+    }
+
+    /// Expression decoding `value` (a `&serde_json::Value`) into a
+    /// `Result<Option<T>, OvsdbConversionError>`
+    pub fn decode(&self, value: TokenStream, mode: TokenStream) -> TokenStream {
+        let ty = self.ty;
+        match &self.with {
+            Some(with) => quote! {
+                ::ovsdb_schema::__private::decode_with::<#ty>(#value, #mode, #with::try_from_ovsdb)
+            },
+            None => quote! {
+                <#ty as ::ovsdb_schema::OvsdbSerializableExt>::from_ovsdb_json_with_mode(#value, #mode)
+            },
+        }
+    }
+}
+
+/// Types which can represent a missing column, as an empty set or map
+const OPTIONAL_TYPES: &[&str] = &[
+    "Option", "Vec", "HashSet", "BTreeSet", "HashMap", "BTreeMap", "OvsdbSet", "OvsdbMap",
+];
+
+fn is_optional_type(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| OPTIONAL_TYPES.iter().any(|t| segment.ident == t)),
+        _ => false,
+    }
+}
+
+/// Check that the input is a non-generic struct with named fields
+pub fn named_fields<'a>(input: &'a DeriveInput, macro_name: &str) -> syn::Result<&'a Fields> {
+    let fields = match &input.data {
+        Data::Struct(data_struct) => &data_struct.fields,
+        Data::Enum(data_enum) => {
+            return Err(syn::Error::new_spanned(
+                data_enum.enum_token,
+                format!("{} can only be used on structs, not enums", macro_name),
+            ))
+        }
+        Data::Union(data_union) => {
+            return Err(syn::Error::new_spanned(
+                data_union.union_token,
+                format!("{} can only be used on structs, not unions", macro_name),
+            ))
+        }
+    };
+
+    if !matches!(fields, Fields::Named(_)) {
+        return Err(syn::Error::new_spanned(
+            fields,
+            format!(
+                "{} can only be used on structs with named fields",
+                macro_name
+            ),
+        ));
+    }
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            format!("{} does not support generic structs", macro_name),
+        ));
+    }
+
+    Ok(fields)
+}
+
+/// Default column name of a field, without the `r#` of raw identifiers or the
+/// underscore appended to keywords, so `r#type` and `type_` both map to `type`
+fn column_name(ident: &Ident) -> String {
+    let name = ident.unraw().to_string();
+    match name.strip_suffix('_') {
+        Some(keyword) if !keyword.is_empty() && syn::parse_str::<Ident>(keyword).is_err() => {
+            keyword.to_owned()
+        }
+        _ => name,
+    }
+}
+
+/// Collect the fields of a table struct, excluding `_uuid` and `_version`
+pub fn table_fields(fields: &Fields) -> syn::Result<Vec<TableField<'_>>> {
+    let mut columns = Vec::new();
+
+    for field in fields {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+        if ident == "_uuid" || ident == "_version" {
+            continue;
+        }
+
+        if let Type::Reference(reference) = &field.ty {
+            return Err(syn::Error::new_spanned(
+                reference,
+                "references can't be decoded from OVSDB, use an owned type instead",
+            ));
+        }
+
+        let mut column = None;
+        let mut skip = false;
+        let mut flatten_unknown = false;
+        let mut default = None;
+        let mut with = None;
+        let mut reference = None;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("ovsdb")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("column") {
+                    column = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("flatten_unknown") {
+                    if columns.iter().any(|c: &TableField| c.flatten_unknown) {
+                        return Err(meta.error("only one field can use flatten_unknown"));
+                    }
+                    flatten_unknown = true;
+                    Ok(())
+                } else if meta.path.is_ident("default") {
+                    // A bare `default` uses `Default::default()`
+                    default = Some(if meta.input.peek(Token![=]) {
+                        meta.value()?.parse::<Expr>()?
+                    } else {
+                        parse_quote! { ::core::default::Default::default() }
+                    });
+                    Ok(())
+                } else if meta.path.is_ident("with") {
+                    with = Some(meta.value()?.parse::<LitStr>()?.parse::<Path>()?);
+                    Ok(())
+                } else if meta.path.is_ident("ref") {
+                    let target = meta.value()?.parse::<LitStr>()?.parse::<Path>()?;
+                    let shape = RefShape::of(&field.ty).ok_or_else(|| {
+                        syn::Error::new_spanned(
+                            &field.ty,
+                            "ref is only supported on Uuid, Option<Uuid>, Vec<Uuid> and Option<Vec<Uuid>> fields",
+                        )
+                    })?;
+                    reference = Some((target, shape));
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported ovsdb attribute"))
+                }
+            })?;
+        }
+
+        // The unknown columns are handled separately from the mapped ones
+        let skip = skip || flatten_unknown;
+        columns.push(TableField {
+            ident,
+            ty: &field.ty,
+            column: column.unwrap_or_else(|| column_name(ident)),
+            required: !skip && default.is_none() && !is_optional_type(&field.ty),
+            skip,
+            flatten_unknown,
+            default,
+            with,
+            reference,
+        });
+    }
+
+    Ok(columns)
+}
+
+/// Remove the `#[ovsdb(...)]` helper attributes, which only derive macros
+/// are allowed to leave behind
+pub fn strip_ovsdb_attrs(input: &mut DeriveInput) {
+    input.attrs.retain(|a| !a.path().is_ident("ovsdb"));
+
+    if let Data::Struct(ref mut data_struct) = input.data {
+        for field in data_struct.fields.iter_mut() {
+            field.attrs.retain(|a| !a.path().is_ident("ovsdb"));
+        }
+    }
+}
+
+/// Generate the implementations shared by `#[ovsdb_object]` and `#[derive(OVSDB)]`
+pub fn expand_table(
+    input: &DeriveInput,
+    options: &TableOptions,
+    fields: &[TableField],
+) -> TokenStream {
+    let struct_name = &input.ident;
+    let vis = &input.vis;
+    let table = options
+        .table
+        .clone()
+        .unwrap_or_else(|| struct_name.to_string());
+
+    // Every field is initialized by new(), but skipped fields are not columns
+    let all_field_names: Vec<_> = fields.iter().map(|f| f.ident).collect();
+    let defaults: Vec<_> = fields
+        .iter()
+        .map(|f| match &f.default {
+            Some(default) => quote! { #default },
+            None => quote! { ::core::default::Default::default() },
+        })
+        .collect();
+
+    let (unknown_to_map, unknown_from_map) = match fields.iter().find(|f| f.flatten_unknown) {
+        Some(field) => {
+            let ident = field.ident;
+            (
+                quote! {
+                    for (column, value) in &self.#ident {
+                        map.entry(column.clone()).or_insert_with(|| value.clone());
+                    }
+                },
+                quote! {
+                    ::core::iter::Extend::extend(
+                        &mut result.#ident,
+                        map.iter()
+                            .filter(|(column, _)| {
+                                column.as_str() != "_uuid"
+                                    && column.as_str() != "_version"
+                                    && !Self::COLUMNS.contains(&column.as_str())
+                            })
+                            .map(|(column, value)| (column.clone(), value.clone())),
+                    );
+                },
+            )
+        }
+        None => (quote! {}, quote! {}),
+    };
+
+    let fields: Vec<_> = fields.iter().filter(|f| !f.skip).collect();
+    let field_names: Vec<_> = fields.iter().map(|f| f.ident).collect();
+    let columns: Vec<_> = fields.iter().map(|f| &f.column).collect();
+    let required: Vec<_> = fields.iter().map(|f| f.required).collect();
+    let encoders: Vec<_> = fields
+        .iter()
+        .map(|f| f.encode(quote! { field_value }))
+        .collect();
+    let try_encoders: Vec<_> = fields
+        .iter()
+        .map(|f| f.try_encode(quote! { field_value }))
+        .collect();
+    let decoders: Vec<_> = fields
+        .iter()
+        .map(|f| f.decode(quote! { value }, quote! { mode }))
+        .collect();
+
+    // Constants for each column, in a module since they can't be nested in the impl
+    let columns_module = format_ident!(
+        "{}_columns",
+        RenameRule::SnakeCase.apply(&struct_name.to_string())
+    );
+    let column_consts: Vec<_> = columns
+        .iter()
+        .map(|column| {
+            let name: String = column
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() {
+                        c.to_ascii_uppercase()
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            format_ident!("{}", name)
+        })
+        .collect();
+    let columns_doc = format!("Column names of the `{}` table", table);
+
+    let update = expand_update(input, &fields);
+    let builder = expand_builder(input, &fields);
+    let ref_accessors = expand_ref_accessors(&fields);
+    let content_eq = options.content_eq.then(|| {
+        quote! {
+            impl #struct_name {
+                /// Compare the UUID and columns of two rows, ignoring `_version`
+                /// and skipped fields
+                pub fn content_eq(&self, other: &Self) -> bool {
+                    self._uuid == other._uuid #(&& self.#field_names == other.#field_names)*
+                }
+            }
+        }
+    });
+    let hash_by_uuid = options
+        .hash_by_uuid
+        .then(|| expand_hash_by_uuid(struct_name));
+    let crud = match &options.database {
+        Some(database) if options.crud => expand_crud(struct_name, database),
+        _ => quote! {},
+    };
+
+    quote! {
+        #update
+
+        #builder
+
+        #content_eq
+
+        #hash_by_uuid
+
+        #crud
+
+        #[doc = #columns_doc]
+        #[allow(dead_code)]
+        #vis mod #columns_module {
+            #(
+                pub const #column_consts: &str = #columns;
+            )*
+        }
+
+        impl #struct_name {
+            /// Name of the OVSDB table
+            pub const TABLE: &'static str = #table;
+
+            /// Columns mapped by the struct, excluding `_uuid` and `_version`
+            pub const COLUMNS: &'static [&'static str] = &[#(#columns),*];
+
+            /// Returns the name of the OVSDB table
+            pub fn table_name() -> &'static str {
+                Self::TABLE
+            }
+
+            /// Create a new instance with default values
+            pub fn new() -> Self {
+                Self {
+                    #(
+                        #all_field_names: #defaults,
+                    )*
+                    _uuid: None,
+                    _version: None,
+                }
+            }
+
+            /// Convert to a HashMap for OVSDB serialization, leaving out the
+            /// columns which cannot be sent to OVSDB
+            pub fn to_map(&self) -> ::std::collections::HashMap<::std::string::String, ::ovsdb_schema::__private::serde_json::Value> {
+                <Self as ::ovsdb_schema::OvsdbTable>::to_map(self)
+            }
+
+            /// Convert to a HashMap for OVSDB serialization, failing on the
+            /// first column which cannot be sent to OVSDB
+            pub fn try_to_map(&self) -> Result<::std::collections::HashMap<::std::string::String, ::ovsdb_schema::__private::serde_json::Value>, ::ovsdb_schema::OvsdbConversionError> {
+                <Self as ::ovsdb_schema::OvsdbTable>::try_to_map(self)
+            }
+
+            /// Create from a HashMap received from OVSDB
+            pub fn from_map(map: &::std::collections::HashMap<::std::string::String, ::ovsdb_schema::__private::serde_json::Value>) -> Result<Self, ::ovsdb_schema::OvsdbConversionError> {
+                <Self as ::ovsdb_schema::OvsdbTable>::from_map(map)
+            }
+
+            /// Create from a HashMap received from OVSDB, dropping values which
+            /// fail to decode instead of returning an error in lenient mode
+            pub fn from_map_with_mode(
+                map: &::std::collections::HashMap<::std::string::String, ::ovsdb_schema::__private::serde_json::Value>,
+                mode: ::ovsdb_schema::DecodeMode,
+            ) -> Result<Self, ::ovsdb_schema::OvsdbConversionError> {
+                <Self as ::ovsdb_schema::OvsdbTable>::from_map_with_mode(map, mode)
+            }
+
+            #ref_accessors
+        }
+
+        impl ::ovsdb_schema::OvsdbTable for #struct_name {
+            const TABLE: &'static str = #table;
+
+            const COLUMNS: &'static [&'static str] = &[#(#columns),*];
+
+            fn uuid(&self) -> ::core::option::Option<::ovsdb_schema::__private::uuid::Uuid> {
+                self._uuid
+            }
+
+            fn version(&self) -> ::core::option::Option<::ovsdb_schema::__private::uuid::Uuid> {
+                self._version
+            }
+
+            fn to_map(&self) -> ::std::collections::HashMap<::std::string::String, ::ovsdb_schema::__private::serde_json::Value> {
+                let mut map = ::std::collections::HashMap::new();
+
+                #(
+                    // Leave out the columns which cannot be sent
+                    let field_value = &self.#field_names;
+                    if let Some(value) = #encoders {
+                        map.insert(#columns.to_string(), value);
+                    }
+                )*
+
+                #unknown_to_map
+
+                map
+            }
+
+            fn try_to_map(&self) -> Result<::std::collections::HashMap<::std::string::String, ::ovsdb_schema::__private::serde_json::Value>, ::ovsdb_schema::OvsdbConversionError> {
+                let mut map = ::std::collections::HashMap::new();
+
+                #(
+                    let field_value = &self.#field_names;
+                    let value = #try_encoders.map_err(|e| e.at_column(#columns))?;
+                    map.insert(#columns.to_string(), value);
+                )*
+
+                #unknown_to_map
+
+                Ok(map)
+            }
+
+            fn from_map_with_mode(
+                map: &::std::collections::HashMap<::std::string::String, ::ovsdb_schema::__private::serde_json::Value>,
+                mode: ::ovsdb_schema::DecodeMode,
+            ) -> Result<Self, ::ovsdb_schema::OvsdbConversionError> {
+                let mut result = Self::new();
+
+                // Extract UUID if present
+                if let Some(uuid_val) = map.get("_uuid") {
+                    match ::ovsdb_schema::extract_uuid(uuid_val) {
+                        Some(uuid) => result._uuid = Some(uuid),
+                        None if mode.is_strict() => {
+                            return Err(::ovsdb_schema::OvsdbConversionError::new("uuid", uuid_val.clone()).at_column("_uuid"));
+                        }
+                        None => {}
+                    }
+                }
+
+                // Extract version if present
+                if let Some(version_val) = map.get("_version") {
+                    match ::ovsdb_schema::extract_uuid(version_val) {
+                        Some(version) => result._version = Some(version),
+                        None if mode.is_strict() => {
+                            return Err(::ovsdb_schema::OvsdbConversionError::new("uuid", version_val.clone()).at_column("_version"));
+                        }
+                        None => {}
+                    }
+                }
+
+                // Extract other fields
+                #(
+                    match map.get(#columns) {
+                        Some(value) => {
+                            if let Some(field_value) = #decoders
+                                .map_err(|e| e.at_column(#columns))?
+                            {
+                                result.#field_names = field_value;
+                            }
+                        }
+                        None if #required && mode.is_strict() => {
+                            return Err(::ovsdb_schema::OvsdbConversionError::missing_column(#columns));
+                        }
+                        None => {}
+                    }
+                )*
+
+                #unknown_from_map
+
+                Ok(result)
+            }
+        }
+
+        impl ::core::default::Default for #struct_name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl ::ovsdb_schema::__private::serde::Serialize for #struct_name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: ::ovsdb_schema::__private::serde::Serializer
+            {
+                ::ovsdb_schema::__private::serde::Serialize::serialize(&self.to_map(), serializer)
+            }
+        }
+
+        impl<'de> ::ovsdb_schema::__private::serde::Deserialize<'de> for #struct_name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: ::ovsdb_schema::__private::serde::Deserializer<'de>
+            {
+                let map = <::std::collections::HashMap<::std::string::String, ::ovsdb_schema::__private::serde_json::Value> as ::ovsdb_schema::__private::serde::Deserialize>::deserialize(deserializer)?;
+                Self::from_map(&map).map_err(<D::Error as ::ovsdb_schema::__private::serde::de::Error>::custom)
+            }
+        }
+    }
+}
+
+/// Implement row identity based on `_uuid`, so rows can be stored in a
+/// `HashSet` and looked up by UUID. Rows without a `_uuid` compare by their
+/// columns instead
+fn expand_hash_by_uuid(struct_name: &Ident) -> TokenStream {
+    quote! {
+        impl #struct_name {
+            /// Returns the key the row is hashed and compared by, its UUID,
+            /// none for a row which was not inserted yet and is compared by
+            /// its columns instead
+            pub fn uuid_key(&self) -> ::core::option::Option<::ovsdb_schema::__private::uuid::Uuid> {
+                self._uuid
+            }
+        }
+
+        impl ::core::cmp::PartialEq for #struct_name {
+            fn eq(&self, other: &Self) -> bool {
+                match (&self._uuid, &other._uuid) {
+                    (Some(uuid), Some(other)) => uuid == other,
+                    (None, None) => self.to_map() == other.to_map(),
+                    _ => false,
+                }
+            }
+        }
+
+        impl ::core::cmp::Eq for #struct_name {}
+
+        impl ::core::hash::Hash for #struct_name {
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                // NOTE(mnaser): Rows which were not inserted yet all hash alike, as
+                //               they are compared by their columns.
+                ::core::hash::Hash::hash(&self._uuid, state)
+            }
+        }
+    }
+}
+
+/// Generate async CRUD methods using `ovsdb-client`, for the `crud` option
+fn expand_crud(struct_name: &Ident, database: &str) -> TokenStream {
+    let update_name = format_ident!("{}Update", struct_name);
+
+    quote! {
+        #[allow(dead_code)]
+        impl #struct_name {
+            /// Name of the database holding the table
+            pub const DATABASE: &'static str = #database;
+
+            /// Fetch the row with the given UUID
+            pub async fn get(
+                client: &impl ::ovsdb_client::transact::TransactClient,
+                uuid: ::ovsdb_schema::__private::uuid::Uuid,
+            ) -> ::core::result::Result<::core::option::Option<Self>, ::ovsdb_client::Error> {
+                ::ovsdb_client::crud::get::<Self>(client, Self::DATABASE, uuid).await
+            }
+
+            /// Fetch every row of the table
+            pub async fn list(
+                client: &impl ::ovsdb_client::transact::TransactClient,
+            ) -> ::core::result::Result<::std::vec::Vec<Self>, ::ovsdb_client::Error> {
+                ::ovsdb_client::crud::list::<Self>(client, Self::DATABASE).await
+            }
+
+            /// Insert the row, returning the UUID assigned by the server
+            pub async fn insert(
+                client: &impl ::ovsdb_client::transact::TransactClient,
+                row: &Self,
+            ) -> ::core::result::Result<::ovsdb_schema::__private::uuid::Uuid, ::ovsdb_client::Error> {
+                ::ovsdb_client::crud::insert::<Self>(client, Self::DATABASE, row).await
+            }
+
+            /// Apply the changed columns to the row with the given UUID,
+            /// returning the number of rows updated
+            pub async fn update(
+                client: &impl ::ovsdb_client::transact::TransactClient,
+                uuid: ::ovsdb_schema::__private::uuid::Uuid,
+                changes: &#update_name,
+            ) -> ::core::result::Result<u64, ::ovsdb_client::Error> {
+                let row = changes.try_to_update_map().map_err(::ovsdb_client::Error::Conversion)?;
+                ::ovsdb_client::crud::update::<Self>(client, Self::DATABASE, uuid, row).await
+            }
+
+            /// Delete the row with the given UUID, returning the number of
+            /// rows deleted
+            pub async fn delete(
+                client: &impl ::ovsdb_client::transact::TransactClient,
+                uuid: ::ovsdb_schema::__private::uuid::Uuid,
+            ) -> ::core::result::Result<u64, ::ovsdb_client::Error> {
+                ::ovsdb_client::crud::delete::<Self>(client, Self::DATABASE, uuid).await
+            }
+        }
+    }
+}
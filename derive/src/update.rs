@@ -0,0 +1,126 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::DeriveInput;
+
+use crate::table::TableField;
+
+/// Generate the `<Struct>Update` companion which records changed columns
+pub fn expand_update(input: &DeriveInput, fields: &[&TableField]) -> TokenStream {
+    let struct_name = &input.ident;
+    let vis = &input.vis;
+    let update_name = format_ident!("{}Update", struct_name);
+
+    let field_names: Vec<_> = fields.iter().map(|f| f.ident).collect();
+    let field_types: Vec<_> = fields.iter().map(|f| f.ty).collect();
+    let columns: Vec<_> = fields.iter().map(|f| &f.column).collect();
+    let encoders: Vec<_> = fields
+        .iter()
+        .map(|f| f.encode(quote! { field_value }))
+        .collect();
+    let try_encoders: Vec<_> = fields
+        .iter()
+        .map(|f| f.try_encode(quote! { field_value }))
+        .collect();
+    let setters: Vec<_> = fields
+        .iter()
+        .map(|f| format_ident!("set_{}", f.ident.unraw()))
+        .collect();
+
+    let struct_doc = format!(
+        "Changes to a `{}`, only containing the columns which were set",
+        struct_name
+    );
+
+    quote! {
+        #[doc = #struct_doc]
+        #[allow(dead_code)]
+        #vis struct #update_name {
+            #(
+                #field_names: ::core::option::Option<#field_types>,
+            )*
+        }
+
+        #[allow(dead_code)]
+        impl #update_name {
+            /// Create an update which doesn't change any column
+            pub fn new() -> Self {
+                Self {
+                    #(
+                        #field_names: None,
+                    )*
+                }
+            }
+
+            #(
+                /// Set the column, marking it as changed
+                pub fn #setters(&mut self, value: #field_types) -> &mut Self {
+                    self.#field_names = Some(value);
+                    self
+                }
+            )*
+
+            /// Returns true if no column was changed
+            pub fn is_empty(&self) -> bool {
+                true #(&& self.#field_names.is_none())*
+            }
+
+            /// Names of the changed columns
+            pub fn columns(&self) -> ::std::vec::Vec<&'static str> {
+                let mut columns = ::std::vec::Vec::new();
+                #(
+                    if self.#field_names.is_some() {
+                        columns.push(#columns);
+                    }
+                )*
+                columns
+            }
+
+            /// Convert the changed columns to a HashMap for an `update` operation,
+            /// leaving out those which cannot be sent to OVSDB
+            pub fn to_update_map(&self) -> ::std::collections::HashMap<::std::string::String, ::ovsdb_schema::__private::serde_json::Value> {
+                let mut map = ::std::collections::HashMap::new();
+
+                #(
+                    if let Some(field_value) = &self.#field_names {
+                        if let Some(value) = #encoders {
+                            map.insert(#columns.to_string(), value);
+                        }
+                    }
+                )*
+
+                map
+            }
+
+            /// Convert the changed columns to a HashMap for an `update` operation,
+            /// failing on the first one which cannot be sent to OVSDB
+            pub fn try_to_update_map(&self) -> Result<::std::collections::HashMap<::std::string::String, ::ovsdb_schema::__private::serde_json::Value>, ::ovsdb_schema::OvsdbConversionError> {
+                let mut map = ::std::collections::HashMap::new();
+
+                #(
+                    if let Some(field_value) = &self.#field_names {
+                        let value = #try_encoders.map_err(|e| e.at_column(#columns))?;
+                        map.insert(#columns.to_string(), value);
+                    }
+                )*
+
+                Ok(map)
+            }
+
+            /// Apply the changed columns to a row
+            pub fn apply_to(self, row: &mut #struct_name) {
+                #(
+                    if let Some(value) = self.#field_names {
+                        row.#field_names = value;
+                    }
+                )*
+            }
+        }
+
+        impl ::core::default::Default for #update_name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+    }
+}
@@ -0,0 +1,8 @@
+use ovsdb_derive::ovsdb_object;
+
+#[ovsdb_object(table = "NB_Global", crud)]
+pub struct NbGlobal {
+    pub name: String,
+}
+
+fn main() {}
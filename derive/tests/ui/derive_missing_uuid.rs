@@ -0,0 +1,9 @@
+use ovsdb_derive::OVSDB;
+
+#[derive(OVSDB)]
+pub struct NbGlobal {
+    pub name: Option<String>,
+    pub _version: Option<uuid::Uuid>,
+}
+
+fn main() {}
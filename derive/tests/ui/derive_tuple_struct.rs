@@ -0,0 +1,6 @@
+use ovsdb_derive::OVSDB;
+
+#[derive(OVSDB)]
+pub struct NbGlobal(Option<String>);
+
+fn main() {}
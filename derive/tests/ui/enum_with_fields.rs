@@ -0,0 +1,9 @@
+use ovsdb_derive::OvsdbEnum;
+
+#[derive(OvsdbEnum)]
+pub enum PortType {
+    Router,
+    Localnet { network: String },
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use ovsdb_derive::ovsdb_object;
+
+#[ovsdb_object]
+pub struct NbGlobal<T> {
+    pub name: Option<T>,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use ovsdb_derive::ovsdb_object;
+
+#[ovsdb_object]
+pub enum NbGlobal {
+    Name(String),
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use ovsdb_derive::ovsdb_object;
+
+#[ovsdb_object]
+pub struct LogicalSwitchPort {
+    pub name: Option<String>,
+}
+
+#[ovsdb_object]
+pub struct LogicalSwitch {
+    #[ovsdb(ref = "LogicalSwitchPort")]
+    pub ports: Vec<String>,
+}
+
+fn main() {}
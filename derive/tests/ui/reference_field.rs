@@ -0,0 +1,8 @@
+use ovsdb_derive::ovsdb_object;
+
+#[ovsdb_object]
+pub struct NbGlobal {
+    pub name: &'static str,
+}
+
+fn main() {}
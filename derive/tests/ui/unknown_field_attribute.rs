@@ -0,0 +1,9 @@
+use ovsdb_derive::ovsdb_object;
+
+#[ovsdb_object]
+pub struct NbGlobal {
+    #[ovsdb(rename = "name")]
+    pub name: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,43 @@
+//! Frames arbitrary bytes with `JsonCodec`, as read from a server which may
+//! send anything.
+
+#![no_main]
+
+use bytes::BytesMut;
+use libfuzzer_sys::fuzz_target;
+use ovsdb_client::transports::codec::JsonCodec;
+use serde_json::Value;
+use tokio_util::codec::Decoder;
+
+/// Longest message accepted, so that the rejection of the messages which are
+/// too long is fuzzed as well
+const MAX_LENGTH: usize = 4096;
+
+fuzz_target!(|data: &[u8]| {
+    // NOTE(mnaser): The first byte gives the size of the reads, as the scan
+    //               of a message resumes where the previous read stopped.
+    let Some((&chunk, data)) = data.split_first() else {
+        return;
+    };
+
+    let mut codec = JsonCodec::with_max_length(MAX_LENGTH);
+    let mut buf = BytesMut::new();
+    for bytes in data.chunks(usize::from(chunk).max(1)) {
+        buf.extend_from_slice(bytes);
+
+        loop {
+            match codec.decode(&mut buf) {
+                Ok(Some(frame)) => {
+                    assert!(frame.len() <= MAX_LENGTH);
+                    assert!(matches!(frame.first(), Some(b'{' | b'[')));
+                    assert!(matches!(frame.last(), Some(b'}' | b']')));
+                    let _ = serde_json::from_slice::<Value>(&frame);
+                }
+                Ok(None) => break,
+                Err(_) => return,
+            }
+        }
+
+        assert!(buf.len() <= MAX_LENGTH, "buffered {} bytes", buf.len());
+    }
+});
@@ -0,0 +1,26 @@
+//! Parses arbitrary JSON as OVSDB values, as received in the rows of a
+//! server which may send anything.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use ovsdb_schema::{json_to_ovsdb_value, ovsdb_value_to_json, OvsdbValue};
+use serde_json::Value;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = serde_json::from_slice::<OvsdbValue>(data);
+
+    let Ok(json) = serde_json::from_slice::<Value>(data) else {
+        return;
+    };
+    let Ok(value) = json_to_ovsdb_value(&json) else {
+        return;
+    };
+
+    // NOTE(mnaser): A value may be received in several forms, such as a set
+    //               of one element or its bare atom, but it is always sent
+    //               in the same one.
+    let sent = ovsdb_value_to_json(&value).expect("received reals must be finite");
+    let received = json_to_ovsdb_value(&sent).expect("sent values must be received");
+    assert_eq!(ovsdb_value_to_json(&received).unwrap(), sent);
+});
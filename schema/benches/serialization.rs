@@ -0,0 +1,122 @@
+//! Benchmarks of the conversions between rows, OVSDB values and JSON, run
+//! with `cargo bench -p ovsdb-schema [filter]`.
+//!
+//! Criterion keeps the results of the last run in `target/criterion`, and
+//! reports the change of every benchmark against them.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use ovsdb_derive::ovsdb_object;
+use ovsdb_schema::{OvsdbAtom, OvsdbValue};
+use serde_json::{json, Value};
+use std::{collections::HashMap, hint::black_box};
+use uuid::Uuid;
+
+/// A row as wide as a Logical_Flow or a Logical_Switch_Port of OVN
+#[ovsdb_object(table = "Logical_Flow")]
+pub struct LogicalFlow {
+    pub logical_datapath: Option<Uuid>,
+    pub logical_dp_group: Option<Uuid>,
+    pub pipeline: String,
+    pub table_id: i64,
+    pub priority: i64,
+    pub r#match: String,
+    pub actions: String,
+    pub controller_meter: Option<String>,
+    pub tags: HashMap<String, String>,
+    pub external_ids: HashMap<String, String>,
+    pub flow_desc: Option<String>,
+    pub hash: i64,
+    pub addresses: Vec<String>,
+    pub port_security: Vec<String>,
+    pub ports: Vec<Uuid>,
+    pub enabled: Option<bool>,
+}
+
+fn logical_flow(n: u128) -> LogicalFlow {
+    let mut flow = LogicalFlow::new();
+    flow.logical_datapath = Some(Uuid::from_u128(n));
+    flow.pipeline = "ingress".to_owned();
+    flow.table_id = 23;
+    flow.priority = 100;
+    flow.r#match = format!(
+        "inport == \"lsp{n}\" && eth.src == {{00:00:00:00:00:01}} && ip4.src == {{10.0.0.1, 10.0.0.2}}"
+    );
+    flow.actions = "reg0[0] = 1; reg9[2] = lookup_arp(inport, ip4.src, eth.src); next;".to_owned();
+    flow.tags = HashMap::from([("in_out_port".to_owned(), format!("lsp{n}"))]);
+    flow.external_ids = HashMap::from([
+        ("source".to_owned(), "northd.c:5432".to_owned()),
+        ("stage-name".to_owned(), "ls_in_port_sec_ip".to_owned()),
+        ("stage-hint".to_owned(), format!("{n:08x}")),
+    ]);
+    flow.addresses = vec!["00:00:00:00:00:01 10.0.0.1".to_owned()];
+    flow.port_security = vec!["00:00:00:00:00:01 10.0.0.1 10.0.0.2".to_owned()];
+    flow.ports = (0..4).map(|i| Uuid::from_u128(n * 4 + i)).collect();
+    flow.enabled = Some(true);
+    flow
+}
+
+fn rows(c: &mut Criterion) {
+    let flow = logical_flow(1);
+    c.bench_function("to_map", |b| b.iter(|| black_box(&flow).to_map()));
+
+    let map = flow.to_map();
+    c.bench_function("from_map", |b| {
+        b.iter(|| LogicalFlow::from_map(black_box(&map)).unwrap())
+    });
+
+    let rows: Vec<Value> = (0..1000)
+        .map(|n| serde_json::to_value(logical_flow(n).to_map()).unwrap())
+        .collect();
+    c.bench_function("from_map/1000_rows", |b| {
+        b.iter(|| {
+            for row in black_box(&rows) {
+                let map: HashMap<String, Value> = serde_json::from_value(row.clone()).unwrap();
+                black_box(LogicalFlow::from_map(&map).unwrap());
+            }
+        })
+    });
+}
+
+fn values(c: &mut Criterion) {
+    let values = [
+        ("atom", json!("ls_in_port_sec_ip")),
+        ("uuid", json!(["uuid", Uuid::from_u128(1).to_string()])),
+        (
+            "set",
+            json!([
+                "set",
+                (0..64)
+                    .map(|n| json!(["uuid", Uuid::from_u128(n).to_string()]))
+                    .collect::<Vec<_>>()
+            ]),
+        ),
+        (
+            "map",
+            json!([
+                "map",
+                (0..64)
+                    .map(|n| [format!("key{n}"), format!("value{n}")])
+                    .collect::<Vec<_>>()
+            ]),
+        ),
+    ];
+    let mut group = c.benchmark_group("value_round_trip");
+    for (kind, json) in values {
+        let text = json.to_string();
+        group.bench_function(kind, |b| {
+            b.iter(|| {
+                let value: OvsdbValue = serde_json::from_str(black_box(&text)).unwrap();
+                serde_json::to_string(&value).unwrap()
+            })
+        });
+    }
+    group.finish();
+
+    let value = OvsdbValue::Set((0..64).map(OvsdbAtom::Integer).collect());
+    c.bench_function("value_to_json/set", |b| {
+        b.iter(|| serde_json::to_value(black_box(&value)).unwrap())
+    });
+}
+
+criterion_group!(benches, rows, values);
+criterion_main!(benches);
@@ -0,0 +1,374 @@
+use crate::{
+    map_to_ovsdb, map_try_to_ovsdb, set_to_ovsdb, set_try_to_ovsdb, CardinalityError,
+    OvsdbConversionError, OvsdbSerializable, OvsdbValue,
+};
+
+/// An OVSDB set with unique elements kept in insertion order
+///
+/// The `MIN` and `MAX` parameters mirror the `min` and `max` of the column
+/// type and are checked when receiving values from OVSDB, when inserting and
+/// removing elements, and when sending the set. Insertion order is preserved
+/// so serialization is deterministic, but is not meaningful: two sets holding
+/// the same elements are equal.
+///
+/// The default set is empty whatever `MIN`, as the column of a row being
+/// built, and cannot be sent until it holds `MIN` elements.
+#[derive(Debug, Clone)]
+pub struct OvsdbSet<T, const MIN: usize = 0, const MAX: usize = { usize::MAX }> {
+    items: Vec<T>,
+}
+
+impl<T: PartialEq, const MIN: usize, const MAX: usize> OvsdbSet<T, MIN, MAX> {
+    /// Create an empty set, which only sets without a `MIN` can be; the
+    /// others are built with `try_from`.
+    pub fn new() -> Self {
+        const { assert!(MIN == 0, "an empty set needs MIN = 0, use try_from") };
+        Self::empty()
+    }
+
+    fn empty() -> Self {
+        OvsdbSet { items: Vec::new() }
+    }
+
+    /// Add an element, returning `Ok(false)` if it was already present or an
+    /// error if the set already holds `MAX` elements.
+    pub fn insert(&mut self, item: T) -> Result<bool, CardinalityError> {
+        if self.items.contains(&item) {
+            return Ok(false);
+        }
+
+        if self.items.len() >= MAX {
+            return Err(CardinalityError::new(MIN, MAX, self.items.len() + 1));
+        }
+
+        self.items.push(item);
+        Ok(true)
+    }
+
+    /// Remove an element, returning whether it was present or an error if
+    /// the set only holds `MIN` elements.
+    pub fn remove(&mut self, item: &T) -> Result<bool, CardinalityError> {
+        let Some(index) = self.items.iter().position(|i| i == item) else {
+            return Ok(false);
+        };
+
+        if self.items.len() <= MIN {
+            return Err(CardinalityError::new(MIN, MAX, self.items.len() - 1));
+        }
+
+        self.items.remove(index);
+        Ok(true)
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        self.items.contains(item)
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+
+    /// Check that the number of elements is within `MIN..=MAX`
+    pub fn validate(&self) -> Result<(), CardinalityError> {
+        CardinalityError::check(MIN, MAX, self.items.len())
+    }
+}
+
+impl<T: PartialEq, const MIN: usize, const MAX: usize> Default for OvsdbSet<T, MIN, MAX> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T: PartialEq, const MIN: usize, const MAX: usize> PartialEq for OvsdbSet<T, MIN, MAX> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|item| other.contains(item))
+    }
+}
+
+impl<T: Eq, const MIN: usize, const MAX: usize> Eq for OvsdbSet<T, MIN, MAX> {}
+
+impl<T: PartialEq, const MIN: usize, const MAX: usize> TryFrom<Vec<T>> for OvsdbSet<T, MIN, MAX> {
+    type Error = CardinalityError;
+
+    /// Build a set from a vector, dropping duplicate elements
+    fn try_from(items: Vec<T>) -> Result<Self, Self::Error> {
+        let mut set = Self::empty();
+        for item in items {
+            set.insert(item)?;
+        }
+        set.validate()?;
+        Ok(set)
+    }
+}
+
+impl<'a, T, const MIN: usize, const MAX: usize> IntoIterator for &'a OvsdbSet<T, MIN, MAX> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl<T, const MIN: usize, const MAX: usize> IntoIterator for OvsdbSet<T, MIN, MAX> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<T: OvsdbSerializable + PartialEq, const MIN: usize, const MAX: usize> OvsdbSerializable
+    for OvsdbSet<T, MIN, MAX>
+{
+    fn to_ovsdb(&self) -> OvsdbValue {
+        set_to_ovsdb(self.items.iter())
+    }
+
+    fn try_to_ovsdb(&self) -> Result<OvsdbValue, OvsdbConversionError> {
+        let value = set_try_to_ovsdb(self.items.iter())?;
+        self.validate().map_err(|e| {
+            OvsdbConversionError::mismatch("set", &value).with_reason(e.to_string())
+        })?;
+        Ok(value)
+    }
+
+    fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+        let items: Vec<T> = Vec::try_from_ovsdb(value)?;
+
+        let mut set = Self::empty();
+        for (index, item) in items.into_iter().enumerate() {
+            match set.insert(item) {
+                Ok(true) => {}
+                Ok(false) => {
+                    return Err(OvsdbConversionError::mismatch("set", value)
+                        .with_reason("duplicate element")
+                        .at_index(index))
+                }
+                Err(e) => {
+                    return Err(
+                        OvsdbConversionError::mismatch("set", value).with_reason(e.to_string())
+                    )
+                }
+            }
+        }
+
+        set.validate()
+            .map_err(|e| OvsdbConversionError::mismatch("set", value).with_reason(e.to_string()))?;
+
+        Ok(set)
+    }
+}
+
+/// An OVSDB map with unique keys kept in insertion order
+///
+/// Like [`OvsdbSet`], `MIN` and `MAX` bound the number of entries, and two
+/// maps holding the same entries are equal whatever their order.
+#[derive(Debug, Clone)]
+pub struct OvsdbMap<K, V, const MIN: usize = 0, const MAX: usize = { usize::MAX }> {
+    entries: Vec<(K, V)>,
+}
+
+impl<K: PartialEq, V, const MIN: usize, const MAX: usize> OvsdbMap<K, V, MIN, MAX> {
+    /// Create an empty map, which only maps without a `MIN` can be; the
+    /// others are built with `try_from`.
+    pub fn new() -> Self {
+        const { assert!(MIN == 0, "an empty map needs MIN = 0, use try_from") };
+        Self::empty()
+    }
+
+    fn empty() -> Self {
+        OvsdbMap {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Insert an entry, returning the previous value for the key if there was
+    /// one or an error if the map already holds `MAX` entries.
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, CardinalityError> {
+        if let Some(entry) = self.entries.iter_mut().find(|(k, _)| *k == key) {
+            return Ok(Some(std::mem::replace(&mut entry.1, value)));
+        }
+
+        if self.entries.len() >= MAX {
+            return Err(CardinalityError::new(MIN, MAX, self.entries.len() + 1));
+        }
+
+        self.entries.push((key, value));
+        Ok(None)
+    }
+
+    /// Remove an entry, returning its value if it was present or an error if
+    /// the map only holds `MIN` entries.
+    pub fn remove(&mut self, key: &K) -> Result<Option<V>, CardinalityError> {
+        let Some(index) = self.entries.iter().position(|(k, _)| k == key) else {
+            return Ok(None);
+        };
+
+        if self.entries.len() <= MIN {
+            return Err(CardinalityError::new(MIN, MAX, self.entries.len() - 1));
+        }
+
+        Ok(Some(self.entries.remove(index).1))
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl ExactSizeIterator<Item = &K> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl ExactSizeIterator<Item = &V> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+
+    pub fn into_vec(self) -> Vec<(K, V)> {
+        self.entries
+    }
+
+    /// Check that the number of entries is within `MIN..=MAX`
+    pub fn validate(&self) -> Result<(), CardinalityError> {
+        CardinalityError::check(MIN, MAX, self.entries.len())
+    }
+}
+
+impl<K: PartialEq, V, const MIN: usize, const MAX: usize> Default for OvsdbMap<K, V, MIN, MAX> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq, const MIN: usize, const MAX: usize> PartialEq
+    for OvsdbMap<K, V, MIN, MAX>
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K: Eq, V: Eq, const MIN: usize, const MAX: usize> Eq for OvsdbMap<K, V, MIN, MAX> {}
+
+impl<K: PartialEq, V, const MIN: usize, const MAX: usize> TryFrom<Vec<(K, V)>>
+    for OvsdbMap<K, V, MIN, MAX>
+{
+    type Error = CardinalityError;
+
+    /// Build a map from key/value pairs, later values replacing earlier ones
+    fn try_from(entries: Vec<(K, V)>) -> Result<Self, Self::Error> {
+        let mut map = Self::empty();
+        for (key, value) in entries {
+            map.insert(key, value)?;
+        }
+        map.validate()?;
+        Ok(map)
+    }
+}
+
+impl<K, V, const MIN: usize, const MAX: usize> IntoIterator for OvsdbMap<K, V, MIN, MAX> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<K, V, const MIN: usize, const MAX: usize> OvsdbSerializable for OvsdbMap<K, V, MIN, MAX>
+where
+    K: OvsdbSerializable + PartialEq,
+    V: OvsdbSerializable,
+{
+    fn to_ovsdb(&self) -> OvsdbValue {
+        map_to_ovsdb(self.iter())
+    }
+
+    fn try_to_ovsdb(&self) -> Result<OvsdbValue, OvsdbConversionError> {
+        let value = map_try_to_ovsdb(self.iter())?;
+        self.validate().map_err(|e| {
+            OvsdbConversionError::mismatch("map", &value).with_reason(e.to_string())
+        })?;
+        Ok(value)
+    }
+
+    fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+        let pairs = match value {
+            OvsdbValue::Map(pairs) => pairs.as_slice(),
+            OvsdbValue::Set(atoms) if atoms.is_empty() => &[],
+            _ => return Err(OvsdbConversionError::mismatch("map", value)),
+        };
+
+        let mut map = Self::empty();
+        for (key, val) in pairs {
+            let key_converted =
+                K::try_from_ovsdb(&OvsdbValue::Atom(key.clone())).map_err(|e| e.at_key(key))?;
+            let val_converted =
+                V::try_from_ovsdb(&OvsdbValue::Atom(val.clone())).map_err(|e| e.at_key(key))?;
+
+            match map.insert(key_converted, val_converted) {
+                Ok(None) => {}
+                Ok(Some(_)) => {
+                    return Err(OvsdbConversionError::mismatch("map", value)
+                        .with_reason("duplicate key")
+                        .at_key(key))
+                }
+                Err(e) => {
+                    return Err(
+                        OvsdbConversionError::mismatch("map", value).with_reason(e.to_string())
+                    )
+                }
+            }
+        }
+
+        map.validate()
+            .map_err(|e| OvsdbConversionError::mismatch("map", value).with_reason(e.to_string()))?;
+
+        Ok(map)
+    }
+}
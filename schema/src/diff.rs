@@ -0,0 +1,194 @@
+use crate::{OvsdbAtom, OvsdbValue};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Per-column differences between two versions of a row
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RowDiff {
+    /// Changed columns, keyed by column name
+    pub columns: BTreeMap<String, ColumnDiff>,
+}
+
+impl RowDiff {
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+}
+
+/// Change to a single column
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnDiff {
+    /// The whole value changed, or the column is only present on one side
+    Replace {
+        old: Option<OvsdbValue>,
+        new: Option<OvsdbValue>,
+    },
+    /// Elements added to and removed from a set
+    Set {
+        inserted: Vec<OvsdbAtom>,
+        removed: Vec<OvsdbAtom>,
+    },
+    /// Entries added to, removed from and changed in a map
+    Map {
+        inserted: Vec<(OvsdbAtom, OvsdbAtom)>,
+        /// Removed entries with their old value
+        removed: Vec<(OvsdbAtom, OvsdbAtom)>,
+        /// Entries whose key was kept, with their new value
+        updated: Vec<(OvsdbAtom, OvsdbAtom)>,
+    },
+}
+
+impl ColumnDiff {
+    /// Encode the change using the `<row>` diff notation of `update2`
+    /// notifications, returning `None` if the column was removed
+    ///
+    /// Sets are sent as the elements to toggle, maps as the entries to add,
+    /// remove (with their current value) or replace (with their new value)
+    /// and other columns as their new value.
+    pub fn to_update2(&self) -> Option<OvsdbValue> {
+        match self {
+            ColumnDiff::Replace { new, .. } => new.clone(),
+            ColumnDiff::Set { inserted, removed } => Some(OvsdbValue::Set(
+                inserted.iter().chain(removed).cloned().collect(),
+            )),
+            ColumnDiff::Map {
+                inserted,
+                removed,
+                updated,
+            } => Some(OvsdbValue::Map(
+                inserted
+                    .iter()
+                    .chain(removed)
+                    .chain(updated)
+                    .cloned()
+                    .collect(),
+            )),
+        }
+    }
+}
+
+/// Compute the changes between two versions of a row
+///
+/// Set and map columns are compared element by element, so the result only
+/// lists what was added or removed rather than the whole new value. A single
+/// atom is treated as a set with one element when the other side is a set,
+/// since OVSDB encodes both the same way.
+pub fn diff_rows(old: &HashMap<String, OvsdbValue>, new: &HashMap<String, OvsdbValue>) -> RowDiff {
+    let mut diff = RowDiff::default();
+
+    for (column, old_value) in old {
+        if let Some(change) = diff_values(Some(old_value), new.get(column)) {
+            diff.columns.insert(column.clone(), change);
+        }
+    }
+
+    for (column, new_value) in new {
+        if !old.contains_key(column) {
+            if let Some(change) = diff_values(None, Some(new_value)) {
+                diff.columns.insert(column.clone(), change);
+            }
+        }
+    }
+
+    diff
+}
+
+/// Compute the change between two values of the same column
+pub fn diff_values(old: Option<&OvsdbValue>, new: Option<&OvsdbValue>) -> Option<ColumnDiff> {
+    if old == new {
+        return None;
+    }
+
+    let (Some(old), Some(new)) = (old, new) else {
+        return Some(ColumnDiff::Replace {
+            old: old.cloned(),
+            new: new.cloned(),
+        });
+    };
+
+    let is_map = matches!(old, OvsdbValue::Map(_)) || matches!(new, OvsdbValue::Map(_));
+    let is_set = matches!(old, OvsdbValue::Set(_)) || matches!(new, OvsdbValue::Set(_));
+
+    if is_map {
+        if let (Some(old), Some(new)) = (map_entries(old), map_entries(new)) {
+            return diff_maps(old, new);
+        }
+    } else if is_set {
+        if let (Some(old), Some(new)) = (set_elements(old), set_elements(new)) {
+            return diff_sets(old, new);
+        }
+    }
+
+    Some(ColumnDiff::Replace {
+        old: Some(old.clone()),
+        new: Some(new.clone()),
+    })
+}
+
+fn set_elements(value: &OvsdbValue) -> Option<&[OvsdbAtom]> {
+    match value {
+        OvsdbValue::Atom(atom) => Some(std::slice::from_ref(atom)),
+        OvsdbValue::Set(set) => Some(set),
+        OvsdbValue::Map(_) => None,
+    }
+}
+
+fn map_entries(value: &OvsdbValue) -> Option<&[(OvsdbAtom, OvsdbAtom)]> {
+    match value {
+        OvsdbValue::Map(map) => Some(map),
+        OvsdbValue::Set(set) if set.is_empty() => Some(&[]),
+        _ => None,
+    }
+}
+
+fn diff_sets(old: &[OvsdbAtom], new: &[OvsdbAtom]) -> Option<ColumnDiff> {
+    let old_elements: HashSet<&OvsdbAtom> = old.iter().collect();
+    let new_elements: HashSet<&OvsdbAtom> = new.iter().collect();
+
+    let inserted: Vec<OvsdbAtom> = new
+        .iter()
+        .filter(|atom| !old_elements.contains(atom))
+        .cloned()
+        .collect();
+    let removed: Vec<OvsdbAtom> = old
+        .iter()
+        .filter(|atom| !new_elements.contains(atom))
+        .cloned()
+        .collect();
+
+    if inserted.is_empty() && removed.is_empty() {
+        return None;
+    }
+
+    Some(ColumnDiff::Set { inserted, removed })
+}
+
+fn diff_maps(old: &[(OvsdbAtom, OvsdbAtom)], new: &[(OvsdbAtom, OvsdbAtom)]) -> Option<ColumnDiff> {
+    let old_entries: HashMap<&OvsdbAtom, &OvsdbAtom> = old.iter().map(|(k, v)| (k, v)).collect();
+    let new_entries: HashMap<&OvsdbAtom, &OvsdbAtom> = new.iter().map(|(k, v)| (k, v)).collect();
+
+    let mut inserted = Vec::new();
+    let mut updated = Vec::new();
+    for (key, value) in new {
+        match old_entries.get(key) {
+            None => inserted.push((key.clone(), value.clone())),
+            Some(old_value) if *old_value != value => updated.push((key.clone(), value.clone())),
+            Some(_) => {}
+        }
+    }
+
+    let removed: Vec<(OvsdbAtom, OvsdbAtom)> = old
+        .iter()
+        .filter(|(key, _)| !new_entries.contains_key(key))
+        .cloned()
+        .collect();
+
+    if inserted.is_empty() && removed.is_empty() && updated.is_empty() {
+        return None;
+    }
+
+    Some(ColumnDiff::Map {
+        inserted,
+        removed,
+        updated,
+    })
+}
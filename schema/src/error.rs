@@ -0,0 +1,241 @@
+use crate::{OvsdbAtom, OvsdbValue};
+use std::fmt;
+
+/// Location of a failed conversion inside a row, set or map
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// Position of an element inside a set
+    Index(usize),
+    /// Key of an entry inside a map
+    Key(OvsdbAtom),
+    /// Column of a row
+    Column(String),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Index(index) => write!(f, "[{}]", index),
+            PathSegment::Key(key) => match serde_json::to_string(key) {
+                Ok(key) => write!(f, "[{}]", key),
+                Err(_) => write!(f, "[{:?}]", key),
+            },
+            PathSegment::Column(column) => write!(f, "{}", column),
+        }
+    }
+}
+
+/// Error returned when an OVSDB value cannot be converted into a Rust type
+#[derive(Debug, Clone, PartialEq)]
+pub struct OvsdbConversionError {
+    /// Human readable description of the expected OVSDB type
+    pub expected: String,
+    /// The value which failed to convert, in OVSDB JSON notation
+    pub found: serde_json::Value,
+    /// Additional detail on why the value was rejected
+    pub reason: Option<String>,
+    /// Path to the failing element, outermost first
+    pub path: Vec<PathSegment>,
+}
+
+impl OvsdbConversionError {
+    /// Create an error for a value which does not have the expected type
+    pub fn mismatch(expected: impl Into<String>, found: &OvsdbValue) -> Self {
+        Self::new(expected, crate::json::describe(found))
+    }
+
+    /// Create an error for a value in OVSDB JSON notation
+    pub fn new(expected: impl Into<String>, found: serde_json::Value) -> Self {
+        OvsdbConversionError {
+            expected: expected.into(),
+            found,
+            reason: None,
+            path: Vec::new(),
+        }
+    }
+
+    /// Attach additional detail on why the value was rejected
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+
+    /// Record that the error happened at the given index of a set
+    pub fn at_index(mut self, index: usize) -> Self {
+        self.path.insert(0, PathSegment::Index(index));
+        self
+    }
+
+    /// Record that the error happened at the given key of a map
+    pub fn at_key(mut self, key: &OvsdbAtom) -> Self {
+        self.path.insert(0, PathSegment::Key(key.clone()));
+        self
+    }
+
+    /// Record that the error happened in the given column of a row
+    pub fn at_column(mut self, column: impl Into<String>) -> Self {
+        self.path.insert(0, PathSegment::Column(column.into()));
+        self
+    }
+
+    /// Create an error for a required column missing from a row
+    pub fn missing_column(column: impl Into<String>) -> Self {
+        Self::new("a value", serde_json::Value::Null)
+            .with_reason("missing required column")
+            .at_column(column)
+    }
+}
+
+impl fmt::Display for OvsdbConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {}, found {}", self.expected, self.found)?;
+
+        if let Some(reason) = &self.reason {
+            write!(f, " ({})", reason)?;
+        }
+
+        if !self.path.is_empty() {
+            write!(f, " at ")?;
+            for segment in &self.path {
+                write!(f, "{}", segment)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for OvsdbConversionError {}
+
+/// Error returned when a set or map has too few or too many elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CardinalityError {
+    pub min: usize,
+    /// Maximum number of elements, `None` meaning "unlimited"
+    pub max: Option<usize>,
+    pub found: usize,
+}
+
+impl CardinalityError {
+    pub(crate) fn new(min: usize, max: usize, found: usize) -> Self {
+        CardinalityError {
+            min,
+            max: (max != usize::MAX).then_some(max),
+            found,
+        }
+    }
+
+    pub(crate) fn check(min: usize, max: usize, found: usize) -> Result<(), Self> {
+        if found < min || found > max {
+            return Err(Self::new(min, max, found));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CardinalityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) => write!(
+                f,
+                "expected between {} and {} elements, found {}",
+                self.min, max, self.found
+            ),
+            None => write!(
+                f,
+                "expected at least {} elements, found {}",
+                self.min, self.found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CardinalityError {}
+
+/// Error returned when a JSON value is not valid OVSDB JSON notation
+#[derive(Debug, Clone, PartialEq)]
+pub enum OvsdbJsonError {
+    /// The JSON value has a type OVSDB has no notation for, such as an object
+    UnexpectedType(serde_json::Value),
+    /// An integer does not fit in the signed 64-bit range used by OVSDB
+    IntegerOutOfRange(serde_json::Number),
+    /// A tagged array uses a tag other than `set`, `map`, `uuid` or `named-uuid`
+    UnknownTag(String),
+    /// A tagged array does not have the shape required by its tag
+    MalformedTag {
+        tag: String,
+        found: serde_json::Value,
+    },
+    /// The payload of a `uuid` tagged array is not a valid UUID
+    InvalidUuid(String),
+    /// A set element or map entry is not an atom
+    NotAnAtom(serde_json::Value),
+    /// A real is NaN or infinite, which JSON and OVSDB have no notation for
+    NonFiniteReal(f64),
+}
+
+impl fmt::Display for OvsdbJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OvsdbJsonError::UnexpectedType(found) => {
+                write!(f, "unexpected JSON value {}", found)
+            }
+            OvsdbJsonError::IntegerOutOfRange(number) => {
+                write!(f, "integer {} is out of range for a 64-bit integer", number)
+            }
+            OvsdbJsonError::UnknownTag(tag) => write!(f, "unknown tag \"{}\"", tag),
+            OvsdbJsonError::MalformedTag { tag, found } => {
+                write!(f, "malformed \"{}\" value {}", tag, found)
+            }
+            OvsdbJsonError::InvalidUuid(uuid) => write!(f, "invalid UUID \"{}\"", uuid),
+            OvsdbJsonError::NotAnAtom(found) => write!(f, "expected an atom, found {}", found),
+            OvsdbJsonError::NonFiniteReal(real) => write!(f, "{} is not a finite real", real),
+        }
+    }
+}
+
+impl std::error::Error for OvsdbJsonError {}
+
+impl From<NonFiniteReal> for OvsdbJsonError {
+    fn from(error: NonFiniteReal) -> Self {
+        OvsdbJsonError::NonFiniteReal(error.0)
+    }
+}
+
+/// Error returned when parsing an OVSDB value from its textual syntax
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OvsdbParseError {
+    /// Byte offset in the input where parsing failed
+    pub position: usize,
+    /// Description of what was expected at that position
+    pub reason: String,
+}
+
+impl OvsdbParseError {
+    pub(crate) fn new(position: usize, reason: impl Into<String>) -> Self {
+        OvsdbParseError {
+            position,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for OvsdbParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.reason, self.position)
+    }
+}
+
+impl std::error::Error for OvsdbParseError {}
+
+/// Error returned for a real value that is NaN or infinite, which OVSDB forbids
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NonFiniteReal(pub f64);
+
+impl fmt::Display for NonFiniteReal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a finite real", self.0)
+    }
+}
+
+impl std::error::Error for NonFiniteReal {}
@@ -0,0 +1,186 @@
+use crate::{OvsdbAtom, OvsdbJsonError, OvsdbValue};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Convert a JSON value in OVSDB notation into an [`OvsdbValue`]
+///
+/// This accepts the tagged arrays described in RFC 7047 (`["set", ...]`,
+/// `["map", ...]`, `["uuid", ...]` and `["named-uuid", ...]`), bare atoms as
+/// single values, and `null` or `[]` as the empty set.
+pub fn json_to_ovsdb_value(json: &Value) -> Result<OvsdbValue, OvsdbJsonError> {
+    match json {
+        Value::Null => Ok(OvsdbValue::Set(vec![])),
+        Value::Array(arr) if arr.is_empty() => Ok(OvsdbValue::Set(vec![])),
+        Value::Array(arr) => match tag(arr)? {
+            "set" => {
+                let Some(Value::Array(elements)) = arr.get(1).filter(|_| arr.len() == 2) else {
+                    return Err(malformed("set", json));
+                };
+                elements
+                    .iter()
+                    .map(json_to_ovsdb_atom)
+                    .collect::<Result<_, _>>()
+                    .map(OvsdbValue::Set)
+            }
+            "map" => {
+                let Some(Value::Array(pairs)) = arr.get(1).filter(|_| arr.len() == 2) else {
+                    return Err(malformed("map", json));
+                };
+                pairs
+                    .iter()
+                    .map(|pair| match pair {
+                        Value::Array(kv) if kv.len() == 2 => {
+                            Ok((json_to_ovsdb_atom(&kv[0])?, json_to_ovsdb_atom(&kv[1])?))
+                        }
+                        _ => Err(malformed("map", json)),
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(OvsdbValue::Map)
+            }
+            _ => json_to_ovsdb_atom(json).map(OvsdbValue::Atom),
+        },
+        _ => json_to_ovsdb_atom(json).map(OvsdbValue::Atom),
+    }
+}
+
+/// Convert a JSON value in OVSDB notation into an [`OvsdbAtom`]
+pub fn json_to_ovsdb_atom(json: &Value) -> Result<OvsdbAtom, OvsdbJsonError> {
+    match json {
+        Value::String(s) => Ok(OvsdbAtom::String(s.clone())),
+        Value::Bool(b) => Ok(OvsdbAtom::Boolean(*b)),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Ok(OvsdbAtom::Integer(i))
+            } else if n.is_u64() {
+                Err(OvsdbJsonError::IntegerOutOfRange(n.clone()))
+            } else {
+                n.as_f64()
+                    .map(OvsdbAtom::Real)
+                    .ok_or_else(|| OvsdbJsonError::UnexpectedType(json.clone()))
+            }
+        }
+        Value::Array(arr) if !arr.is_empty() => {
+            let tag = tag(arr)?;
+            let payload = match arr.as_slice() {
+                [_, Value::String(payload)] => payload,
+                _ if tag == "set" || tag == "map" => {
+                    return Err(OvsdbJsonError::NotAnAtom(json.clone()))
+                }
+                _ => return Err(malformed(tag, json)),
+            };
+
+            match tag {
+                "uuid" => Uuid::parse_str(payload)
+                    .map(OvsdbAtom::Uuid)
+                    .map_err(|_| OvsdbJsonError::InvalidUuid(payload.clone())),
+                "named-uuid" => Ok(OvsdbAtom::NamedUuid(payload.clone())),
+                "set" | "map" => Err(OvsdbJsonError::NotAnAtom(json.clone())),
+                _ => Err(OvsdbJsonError::UnknownTag(tag.to_owned())),
+            }
+        }
+        Value::Array(_) | Value::Null => Err(OvsdbJsonError::NotAnAtom(json.clone())),
+        Value::Object(_) => Err(OvsdbJsonError::UnexpectedType(json.clone())),
+    }
+}
+
+/// Convert an [`OvsdbValue`] into JSON in OVSDB notation
+///
+/// Empty sets become `[]` and single-element sets become the bare atom, as
+/// with the `Serialize` implementation. Non-finite reals, which JSON has no
+/// notation for, are rejected.
+pub fn ovsdb_value_to_json(value: &OvsdbValue) -> Result<Value, OvsdbJsonError> {
+    Ok(match value {
+        OvsdbValue::Atom(atom) => ovsdb_atom_to_json(atom)?,
+        OvsdbValue::Set(set) => match set.as_slice() {
+            [] => json!([]),
+            [atom] => ovsdb_atom_to_json(atom)?,
+            _ => json!([
+                "set",
+                set.iter()
+                    .map(ovsdb_atom_to_json)
+                    .collect::<Result<Vec<_>, _>>()?
+            ]),
+        },
+        OvsdbValue::Map(map) => json!([
+            "map",
+            map.iter()
+                .map(|(k, v)| Ok(json!([ovsdb_atom_to_json(k)?, ovsdb_atom_to_json(v)?])))
+                .collect::<Result<Vec<_>, OvsdbJsonError>>()?
+        ]),
+    })
+}
+
+/// Convert an [`OvsdbAtom`] into JSON in OVSDB notation, rejecting NaN and
+/// infinities
+pub fn ovsdb_atom_to_json(atom: &OvsdbAtom) -> Result<Value, OvsdbJsonError> {
+    atom.validate()?;
+
+    Ok(match atom {
+        OvsdbAtom::String(s) => json!(s),
+        OvsdbAtom::Integer(i) => json!(i),
+        OvsdbAtom::Real(r) => json!(r),
+        OvsdbAtom::Boolean(b) => json!(b),
+        OvsdbAtom::Uuid(uuid) => json!(["uuid", uuid.to_string()]),
+        OvsdbAtom::NamedUuid(name) => json!(["named-uuid", name]),
+    })
+}
+
+/// Describe a value in OVSDB notation for an error, as far as JSON allows
+///
+/// Non-finite reals, which are often the reason of the error, become their
+/// textual form.
+pub(crate) fn describe(value: &OvsdbValue) -> Value {
+    let atom = |atom: &OvsdbAtom| match atom {
+        OvsdbAtom::Real(r) if !r.is_finite() => json!(r.to_string()),
+        atom => ovsdb_atom_to_json(atom).unwrap_or_default(),
+    };
+
+    match value {
+        OvsdbValue::Atom(a) => atom(a),
+        OvsdbValue::Set(set) => match set.as_slice() {
+            [] => json!([]),
+            [a] => atom(a),
+            _ => json!(["set", set.iter().map(atom).collect::<Vec<_>>()]),
+        },
+        OvsdbValue::Map(map) => json!([
+            "map",
+            map.iter()
+                .map(|(k, v)| json!([atom(k), atom(v)]))
+                .collect::<Vec<_>>()
+        ]),
+    }
+}
+
+/// Read the tag of a non-empty tagged array
+fn tag(arr: &[Value]) -> Result<&str, OvsdbJsonError> {
+    match &arr[0] {
+        Value::String(tag) => match tag.as_str() {
+            "set" | "map" | "uuid" | "named-uuid" => Ok(tag),
+            _ => Err(OvsdbJsonError::UnknownTag(tag.clone())),
+        },
+        _ => Err(OvsdbJsonError::UnexpectedType(Value::Array(arr.to_vec()))),
+    }
+}
+
+fn malformed(tag: &str, found: &Value) -> OvsdbJsonError {
+    OvsdbJsonError::MalformedTag {
+        tag: tag.to_owned(),
+        found: found.clone(),
+    }
+}
+
+impl TryFrom<&Value> for OvsdbValue {
+    type Error = OvsdbJsonError;
+
+    fn try_from(json: &Value) -> Result<Self, Self::Error> {
+        json_to_ovsdb_value(json)
+    }
+}
+
+impl TryFrom<&OvsdbValue> for Value {
+    type Error = OvsdbJsonError;
+
+    fn try_from(value: &OvsdbValue) -> Result<Self, Self::Error> {
+        ovsdb_value_to_json(value)
+    }
+}
@@ -1,9 +1,89 @@
-use serde::{Serialize, Serializer};
-use std::collections::HashMap;
+use serde::de::{self, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::hash::{Hash, Hasher};
 use uuid::Uuid;
 
+mod collections;
+mod diff;
+mod error;
+mod json;
+mod mutation;
+#[cfg(feature = "net-types")]
+mod net;
+mod reference;
+mod table;
+mod text;
+#[cfg(feature = "chrono")]
+mod timestamp;
+
+pub use collections::{OvsdbMap, OvsdbSet};
+pub use diff::{diff_rows, diff_values, ColumnDiff, RowDiff};
+pub use error::{
+    CardinalityError, NonFiniteReal, OvsdbConversionError, OvsdbJsonError, OvsdbParseError,
+    PathSegment,
+};
+#[cfg(feature = "net-types")]
+pub use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+pub use json::{json_to_ovsdb_atom, json_to_ovsdb_value, ovsdb_atom_to_json, ovsdb_value_to_json};
+pub use mutation::{diff_mutations, Mutation, Mutator};
+#[cfg(feature = "net-types")]
+pub use net::{MacAddr, MacAddrParseError};
+pub use reference::{Ref, WeakRef};
+pub use table::OvsdbTable;
+#[cfg(feature = "chrono")]
+pub use timestamp::EpochSeconds;
+
+/// Re-exports used by the code generated by `ovsdb-derive`, not public API
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+    pub use serde_json;
+    pub use uuid;
+
+    use crate::{json_to_ovsdb_value, DecodeMode, OvsdbConversionError, OvsdbValue};
+
+    /// Encode a column converted with a `#[ovsdb(with = "...")]` function
+    pub fn encode_with(value: OvsdbValue) -> Option<serde_json::Value> {
+        try_encode_with(value).ok()
+    }
+
+    /// Encode a column converted with a `#[ovsdb(with = "...")]` function,
+    /// returning an error if it cannot be sent to OVSDB
+    pub fn try_encode_with(value: OvsdbValue) -> Result<serde_json::Value, OvsdbConversionError> {
+        serde_json::to_value(&value).map_err(|e| {
+            OvsdbConversionError::new("an OVSDB value", crate::json::describe(&value))
+                .with_reason(e.to_string())
+        })
+    }
+
+    /// Decode a column with a `#[ovsdb(with = "...")]` conversion function,
+    /// following the same strict/lenient rules as `from_ovsdb_json_with_mode`
+    pub fn decode_with<T>(
+        json: &serde_json::Value,
+        mode: DecodeMode,
+        decode: impl FnOnce(&OvsdbValue) -> Result<T, OvsdbConversionError>,
+    ) -> Result<Option<T>, OvsdbConversionError> {
+        let result = json_to_ovsdb_value(json)
+            .map_err(|e| {
+                OvsdbConversionError::new("an OVSDB value", json.clone()).with_reason(e.to_string())
+            })
+            .and_then(|value| decode(&value));
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if mode.is_strict() => Err(e),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
 /// Primitive OVSDB Atom types
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Atoms implement `Eq`, `Hash` and `Ord` using [`OvsdbAtom::total_cmp`], so
+/// reals follow ordered-float semantics and atoms can be used as map keys.
+#[derive(Debug, Clone)]
 pub enum OvsdbAtom {
     String(String),
     Integer(i64),
@@ -13,18 +93,213 @@ pub enum OvsdbAtom {
     NamedUuid(String),
 }
 
+impl OvsdbAtom {
+    /// Create a real atom, rejecting NaN and infinities which OVSDB forbids
+    pub fn real(value: f64) -> Result<Self, NonFiniteReal> {
+        if !value.is_finite() {
+            return Err(NonFiniteReal(value));
+        }
+        Ok(OvsdbAtom::Real(value))
+    }
+
+    /// Check that the atom can be sent to OVSDB
+    pub fn validate(&self) -> Result<(), NonFiniteReal> {
+        match self {
+            OvsdbAtom::Real(r) if !r.is_finite() => Err(NonFiniteReal(*r)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Compare two atoms using a total order
+    ///
+    /// Atoms of different types are ordered by type (string, integer, real,
+    /// boolean, uuid, named-uuid) and reals are compared with ordered-float
+    /// semantics, where `-0.0` equals `0.0` and all NaNs are equal and greater
+    /// than every other real.
+    pub fn total_cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (OvsdbAtom::String(a), OvsdbAtom::String(b)) => a.cmp(b),
+            (OvsdbAtom::Integer(a), OvsdbAtom::Integer(b)) => a.cmp(b),
+            (OvsdbAtom::Real(a), OvsdbAtom::Real(b)) => {
+                canonical_real(*a).total_cmp(&canonical_real(*b))
+            }
+            (OvsdbAtom::Boolean(a), OvsdbAtom::Boolean(b)) => a.cmp(b),
+            (OvsdbAtom::Uuid(a), OvsdbAtom::Uuid(b)) => a.cmp(b),
+            (OvsdbAtom::NamedUuid(a), OvsdbAtom::NamedUuid(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            OvsdbAtom::String(_) => 0,
+            OvsdbAtom::Integer(_) => 1,
+            OvsdbAtom::Real(_) => 2,
+            OvsdbAtom::Boolean(_) => 3,
+            OvsdbAtom::Uuid(_) => 4,
+            OvsdbAtom::NamedUuid(_) => 5,
+        }
+    }
+}
+
+impl PartialEq for OvsdbAtom {
+    fn eq(&self, other: &Self) -> bool {
+        self.total_cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OvsdbAtom {}
+
+impl PartialOrd for OvsdbAtom {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OvsdbAtom {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.total_cmp(other)
+    }
+}
+
+impl Hash for OvsdbAtom {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rank().hash(state);
+        match self {
+            OvsdbAtom::String(s) | OvsdbAtom::NamedUuid(s) => s.hash(state),
+            OvsdbAtom::Integer(i) => i.hash(state),
+            OvsdbAtom::Real(r) => canonical_real(*r).to_bits().hash(state),
+            OvsdbAtom::Boolean(b) => b.hash(state),
+            OvsdbAtom::Uuid(uuid) => uuid.hash(state),
+        }
+    }
+}
+
+/// Collapse `-0.0` into `0.0` and every NaN into a single positive NaN
+fn canonical_real(value: f64) -> f64 {
+    if value.is_nan() {
+        f64::NAN
+    } else if value == 0.0 {
+        0.0
+    } else {
+        value
+    }
+}
+
 /// OVSDB Value types (atom, set, or map)
-#[derive(Debug, Clone, PartialEq)]
+///
+/// The elements of sets and maps are kept in the order they were received or
+/// built in, which is not meaningful: values are compared, hashed and ordered
+/// with their elements sorted.
+#[derive(Debug, Clone)]
 pub enum OvsdbValue {
     Atom(OvsdbAtom),
     Set(Vec<OvsdbAtom>),
     Map(Vec<(OvsdbAtom, OvsdbAtom)>),
 }
 
+/// A value with the elements of its set or map sorted, to compare it
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Canonical<'a> {
+    Atom(&'a OvsdbAtom),
+    Set(Vec<&'a OvsdbAtom>),
+    Map(Vec<(&'a OvsdbAtom, &'a OvsdbAtom)>),
+}
+
+impl PartialEq for OvsdbValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (OvsdbValue::Atom(a), OvsdbValue::Atom(b)) => a == b,
+            (OvsdbValue::Set(a), OvsdbValue::Set(b)) if a.len() != b.len() => false,
+            (OvsdbValue::Map(a), OvsdbValue::Map(b)) if a.len() != b.len() => false,
+            _ => self.canonical() == other.canonical(),
+        }
+    }
+}
+
+impl Eq for OvsdbValue {}
+
+impl PartialOrd for OvsdbValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OvsdbValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.canonical().cmp(&other.canonical())
+    }
+}
+
+impl Hash for OvsdbValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical().hash(state);
+    }
+}
+
+impl OvsdbValue {
+    /// Check that every atom of the value can be sent to OVSDB
+    pub fn validate(&self) -> Result<(), NonFiniteReal> {
+        match self {
+            OvsdbValue::Atom(atom) => atom.validate(),
+            OvsdbValue::Set(set) => set.iter().try_for_each(OvsdbAtom::validate),
+            OvsdbValue::Map(map) => map
+                .iter()
+                .try_for_each(|(k, v)| k.validate().and_then(|_| v.validate())),
+        }
+    }
+
+    fn canonical(&self) -> Canonical<'_> {
+        match self {
+            OvsdbValue::Atom(atom) => Canonical::Atom(atom),
+            OvsdbValue::Set(set) => {
+                let mut set: Vec<_> = set.iter().collect();
+                set.sort_unstable();
+                Canonical::Set(set)
+            }
+            OvsdbValue::Map(map) => {
+                let mut map: Vec<_> = map.iter().map(|(k, v)| (k, v)).collect();
+                map.sort_unstable();
+                Canonical::Map(map)
+            }
+        }
+    }
+}
+
 /// Trait for converting between Rust types and OVSDB Values
+///
+/// Implementors only need to provide `to_ovsdb` and `from_ovsdb`; overriding
+/// `try_from_ovsdb` and `try_to_ovsdb` allows reporting why a conversion
+/// failed.
 pub trait OvsdbSerializable: Sized {
+    /// Convert to an OVSDB value
+    ///
+    /// # Panics
+    ///
+    /// The implementations for integers wider than OVSDB's and for
+    /// collections panic when the value cannot be sent to OVSDB, such as a
+    /// `u64` out of range or a set whose elements are not atoms, rather than
+    /// replace it with an empty set; `try_to_ovsdb` returns an error instead.
     fn to_ovsdb(&self) -> OvsdbValue;
     fn from_ovsdb(value: &OvsdbValue) -> Option<Self>;
+
+    /// Convert to an OVSDB value, returning an error when the value cannot
+    /// be sent to OVSDB, such as a non-finite real or a set whose elements
+    /// are not atoms
+    fn try_to_ovsdb(&self) -> Result<OvsdbValue, OvsdbConversionError> {
+        let value = self.to_ovsdb();
+        value.validate().map_err(|e| {
+            OvsdbConversionError::new("an OVSDB value", json::describe(&value))
+                .with_reason(e.to_string())
+        })?;
+        Ok(value)
+    }
+
+    /// Convert from an OVSDB value, returning an error describing the failure
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+        Self::from_ovsdb(value)
+            .ok_or_else(|| OvsdbConversionError::mismatch(std::any::type_name::<Self>(), value))
+    }
 }
 
 impl<T: OvsdbSerializable> OvsdbSerializable for Option<T> {
@@ -35,8 +310,44 @@ impl<T: OvsdbSerializable> OvsdbSerializable for Option<T> {
         }
     }
 
+    fn try_to_ovsdb(&self) -> Result<OvsdbValue, OvsdbConversionError> {
+        match self {
+            Some(val) => val.try_to_ovsdb(),
+            None => Ok(OvsdbValue::Set(vec![])),
+        }
+    }
+
     fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
-        T::from_ovsdb(value).map(Some)
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+        // NOTE(mnaser): Optional columns are sent as an empty set when unset, which
+        //               only becomes `None` if `T` itself can't be empty (i.e. an
+        //               `Option<Vec<T>>` still decodes to `Some(vec![])`).
+        match T::try_from_ovsdb(value) {
+            Ok(val) => Ok(Some(val)),
+            Err(_) if matches!(value, OvsdbValue::Set(set) if set.is_empty()) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// How strictly values received from OVSDB are decoded
+///
+/// In strict mode type mismatches, unknown tags and out of range numbers are
+/// reported as errors. In lenient mode the offending value is dropped and
+/// the field is left at its default.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeMode {
+    #[default]
+    Strict,
+    Lenient,
+}
+
+impl DecodeMode {
+    pub fn is_strict(&self) -> bool {
+        matches!(self, DecodeMode::Strict)
     }
 }
 
@@ -46,9 +357,13 @@ impl OvsdbSerializable for String {
     }
 
     fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
         match value {
-            OvsdbValue::Atom(OvsdbAtom::String(s)) => Some(s.clone()),
-            _ => None,
+            OvsdbValue::Atom(OvsdbAtom::String(s)) => Ok(s.clone()),
+            _ => Err(OvsdbConversionError::mismatch("string", value)),
         }
     }
 }
@@ -59,22 +374,87 @@ impl OvsdbSerializable for i64 {
     }
 
     fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
         match value {
-            OvsdbValue::Atom(OvsdbAtom::Integer(i)) => Some(*i),
-            _ => None,
+            OvsdbValue::Atom(OvsdbAtom::Integer(i)) => Ok(*i),
+            _ => Err(OvsdbConversionError::mismatch("integer", value)),
         }
     }
 }
 
+/// Implement OvsdbSerializable for integer types narrower than (or unsigned
+/// relative to) OVSDB's 64-bit signed integers, with checked conversions.
+macro_rules! impl_ovsdb_integer {
+    ($($ty:ty),*) => {
+        $(
+            impl OvsdbSerializable for $ty {
+                fn to_ovsdb(&self) -> OvsdbValue {
+                    // NOTE(mnaser): OVSDB integers are 64-bit signed, so values which
+                    //               do not fit (only possible for u64) have no OVSDB
+                    //               representation.
+                    expect_ovsdb(self.try_to_ovsdb())
+                }
+
+                fn try_to_ovsdb(&self) -> Result<OvsdbValue, OvsdbConversionError> {
+                    i64::try_from(*self)
+                        .map(|i| OvsdbValue::Atom(OvsdbAtom::Integer(i)))
+                        .map_err(|_| {
+                            OvsdbConversionError::new("integer", serde_json::Value::from(*self))
+                                .with_reason(format!(
+                                    "{} is out of range {}..={}",
+                                    self,
+                                    i64::MIN,
+                                    i64::MAX
+                                ))
+                        })
+                }
+
+                fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+                    Self::try_from_ovsdb(value).ok()
+                }
+
+                fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+                    match value {
+                        OvsdbValue::Atom(OvsdbAtom::Integer(i)) => <$ty>::try_from(*i).map_err(|_| {
+                            OvsdbConversionError::mismatch(stringify!($ty), value).with_reason(
+                                format!(
+                                    "{} is out of range {}..={}",
+                                    i,
+                                    <$ty>::MIN,
+                                    <$ty>::MAX
+                                ),
+                            )
+                        }),
+                        _ => Err(OvsdbConversionError::mismatch("integer", value)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_ovsdb_integer!(i8, i16, i32, u8, u16, u32, u64);
+
 impl OvsdbSerializable for f64 {
     fn to_ovsdb(&self) -> OvsdbValue {
         OvsdbValue::Atom(OvsdbAtom::Real(*self))
     }
 
     fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
         match value {
-            OvsdbValue::Atom(OvsdbAtom::Real(r)) => Some(*r),
-            _ => None,
+            OvsdbValue::Atom(OvsdbAtom::Real(r)) if r.is_finite() => Ok(*r),
+            OvsdbValue::Atom(OvsdbAtom::Real(r)) => {
+                Err(OvsdbConversionError::mismatch("real", value)
+                    .with_reason(NonFiniteReal(*r).to_string()))
+            }
+            _ => Err(OvsdbConversionError::mismatch("real", value)),
         }
     }
 }
@@ -85,9 +465,13 @@ impl OvsdbSerializable for bool {
     }
 
     fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
         match value {
-            OvsdbValue::Atom(OvsdbAtom::Boolean(b)) => Some(*b),
-            _ => None,
+            OvsdbValue::Atom(OvsdbAtom::Boolean(b)) => Ok(*b),
+            _ => Err(OvsdbConversionError::mismatch("boolean", value)),
         }
     }
 }
@@ -98,93 +482,239 @@ impl OvsdbSerializable for Uuid {
     }
 
     fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
         match value {
-            OvsdbValue::Atom(OvsdbAtom::Uuid(uuid)) => Some(*uuid),
-            _ => None,
+            OvsdbValue::Atom(OvsdbAtom::Uuid(uuid)) => Ok(*uuid),
+            _ => Err(OvsdbConversionError::mismatch("uuid", value)),
         }
     }
 }
 
+/// Trait for Rust enums mapped to string columns constrained by a schema `enum`
+///
+/// This is usually implemented with `#[derive(OvsdbEnum)]` from `ovsdb-derive`,
+/// which also implements `OvsdbSerializable` for the enum.
+pub trait OvsdbEnum: Sized {
+    /// The strings allowed by the schema, in declaration order
+    const VALUES: &'static [&'static str];
+
+    /// Returns the string stored in OVSDB for this value
+    fn as_str(&self) -> &str;
+
+    /// Parse a string stored in OVSDB, returning `None` if it is not allowed
+    fn from_ovsdb_str(value: &str) -> Option<Self>;
+}
+
+/// Returns the value of an infallible `to_ovsdb`, which has no way to report
+/// that the value cannot be sent to OVSDB but must not silently replace it.
+fn expect_ovsdb(value: Result<OvsdbValue, OvsdbConversionError>) -> OvsdbValue {
+    value.unwrap_or_else(|e| panic!("value cannot be sent to OVSDB: {}", e))
+}
+
+/// Convert the items of a collection into an OVSDB set, panicking if any of
+/// them is not an atom
+fn set_to_ovsdb<'a, T: OvsdbSerializable + 'a>(
+    items: impl ExactSizeIterator<Item = &'a T>,
+) -> OvsdbValue {
+    expect_ovsdb(set_try_to_ovsdb(items))
+}
+
+/// Convert the items of a collection into an OVSDB set, failing on the first
+/// item which cannot be converted to an atom
+fn set_try_to_ovsdb<'a, T: OvsdbSerializable + 'a>(
+    items: impl ExactSizeIterator<Item = &'a T>,
+) -> Result<OvsdbValue, OvsdbConversionError> {
+    set_atoms(items, T::try_to_ovsdb).map(OvsdbValue::Set)
+}
+
+fn set_atoms<'a, T: 'a>(
+    items: impl ExactSizeIterator<Item = &'a T>,
+    convert: impl Fn(&T) -> Result<OvsdbValue, OvsdbConversionError>,
+) -> Result<Vec<OvsdbAtom>, OvsdbConversionError> {
+    let mut atoms = Vec::with_capacity(items.len());
+    for (index, item) in items.enumerate() {
+        let atom = convert(item)
+            .and_then(into_atom)
+            .map_err(|e| e.at_index(index))?;
+        atoms.push(atom);
+    }
+
+    Ok(atoms)
+}
+
+/// Unwrap the atom of a value, the element of a set or map
+fn into_atom(value: OvsdbValue) -> Result<OvsdbAtom, OvsdbConversionError> {
+    match value {
+        OvsdbValue::Atom(atom) => Ok(atom),
+        value => Err(OvsdbConversionError::new("an atom", json::describe(&value))),
+    }
+}
+
+/// Convert an OVSDB set (or a single atom) into a collection
+fn set_try_from_ovsdb<T: OvsdbSerializable, C: FromIterator<T>>(
+    value: &OvsdbValue,
+) -> Result<C, OvsdbConversionError> {
+    match value {
+        OvsdbValue::Set(atoms) => atoms
+            .iter()
+            .enumerate()
+            .map(|(index, atom)| {
+                T::try_from_ovsdb(&OvsdbValue::Atom(atom.clone())).map_err(|e| e.at_index(index))
+            })
+            .collect(),
+        // Handle single atom as a one-element set
+        OvsdbValue::Atom(atom) => T::try_from_ovsdb(&OvsdbValue::Atom(atom.clone()))
+            .map(|item| std::iter::once(item).collect()),
+        _ => Err(OvsdbConversionError::mismatch("set", value)),
+    }
+}
+
+/// Convert the entries of a map into an OVSDB map, panicking if any of
+/// their keys or values is not an atom
+fn map_to_ovsdb<'a, K: OvsdbSerializable + 'a, V: OvsdbSerializable + 'a>(
+    entries: impl ExactSizeIterator<Item = (&'a K, &'a V)>,
+) -> OvsdbValue {
+    expect_ovsdb(map_try_to_ovsdb(entries))
+}
+
+/// Convert the entries of a map into an OVSDB map, failing on the first key
+/// or value which cannot be converted to an atom
+fn map_try_to_ovsdb<'a, K: OvsdbSerializable + 'a, V: OvsdbSerializable + 'a>(
+    entries: impl ExactSizeIterator<Item = (&'a K, &'a V)>,
+) -> Result<OvsdbValue, OvsdbConversionError> {
+    map_pairs(entries, K::try_to_ovsdb, V::try_to_ovsdb).map(OvsdbValue::Map)
+}
+
+fn map_pairs<'a, K: 'a, V: 'a>(
+    entries: impl ExactSizeIterator<Item = (&'a K, &'a V)>,
+    convert_key: impl Fn(&K) -> Result<OvsdbValue, OvsdbConversionError>,
+    convert_value: impl Fn(&V) -> Result<OvsdbValue, OvsdbConversionError>,
+) -> Result<Vec<(OvsdbAtom, OvsdbAtom)>, OvsdbConversionError> {
+    let mut pairs = Vec::with_capacity(entries.len());
+    for (index, (key, value)) in entries.enumerate() {
+        let key = convert_key(key)
+            .and_then(into_atom)
+            .map_err(|e| e.at_index(index))?;
+        let value = convert_value(value)
+            .and_then(into_atom)
+            .map_err(|e| e.at_key(&key))?;
+        pairs.push((key, value));
+    }
+
+    Ok(pairs)
+}
+
+/// Convert an OVSDB map into a map collection
+fn map_try_from_ovsdb<K: OvsdbSerializable, V: OvsdbSerializable, C: FromIterator<(K, V)>>(
+    value: &OvsdbValue,
+) -> Result<C, OvsdbConversionError> {
+    match value {
+        OvsdbValue::Map(map) => map
+            .iter()
+            .map(|(key, val)| {
+                let key_converted =
+                    K::try_from_ovsdb(&OvsdbValue::Atom(key.clone())).map_err(|e| e.at_key(key))?;
+                let val_converted =
+                    V::try_from_ovsdb(&OvsdbValue::Atom(val.clone())).map_err(|e| e.at_key(key))?;
+                Ok((key_converted, val_converted))
+            })
+            .collect(),
+        // An empty map may be sent as an empty set, such as `[]` for a `None`
+        OvsdbValue::Set(atoms) if atoms.is_empty() => Ok(std::iter::empty().collect()),
+        _ => Err(OvsdbConversionError::mismatch("map", value)),
+    }
+}
+
 impl<T: OvsdbSerializable> OvsdbSerializable for Vec<T> {
     fn to_ovsdb(&self) -> OvsdbValue {
-        if self.is_empty() {
-            return OvsdbValue::Set(vec![]);
-        }
+        set_to_ovsdb(self.iter())
+    }
 
-        // Try to convert each item to an OvsdbAtom
-        let mut atoms = Vec::with_capacity(self.len());
-        for item in self {
-            match item.to_ovsdb() {
-                OvsdbValue::Atom(atom) => atoms.push(atom),
-                _ => return OvsdbValue::Set(vec![]), // Invalid conversion, return empty set
-            }
-        }
+    fn try_to_ovsdb(&self) -> Result<OvsdbValue, OvsdbConversionError> {
+        set_try_to_ovsdb(self.iter())
+    }
+
+    fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+        set_try_from_ovsdb(value)
+    }
+}
+
+impl<T: OvsdbSerializable + Eq + Hash> OvsdbSerializable for HashSet<T> {
+    fn to_ovsdb(&self) -> OvsdbValue {
+        set_to_ovsdb(self.iter())
+    }
 
-        OvsdbValue::Set(atoms)
+    fn try_to_ovsdb(&self) -> Result<OvsdbValue, OvsdbConversionError> {
+        set_try_to_ovsdb(self.iter())
     }
 
     fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
-        match value {
-            OvsdbValue::Set(atoms) => {
-                let mut result = Vec::with_capacity(atoms.len());
-                for atom in atoms {
-                    if let Some(item) = T::from_ovsdb(&OvsdbValue::Atom(atom.clone())) {
-                        result.push(item);
-                    } else {
-                        return None;
-                    }
-                }
-                Some(result)
-            }
-            // Handle single atom as a one-element set
-            OvsdbValue::Atom(atom) => {
-                T::from_ovsdb(&OvsdbValue::Atom(atom.clone())).map(|item| vec![item])
-            }
-            _ => None,
-        }
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+        set_try_from_ovsdb(value)
     }
 }
 
-impl<K: OvsdbSerializable + ToString + Eq + std::hash::Hash, V: OvsdbSerializable> OvsdbSerializable
-    for HashMap<K, V>
-{
+impl<T: OvsdbSerializable + Ord> OvsdbSerializable for BTreeSet<T> {
     fn to_ovsdb(&self) -> OvsdbValue {
-        let mut pairs = Vec::with_capacity(self.len());
+        set_to_ovsdb(self.iter())
+    }
 
-        for (key, value) in self {
-            if let OvsdbValue::Atom(key_atom) = key.to_ovsdb() {
-                if let OvsdbValue::Atom(value_atom) = value.to_ovsdb() {
-                    pairs.push((key_atom, value_atom));
-                    continue;
-                }
-            }
-            return OvsdbValue::Map(vec![]);
-        }
+    fn try_to_ovsdb(&self) -> Result<OvsdbValue, OvsdbConversionError> {
+        set_try_to_ovsdb(self.iter())
+    }
+
+    fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        Self::try_from_ovsdb(value).ok()
+    }
 
-        OvsdbValue::Map(pairs)
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+        set_try_from_ovsdb(value)
+    }
+}
+
+impl<K: OvsdbSerializable + Eq + Hash, V: OvsdbSerializable> OvsdbSerializable for HashMap<K, V> {
+    fn to_ovsdb(&self) -> OvsdbValue {
+        map_to_ovsdb(self.iter())
+    }
+
+    fn try_to_ovsdb(&self) -> Result<OvsdbValue, OvsdbConversionError> {
+        map_try_to_ovsdb(self.iter())
     }
 
     fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
-        match value {
-            OvsdbValue::Map(map) => {
-                let mut result = HashMap::with_capacity(map.len());
-
-                for (key, val) in map {
-                    if let Some(key_converted) = K::from_ovsdb(&OvsdbValue::Atom(key.clone())) {
-                        if let Some(val_converted) = V::from_ovsdb(&OvsdbValue::Atom(val.clone())) {
-                            result.insert(key_converted, val_converted);
-                        } else {
-                            return None;
-                        }
-                    } else {
-                        return None;
-                    }
-                }
+        Self::try_from_ovsdb(value).ok()
+    }
 
-                Some(result)
-            }
-            _ => None,
-        }
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+        map_try_from_ovsdb(value)
+    }
+}
+
+impl<K: OvsdbSerializable + Ord, V: OvsdbSerializable> OvsdbSerializable for BTreeMap<K, V> {
+    fn to_ovsdb(&self) -> OvsdbValue {
+        map_to_ovsdb(self.iter())
+    }
+
+    fn try_to_ovsdb(&self) -> Result<OvsdbValue, OvsdbConversionError> {
+        map_try_to_ovsdb(self.iter())
+    }
+
+    fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+        map_try_from_ovsdb(value)
     }
 }
 
@@ -225,7 +755,10 @@ impl Serialize for OvsdbAtom {
         match self {
             OvsdbAtom::String(s) => s.serialize(serializer),
             OvsdbAtom::Integer(i) => i.serialize(serializer),
-            OvsdbAtom::Real(r) => r.serialize(serializer),
+            OvsdbAtom::Real(r) => {
+                self.validate().map_err(serde::ser::Error::custom)?;
+                r.serialize(serializer)
+            }
             OvsdbAtom::Boolean(b) => b.serialize(serializer),
             OvsdbAtom::Uuid(uuid) => {
                 let wrapper = ("uuid", uuid.to_string());
@@ -239,17 +772,219 @@ impl Serialize for OvsdbAtom {
     }
 }
 
+/// Custom serde deserialization format for OvsdbValue
+/// Accepts the tagged-array JSON format used by OVSDB
+impl<'de> Deserialize<'de> for OvsdbValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(OvsdbValueVisitor)
+    }
+}
+
+struct OvsdbValueVisitor;
+
+impl<'de> Visitor<'de> for OvsdbValueVisitor {
+    type Value = OvsdbValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an OVSDB atom, set or map")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        OvsdbAtomVisitor.visit_bool(v).map(OvsdbValue::Atom)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        OvsdbAtomVisitor.visit_i64(v).map(OvsdbValue::Atom)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        OvsdbAtomVisitor.visit_u64(v).map(OvsdbValue::Atom)
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        OvsdbAtomVisitor.visit_f64(v).map(OvsdbValue::Atom)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        OvsdbAtomVisitor.visit_str(v).map(OvsdbValue::Atom)
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        OvsdbAtomVisitor.visit_string(v).map(OvsdbValue::Atom)
+    }
+
+    // Null is represented as an empty set
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(OvsdbValue::Set(vec![]))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(OvsdbValue::Set(vec![]))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // Empty array means empty set
+        let Some(tag) = seq.next_element::<String>()? else {
+            return Ok(OvsdbValue::Set(vec![]));
+        };
+
+        let value = match tag.as_str() {
+            "set" => OvsdbValue::Set(
+                seq.next_element::<Vec<OvsdbAtom>>()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?,
+            ),
+            "map" => OvsdbValue::Map(
+                seq.next_element::<Vec<(OvsdbAtom, OvsdbAtom)>>()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?,
+            ),
+            "uuid" | "named-uuid" => OvsdbValue::Atom(tagged_atom(&tag, &mut seq)?),
+            _ => {
+                return Err(de::Error::unknown_variant(
+                    &tag,
+                    &["set", "map", "uuid", "named-uuid"],
+                ))
+            }
+        };
+
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(3, &self));
+        }
+
+        Ok(value)
+    }
+}
+
+impl<'de> Deserialize<'de> for OvsdbAtom {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(OvsdbAtomVisitor)
+    }
+}
+
+struct OvsdbAtomVisitor;
+
+impl<'de> Visitor<'de> for OvsdbAtomVisitor {
+    type Value = OvsdbAtom;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an OVSDB atom")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(OvsdbAtom::Boolean(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(OvsdbAtom::Integer(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        i64::try_from(v)
+            .map(OvsdbAtom::Integer)
+            .map_err(|_| de::Error::invalid_value(de::Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(OvsdbAtom::Real(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(OvsdbAtom::String(v.to_owned()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(OvsdbAtom::String(v))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let tag = seq
+            .next_element::<String>()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+
+        let atom = match tag.as_str() {
+            "uuid" | "named-uuid" => tagged_atom(&tag, &mut seq)?,
+            _ => return Err(de::Error::unknown_variant(&tag, &["uuid", "named-uuid"])),
+        };
+
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(3, &self));
+        }
+
+        Ok(atom)
+    }
+}
+
+/// Read the payload of a `["uuid", ...]` or `["named-uuid", ...]` array
+fn tagged_atom<'de, A>(tag: &str, seq: &mut A) -> Result<OvsdbAtom, A::Error>
+where
+    A: SeqAccess<'de>,
+{
+    let payload = seq
+        .next_element::<String>()?
+        .ok_or_else(|| de::Error::invalid_length(1, &"a tagged array with two elements"))?;
+
+    if tag == "named-uuid" {
+        return Ok(OvsdbAtom::NamedUuid(payload));
+    }
+
+    Uuid::parse_str(&payload)
+        .map(OvsdbAtom::Uuid)
+        .map_err(de::Error::custom)
+}
+
 /// Extension trait for OvsdbSerializable to handle JSON conversion
 pub trait OvsdbSerializableExt: OvsdbSerializable {
+    /// Convert to JSON, `None` if the value cannot be sent to OVSDB
     fn to_ovsdb_json(&self) -> Option<serde_json::Value> {
-        serde_json::to_value(self.to_ovsdb()).ok()
+        self.try_to_ovsdb_json().ok()
+    }
+
+    /// Convert to JSON, returning an error describing why the value cannot
+    /// be sent to OVSDB
+    fn try_to_ovsdb_json(&self) -> Result<serde_json::Value, OvsdbConversionError> {
+        let value = self.try_to_ovsdb()?;
+        ovsdb_value_to_json(&value).map_err(|e| {
+            OvsdbConversionError::new("an OVSDB value", json::describe(&value))
+                .with_reason(e.to_string())
+        })
     }
 
     fn from_ovsdb_json(json: &serde_json::Value) -> Option<Self> {
         // Convert JSON to OvsdbValue
-        let value = json_to_ovsdb_value(json)?;
+        let value = json_to_ovsdb_value(json).ok()?;
         Self::from_ovsdb(&value)
     }
+
+    fn try_from_ovsdb_json(json: &serde_json::Value) -> Result<Self, OvsdbConversionError> {
+        let value = json_to_ovsdb_value(json).map_err(|e| {
+            OvsdbConversionError::new("an OVSDB value", json.clone()).with_reason(e.to_string())
+        })?;
+        Self::try_from_ovsdb(&value)
+    }
+
+    /// Convert from JSON, failing in strict mode and returning `None` in
+    /// lenient mode when the value can't be decoded
+    fn from_ovsdb_json_with_mode(
+        json: &serde_json::Value,
+        mode: DecodeMode,
+    ) -> Result<Option<Self>, OvsdbConversionError> {
+        match Self::try_from_ovsdb_json(json) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if mode.is_strict() => Err(e),
+            Err(_) => Ok(None),
+        }
+    }
 }
 
 // Implement the extension trait for all types that implement OvsdbSerializable
@@ -266,88 +1001,3 @@ pub fn extract_uuid(value: &serde_json::Value) -> Option<Uuid> {
     }
     None
 }
-
-/// Convert a JSON value to an OvsdbValue
-fn json_to_ovsdb_value(json: &serde_json::Value) -> Option<OvsdbValue> {
-    match json {
-        serde_json::Value::String(s) => Some(OvsdbValue::Atom(OvsdbAtom::String(s.clone()))),
-        serde_json::Value::Number(n) => {
-            if let Some(i) = n.as_i64() {
-                Some(OvsdbValue::Atom(OvsdbAtom::Integer(i)))
-            } else {
-                n.as_f64().map(|f| OvsdbValue::Atom(OvsdbAtom::Real(f)))
-            }
-        }
-        serde_json::Value::Bool(b) => Some(OvsdbValue::Atom(OvsdbAtom::Boolean(*b))),
-        serde_json::Value::Array(arr) => {
-            if arr.len() == 2 {
-                if let serde_json::Value::String(tag) = &arr[0] {
-                    match tag.as_str() {
-                        "uuid" => {
-                            if let serde_json::Value::String(uuid_str) = &arr[1] {
-                                if let Ok(uuid) = Uuid::parse_str(uuid_str) {
-                                    return Some(OvsdbValue::Atom(OvsdbAtom::Uuid(uuid)));
-                                }
-                            }
-                        }
-                        "named-uuid" => {
-                            if let serde_json::Value::String(name) = &arr[1] {
-                                return Some(OvsdbValue::Atom(OvsdbAtom::NamedUuid(name.clone())));
-                            }
-                        }
-                        "set" => {
-                            if let serde_json::Value::Array(elements) = &arr[1] {
-                                let mut atoms = Vec::with_capacity(elements.len());
-                                for elem in elements {
-                                    if let Some(OvsdbValue::Atom(atom)) = json_to_ovsdb_value(elem)
-                                    {
-                                        atoms.push(atom);
-                                    } else {
-                                        return None;
-                                    }
-                                }
-                                return Some(OvsdbValue::Set(atoms));
-                            }
-                        }
-                        "map" => {
-                            if let serde_json::Value::Array(pairs) = &arr[1] {
-                                let mut map_pairs = Vec::with_capacity(pairs.len());
-                                for pair in pairs {
-                                    if let serde_json::Value::Array(kv) = pair {
-                                        if kv.len() == 2 {
-                                            if let (
-                                                Some(OvsdbValue::Atom(key)),
-                                                Some(OvsdbValue::Atom(value)),
-                                            ) = (
-                                                json_to_ovsdb_value(&kv[0]),
-                                                json_to_ovsdb_value(&kv[1]),
-                                            ) {
-                                                map_pairs.push((key, value));
-                                                continue;
-                                            }
-                                        }
-                                    }
-                                    return None;
-                                }
-                                return Some(OvsdbValue::Map(map_pairs));
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
-
-            // Empty array means empty set
-            if arr.is_empty() {
-                return Some(OvsdbValue::Set(vec![]));
-            }
-
-            None
-        }
-        serde_json::Value::Null => {
-            // Null is represented as an empty set
-            Some(OvsdbValue::Set(vec![]))
-        }
-        _ => None,
-    }
-}
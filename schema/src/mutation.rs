@@ -0,0 +1,133 @@
+use crate::{diff_values, ColumnDiff, OvsdbValue};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Mutator of a `mutate` operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Mutator {
+    #[serde(rename = "+=")]
+    Add,
+    #[serde(rename = "-=")]
+    Subtract,
+    #[serde(rename = "*=")]
+    Multiply,
+    #[serde(rename = "/=")]
+    Divide,
+    #[serde(rename = "%=")]
+    Modulo,
+    #[serde(rename = "insert")]
+    Insert,
+    #[serde(rename = "delete")]
+    Delete,
+}
+
+/// A single mutation of a `mutate` operation, encoded as `[column, mutator, value]`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mutation {
+    pub column: String,
+    pub mutator: Mutator,
+    pub value: OvsdbValue,
+}
+
+impl Mutation {
+    pub fn new(column: impl Into<String>, mutator: Mutator, value: OvsdbValue) -> Self {
+        Mutation {
+            column: column.into(),
+            mutator,
+            value,
+        }
+    }
+}
+
+impl Serialize for Mutation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (&self.column, &self.mutator, &self.value).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Mutation {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (column, mutator, value) = <(String, Mutator, OvsdbValue)>::deserialize(deserializer)?;
+        Ok(Mutation {
+            column,
+            mutator,
+            value,
+        })
+    }
+}
+
+impl ColumnDiff {
+    /// Express the change as `insert` and `delete` mutations of the column
+    ///
+    /// Returns `None` for [`ColumnDiff::Replace`], which can only be applied
+    /// with an `update` operation. Changed map entries are deleted by key and
+    /// inserted again, since `insert` does not replace existing keys.
+    pub fn to_mutations(&self, column: &str) -> Option<Vec<Mutation>> {
+        let mut mutations = Vec::new();
+
+        match self {
+            ColumnDiff::Replace { .. } => return None,
+            ColumnDiff::Set { inserted, removed } => {
+                if !removed.is_empty() {
+                    mutations.push(Mutation::new(
+                        column,
+                        Mutator::Delete,
+                        OvsdbValue::Set(removed.clone()),
+                    ));
+                }
+                if !inserted.is_empty() {
+                    mutations.push(Mutation::new(
+                        column,
+                        Mutator::Insert,
+                        OvsdbValue::Set(inserted.clone()),
+                    ));
+                }
+            }
+            ColumnDiff::Map {
+                inserted,
+                removed,
+                updated,
+            } => {
+                let deleted_keys: Vec<_> = removed
+                    .iter()
+                    .chain(updated)
+                    .map(|(key, _)| key.clone())
+                    .collect();
+                if !deleted_keys.is_empty() {
+                    mutations.push(Mutation::new(
+                        column,
+                        Mutator::Delete,
+                        OvsdbValue::Set(deleted_keys),
+                    ));
+                }
+
+                let inserted: Vec<_> = inserted.iter().chain(updated).cloned().collect();
+                if !inserted.is_empty() {
+                    mutations.push(Mutation::new(
+                        column,
+                        Mutator::Insert,
+                        OvsdbValue::Map(inserted),
+                    ));
+                }
+            }
+        }
+
+        Some(mutations)
+    }
+}
+
+/// Compute the minimal mutations turning `old` into `new` for a set or map column
+///
+/// Returns an empty list if the values are equal and `None` if the change
+/// can't be expressed as mutations, in which case an `update` is needed.
+pub fn diff_mutations(column: &str, old: &OvsdbValue, new: &OvsdbValue) -> Option<Vec<Mutation>> {
+    match diff_values(Some(old), Some(new)) {
+        Some(diff) => diff.to_mutations(column),
+        None => Some(Vec::new()),
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{OvsdbAtom, OvsdbConversionError, OvsdbSerializable, OvsdbValue};
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+/// Implement OvsdbSerializable for types stored as strings in OVSDB, using
+/// their `Display` and `FromStr` implementations.
+macro_rules! impl_ovsdb_string_parse {
+    ($($ty:ty => $expected:literal),* $(,)?) => {
+        $(
+            impl OvsdbSerializable for $ty {
+                fn to_ovsdb(&self) -> OvsdbValue {
+                    OvsdbValue::Atom(OvsdbAtom::String(self.to_string()))
+                }
+
+                fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+                    Self::try_from_ovsdb(value).ok()
+                }
+
+                fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+                    match value {
+                        OvsdbValue::Atom(OvsdbAtom::String(s)) => s.parse().map_err(|e| {
+                            OvsdbConversionError::mismatch($expected, value)
+                                .with_reason(format!("{}", e))
+                        }),
+                        _ => Err(OvsdbConversionError::mismatch($expected, value)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_ovsdb_string_parse!(
+    IpAddr => "IP address",
+    Ipv4Addr => "IPv4 address",
+    Ipv6Addr => "IPv6 address",
+    IpNet => "IP network",
+    Ipv4Net => "IPv4 network",
+    Ipv6Net => "IPv6 network",
+    MacAddr => "MAC address",
+);
+
+/// An Ethernet MAC address, stored in OVSDB as `xx:xx:xx:xx:xx:xx`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct MacAddr(pub [u8; 6]);
+
+impl MacAddr {
+    pub const fn new(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8) -> Self {
+        MacAddr([a, b, c, d, e, f])
+    }
+
+    pub const fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+
+    /// Returns true for multicast (and broadcast) addresses
+    pub const fn is_multicast(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+
+    /// Returns true for locally administered addresses
+    pub const fn is_local(&self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+}
+
+impl From<[u8; 6]> for MacAddr {
+    fn from(octets: [u8; 6]) -> Self {
+        MacAddr(octets)
+    }
+}
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            a, b, c, d, e, g
+        )
+    }
+}
+
+/// Error returned when parsing an invalid MAC address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacAddrParseError(String);
+
+impl fmt::Display for MacAddrParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid MAC address syntax: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for MacAddrParseError {}
+
+impl FromStr for MacAddr {
+    type Err = MacAddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || MacAddrParseError(s.to_owned());
+
+        let mut octets = [0u8; 6];
+        let mut parts = s.split(':');
+
+        for octet in &mut octets {
+            let part = parts.next().ok_or_else(error)?;
+            if part.len() != 2 || !part.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(error());
+            }
+            *octet = u8::from_str_radix(part, 16).map_err(|_| error())?;
+        }
+
+        if parts.next().is_some() {
+            return Err(error());
+        }
+
+        Ok(MacAddr(octets))
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{OvsdbConversionError, OvsdbSerializable, OvsdbValue};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use uuid::Uuid;
+
+/// Define a UUID newtype which carries the referenced table type
+macro_rules! reference_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        pub struct $name<T> {
+            uuid: Uuid,
+            table: PhantomData<fn() -> T>,
+        }
+
+        impl<T> $name<T> {
+            /// Create a reference to the row with the given UUID
+            pub const fn new(uuid: Uuid) -> Self {
+                $name {
+                    uuid,
+                    table: PhantomData,
+                }
+            }
+
+            /// Returns the UUID of the referenced row
+            pub const fn uuid(&self) -> Uuid {
+                self.uuid
+            }
+
+            /// Look up the referenced row in rows keyed by their UUID
+            pub fn resolve<'a>(&self, rows: &'a HashMap<Uuid, T>) -> Option<&'a T> {
+                rows.get(&self.uuid)
+            }
+        }
+
+        impl<T> Clone for $name<T> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<T> Copy for $name<T> {}
+
+        impl<T> PartialEq for $name<T> {
+            fn eq(&self, other: &Self) -> bool {
+                self.uuid == other.uuid
+            }
+        }
+
+        impl<T> Eq for $name<T> {}
+
+        impl<T> PartialOrd for $name<T> {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<T> Ord for $name<T> {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                self.uuid.cmp(&other.uuid)
+            }
+        }
+
+        impl<T> Hash for $name<T> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.uuid.hash(state)
+            }
+        }
+
+        impl<T> fmt::Debug for $name<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(
+                    f,
+                    "{}<{}>({})",
+                    stringify!($name),
+                    std::any::type_name::<T>(),
+                    self.uuid
+                )
+            }
+        }
+
+        impl<T> fmt::Display for $name<T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.uuid.fmt(f)
+            }
+        }
+
+        impl<T> From<Uuid> for $name<T> {
+            fn from(uuid: Uuid) -> Self {
+                Self::new(uuid)
+            }
+        }
+
+        impl<T> From<$name<T>> for Uuid {
+            fn from(reference: $name<T>) -> Self {
+                reference.uuid
+            }
+        }
+
+        impl<T> OvsdbSerializable for $name<T> {
+            fn to_ovsdb(&self) -> OvsdbValue {
+                self.uuid.to_ovsdb()
+            }
+
+            fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+                Self::try_from_ovsdb(value).ok()
+            }
+
+            fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+                Uuid::try_from_ovsdb(value).map(Self::new)
+            }
+        }
+    };
+}
+
+reference_type!(
+    /// A strong reference to a row of the table represented by `T`
+    ///
+    /// This is a UUID which carries the type of the referenced table, so that
+    /// a reference to one table cannot be assigned to a column referencing
+    /// another table.
+    Ref
+);
+
+reference_type!(
+    /// A weak reference to a row of the table represented by `T`
+    ///
+    /// Weak references are removed by the server when the referenced row is
+    /// deleted, instead of preventing the deletion.
+    WeakRef
+);
+
+impl<T> From<Ref<T>> for WeakRef<T> {
+    fn from(reference: Ref<T>) -> Self {
+        WeakRef::new(reference.uuid())
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{DecodeMode, OvsdbConversionError};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Trait implemented by structs mapped to an OVSDB table
+///
+/// This is implemented by `#[ovsdb_object]` and `#[derive(OVSDB)]` from
+/// `ovsdb-derive`, allowing generic code over any table type.
+pub trait OvsdbTable: Sized {
+    /// Name of the OVSDB table
+    const TABLE: &'static str;
+
+    /// Columns mapped by the struct, excluding `_uuid` and `_version`
+    const COLUMNS: &'static [&'static str];
+
+    /// UUID of the row, if known
+    fn uuid(&self) -> Option<Uuid>;
+
+    /// Version of the row, if known
+    fn version(&self) -> Option<Uuid>;
+
+    /// Convert to a HashMap for OVSDB serialization, leaving out the columns
+    /// which cannot be sent to OVSDB
+    fn to_map(&self) -> HashMap<String, serde_json::Value>;
+
+    /// Convert to a HashMap for OVSDB serialization, returning an error for
+    /// the first column which cannot be sent to OVSDB rather than leave it
+    /// out, as inserting or updating a row must not drop columns
+    fn try_to_map(&self) -> Result<HashMap<String, serde_json::Value>, OvsdbConversionError> {
+        Ok(self.to_map())
+    }
+
+    /// Create from a HashMap received from OVSDB
+    fn from_map(map: &HashMap<String, serde_json::Value>) -> Result<Self, OvsdbConversionError> {
+        Self::from_map_with_mode(map, DecodeMode::Strict)
+    }
+
+    /// Create from a HashMap received from OVSDB using the given decode mode
+    fn from_map_with_mode(
+        map: &HashMap<String, serde_json::Value>,
+        mode: DecodeMode,
+    ) -> Result<Self, OvsdbConversionError>;
+}
@@ -0,0 +1,261 @@
+//! Textual syntax used by `ovs-vsctl` and `ovsdb-client` for OVSDB values
+//!
+//! Atoms are written bare (`42`, `true`, `br-int`), strings are quoted when
+//! they could be mistaken for another type (`"eth0"`, `"true"`), sets are
+//! written as `[a, b]` and maps as `{k=v, k2=v2}`.
+
+use crate::{OvsdbAtom, OvsdbParseError, OvsdbValue};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+impl fmt::Display for OvsdbAtom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OvsdbAtom::String(s) if string_needs_quotes(s) => {
+                // NOTE(mnaser): OVS quotes strings using JSON escaping, so we can
+                //               reuse serde_json for both directions.
+                write!(f, "{}", serde_json::Value::String(s.clone()))
+            }
+            OvsdbAtom::String(s) => f.write_str(s),
+            OvsdbAtom::Integer(i) => write!(f, "{}", i),
+            OvsdbAtom::Real(r) => write!(f, "{:?}", r),
+            OvsdbAtom::Boolean(b) => write!(f, "{}", b),
+            OvsdbAtom::Uuid(uuid) => write!(f, "{}", uuid),
+            OvsdbAtom::NamedUuid(name) => write!(f, "@{}", name),
+        }
+    }
+}
+
+impl fmt::Display for OvsdbValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OvsdbValue::Atom(atom) => write!(f, "{}", atom),
+            OvsdbValue::Set(set) => {
+                f.write_str("[")?;
+                for (i, atom) in set.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}", atom)?;
+                }
+                f.write_str("]")
+            }
+            OvsdbValue::Map(map) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in map.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}={}", key, value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+impl FromStr for OvsdbAtom {
+    type Err = OvsdbParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let atom = parser.atom()?;
+        parser.finish()?;
+        Ok(atom)
+    }
+}
+
+impl FromStr for OvsdbValue {
+    type Err = OvsdbParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let value = parser.value()?;
+        parser.finish()?;
+        Ok(value)
+    }
+}
+
+/// Mirrors `string_needs_quotes()` from OVS so bare strings stay unambiguous
+fn string_needs_quotes(s: &str) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return true,
+    }
+
+    if !chars.all(|c| c.is_ascii_alphabetic() || c == '_' || c == '-' || c == '.') {
+        return true;
+    }
+
+    s == "true" || s == "false" || parse_uuid(s).is_some()
+}
+
+/// Only accept the hyphenated form, which is what OVS prints
+fn parse_uuid(s: &str) -> Option<Uuid> {
+    if s.len() != 36 {
+        return None;
+    }
+    Uuid::parse_str(s).ok()
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, ',' | '=' | '[' | ']' | '{' | '}' | '"')
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser { input, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.rest().chars().next()
+    }
+
+    fn error(&self, reason: impl Into<String>) -> OvsdbParseError {
+        OvsdbParseError::new(self.position, reason)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), OvsdbParseError> {
+        if self.peek() != Some(expected) {
+            return Err(self.error(format!("expected '{}'", expected)));
+        }
+        self.position += expected.len_utf8();
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), OvsdbParseError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.error("unexpected trailing input")),
+        }
+    }
+
+    fn value(&mut self) -> Result<OvsdbValue, OvsdbParseError> {
+        match self.peek() {
+            Some('[') => {
+                let atoms = self.list('[', ']', |parser| parser.atom())?;
+                Ok(OvsdbValue::Set(atoms))
+            }
+            Some('{') => {
+                let pairs = self.list('{', '}', |parser| {
+                    let key = parser.atom()?;
+                    parser.expect('=')?;
+                    Ok((key, parser.atom()?))
+                })?;
+                Ok(OvsdbValue::Map(pairs))
+            }
+            _ => self.atom().map(OvsdbValue::Atom),
+        }
+    }
+
+    fn list<T>(
+        &mut self,
+        open: char,
+        close: char,
+        mut element: impl FnMut(&mut Self) -> Result<T, OvsdbParseError>,
+    ) -> Result<Vec<T>, OvsdbParseError> {
+        self.expect(open)?;
+
+        let mut elements = Vec::new();
+        if self.peek() == Some(close) {
+            self.position += close.len_utf8();
+            return Ok(elements);
+        }
+
+        loop {
+            elements.push(element(self)?);
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(c) if c == close => {
+                    self.position += close.len_utf8();
+                    return Ok(elements);
+                }
+                _ => return Err(self.error(format!("expected ',' or '{}'", close))),
+            }
+        }
+    }
+
+    fn atom(&mut self) -> Result<OvsdbAtom, OvsdbParseError> {
+        match self.peek() {
+            None => Err(self.error("expected an atom")),
+            Some('"') => self.quoted_string(),
+            Some(_) => self.bare_atom(),
+        }
+    }
+
+    fn quoted_string(&mut self) -> Result<OvsdbAtom, OvsdbParseError> {
+        let rest = self.rest();
+
+        let mut escaped = false;
+        let end = rest
+            .char_indices()
+            .skip(1)
+            .find(|&(_, c)| {
+                let closing = c == '"' && !escaped;
+                escaped = c == '\\' && !escaped;
+                closing
+            })
+            .map(|(i, _)| i + 1)
+            .ok_or_else(|| self.error("unterminated string"))?;
+
+        let s = serde_json::from_str::<String>(&rest[..end])
+            .map_err(|e| self.error(format!("invalid string: {}", e)))?;
+        self.position += end;
+
+        Ok(OvsdbAtom::String(s))
+    }
+
+    fn bare_atom(&mut self) -> Result<OvsdbAtom, OvsdbParseError> {
+        let rest = self.rest();
+        let end = rest.find(is_delimiter).unwrap_or(rest.len());
+        if end == 0 {
+            return Err(self.error("expected an atom"));
+        }
+
+        let token = &rest[..end];
+        self.position += end;
+
+        if let Some(name) = token.strip_prefix('@') {
+            return Ok(OvsdbAtom::NamedUuid(name.to_owned()));
+        }
+
+        Ok(match token {
+            "true" => OvsdbAtom::Boolean(true),
+            "false" => OvsdbAtom::Boolean(false),
+            _ => {
+                if let Ok(i) = token.parse::<i64>() {
+                    OvsdbAtom::Integer(i)
+                } else if let Some(uuid) = parse_uuid(token) {
+                    OvsdbAtom::Uuid(uuid)
+                } else if token.starts_with(|c: char| c.is_ascii_digit() || "+-.".contains(c)) {
+                    token
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(|r| OvsdbAtom::real(r).ok())
+                        .ok_or_else(|| {
+                            OvsdbParseError::new(self.position - end, "invalid number")
+                        })?
+                } else {
+                    OvsdbAtom::String(token.to_owned())
+                }
+            }
+        })
+    }
+}
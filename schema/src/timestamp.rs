@@ -0,0 +1,58 @@
+use crate::{OvsdbAtom, OvsdbConversionError, OvsdbSerializable, OvsdbValue};
+use chrono::{DateTime, Utc};
+
+/// Timestamps are stored as integer milliseconds since the epoch, which is
+/// the unit used by columns such as `NB_Global.nb_cfg_timestamp`.
+impl OvsdbSerializable for DateTime<Utc> {
+    fn to_ovsdb(&self) -> OvsdbValue {
+        OvsdbValue::Atom(OvsdbAtom::Integer(self.timestamp_millis()))
+    }
+
+    fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+        let millis = i64::try_from_ovsdb(value)?;
+        DateTime::from_timestamp_millis(millis).ok_or_else(|| {
+            OvsdbConversionError::mismatch("timestamp in milliseconds", value)
+                .with_reason("out of range for a timestamp")
+        })
+    }
+}
+
+/// A timestamp stored as integer seconds since the epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EpochSeconds(pub DateTime<Utc>);
+
+impl From<DateTime<Utc>> for EpochSeconds {
+    fn from(timestamp: DateTime<Utc>) -> Self {
+        EpochSeconds(timestamp)
+    }
+}
+
+impl From<EpochSeconds> for DateTime<Utc> {
+    fn from(timestamp: EpochSeconds) -> Self {
+        timestamp.0
+    }
+}
+
+impl OvsdbSerializable for EpochSeconds {
+    fn to_ovsdb(&self) -> OvsdbValue {
+        OvsdbValue::Atom(OvsdbAtom::Integer(self.0.timestamp()))
+    }
+
+    fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        Self::try_from_ovsdb(value).ok()
+    }
+
+    fn try_from_ovsdb(value: &OvsdbValue) -> Result<Self, OvsdbConversionError> {
+        let seconds = i64::try_from_ovsdb(value)?;
+        DateTime::from_timestamp(seconds, 0)
+            .map(EpochSeconds)
+            .ok_or_else(|| {
+                OvsdbConversionError::mismatch("timestamp in seconds", value)
+                    .with_reason("out of range for a timestamp")
+            })
+    }
+}
@@ -0,0 +1,137 @@
+use ovsdb_schema::{OvsdbMap, OvsdbSerializable, OvsdbSet, OvsdbValue};
+use serde_json::json;
+
+fn value(json: serde_json::Value) -> OvsdbValue {
+    serde_json::from_value(json).unwrap()
+}
+
+#[test]
+fn test_set_preserves_insertion_order() {
+    let mut set: OvsdbSet<String> = OvsdbSet::new();
+    assert_eq!(set.insert("b".to_string()), Ok(true));
+    assert_eq!(set.insert("a".to_string()), Ok(true));
+    assert_eq!(set.insert("b".to_string()), Ok(false));
+
+    assert_eq!(set.as_slice(), ["b".to_string(), "a".to_string()]);
+    assert_eq!(
+        serde_json::to_value(set.to_ovsdb()).unwrap(),
+        json!(["set", ["b", "a"]])
+    );
+
+    assert_eq!(set.remove(&"b".to_string()), Ok(true));
+    assert_eq!(serde_json::to_value(set.to_ovsdb()).unwrap(), json!("a"));
+}
+
+#[test]
+fn test_set_cardinality() {
+    let mut set: OvsdbSet<i64, 0, 1> = OvsdbSet::new();
+    assert_eq!(set.insert(1), Ok(true));
+
+    let err = set.insert(2).unwrap_err();
+    assert_eq!(err.max, Some(1));
+    assert_eq!(err.found, 2);
+
+    assert!(OvsdbSet::<i64, 0, 1>::try_from_ovsdb(&value(json!(["set", [1, 2]]))).is_err());
+    assert!(OvsdbSet::<i64, 1, 2>::try_from_ovsdb(&value(json!([]))).is_err());
+    assert_eq!(
+        OvsdbSet::<i64, 1, 2>::try_from_ovsdb(&value(json!(5)))
+            .unwrap()
+            .into_vec(),
+        vec![5]
+    );
+}
+
+#[test]
+fn test_set_min() {
+    let mut set = OvsdbSet::<i64, 1, 2>::try_from(vec![1, 2]).unwrap();
+    assert_eq!(set.remove(&3), Ok(false));
+    assert_eq!(set.remove(&1), Ok(true));
+
+    let err = set.remove(&2).unwrap_err();
+    assert_eq!((err.min, err.found), (1, 0));
+    assert_eq!(set.as_slice(), [2]);
+
+    assert!(OvsdbSet::<i64, 1, 2>::try_from(vec![]).is_err());
+    assert!(OvsdbSet::<i64, 1, 2>::default().try_to_ovsdb().is_err());
+    assert!(set.try_to_ovsdb().is_ok());
+}
+
+#[test]
+fn test_set_equality_ignores_order() {
+    let a = OvsdbSet::<i64>::try_from(vec![1, 2, 3]).unwrap();
+    let b = OvsdbSet::<i64>::try_from(vec![3, 1, 2]).unwrap();
+    assert_eq!(a, b);
+    assert_ne!(a, OvsdbSet::try_from(vec![1, 2]).unwrap());
+    assert_ne!(a, OvsdbSet::try_from(vec![1, 2, 4]).unwrap());
+}
+
+#[test]
+fn test_set_rejects_duplicates_from_ovsdb() {
+    let err = OvsdbSet::<i64>::try_from_ovsdb(&value(json!(["set", [1, 2, 1]]))).unwrap_err();
+    assert_eq!(err.reason.as_deref(), Some("duplicate element"));
+    assert_eq!(
+        err.to_string(),
+        "expected set, found [\"set\",[1,2,1]] (duplicate element) at [2]"
+    );
+}
+
+#[test]
+fn test_map_operations() {
+    let mut map: OvsdbMap<String, String> = OvsdbMap::new();
+    assert_eq!(map.insert("z".to_string(), "1".to_string()), Ok(None));
+    assert_eq!(map.insert("a".to_string(), "2".to_string()), Ok(None));
+    assert_eq!(
+        map.insert("z".to_string(), "3".to_string()),
+        Ok(Some("1".to_string()))
+    );
+
+    assert_eq!(map.get(&"z".to_string()), Some(&"3".to_string()));
+    assert_eq!(
+        serde_json::to_value(map.to_ovsdb()).unwrap(),
+        json!(["map", [["z", "3"], ["a", "2"]]])
+    );
+
+    assert_eq!(map.remove(&"z".to_string()), Ok(Some("3".to_string())));
+    assert_eq!(map.len(), 1);
+}
+
+#[test]
+fn test_map_from_ovsdb() {
+    let map = OvsdbMap::<String, i64>::try_from_ovsdb(&value(json!(["map", [["a", 1], ["b", 2]]])))
+        .unwrap();
+    assert_eq!(map.keys().collect::<Vec<_>>(), ["a", "b"]);
+
+    let err = OvsdbMap::<String, i64>::try_from_ovsdb(&value(json!(["map", [["a", 1], ["a", 2]]])))
+        .unwrap_err();
+    assert_eq!(err.reason.as_deref(), Some("duplicate key"));
+
+    assert!(OvsdbMap::<String, i64, 0, 1>::try_from_ovsdb(&value(json!([
+        "map",
+        [["a", 1], ["b", 2]]
+    ])))
+    .is_err());
+}
+
+#[test]
+fn test_map_min() {
+    let mut map = OvsdbMap::<String, i64, 1>::try_from(vec![("a".to_string(), 1)]).unwrap();
+    assert!(map.remove(&"a".to_string()).is_err());
+    assert_eq!(map.len(), 1);
+
+    assert!(OvsdbMap::<String, i64, 1>::default()
+        .try_to_ovsdb()
+        .is_err());
+}
+
+#[test]
+fn test_map_equality_ignores_order() {
+    let a = OvsdbMap::<String, i64>::try_from(vec![("a".to_string(), 1), ("b".to_string(), 2)])
+        .unwrap();
+    let b = OvsdbMap::<String, i64>::try_from(vec![("b".to_string(), 2), ("a".to_string(), 1)])
+        .unwrap();
+    assert_eq!(a, b);
+    assert_ne!(
+        a,
+        OvsdbMap::try_from(vec![("a".to_string(), 1), ("b".to_string(), 3)]).unwrap()
+    );
+}
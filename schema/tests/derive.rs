@@ -0,0 +1,543 @@
+use ovsdb_derive::{ovsdb_object, OVSDB};
+use ovsdb_schema::{DecodeMode, OvsdbTable, PathSegment};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[ovsdb_object(table = "ACL")]
+#[derive(Debug, PartialEq)]
+pub struct Acl {
+    pub action: Option<String>,
+    #[ovsdb(column = "match")]
+    pub match_: Option<String>,
+    pub r#type: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, OVSDB)]
+#[ovsdb(table = "Logical_Switch_Port")]
+pub struct LogicalSwitchPort {
+    pub name: Option<String>,
+    #[ovsdb(column = "dhcpv4_options")]
+    pub dhcp: Option<Vec<Uuid>>,
+
+    pub _uuid: Option<Uuid>,
+    pub _version: Option<Uuid>,
+}
+
+#[ovsdb_object(table = "Logical_Switch")]
+pub struct LogicalSwitch {
+    pub name: Option<String>,
+}
+
+#[test]
+fn test_column_rename() {
+    let mut acl = Acl::new();
+    acl.action = Some("drop".to_string());
+    acl.match_ = Some("ip4".to_string());
+    acl.r#type = Some("x".to_string());
+
+    let map = acl.to_map();
+    assert_eq!(map["match"], json!("ip4"));
+    assert_eq!(map["type"], json!("x"));
+    assert!(!map.contains_key("match_"));
+
+    assert_eq!(Acl::from_map(&map).unwrap(), acl);
+}
+
+#[test]
+fn test_column_rename_with_derive() {
+    let uuid = Uuid::parse_str("601c7161-97df-42ae-b377-3baf21830d8f").unwrap();
+    let map: HashMap<String, Value> = serde_json::from_value(json!({
+        "name": "lsp1",
+        "dhcpv4_options": ["uuid", uuid.to_string()],
+    }))
+    .unwrap();
+
+    let lsp = LogicalSwitchPort::from_map(&map).unwrap();
+    assert_eq!(lsp.dhcp, Some(vec![uuid]));
+    assert_eq!(
+        lsp.to_map()["dhcpv4_options"],
+        json!(["uuid", uuid.to_string()])
+    );
+}
+
+#[test]
+fn test_table_name() {
+    assert_eq!(Acl::TABLE, "ACL");
+    assert_eq!(Acl::table_name(), "ACL");
+    assert_eq!(LogicalSwitchPort::table_name(), "Logical_Switch_Port");
+}
+
+fn describe<T: OvsdbTable>(row: &T) -> String {
+    format!("{} {:?} {}", T::TABLE, row.uuid(), T::COLUMNS.join(","))
+}
+
+#[test]
+fn test_table_trait() {
+    let uuid = Uuid::parse_str("601c7161-97df-42ae-b377-3baf21830d8f").unwrap();
+    let mut acl = Acl::new();
+    acl._uuid = Some(uuid);
+
+    assert_eq!(
+        describe(&acl),
+        format!("ACL Some({}) action,match,type", uuid)
+    );
+    assert_eq!(
+        <LogicalSwitchPort as OvsdbTable>::COLUMNS,
+        &["name", "dhcpv4_options"]
+    );
+
+    let map = OvsdbTable::to_map(&acl);
+    assert_eq!(<Acl as OvsdbTable>::from_map(&map).unwrap().action, None);
+}
+
+#[test]
+fn test_multiple_tables_in_one_module() {
+    assert_eq!(Acl::TABLE, "ACL");
+    assert_eq!(LogicalSwitch::TABLE, "Logical_Switch");
+    assert_eq!(LogicalSwitchPort::TABLE, "Logical_Switch_Port");
+}
+
+mod no_imports {
+    // The generated code must not rely on anything being in scope
+    #[ovsdb_derive::ovsdb_object]
+    pub struct NbGlobal {
+        pub name: Option<String>,
+    }
+
+    #[ovsdb_derive::ovsdb_object]
+    pub struct SbGlobal {
+        pub name: Option<String>,
+    }
+}
+
+#[test]
+fn test_generated_code_without_imports() {
+    let mut nb_global = no_imports::NbGlobal::new();
+    nb_global.name = Some("global".to_string());
+    let json = serde_json::to_value(&nb_global).unwrap();
+    assert_eq!(json, json!({"name": "global"}));
+    assert_eq!(no_imports::SbGlobal::new().name, None);
+}
+
+#[ovsdb_object(table = "Logical_Router")]
+#[derive(Debug)]
+pub struct LogicalRouter {
+    pub name: Option<String>,
+    #[ovsdb(default = Some(true))]
+    pub enabled: Option<bool>,
+    #[ovsdb(default = 1442)]
+    pub mtu: i64,
+    #[ovsdb(skip)]
+    pub port_count: usize,
+    #[ovsdb(skip, default = std::cell::Cell::new(7))]
+    pub cache: std::cell::Cell<u32>,
+}
+
+#[test]
+fn test_skip_and_default() {
+    assert_eq!(
+        <LogicalRouter as OvsdbTable>::COLUMNS,
+        &["name", "enabled", "mtu"]
+    );
+
+    let mut router = LogicalRouter::new();
+    assert_eq!(router.enabled, Some(true));
+    assert_eq!(router.mtu, 1442);
+    assert_eq!(router.cache.get(), 7);
+
+    router.port_count = 3;
+    let map = router.to_map();
+    assert!(!map.contains_key("port_count"));
+    assert!(!map.contains_key("cache"));
+
+    // Missing columns take the default, present columns override it
+    let map: HashMap<String, Value> =
+        serde_json::from_value(json!({"name": "lr0", "mtu": 9000})).unwrap();
+    let router = LogicalRouter::from_map(&map).unwrap();
+    assert_eq!(router.enabled, Some(true));
+    assert_eq!(router.mtu, 9000);
+    assert_eq!(router.port_count, 0);
+}
+
+#[ovsdb_object(table = "Logical_Switch_Port")]
+#[derive(Debug)]
+pub struct RequiredPort {
+    pub name: String,
+    pub tag: Option<i64>,
+    pub addresses: Vec<String>,
+    pub options: HashMap<String, String>,
+    #[ovsdb(default)]
+    pub up: bool,
+}
+
+#[test]
+fn test_required_columns() {
+    let map: HashMap<String, Value> = serde_json::from_value(json!({"name": "lsp1"})).unwrap();
+    let port = RequiredPort::from_map(&map).unwrap();
+    assert_eq!(port.name, "lsp1");
+    assert_eq!(port.tag, None);
+    assert!(port.addresses.is_empty());
+    assert!(port.options.is_empty());
+    assert!(!port.up);
+
+    let map: HashMap<String, Value> = serde_json::from_value(json!({"tag": 10})).unwrap();
+    let err = RequiredPort::from_map(&map).unwrap_err();
+    assert_eq!(err.path, [PathSegment::Column("name".to_string())]);
+    assert_eq!(
+        err.to_string(),
+        "expected a value, found null (missing required column) at name"
+    );
+
+    let port = RequiredPort::from_map_with_mode(&map, DecodeMode::Lenient).unwrap();
+    assert_eq!(port.name, "");
+    assert_eq!(port.tag, Some(10));
+}
+
+#[test]
+fn test_column_constants() {
+    assert_eq!(Acl::COLUMNS, &["action", "match", "type"]);
+    assert_eq!(acl_columns::MATCH, "match");
+    assert_eq!(acl_columns::TYPE, "type");
+    assert_eq!(
+        logical_switch_port_columns::DHCPV4_OPTIONS,
+        "dhcpv4_options"
+    );
+    assert_eq!(
+        LogicalRouter::COLUMNS,
+        &[
+            logical_router_columns::NAME,
+            logical_router_columns::ENABLED,
+            logical_router_columns::MTU,
+        ]
+    );
+}
+
+#[test]
+fn test_update_tracks_changed_columns() {
+    let mut update = AclUpdate::new();
+    assert!(update.is_empty());
+    assert!(update.to_update_map().is_empty());
+
+    update
+        .set_match_(Some("ip4.src == 10.0.0.1".to_string()))
+        .set_type(None);
+    assert!(!update.is_empty());
+    assert_eq!(update.columns(), vec!["match", "type"]);
+
+    let map = update.to_update_map();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map["match"], json!("ip4.src == 10.0.0.1"));
+    assert_eq!(map["type"], json!([]));
+
+    let mut acl = Acl::new();
+    acl.action = Some("drop".to_string());
+    acl.r#type = Some("x".to_string());
+    update.apply_to(&mut acl);
+    assert_eq!(acl.action, Some("drop".to_string()));
+    assert_eq!(acl.match_, Some("ip4.src == 10.0.0.1".to_string()));
+    assert_eq!(acl.r#type, None);
+}
+
+#[ovsdb_object(table = "Meter_Band")]
+pub struct MeterBand {
+    pub rate: u64,
+    pub burst_size: Vec<u64>,
+}
+
+#[test]
+fn test_try_to_map() {
+    let mut band = MeterBand::new();
+    band.rate = 100;
+    band.burst_size = vec![10];
+    let map = band.try_to_map().unwrap();
+    assert_eq!(map, band.to_map());
+    assert_eq!(map["burst_size"], json!(10));
+
+    // A value which cannot be sent is reported with its column, rather than
+    // left out of the row
+    band.burst_size = vec![10, u64::MAX];
+    let err = band.try_to_map().unwrap_err();
+    assert_eq!(
+        err.path,
+        vec![
+            PathSegment::Column("burst_size".to_string()),
+            PathSegment::Index(1)
+        ]
+    );
+    assert!(!band.to_map().contains_key("burst_size"));
+
+    let mut update = MeterBandUpdate::new();
+    update.set_rate(u64::MAX);
+    let err = update.try_to_update_map().unwrap_err();
+    assert_eq!(err.path, vec![PathSegment::Column("rate".to_string())]);
+    assert!(update.to_update_map().is_empty());
+}
+
+#[test]
+fn test_builder() {
+    let port = RequiredPort::builder()
+        .name("lsp1".to_string())
+        .addresses(vec!["router".to_string()])
+        .build()
+        .unwrap();
+    assert_eq!(port.name, "lsp1");
+    assert_eq!(port.addresses, vec!["router"]);
+    assert_eq!(port.tag, None);
+
+    assert_eq!(
+        RequiredPort::builder().tag(Some(1)).build().unwrap_err(),
+        "Missing required column name"
+    );
+
+    let router = LogicalRouter::builder()
+        .name(Some("lr0".to_string()))
+        .build()
+        .unwrap();
+    assert_eq!(router.enabled, Some(true));
+    assert_eq!(router.mtu, 1442);
+    assert_eq!(router.cache.get(), 7);
+}
+
+/// A string column holding a JSON document
+mod json_string {
+    use ovsdb_schema::{OvsdbAtom, OvsdbConversionError, OvsdbValue};
+    use serde_json::Value;
+
+    pub fn to_ovsdb(value: &Value) -> OvsdbValue {
+        OvsdbValue::Atom(OvsdbAtom::String(value.to_string()))
+    }
+
+    pub fn try_from_ovsdb(value: &OvsdbValue) -> Result<Value, OvsdbConversionError> {
+        match value {
+            OvsdbValue::Atom(OvsdbAtom::String(s)) => serde_json::from_str(s).map_err(|e| {
+                OvsdbConversionError::mismatch("a JSON string", value).with_reason(e.to_string())
+            }),
+            _ => Err(OvsdbConversionError::mismatch("a JSON string", value)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flags {
+    pub enabled: bool,
+    pub logged: bool,
+}
+
+/// An integer column used as a bitmask
+mod bitmask {
+    use super::Flags;
+    use ovsdb_schema::{OvsdbAtom, OvsdbConversionError, OvsdbValue};
+
+    pub fn to_ovsdb(flags: &Flags) -> OvsdbValue {
+        let bits = flags.enabled as i64 | (flags.logged as i64) << 1;
+        OvsdbValue::Atom(OvsdbAtom::Integer(bits))
+    }
+
+    pub fn try_from_ovsdb(value: &OvsdbValue) -> Result<Flags, OvsdbConversionError> {
+        match value {
+            OvsdbValue::Atom(OvsdbAtom::Integer(bits)) => Ok(Flags {
+                enabled: bits & 1 != 0,
+                logged: bits & 2 != 0,
+            }),
+            _ => Err(OvsdbConversionError::mismatch("integer", value)),
+        }
+    }
+}
+
+#[ovsdb_object(table = "Meter")]
+#[derive(Debug)]
+pub struct Meter {
+    #[ovsdb(with = "json_string")]
+    pub options: Value,
+    #[ovsdb(with = "bitmask", default = Flags { enabled: true, logged: false })]
+    pub flags: Flags,
+}
+
+#[test]
+fn test_with_conversion() {
+    let meter = Meter::builder()
+        .options(json!({"rate": 10}))
+        .flags(Flags {
+            enabled: true,
+            logged: true,
+        })
+        .build()
+        .unwrap();
+
+    let map = meter.to_map();
+    assert_eq!(map["options"], json!(r#"{"rate":10}"#));
+    assert_eq!(map["flags"], json!(3));
+
+    let decoded = Meter::from_map(&map).unwrap();
+    assert_eq!(decoded.options, json!({"rate": 10}));
+    assert_eq!(decoded.flags, meter.flags);
+
+    let mut update = MeterUpdate::new();
+    update.set_flags(Flags {
+        enabled: false,
+        logged: true,
+    });
+    assert_eq!(update.to_update_map()["flags"], json!(2));
+}
+
+#[test]
+fn test_with_conversion_errors() {
+    let mut map = HashMap::new();
+    map.insert("options".to_string(), json!("not json"));
+
+    let err = Meter::from_map(&map).unwrap_err();
+    assert_eq!(err.expected, "a JSON string");
+    assert_eq!(err.path, [PathSegment::Column("options".to_string())]);
+
+    // Lenient decoding keeps the default for the broken column
+    map.insert("options".to_string(), json!("{}"));
+    map.insert("flags".to_string(), json!("bogus"));
+    let meter = Meter::from_map_with_mode(&map, DecodeMode::Lenient).unwrap();
+    assert_eq!(meter.options, json!({}));
+    assert_eq!(
+        meter.flags,
+        Flags {
+            enabled: true,
+            logged: false
+        }
+    );
+}
+
+#[ovsdb_object(table = "Logical_Switch", content_eq, hash_by_uuid)]
+#[derive(Debug, Clone)]
+pub struct CachedSwitch {
+    pub name: Option<String>,
+    #[ovsdb(skip)]
+    pub hits: usize,
+}
+
+#[test]
+fn test_content_eq() {
+    let mut a = CachedSwitch::new();
+    a._uuid = Some(Uuid::from_u128(1));
+    a._version = Some(Uuid::from_u128(10));
+    a.name = Some("ls0".to_string());
+
+    let mut b = a.clone();
+    b._version = Some(Uuid::from_u128(11));
+    b.hits = 5;
+    assert!(a.content_eq(&b));
+
+    b.name = Some("ls1".to_string());
+    assert!(!a.content_eq(&b));
+}
+
+#[test]
+fn test_hash_by_uuid() {
+    use std::collections::HashSet;
+
+    let mut a = CachedSwitch::new();
+    a._uuid = Some(Uuid::from_u128(1));
+    a.name = Some("ls0".to_string());
+
+    let mut renamed = a.clone();
+    renamed.name = Some("ls1".to_string());
+    assert_eq!(a, renamed);
+
+    let mut rows = HashSet::new();
+    rows.insert(a.clone());
+    assert!(!rows.insert(renamed));
+    assert_eq!(rows.get(&a).and_then(|r| r.name.as_deref()), Some("ls0"));
+
+    let by_uuid: HashMap<_, _> = rows.iter().map(|r| (r.uuid_key(), r)).collect();
+    assert!(by_uuid.contains_key(&Some(Uuid::from_u128(1))));
+    assert!(!by_uuid.contains_key(&Some(Uuid::from_u128(2))));
+}
+
+#[test]
+fn test_hash_by_uuid_unsaved() {
+    use std::collections::HashSet;
+
+    let mut a = CachedSwitch::new();
+    a.name = Some("ls0".to_string());
+    let mut b = CachedSwitch::new();
+    b.name = Some("ls1".to_string());
+
+    let mut rows = HashSet::new();
+    assert!(rows.insert(a.clone()));
+    assert!(rows.insert(b));
+    assert!(!rows.insert(a.clone()));
+    assert_eq!(rows.len(), 2);
+
+    let mut saved = a.clone();
+    saved._uuid = Some(Uuid::from_u128(1));
+    assert_ne!(a, saved);
+    assert!(rows.insert(saved));
+
+    // Unsaved rows have no UUID to be looked up by
+    assert_eq!(a.uuid_key(), None);
+}
+
+#[ovsdb_object(table = "Logical_Switch")]
+#[derive(Debug)]
+pub struct PartialSwitch {
+    pub name: Option<String>,
+    #[ovsdb(flatten_unknown)]
+    pub unknown: HashMap<String, Value>,
+}
+
+#[test]
+fn test_flatten_unknown() {
+    let map: HashMap<String, Value> = serde_json::from_value(json!({
+        "_uuid": ["uuid", "36ed24e6-6ba4-4a37-a5e1-8e0ce1dbd89a"],
+        "name": "ls0",
+        "ports": ["set", []],
+        "other_config": ["map", [["mcast_snoop", "true"]]],
+    }))
+    .unwrap();
+
+    let ls = PartialSwitch::from_map(&map).unwrap();
+    assert_eq!(PartialSwitch::COLUMNS, &["name"]);
+    assert_eq!(ls.name.as_deref(), Some("ls0"));
+    assert_eq!(ls.unknown.len(), 2);
+    assert_eq!(ls.unknown["ports"], json!(["set", []]));
+
+    let round_trip = ls.to_map();
+    assert_eq!(round_trip["name"], json!("ls0"));
+    assert_eq!(round_trip["other_config"], map["other_config"]);
+    assert!(!round_trip.contains_key("_uuid"));
+
+    // Mapped columns take precedence over stale unknown values
+    let mut ls = ls;
+    ls.unknown.insert("name".to_string(), json!("stale"));
+    assert_eq!(ls.to_map()["name"], json!("ls0"));
+}
+
+#[ovsdb_object(table = "Logical_Flow")]
+#[derive(Debug)]
+pub struct LogicalFlow {
+    pub r#match: Option<String>,
+    pub type_: Option<String>,
+    pub priority: Option<i64>,
+    pub name_: Option<String>,
+}
+
+#[test]
+fn test_keyword_columns() {
+    assert_eq!(
+        LogicalFlow::COLUMNS,
+        &["match", "type", "priority", "name_"]
+    );
+
+    let map: HashMap<String, Value> = serde_json::from_value(json!({
+        "match": "ip4.dst == 10.0.0.1",
+        "type": "lr_in_ip_routing",
+        "priority": 100,
+    }))
+    .unwrap();
+    let flow = LogicalFlow::from_map(&map).unwrap();
+    assert_eq!(flow.r#match.as_deref(), Some("ip4.dst == 10.0.0.1"));
+    assert_eq!(flow.type_.as_deref(), Some("lr_in_ip_routing"));
+    assert_eq!(flow.to_map()["match"], map["match"]);
+    assert_eq!(flow.to_map()["type"], map["type"]);
+
+    let mut update = LogicalFlowUpdate::new();
+    update.set_match(None).set_type_(Some("drop".to_string()));
+    assert_eq!(update.columns(), vec!["match", "type"]);
+}
@@ -0,0 +1,122 @@
+use ovsdb_schema::{diff_rows, diff_values, ColumnDiff, OvsdbAtom, OvsdbValue};
+use std::collections::HashMap;
+
+fn string(s: &str) -> OvsdbAtom {
+    OvsdbAtom::String(s.to_string())
+}
+
+fn map(entries: &[(&str, &str)]) -> OvsdbValue {
+    OvsdbValue::Map(
+        entries
+            .iter()
+            .map(|(k, v)| (string(k), string(v)))
+            .collect(),
+    )
+}
+
+#[test]
+fn test_diff_identical_rows() {
+    let row = HashMap::from([
+        ("name".to_string(), OvsdbValue::Atom(string("sw0"))),
+        ("external_ids".to_string(), map(&[("a", "1")])),
+    ]);
+    assert!(diff_rows(&row, &row).is_empty());
+}
+
+#[test]
+fn test_diff_scalar_and_missing_columns() {
+    let old = HashMap::from([
+        ("name".to_string(), OvsdbValue::Atom(string("sw0"))),
+        ("other".to_string(), OvsdbValue::Atom(OvsdbAtom::Integer(1))),
+    ]);
+    let new = HashMap::from([
+        ("name".to_string(), OvsdbValue::Atom(string("sw1"))),
+        (
+            "added".to_string(),
+            OvsdbValue::Atom(OvsdbAtom::Boolean(true)),
+        ),
+    ]);
+
+    let diff = diff_rows(&old, &new);
+    assert_eq!(
+        diff.columns.keys().collect::<Vec<_>>(),
+        vec!["added", "name", "other"]
+    );
+    assert_eq!(
+        diff.columns["name"],
+        ColumnDiff::Replace {
+            old: Some(OvsdbValue::Atom(string("sw0"))),
+            new: Some(OvsdbValue::Atom(string("sw1"))),
+        }
+    );
+    assert_eq!(
+        diff.columns["other"],
+        ColumnDiff::Replace {
+            old: Some(OvsdbValue::Atom(OvsdbAtom::Integer(1))),
+            new: None,
+        }
+    );
+}
+
+#[test]
+fn test_diff_sets() {
+    let old = OvsdbValue::Set(vec![string("a"), string("b")]);
+    let new = OvsdbValue::Set(vec![string("b"), string("c")]);
+
+    let diff = diff_values(Some(&old), Some(&new)).unwrap();
+    assert_eq!(
+        diff,
+        ColumnDiff::Set {
+            inserted: vec![string("c")],
+            removed: vec![string("a")],
+        }
+    );
+    assert_eq!(
+        diff.to_update2(),
+        Some(OvsdbValue::Set(vec![string("c"), string("a")]))
+    );
+
+    // A single atom is the same as a set with one element
+    let single = OvsdbValue::Atom(string("a"));
+    assert_eq!(
+        diff_values(Some(&single), Some(&OvsdbValue::Set(vec![string("a")]))),
+        None
+    );
+    assert_eq!(
+        diff_values(Some(&single), Some(&OvsdbValue::Set(vec![]))),
+        Some(ColumnDiff::Set {
+            inserted: vec![],
+            removed: vec![string("a")],
+        })
+    );
+}
+
+#[test]
+fn test_diff_maps() {
+    let old = map(&[("a", "1"), ("b", "2"), ("c", "3")]);
+    let new = map(&[("a", "1"), ("b", "20"), ("d", "4")]);
+
+    let diff = diff_values(Some(&old), Some(&new)).unwrap();
+    assert_eq!(
+        diff,
+        ColumnDiff::Map {
+            inserted: vec![(string("d"), string("4"))],
+            removed: vec![(string("c"), string("3"))],
+            updated: vec![(string("b"), string("20"))],
+        }
+    );
+    assert_eq!(
+        diff.to_update2(),
+        Some(map(&[("d", "4"), ("c", "3"), ("b", "20")]))
+    );
+
+    let diff = diff_values(Some(&OvsdbValue::Set(vec![])), Some(&map(&[("a", "1")])));
+    assert_eq!(
+        diff,
+        Some(ColumnDiff::Map {
+            inserted: vec![(string("a"), string("1"))],
+            removed: vec![],
+            updated: vec![],
+        })
+    );
+}
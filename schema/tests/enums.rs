@@ -0,0 +1,96 @@
+use ovsdb_derive::OvsdbEnum;
+use ovsdb_schema::{OvsdbEnum, OvsdbSerializableExt};
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, OvsdbEnum)]
+pub enum LogicalSwitchPortType {
+    #[ovsdb(rename = "")]
+    Normal,
+    Router,
+    Localnet,
+    L2gateway,
+    Other(String),
+}
+
+#[derive(Debug, Clone, PartialEq, OvsdbEnum)]
+#[ovsdb(rename_all = "kebab-case")]
+pub enum AclAction {
+    Allow,
+    AllowRelated,
+    AllowStateless,
+    Drop,
+    Reject,
+}
+
+#[derive(Debug, Clone, PartialEq, OvsdbEnum)]
+pub enum NatType {
+    Dnat,
+    Snat,
+    DnatAndSnat,
+}
+
+#[test]
+fn test_enum_values() {
+    assert_eq!(
+        LogicalSwitchPortType::VALUES,
+        &["", "router", "localnet", "l2gateway"]
+    );
+    assert_eq!(
+        AclAction::VALUES,
+        &[
+            "allow",
+            "allow-related",
+            "allow-stateless",
+            "drop",
+            "reject"
+        ]
+    );
+    assert_eq!(NatType::VALUES, &["dnat", "snat", "dnat_and_snat"]);
+}
+
+#[test]
+fn test_enum_round_trip() {
+    assert_eq!(
+        LogicalSwitchPortType::Normal.to_ovsdb_json(),
+        Some(json!(""))
+    );
+    assert_eq!(
+        LogicalSwitchPortType::try_from_ovsdb_json(&json!("router")),
+        Ok(LogicalSwitchPortType::Router)
+    );
+    assert_eq!(
+        AclAction::try_from_ovsdb_json(&json!("allow-related")),
+        Ok(AclAction::AllowRelated)
+    );
+    assert_eq!(
+        NatType::DnatAndSnat.to_ovsdb_json(),
+        Some(json!("dnat_and_snat"))
+    );
+}
+
+#[test]
+fn test_enum_fallback() {
+    let value = LogicalSwitchPortType::try_from_ovsdb_json(&json!("virtual")).unwrap();
+    assert_eq!(value, LogicalSwitchPortType::Other("virtual".to_string()));
+    assert_eq!(value.as_str(), "virtual");
+    assert_eq!(value.to_ovsdb_json(), Some(json!("virtual")));
+}
+
+#[test]
+fn test_enum_unknown_value() {
+    let err = AclAction::try_from_ovsdb_json(&json!("pass")).unwrap_err();
+    assert_eq!(
+        err.expected,
+        r#"one of ["allow", "allow-related", "allow-stateless", "drop", "reject"]"#
+    );
+
+    assert!(AclAction::try_from_ovsdb_json(&json!(1)).is_err());
+}
+
+#[test]
+fn test_enum_in_collections() {
+    assert_eq!(
+        Vec::<AclAction>::try_from_ovsdb_json(&json!(["set", ["drop", "reject"]])),
+        Ok(vec![AclAction::Drop, AclAction::Reject])
+    );
+}
@@ -1,4 +1,5 @@
 use ovsdb_derive::ovsdb_object;
+use ovsdb_schema::{DecodeMode, PathSegment};
 use serde_json::Value;
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -272,3 +273,80 @@ fn test_serialization_multiple_element_set() {
     let connections_array = connections_json.as_array().unwrap();
     assert_eq!(connections_array[0].as_str().unwrap(), "set");
 }
+
+#[test]
+fn test_from_map_error_reports_field_and_detail() {
+    let json_str = r#"{
+        "name": "global",
+        "connections": ["set", [
+            ["uuid", "601c7161-97df-42ae-b377-3baf21830d8f"],
+            "not-a-uuid"
+        ]]
+    }"#;
+
+    let json_value: Value = serde_json::from_str(json_str).unwrap();
+    let err = NbGlobal::from_map(&serde_json::from_value(json_value).unwrap()).unwrap_err();
+
+    assert_eq!(
+        err.path,
+        [
+            PathSegment::Column("connections".to_string()),
+            PathSegment::Index(1)
+        ]
+    );
+    assert_eq!(
+        err.to_string(),
+        r#"expected uuid, found "not-a-uuid" at connections[1]"#
+    );
+}
+
+#[test]
+fn test_from_map_decode_modes() {
+    let json_str = r#"{
+        "_uuid": ["uuid", "not-a-uuid"],
+        "name": ["bogus", "global"],
+        "nb_cfg": 1.5,
+        "hv_cfg": 10,
+        "ipsec": []
+    }"#;
+
+    let map: HashMap<String, Value> = serde_json::from_str(json_str).unwrap();
+
+    let err = NbGlobal::from_map(&map).unwrap_err();
+    assert_eq!(err.path, [PathSegment::Column("_uuid".to_string())]);
+
+    let nb_global = NbGlobal::from_map_with_mode(&map, DecodeMode::Lenient).unwrap();
+    assert_eq!(nb_global._uuid, None);
+    assert_eq!(nb_global.name, None);
+    assert_eq!(nb_global.nb_cfg, None);
+    assert_eq!(nb_global.hv_cfg, Some(10));
+    assert_eq!(nb_global.ipsec, None);
+}
+
+#[test]
+fn test_from_map_strict_reports_unknown_tag() {
+    let json_str = r#"{"name": ["bogus", "global"]}"#;
+    let map: HashMap<String, Value> = serde_json::from_str(json_str).unwrap();
+
+    let err = NbGlobal::from_map_with_mode(&map, DecodeMode::Strict).unwrap_err();
+    assert!(
+        err.to_string().contains(r#"(unknown tag "bogus")"#),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_empty_set_for_optional_scalar() {
+    let map: HashMap<String, Value> =
+        serde_json::from_str(r#"{"name": ["set", []], "ipsec": []}"#).unwrap();
+
+    let nb_global = NbGlobal::from_map(&map).unwrap();
+    assert_eq!(nb_global.name, None);
+    assert_eq!(nb_global.ipsec, None);
+}
+
+#[test]
+fn test_default_table_name() {
+    assert_eq!(NbGlobal::TABLE, "NbGlobal");
+}
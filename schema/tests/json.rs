@@ -0,0 +1,75 @@
+use ovsdb_schema::{
+    json_to_ovsdb_atom, json_to_ovsdb_value, ovsdb_value_to_json, OvsdbAtom, OvsdbJsonError,
+    OvsdbValue,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+#[test]
+fn test_json_round_trip() {
+    let uuid = Uuid::parse_str("36e4e3ea-ef39-4ff3-a07c-5ee4eb5db3e1").unwrap();
+    let cases = [
+        json!("a"),
+        json!(42),
+        json!(1.5),
+        json!(true),
+        json!(["uuid", uuid.to_string()]),
+        json!(["named-uuid", "row1"]),
+        json!([]),
+        json!(["set", [1, 2, 3]]),
+        json!(["map", [["a", 1], ["b", ["uuid", uuid.to_string()]]]]),
+    ];
+
+    for case in cases {
+        let value = json_to_ovsdb_value(&case).unwrap();
+        assert_eq!(ovsdb_value_to_json(&value), Ok(case.clone()));
+        assert_eq!(serde_json::to_value(&value).unwrap(), case);
+        assert_eq!(OvsdbValue::try_from(&case), Ok(value));
+    }
+}
+
+#[test]
+fn test_json_empty_set() {
+    assert_eq!(
+        json_to_ovsdb_value(&json!(null)),
+        Ok(OvsdbValue::Set(vec![]))
+    );
+    assert_eq!(
+        ovsdb_value_to_json(&OvsdbValue::Set(vec![OvsdbAtom::Integer(1)])),
+        Ok(json!(1))
+    );
+}
+
+#[test]
+fn test_json_errors() {
+    assert_eq!(
+        json_to_ovsdb_value(&json!({"a": 1})),
+        Err(OvsdbJsonError::UnexpectedType(json!({"a": 1})))
+    );
+    assert_eq!(
+        json_to_ovsdb_value(&json!(["bag", []])),
+        Err(OvsdbJsonError::UnknownTag("bag".to_string()))
+    );
+    assert_eq!(
+        json_to_ovsdb_value(&json!(["uuid", "nope"])),
+        Err(OvsdbJsonError::InvalidUuid("nope".to_string()))
+    );
+    assert_eq!(
+        json_to_ovsdb_value(&json!(["set", ["a"], "extra"])),
+        Err(OvsdbJsonError::MalformedTag {
+            tag: "set".to_string(),
+            found: json!(["set", ["a"], "extra"]),
+        })
+    );
+    assert_eq!(
+        json_to_ovsdb_value(&json!(["set", [["set", [1]]]])),
+        Err(OvsdbJsonError::NotAnAtom(json!(["set", [1]])))
+    );
+    assert!(matches!(
+        json_to_ovsdb_atom(&json!(u64::MAX)),
+        Err(OvsdbJsonError::IntegerOutOfRange(_))
+    ));
+
+    let err = json_to_ovsdb_value(&json!(["uuid", "nope"])).unwrap_err();
+    assert_eq!(err.to_string(), r#"invalid UUID "nope""#);
+}
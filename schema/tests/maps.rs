@@ -0,0 +1,145 @@
+use ovsdb_derive::ovsdb_object;
+use ovsdb_schema::{
+    OvsdbAtom, OvsdbMap, OvsdbSerializable, OvsdbSerializableExt, OvsdbValue, PathSegment,
+};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+const Q0: &str = "0b4ad97e-5a8e-4b6c-9b1b-3c1f5e7a9d01";
+const Q1: &str = "5f0c4d2e-8b7a-4f3e-a1d2-6e9b8c7a5d02";
+
+#[ovsdb_object(table = "QoS")]
+#[derive(Debug, PartialEq)]
+pub struct Qos {
+    pub r#type: String,
+    pub queues: HashMap<i64, Uuid>,
+    pub other_config: HashMap<String, String>,
+}
+
+#[ovsdb_object(table = "Queue")]
+#[derive(Debug, PartialEq)]
+pub struct Queue {
+    pub dscp: Option<i64>,
+    pub weights: BTreeMap<i64, i64>,
+    pub owners: HashMap<Uuid, String>,
+    pub limits: OvsdbMap<String, f64>,
+}
+
+/// A map key without `Display`, only convertible to and from an atom
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vlan(u16);
+
+impl OvsdbSerializable for Vlan {
+    fn to_ovsdb(&self) -> OvsdbValue {
+        self.0.to_ovsdb()
+    }
+
+    fn from_ovsdb(value: &OvsdbValue) -> Option<Self> {
+        u16::from_ovsdb(value).filter(|vlan| *vlan < 4096).map(Vlan)
+    }
+}
+
+fn uuid(uuid: &str) -> Uuid {
+    Uuid::parse_str(uuid).unwrap()
+}
+
+#[test]
+fn test_integer_to_uuid_map() {
+    let queues = HashMap::from([(0, uuid(Q0)), (1, uuid(Q1))]);
+
+    let json = queues.to_ovsdb_json().unwrap();
+    assert_eq!(json[0], "map");
+    let mut pairs = json[1].as_array().unwrap().clone();
+    pairs.sort_by_key(|pair| pair[0].as_i64());
+    assert_eq!(
+        pairs,
+        vec![json!([0, ["uuid", Q0]]), json!([1, ["uuid", Q1]])]
+    );
+
+    assert_eq!(HashMap::<i64, Uuid>::try_from_ovsdb_json(&json), Ok(queues));
+}
+
+#[test]
+fn test_table_with_maps() {
+    let qos = Qos {
+        r#type: "linux-htb".to_owned(),
+        queues: HashMap::from([(0, uuid(Q0))]),
+        other_config: HashMap::new(),
+        ..Default::default()
+    };
+
+    let map = qos.to_map();
+    assert_eq!(map["queues"], json!(["map", [[0, ["uuid", Q0]]]]));
+    assert_eq!(map["other_config"], json!(["map", []]));
+    assert_eq!(Qos::from_map(&map).unwrap(), qos);
+
+    let row: HashMap<String, Value> = serde_json::from_value(json!({
+        "dscp": ["set", []],
+        "weights": ["map", [[2, 20], [1, 10]]],
+        "owners": ["map", [[["uuid", Q1], "ovn"]]],
+        "limits": ["map", [["max", 1.5]]],
+    }))
+    .unwrap();
+    let queue = Queue::from_map(&row).unwrap();
+    assert_eq!(queue.dscp, None);
+    assert_eq!(queue.weights, BTreeMap::from([(1, 10), (2, 20)]));
+    assert_eq!(queue.owners[&uuid(Q1)], "ovn");
+    assert_eq!(queue.limits.get(&"max".to_owned()), Some(&1.5));
+
+    let map = queue.to_map();
+    assert_eq!(map["weights"], json!(["map", [[1, 10], [2, 20]]]));
+    assert_eq!(map["owners"], json!(["map", [[["uuid", Q1], "ovn"]]]));
+    assert_eq!(Queue::from_map(&map).unwrap(), queue);
+}
+
+#[test]
+fn test_map_type_errors() {
+    let value: OvsdbValue = serde_json::from_value(json!(["map", [["a", ["uuid", Q0]]]])).unwrap();
+    let err = HashMap::<i64, Uuid>::try_from_ovsdb(&value).unwrap_err();
+    assert_eq!(err.expected, "integer");
+    assert_eq!(
+        err.path,
+        vec![PathSegment::Key(OvsdbAtom::String("a".to_string()))]
+    );
+
+    let value: OvsdbValue = serde_json::from_value(json!(["map", [[1, "x"]]])).unwrap();
+    let err = HashMap::<i64, Uuid>::try_from_ovsdb(&value).unwrap_err();
+    assert_eq!(err.expected, "uuid");
+    assert_eq!(err.path, vec![PathSegment::Key(OvsdbAtom::Integer(1))]);
+}
+
+#[test]
+fn test_empty_map() {
+    let empty: Option<HashMap<i64, Uuid>> = None;
+    let json = empty.to_ovsdb_json().unwrap();
+    assert_eq!(json, json!([]));
+
+    assert_eq!(
+        HashMap::<i64, Uuid>::try_from_ovsdb_json(&json),
+        Ok(HashMap::new())
+    );
+    assert_eq!(
+        BTreeMap::<i64, Uuid>::try_from_ovsdb_json(&Value::Null),
+        Ok(BTreeMap::new())
+    );
+    assert!(OvsdbMap::<i64, Uuid>::try_from_ovsdb_json(&json)
+        .unwrap()
+        .is_empty());
+    assert!(HashMap::<i64, Uuid>::try_from_ovsdb_json(&json!(["set", [1]])).is_err());
+}
+
+#[test]
+fn test_newtype_keys() {
+    let names = HashMap::from([(Vlan(10), "storage".to_owned())]);
+    let json = names.to_ovsdb_json().unwrap();
+    assert_eq!(json, json!(["map", [[10, "storage"]]]));
+    assert_eq!(
+        HashMap::<Vlan, String>::try_from_ovsdb_json(&json),
+        Ok(names)
+    );
+
+    let json = json!(["map", [[4096, "storage"]]]);
+    assert!(HashMap::<Vlan, String>::try_from_ovsdb_json(&json).is_err());
+    assert!(BTreeMap::<Vlan, String>::try_from_ovsdb_json(&json).is_err());
+}
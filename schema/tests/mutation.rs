@@ -0,0 +1,68 @@
+use ovsdb_schema::{diff_mutations, Mutation, Mutator, OvsdbAtom, OvsdbValue};
+use serde_json::json;
+
+fn string(s: &str) -> OvsdbAtom {
+    OvsdbAtom::String(s.to_string())
+}
+
+#[test]
+fn test_mutation_json() {
+    let mutation = Mutation::new(
+        "external_ids",
+        Mutator::Delete,
+        OvsdbValue::Set(vec![string("a"), string("b")]),
+    );
+    let value = json!(["external_ids", "delete", ["set", ["a", "b"]]]);
+
+    assert_eq!(serde_json::to_value(&mutation).unwrap(), value);
+    assert_eq!(serde_json::from_value::<Mutation>(value).unwrap(), mutation);
+
+    let counter: Mutation = serde_json::from_value(json!(["nb_cfg", "+=", 1])).unwrap();
+    assert_eq!(counter.mutator, Mutator::Add);
+    assert!(serde_json::from_value::<Mutation>(json!(["nb_cfg", "^=", 1])).is_err());
+}
+
+#[test]
+fn test_diff_mutations_for_map() {
+    let old = OvsdbValue::Map(vec![
+        (string("a"), string("1")),
+        (string("b"), string("2")),
+        (string("c"), string("3")),
+    ]);
+    let new = OvsdbValue::Map(vec![
+        (string("a"), string("1")),
+        (string("b"), string("20")),
+        (string("d"), string("4")),
+    ]);
+
+    let mutations = diff_mutations("external_ids", &old, &new).unwrap();
+    assert_eq!(
+        serde_json::to_value(&mutations).unwrap(),
+        json!([
+            ["external_ids", "delete", ["set", ["c", "b"]]],
+            ["external_ids", "insert", ["map", [["d", "4"], ["b", "20"]]]],
+        ])
+    );
+}
+
+#[test]
+fn test_diff_mutations_for_set() {
+    let old = OvsdbValue::Set(vec![string("a"), string("b")]);
+    let new = OvsdbValue::Set(vec![string("a"), string("c")]);
+
+    assert_eq!(
+        diff_mutations("ports", &old, &new),
+        Some(vec![
+            Mutation::new("ports", Mutator::Delete, OvsdbValue::Set(vec![string("b")])),
+            Mutation::new("ports", Mutator::Insert, OvsdbValue::Set(vec![string("c")])),
+        ])
+    );
+    assert_eq!(diff_mutations("ports", &old, &old), Some(vec![]));
+}
+
+#[test]
+fn test_diff_mutations_for_scalar() {
+    let old = OvsdbValue::Atom(string("sw0"));
+    let new = OvsdbValue::Atom(string("sw1"));
+    assert_eq!(diff_mutations("name", &old, &new), None);
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "net-types")]
+
+use ovsdb_schema::{
+    IpNet, Ipv4Net, MacAddr, OvsdbAtom, OvsdbSerializable, OvsdbSerializableExt, OvsdbValue,
+};
+use serde_json::json;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[test]
+fn test_ip_addresses() {
+    let addr: IpAddr = "10.0.0.1".parse().unwrap();
+    assert_eq!(
+        addr.to_ovsdb(),
+        OvsdbValue::Atom(OvsdbAtom::String("10.0.0.1".to_string()))
+    );
+    assert_eq!(
+        IpAddr::try_from_ovsdb_json(&json!("fd00::1")),
+        Ok("fd00::1".parse().unwrap())
+    );
+    assert_eq!(
+        Ipv4Addr::try_from_ovsdb_json(&json!("192.168.1.1")),
+        Ok(Ipv4Addr::new(192, 168, 1, 1))
+    );
+    assert!(Ipv6Addr::try_from_ovsdb_json(&json!("192.168.1.1")).is_err());
+
+    let err = IpAddr::try_from_ovsdb_json(&json!("10.0.0.256")).unwrap_err();
+    assert_eq!(err.expected, "IP address");
+    assert!(err.reason.is_some());
+}
+
+#[test]
+fn test_networks() {
+    let net = Ipv4Net::try_from_ovsdb_json(&json!("10.0.0.1/24")).unwrap();
+    assert_eq!(net.addr(), Ipv4Addr::new(10, 0, 0, 1));
+    assert_eq!(net.prefix_len(), 24);
+    assert_eq!(net.to_ovsdb_json(), Some(json!("10.0.0.1/24")));
+
+    let nets =
+        Vec::<IpNet>::try_from_ovsdb_json(&json!(["set", ["10.0.0.0/8", "fd00::/64"]])).unwrap();
+    assert_eq!(nets.len(), 2);
+}
+
+#[test]
+fn test_mac_addresses() {
+    let mac: MacAddr = "0A:00:00:00:00:FF".parse().unwrap();
+    assert_eq!(mac, MacAddr::new(0x0a, 0, 0, 0, 0, 0xff));
+    assert!(mac.is_local());
+    assert!(!mac.is_multicast());
+    assert_eq!(mac.to_ovsdb_json(), Some(json!("0a:00:00:00:00:ff")));
+
+    assert!("0a:00:00:00:00".parse::<MacAddr>().is_err());
+    assert!("0a:00:00:00:00:00:00".parse::<MacAddr>().is_err());
+    assert!("0a:00:00:00:00:0g".parse::<MacAddr>().is_err());
+    assert!("0a:00:00:00:00:+f".parse::<MacAddr>().is_err());
+    assert!(MacAddr::try_from_ovsdb_json(&json!("router")).is_err());
+}
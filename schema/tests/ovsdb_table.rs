@@ -0,0 +1,91 @@
+use ovsdb_derive::ovsdb_table;
+use ovsdb_schema::{OvsdbEnum, Ref};
+use serde_json::json;
+use uuid::Uuid;
+
+ovsdb_table!(
+    "tests/fixtures/ovn-nb.ovsschema",
+    "Logical_Switch",
+    "Logical_Switch_Port"
+);
+
+mod all_tables {
+    use ovsdb_derive::ovsdb_table;
+
+    ovsdb_table!("tests/fixtures/ovn-nb.ovsschema");
+}
+
+use all_tables::{Acl, AclAction, AclDirection, AclSeverity};
+
+const PORT_UUID: &str = "601c7161-97df-42ae-b377-3baf21830d8f";
+
+#[test]
+fn test_generated_table() {
+    assert_eq!(LogicalSwitch::TABLE, "Logical_Switch");
+    assert_eq!(
+        LogicalSwitch::COLUMNS,
+        &["acls", "external_ids", "name", "other_config", "ports"]
+    );
+
+    let uuid = Uuid::parse_str(PORT_UUID).unwrap();
+    let map = serde_json::from_value(json!({
+        "name": "ls0",
+        "ports": ["uuid", PORT_UUID],
+        "external_ids": ["map", [["owner", "neutron"]]],
+    }))
+    .unwrap();
+
+    let ls = LogicalSwitch::from_map(&map).unwrap();
+    assert_eq!(ls.name, "ls0");
+    assert_eq!(ls.ports, vec![uuid]);
+    assert_eq!(ls.external_ids["owner"], "neutron");
+
+    // Logical_Switch_Port is generated alongside, so references are typed
+    let ports: Vec<Ref<LogicalSwitchPort>> = ls.ports_refs();
+    assert_eq!(ports, vec![Ref::new(uuid)]);
+}
+
+#[test]
+fn test_generated_column_types() {
+    let mut port = LogicalSwitchPort::new();
+    port.r#type = "router".to_string();
+    port.tag = Some(100);
+    port.enabled = Some(true);
+    port.addresses = vec!["router".to_string()];
+
+    let map = port.to_map();
+    assert_eq!(map["type"], json!("router"));
+    assert_eq!(map["tag"], json!(100));
+
+    let decoded = LogicalSwitchPort::from_map(&map).unwrap();
+    assert_eq!(decoded, port);
+}
+
+#[test]
+fn test_generated_enums() {
+    assert_eq!(
+        AclAction::VALUES,
+        &["allow", "allow-related", "drop", "reject"]
+    );
+    assert_eq!(AclAction::AllowRelated.as_str(), "allow-related");
+    assert_eq!(AclDirection::default(), AclDirection::FromLport);
+
+    let acl = Acl::from_map(
+        &serde_json::from_value(json!({
+            "priority": 1001,
+            "direction": "to-lport",
+            "match": "ip4",
+            "action": "allow-stateless",
+            "severity": ["set", []],
+        }))
+        .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(acl.priority, 1001);
+    assert_eq!(acl.direction, AclDirection::ToLport);
+    assert_eq!(acl.r#match, "ip4");
+    assert_eq!(acl.action, AclAction::Other("allow-stateless".to_string()));
+    assert_eq!(acl.severity, None::<AclSeverity>);
+    assert!(!acl.log);
+}
@@ -0,0 +1,171 @@
+use ovsdb_schema::{
+    ovsdb_atom_to_json, ovsdb_value_to_json, NonFiniteReal, OvsdbAtom, OvsdbJsonError,
+    OvsdbSerializable, OvsdbValue,
+};
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
+
+#[test]
+fn test_real_constructor_rejects_non_finite() {
+    assert_eq!(OvsdbAtom::real(1.5), Ok(OvsdbAtom::Real(1.5)));
+    assert!(OvsdbAtom::real(f64::NAN).is_err());
+    assert_eq!(
+        OvsdbAtom::real(f64::INFINITY),
+        Err(NonFiniteReal(f64::INFINITY))
+    );
+    assert_eq!(
+        NonFiniteReal(f64::NEG_INFINITY).to_string(),
+        "-inf is not a finite real"
+    );
+}
+
+#[test]
+fn test_serialize_rejects_non_finite() {
+    assert!(serde_json::to_string(&OvsdbAtom::Real(f64::NAN)).is_err());
+
+    let value = OvsdbValue::Map(vec![(
+        OvsdbAtom::String("load".to_string()),
+        OvsdbAtom::Real(f64::INFINITY),
+    )]);
+    assert!(value.validate().is_err());
+    assert!(serde_json::to_string(&value).is_err());
+
+    assert_eq!(serde_json::to_string(&OvsdbAtom::Real(0.5)).unwrap(), "0.5");
+}
+
+#[test]
+fn test_to_json_rejects_non_finite() {
+    assert_eq!(
+        ovsdb_atom_to_json(&OvsdbAtom::Real(f64::INFINITY)),
+        Err(OvsdbJsonError::NonFiniteReal(f64::INFINITY))
+    );
+
+    let value = OvsdbValue::Set(vec![OvsdbAtom::Real(1.0), OvsdbAtom::Real(f64::NAN)]);
+    assert!(matches!(
+        ovsdb_value_to_json(&value),
+        Err(OvsdbJsonError::NonFiniteReal(real)) if real.is_nan()
+    ));
+    assert!(Value::try_from(&value).is_err());
+
+    let value = OvsdbValue::Map(vec![(
+        OvsdbAtom::String("load".to_string()),
+        OvsdbAtom::Real(f64::NEG_INFINITY),
+    )]);
+    assert_eq!(
+        ovsdb_value_to_json(&value).unwrap_err().to_string(),
+        "-inf is not a finite real"
+    );
+
+    assert_eq!(
+        ovsdb_value_to_json(&OvsdbValue::Atom(OvsdbAtom::Real(0.5))),
+        Ok(json!(0.5))
+    );
+}
+
+#[test]
+fn test_f64_rejects_non_finite() {
+    assert!(f64::try_from_ovsdb(&OvsdbValue::Atom(OvsdbAtom::Real(f64::NAN))).is_err());
+    assert!("1e999".parse::<OvsdbAtom>().is_err());
+}
+
+#[test]
+fn test_total_cmp() {
+    assert_eq!(
+        OvsdbAtom::Real(-0.0).total_cmp(&OvsdbAtom::Real(0.0)),
+        Ordering::Equal
+    );
+    assert_eq!(
+        OvsdbAtom::Real(f64::NAN).total_cmp(&OvsdbAtom::Real(-f64::NAN)),
+        Ordering::Equal
+    );
+    assert_eq!(
+        OvsdbAtom::Real(f64::INFINITY).total_cmp(&OvsdbAtom::Real(f64::NAN)),
+        Ordering::Less
+    );
+    assert_eq!(
+        OvsdbAtom::String("z".to_string()).total_cmp(&OvsdbAtom::Integer(0)),
+        Ordering::Less
+    );
+    assert_eq!(
+        OvsdbAtom::Integer(2).total_cmp(&OvsdbAtom::Integer(10)),
+        Ordering::Less
+    );
+}
+
+#[test]
+fn test_atoms_as_keys() {
+    let mut set = HashSet::new();
+    assert!(set.insert(OvsdbAtom::Real(0.0)));
+    assert!(!set.insert(OvsdbAtom::Real(-0.0)));
+    assert!(set.insert(OvsdbAtom::Integer(0)));
+    assert!(set.insert(OvsdbAtom::String("0".to_string())));
+    assert_eq!(set.len(), 3);
+
+    let mut values: BTreeSet<OvsdbValue> = BTreeSet::new();
+    values.insert(OvsdbValue::Map(vec![]));
+    values.insert(OvsdbValue::Set(vec![OvsdbAtom::Integer(2)]));
+    values.insert(OvsdbValue::Set(vec![OvsdbAtom::Integer(1)]));
+    values.insert(OvsdbValue::Atom(OvsdbAtom::Boolean(true)));
+    assert_eq!(
+        values.into_iter().collect::<Vec<_>>(),
+        vec![
+            OvsdbValue::Atom(OvsdbAtom::Boolean(true)),
+            OvsdbValue::Set(vec![OvsdbAtom::Integer(1)]),
+            OvsdbValue::Set(vec![OvsdbAtom::Integer(2)]),
+            OvsdbValue::Map(vec![]),
+        ]
+    );
+}
+
+#[test]
+fn test_sort_atoms() {
+    let mut atoms = vec![
+        OvsdbAtom::Real(2.5),
+        OvsdbAtom::Integer(3),
+        OvsdbAtom::String("b".to_string()),
+        OvsdbAtom::Real(-1.0),
+        OvsdbAtom::String("a".to_string()),
+    ];
+    atoms.sort();
+    assert_eq!(
+        atoms,
+        vec![
+            OvsdbAtom::String("a".to_string()),
+            OvsdbAtom::String("b".to_string()),
+            OvsdbAtom::Integer(3),
+            OvsdbAtom::Real(-1.0),
+            OvsdbAtom::Real(2.5),
+        ]
+    );
+}
+
+#[test]
+fn test_values_ignore_order() {
+    let set =
+        |atoms: &[i64]| OvsdbValue::Set(atoms.iter().map(|&i| OvsdbAtom::Integer(i)).collect());
+    let map = |pairs: &[(&str, i64)]| {
+        OvsdbValue::Map(
+            pairs
+                .iter()
+                .map(|&(k, v)| (OvsdbAtom::String(k.to_string()), OvsdbAtom::Integer(v)))
+                .collect(),
+        )
+    };
+
+    assert_eq!(set(&[1, 2, 3]), set(&[3, 1, 2]));
+    assert_ne!(set(&[1, 2, 3]), set(&[1, 2]));
+    assert_eq!(set(&[1, 2, 3]).cmp(&set(&[3, 2, 1])), Ordering::Equal);
+    assert_eq!(map(&[("a", 1), ("b", 2)]), map(&[("b", 2), ("a", 1)]));
+    assert_ne!(map(&[("a", 1), ("b", 2)]), map(&[("a", 2), ("b", 1)]));
+
+    let values = HashSet::from([
+        set(&[1, 2]),
+        set(&[2, 1]),
+        map(&[("a", 1), ("b", 2)]),
+        map(&[("b", 2), ("a", 1)]),
+    ]);
+    assert_eq!(values.len(), 2);
+    let values = BTreeSet::from([set(&[1, 2]), set(&[2, 1])]);
+    assert_eq!(values.len(), 1);
+}
@@ -0,0 +1,108 @@
+use ovsdb_derive::ovsdb_object;
+use ovsdb_schema::{OvsdbAtom, OvsdbSerializable, OvsdbValue, Ref, WeakRef};
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+#[ovsdb_object]
+#[derive(Debug, PartialEq)]
+pub struct LogicalSwitchPort {
+    pub name: Option<String>,
+}
+
+#[ovsdb_object]
+#[derive(Debug, PartialEq)]
+pub struct LogicalSwitch {
+    pub name: Option<String>,
+    pub ports: Option<Vec<Ref<LogicalSwitchPort>>>,
+}
+
+#[ovsdb_object(table = "Logical_Router")]
+#[derive(Debug, PartialEq)]
+pub struct LogicalRouter {
+    #[ovsdb(ref = "LogicalSwitchPort")]
+    pub ports: Vec<Uuid>,
+    #[ovsdb(ref = "LogicalSwitch")]
+    pub switch: Option<Uuid>,
+    #[ovsdb(ref = "LogicalSwitchPort")]
+    pub gateway_ports: Option<Vec<Uuid>>,
+}
+
+const PORT_UUID: &str = "601c7161-97df-42ae-b377-3baf21830d8f";
+
+#[test]
+fn test_ref_round_trip() {
+    let uuid = Uuid::parse_str(PORT_UUID).unwrap();
+    let port: Ref<LogicalSwitchPort> = Ref::new(uuid);
+
+    assert_eq!(port.uuid(), uuid);
+    assert_eq!(port.to_ovsdb(), OvsdbValue::Atom(OvsdbAtom::Uuid(uuid)));
+    assert_eq!(
+        Ref::<LogicalSwitchPort>::try_from_ovsdb(&port.to_ovsdb()),
+        Ok(port)
+    );
+    assert_eq!(port.to_string(), PORT_UUID);
+
+    let weak: WeakRef<LogicalSwitchPort> = port.into();
+    assert_eq!(weak.uuid(), uuid);
+}
+
+#[test]
+fn test_ref_in_table_struct() {
+    let json = serde_json::json!({
+        "name": "ls0",
+        "ports": ["uuid", PORT_UUID],
+    });
+
+    let ls = LogicalSwitch::from_map(&serde_json::from_value(json).unwrap()).unwrap();
+    let uuid = Uuid::parse_str(PORT_UUID).unwrap();
+    assert_eq!(ls.ports, Some(vec![Ref::new(uuid)]));
+}
+
+#[test]
+fn test_ref_in_ordered_set() {
+    let a = Ref::<LogicalSwitchPort>::new(Uuid::from_u128(2));
+    let b = Ref::<LogicalSwitchPort>::new(Uuid::from_u128(1));
+
+    let set = BTreeSet::from([a, b]);
+    assert_eq!(set.into_iter().collect::<Vec<_>>(), vec![b, a]);
+}
+
+#[test]
+fn test_ref_accessors() {
+    let uuid = Uuid::parse_str(PORT_UUID).unwrap();
+    let router = LogicalRouter::builder()
+        .ports(vec![uuid])
+        .switch(Some(Uuid::from_u128(1)))
+        .build()
+        .unwrap();
+
+    let ports: Vec<Ref<LogicalSwitchPort>> = router.ports_refs();
+    assert_eq!(ports, vec![Ref::new(uuid)]);
+    let switch: Option<Ref<LogicalSwitch>> = router.switch_ref();
+    assert_eq!(switch, Some(Ref::new(Uuid::from_u128(1))));
+    assert!(router.gateway_ports_refs().is_empty());
+
+    // The referenced columns are still plain UUIDs on the wire
+    assert_eq!(
+        router.to_map()["ports"],
+        serde_json::json!(["uuid", PORT_UUID])
+    );
+}
+
+#[test]
+fn test_ref_resolve() {
+    let uuid = Uuid::parse_str(PORT_UUID).unwrap();
+    let mut port = LogicalSwitchPort::new();
+    port.name = Some("lsp0".to_string());
+    let rows = HashMap::from([(uuid, port)]);
+
+    let reference = Ref::<LogicalSwitchPort>::new(uuid);
+    assert_eq!(
+        reference.resolve(&rows).and_then(|p| p.name.as_deref()),
+        Some("lsp0")
+    );
+    assert_eq!(WeakRef::from(reference).resolve(&rows), rows.get(&uuid));
+    assert!(Ref::<LogicalSwitchPort>::new(Uuid::nil())
+        .resolve(&rows)
+        .is_none());
+}
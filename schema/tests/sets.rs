@@ -0,0 +1,149 @@
+use ovsdb_derive::OvsdbEnum;
+use ovsdb_schema::{
+    OvsdbAtom, OvsdbMap, OvsdbSerializable, OvsdbSerializableExt, OvsdbSet, OvsdbValue, PathSegment,
+};
+use proptest::prelude::*;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Debug;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, OvsdbEnum)]
+#[ovsdb(rename_all = "kebab-case")]
+pub enum AclAction {
+    Allow,
+    AllowRelated,
+    Drop,
+    Reject,
+}
+
+/// Convert to JSON and back, as values are sent to and received from OVSDB
+fn round_trip<T: OvsdbSerializable + PartialEq + Debug>(value: &T) {
+    let json = value.try_to_ovsdb_json().unwrap();
+    let text = serde_json::to_string(&json).unwrap();
+
+    let parsed: OvsdbValue = serde_json::from_str(&text).unwrap();
+    assert_eq!(T::try_from_ovsdb(&parsed).as_ref(), Ok(value));
+    assert_eq!(T::try_from_ovsdb_json(&json).as_ref(), Ok(value));
+}
+
+fn acl_action() -> impl Strategy<Value = AclAction> {
+    prop_oneof![
+        Just(AclAction::Allow),
+        Just(AclAction::AllowRelated),
+        Just(AclAction::Drop),
+        Just(AclAction::Reject),
+    ]
+}
+
+/// Finite reals, which are the only ones OVSDB accepts
+fn real() -> impl Strategy<Value = f64> {
+    use proptest::num::f64::{NEGATIVE, NORMAL, POSITIVE, SUBNORMAL, ZERO};
+    POSITIVE | NEGATIVE | NORMAL | SUBNORMAL | ZERO
+}
+
+fn uuid() -> impl Strategy<Value = Uuid> {
+    any::<u128>().prop_map(Uuid::from_u128)
+}
+
+proptest! {
+    #[test]
+    fn test_integer_sets(set in prop::collection::vec(any::<i64>(), 0..8)) {
+        round_trip(&set);
+    }
+
+    #[test]
+    fn test_real_sets(set in prop::collection::vec(real(), 0..8)) {
+        round_trip(&set);
+    }
+
+    #[test]
+    fn test_boolean_sets(set in prop::collection::btree_set(any::<bool>(), 0..=2)) {
+        round_trip(&set);
+    }
+
+    #[test]
+    fn test_string_sets(set in prop::collection::vec(".*", 0..8)) {
+        round_trip(&set);
+    }
+
+    #[test]
+    fn test_uuid_sets(set in prop::collection::vec(uuid(), 0..8)) {
+        round_trip(&set);
+    }
+
+    #[test]
+    fn test_enum_sets(set in prop::collection::vec(acl_action(), 0..8)) {
+        round_trip(&set);
+    }
+
+    #[test]
+    fn test_optional_atoms(value in prop::option::of(any::<i64>())) {
+        round_trip(&value);
+    }
+
+    #[test]
+    fn test_ovsdb_sets(set in prop::collection::btree_set(any::<i64>(), 0..8)) {
+        let set = OvsdbSet::<i64>::try_from(set.into_iter().collect::<Vec<_>>()).unwrap();
+        round_trip(&set);
+    }
+
+    #[test]
+    fn test_maps(map in prop::collection::btree_map(any::<i64>(), uuid(), 0..8)) {
+        round_trip(&map);
+        round_trip(&map.clone().into_iter().collect::<HashMap<_, _>>());
+    }
+}
+
+#[test]
+fn test_sets_of_non_atoms() {
+    let sets = vec![vec![2i64, 3]];
+    assert!(std::panic::catch_unwind(|| sets.to_ovsdb()).is_err());
+
+    let err = sets.try_to_ovsdb().unwrap_err();
+    assert_eq!(err.expected, "an atom");
+    assert_eq!(err.found, json!(["set", [2, 3]]));
+    assert_eq!(err.path, vec![PathSegment::Index(0)]);
+    assert_eq!(sets.to_ovsdb_json(), None);
+
+    let options = vec![Some(1i64), None];
+    assert_eq!(
+        options.try_to_ovsdb().unwrap_err().path,
+        vec![PathSegment::Index(1)]
+    );
+}
+
+#[test]
+fn test_maps_of_non_atoms() {
+    let map = BTreeMap::from([("a".to_string(), vec![1i64, 2])]);
+    assert!(std::panic::catch_unwind(|| map.to_ovsdb()).is_err());
+
+    let err = map.try_to_ovsdb().unwrap_err();
+    assert_eq!(
+        err.path,
+        vec![PathSegment::Key(OvsdbAtom::String("a".to_string()))]
+    );
+
+    let map: OvsdbMap<String, Vec<i64>> =
+        OvsdbMap::try_from(vec![("a".to_string(), vec![])]).unwrap();
+    assert!(map.try_to_ovsdb().is_err());
+}
+
+#[test]
+fn test_sets_of_non_finite_reals() {
+    let set = vec![1.5, f64::NAN];
+    let err = set.try_to_ovsdb().unwrap_err();
+    assert_eq!(err.path, vec![PathSegment::Index(1)]);
+    assert!(err.reason.unwrap().contains("finite"));
+    assert_eq!(set.to_ovsdb_json(), None);
+
+    assert!(BTreeSet::from([true, false]).try_to_ovsdb().is_ok());
+    assert!(Some(f64::INFINITY).try_to_ovsdb().is_err());
+}
+
+#[test]
+fn test_sets_of_unknown_enum_values() {
+    let err =
+        Vec::<AclAction>::try_from_ovsdb_json(&json!(["set", ["allow", "pass"]])).unwrap_err();
+    assert_eq!(err.path, vec![PathSegment::Index(1)]);
+}
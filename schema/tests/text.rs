@@ -0,0 +1,120 @@
+use ovsdb_schema::{OvsdbAtom, OvsdbValue};
+use uuid::Uuid;
+
+#[test]
+fn test_display_atoms() {
+    assert_eq!(
+        OvsdbAtom::String("br-int".to_string()).to_string(),
+        "br-int"
+    );
+    assert_eq!(
+        OvsdbAtom::String("eth0".to_string()).to_string(),
+        r#""eth0""#
+    );
+    assert_eq!(
+        OvsdbAtom::String("true".to_string()).to_string(),
+        r#""true""#
+    );
+    assert_eq!(OvsdbAtom::String(String::new()).to_string(), r#""""#);
+    assert_eq!(
+        OvsdbAtom::String("a \"b\"".to_string()).to_string(),
+        r#""a \"b\"""#
+    );
+    assert_eq!(OvsdbAtom::Integer(-3).to_string(), "-3");
+    assert_eq!(OvsdbAtom::Real(1.0).to_string(), "1.0");
+    assert_eq!(OvsdbAtom::Boolean(true).to_string(), "true");
+    assert_eq!(
+        OvsdbAtom::NamedUuid("row1".to_string()).to_string(),
+        "@row1"
+    );
+}
+
+#[test]
+fn test_display_values() {
+    let set = OvsdbValue::Set(vec![OvsdbAtom::Integer(1), OvsdbAtom::Integer(2)]);
+    assert_eq!(set.to_string(), "[1, 2]");
+    assert_eq!(OvsdbValue::Set(vec![]).to_string(), "[]");
+
+    let map = OvsdbValue::Map(vec![
+        (
+            OvsdbAtom::String("neutron:network_name".to_string()),
+            OvsdbAtom::String("public".to_string()),
+        ),
+        (
+            OvsdbAtom::String("mtu".to_string()),
+            OvsdbAtom::Integer(1500),
+        ),
+    ]);
+    assert_eq!(
+        map.to_string(),
+        r#"{"neutron:network_name"=public, mtu=1500}"#
+    );
+    assert_eq!(OvsdbValue::Map(vec![]).to_string(), "{}");
+}
+
+#[test]
+fn test_parse_values() {
+    let uuid = Uuid::parse_str("36e4e3ea-ef39-4ff3-a07c-5ee4eb5db3e1").unwrap();
+
+    assert_eq!("42".parse(), Ok(OvsdbAtom::Integer(42)));
+    assert_eq!("-1.5".parse(), Ok(OvsdbAtom::Real(-1.5)));
+    assert_eq!("false".parse(), Ok(OvsdbAtom::Boolean(false)));
+    assert_eq!(uuid.to_string().parse(), Ok(OvsdbAtom::Uuid(uuid)));
+    assert_eq!(
+        "tcp:127.0.0.1:6641".parse(),
+        Ok(OvsdbAtom::String("tcp:127.0.0.1:6641".to_string()))
+    );
+    assert_eq!(
+        r#""eth0""#.parse(),
+        Ok(OvsdbAtom::String("eth0".to_string()))
+    );
+
+    assert_eq!(
+        " [a, \"b c\" ,3] ".parse(),
+        Ok(OvsdbValue::Set(vec![
+            OvsdbAtom::String("a".to_string()),
+            OvsdbAtom::String("b c".to_string()),
+            OvsdbAtom::Integer(3),
+        ]))
+    );
+    assert_eq!(
+        "{k=v, k2=\"1\"}".parse(),
+        Ok(OvsdbValue::Map(vec![
+            (
+                OvsdbAtom::String("k".to_string()),
+                OvsdbAtom::String("v".to_string())
+            ),
+            (
+                OvsdbAtom::String("k2".to_string()),
+                OvsdbAtom::String("1".to_string())
+            ),
+        ]))
+    );
+    assert_eq!("[]".parse(), Ok(OvsdbValue::Set(vec![])));
+    assert_eq!("{}".parse(), Ok(OvsdbValue::Map(vec![])));
+}
+
+#[test]
+fn test_display_parse_round_trip() {
+    let value = OvsdbValue::Map(vec![
+        (
+            OvsdbAtom::String("eth0".to_string()),
+            OvsdbAtom::String("line\nbreak".to_string()),
+        ),
+        (OvsdbAtom::String("42".to_string()), OvsdbAtom::Real(0.5)),
+    ]);
+    assert_eq!(value.to_string().parse(), Ok(value));
+}
+
+#[test]
+fn test_parse_errors() {
+    let err = "[a, b".parse::<OvsdbValue>().unwrap_err();
+    assert_eq!(err.position, 5);
+    assert_eq!(err.to_string(), "expected ',' or ']' at position 5");
+
+    assert!("{a}".parse::<OvsdbValue>().is_err());
+    assert!("\"open".parse::<OvsdbAtom>().is_err());
+    assert!("a b".parse::<OvsdbAtom>().is_err());
+    assert!("[a]".parse::<OvsdbAtom>().is_err());
+    assert!("1.2.3".parse::<OvsdbAtom>().is_err());
+}
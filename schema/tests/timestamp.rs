@@ -0,0 +1,32 @@
+#![cfg(feature = "chrono")]
+
+use chrono::{DateTime, TimeZone, Utc};
+use ovsdb_schema::{EpochSeconds, OvsdbSerializableExt};
+use serde_json::json;
+
+#[test]
+fn test_millisecond_timestamps() {
+    let timestamp = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+
+    assert_eq!(timestamp.to_ovsdb_json(), Some(json!(1740830400000i64)));
+    assert_eq!(
+        DateTime::<Utc>::try_from_ovsdb_json(&json!(1740830400000i64)),
+        Ok(timestamp)
+    );
+    assert!(DateTime::<Utc>::try_from_ovsdb_json(&json!(i64::MAX)).is_err());
+    assert!(DateTime::<Utc>::try_from_ovsdb_json(&json!("now")).is_err());
+}
+
+#[test]
+fn test_second_timestamps() {
+    let timestamp = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+
+    assert_eq!(
+        EpochSeconds(timestamp).to_ovsdb_json(),
+        Some(json!(1740830400))
+    );
+    assert_eq!(
+        EpochSeconds::try_from_ovsdb_json(&json!(1740830400)),
+        Ok(EpochSeconds(timestamp))
+    );
+}
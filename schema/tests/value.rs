@@ -0,0 +1,222 @@
+use ovsdb_schema::{
+    DecodeMode, OvsdbAtom, OvsdbSerializable, OvsdbSerializableExt, OvsdbValue, PathSegment,
+};
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use uuid::Uuid;
+
+#[test]
+fn test_deserialize_atoms() {
+    let value: OvsdbValue = serde_json::from_value(json!("global")).unwrap();
+    assert_eq!(
+        value,
+        OvsdbValue::Atom(OvsdbAtom::String("global".to_string()))
+    );
+
+    let value: OvsdbValue = serde_json::from_value(json!(42)).unwrap();
+    assert_eq!(value, OvsdbValue::Atom(OvsdbAtom::Integer(42)));
+
+    let value: OvsdbValue = serde_json::from_value(json!(1.5)).unwrap();
+    assert_eq!(value, OvsdbValue::Atom(OvsdbAtom::Real(1.5)));
+
+    let value: OvsdbValue = serde_json::from_value(json!(true)).unwrap();
+    assert_eq!(value, OvsdbValue::Atom(OvsdbAtom::Boolean(true)));
+}
+
+#[test]
+fn test_deserialize_tagged_uuids() {
+    let uuid = Uuid::parse_str("601c7161-97df-42ae-b377-3baf21830d8f").unwrap();
+
+    let atom: OvsdbAtom =
+        serde_json::from_value(json!(["uuid", "601c7161-97df-42ae-b377-3baf21830d8f"])).unwrap();
+    assert_eq!(atom, OvsdbAtom::Uuid(uuid));
+
+    let value: OvsdbValue = serde_json::from_value(json!(["named-uuid", "row0"])).unwrap();
+    assert_eq!(
+        value,
+        OvsdbValue::Atom(OvsdbAtom::NamedUuid("row0".to_string()))
+    );
+
+    assert!(serde_json::from_value::<OvsdbAtom>(json!(["uuid", "not-a-uuid"])).is_err());
+}
+
+#[test]
+fn test_deserialize_sets_and_maps() {
+    let value: OvsdbValue = serde_json::from_value(json!(["set", [1, 2, 3]])).unwrap();
+    assert_eq!(
+        value,
+        OvsdbValue::Set(vec![
+            OvsdbAtom::Integer(1),
+            OvsdbAtom::Integer(2),
+            OvsdbAtom::Integer(3)
+        ])
+    );
+
+    let value: OvsdbValue = serde_json::from_value(json!([])).unwrap();
+    assert_eq!(value, OvsdbValue::Set(vec![]));
+
+    let value: OvsdbValue = serde_json::from_value(json!(["map", [["a", 1], ["b", 2]]])).unwrap();
+    assert_eq!(
+        value,
+        OvsdbValue::Map(vec![
+            (OvsdbAtom::String("a".to_string()), OvsdbAtom::Integer(1)),
+            (OvsdbAtom::String("b".to_string()), OvsdbAtom::Integer(2)),
+        ])
+    );
+}
+
+#[test]
+fn test_deserialize_rejects_invalid_values() {
+    assert!(serde_json::from_value::<OvsdbValue>(json!(["list", [1]])).is_err());
+    assert!(serde_json::from_value::<OvsdbValue>(json!(["set", [["set", []]]])).is_err());
+    assert!(serde_json::from_value::<OvsdbValue>(json!(["map", [["a"]]])).is_err());
+    assert!(serde_json::from_value::<OvsdbValue>(json!({"a": 1})).is_err());
+}
+
+#[test]
+fn test_serialize_round_trip() {
+    let values = [
+        json!("global"),
+        json!(["uuid", "601c7161-97df-42ae-b377-3baf21830d8f"]),
+        json!(["set", [1, 2]]),
+        json!(["map", [["a", "b"]]]),
+        json!([]),
+    ];
+
+    for json in values {
+        let value: OvsdbValue = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&value).unwrap(), json);
+    }
+}
+
+#[test]
+fn test_conversion_error_context() {
+    let value: OvsdbValue = serde_json::from_value(json!(["set", [1, 2, "three"]])).unwrap();
+    let err = Vec::<i64>::try_from_ovsdb(&value).unwrap_err();
+    assert_eq!(err.expected, "integer");
+    assert_eq!(err.found, json!("three"));
+    assert_eq!(err.path, vec![PathSegment::Index(2)]);
+    assert_eq!(err.to_string(), r#"expected integer, found "three" at [2]"#);
+
+    let value: OvsdbValue = serde_json::from_value(json!(["map", [["a", "1"], ["b", 2]]])).unwrap();
+    let err = HashMap::<String, String>::try_from_ovsdb(&value).unwrap_err();
+    assert_eq!(err.expected, "string");
+    assert_eq!(
+        err.path,
+        vec![PathSegment::Key(OvsdbAtom::String("b".to_string()))]
+    );
+    assert_eq!(err.to_string(), r#"expected string, found 2 at ["b"]"#);
+}
+
+#[test]
+fn test_conversion_error_from_json() {
+    let err = String::try_from_ovsdb_json(&json!({"a": 1})).unwrap_err();
+    assert_eq!(err.found, json!({"a": 1}));
+    assert!(err.reason.is_some());
+
+    assert_eq!(bool::try_from_ovsdb_json(&json!(true)), Ok(true));
+}
+
+#[test]
+fn test_narrow_integer_types() {
+    let tag: u16 = 4095;
+    assert_eq!(tag.to_ovsdb(), OvsdbValue::Atom(OvsdbAtom::Integer(4095)));
+    assert_eq!(u16::try_from_ovsdb(&tag.to_ovsdb()), Ok(4095));
+    assert_eq!(
+        i32::try_from_ovsdb(&OvsdbValue::Atom(OvsdbAtom::Integer(-1))),
+        Ok(-1)
+    );
+    assert_eq!(
+        Vec::<u8>::from_ovsdb(&OvsdbValue::Set(vec![
+            OvsdbAtom::Integer(1),
+            OvsdbAtom::Integer(2)
+        ])),
+        Some(vec![1, 2])
+    );
+}
+
+#[test]
+fn test_narrow_integer_overflow() {
+    let err = u16::try_from_ovsdb(&OvsdbValue::Atom(OvsdbAtom::Integer(65536))).unwrap_err();
+    assert_eq!(err.expected, "u16");
+    assert_eq!(err.found, json!(65536));
+    assert_eq!(
+        err.to_string(),
+        "expected u16, found 65536 (65536 is out of range 0..=65535)"
+    );
+
+    assert!(u64::try_from_ovsdb(&OvsdbValue::Atom(OvsdbAtom::Integer(-1))).is_err());
+    assert!(u32::try_from_ovsdb(&OvsdbValue::Atom(OvsdbAtom::String("1".into()))).is_err());
+
+    // Values outside of OVSDB's integer range can't be sent
+    assert_eq!(
+        (i64::MAX as u64).try_to_ovsdb(),
+        Ok(OvsdbValue::Atom(OvsdbAtom::Integer(i64::MAX)))
+    );
+    let err = u64::MAX.try_to_ovsdb().unwrap_err();
+    assert_eq!(err.expected, "integer");
+    assert_eq!(err.found, json!(u64::MAX));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "expected integer, found {} ({} is out of range {}..={})",
+            u64::MAX,
+            u64::MAX,
+            i64::MIN,
+            i64::MAX
+        )
+    );
+    assert!(std::panic::catch_unwind(|| u64::MAX.to_ovsdb()).is_err());
+    assert!(vec![1, u64::MAX].try_to_ovsdb().is_err());
+    assert_eq!(Some(u64::MAX).to_ovsdb_json(), None);
+}
+
+#[test]
+fn test_set_collections() {
+    let value: OvsdbValue = serde_json::from_value(json!(["set", ["b", "a", "b"]])).unwrap();
+
+    let set = HashSet::<String>::try_from_ovsdb(&value).unwrap();
+    assert_eq!(set.len(), 2);
+    assert!(set.contains("a") && set.contains("b"));
+
+    let set = BTreeSet::<String>::try_from_ovsdb(&value).unwrap();
+    assert_eq!(
+        serde_json::to_value(set.to_ovsdb()).unwrap(),
+        json!(["set", ["a", "b"]])
+    );
+
+    let single: OvsdbValue = serde_json::from_value(json!(7)).unwrap();
+    assert_eq!(
+        BTreeSet::<i64>::try_from_ovsdb(&single),
+        Ok(BTreeSet::from([7]))
+    );
+}
+
+#[test]
+fn test_btree_map() {
+    let value: OvsdbValue =
+        serde_json::from_value(json!(["map", [["z", "1"], ["a", "2"]]])).unwrap();
+
+    let map = BTreeMap::<String, String>::try_from_ovsdb(&value).unwrap();
+    assert_eq!(map["a"], "2");
+    assert_eq!(map["z"], "1");
+
+    // Serialization follows key order, making it deterministic
+    assert_eq!(
+        serde_json::to_value(map.to_ovsdb()).unwrap(),
+        json!(["map", [["a", "2"], ["z", "1"]]])
+    );
+}
+
+#[test]
+fn test_from_ovsdb_json_with_mode() {
+    assert_eq!(
+        u16::from_ovsdb_json_with_mode(&json!(70000), DecodeMode::Lenient),
+        Ok(None)
+    );
+    assert!(u16::from_ovsdb_json_with_mode(&json!(70000), DecodeMode::Strict).is_err());
+    assert_eq!(
+        u16::from_ovsdb_json_with_mode(&json!(80), DecodeMode::Strict),
+        Ok(Some(80))
+    );
+}
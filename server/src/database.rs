@@ -0,0 +1,684 @@
+use ovsdb_client::{
+    schema::{AtomicType, ColumnType, DatabaseSchema, TableSchema},
+    validation::{validate_row, validate_update},
+};
+use serde_json::{Map, Value, json};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+/// Columns of a stored row, without `_uuid`
+pub(crate) type Row = Map<String, Value>;
+
+/// Rows of every table, keyed by their UUID
+pub(crate) type Tables = HashMap<String, BTreeMap<Uuid, Row>>;
+
+/// A database hosted by the server
+pub(crate) struct Database {
+    /// The schema as loaded, returned by `get_schema`
+    pub raw_schema: Value,
+
+    pub schema: DatabaseSchema,
+
+    pub tables: Tables,
+}
+
+/// The error of a failed operation, as in `{"error": ..., "details": ...}`
+struct OperationError {
+    error: &'static str,
+    details: String,
+}
+
+impl OperationError {
+    fn new(error: &'static str, details: impl Into<String>) -> Self {
+        OperationError {
+            error,
+            details: details.into(),
+        }
+    }
+
+    fn into_json(self) -> Value {
+        json!({"error": self.error, "details": self.details})
+    }
+}
+
+/// Why an operation stopped its transaction
+enum Stop {
+    Failed(OperationError),
+
+    /// A `wait` operation is not satisfied yet
+    Blocked,
+}
+
+impl From<OperationError> for Stop {
+    fn from(err: OperationError) -> Self {
+        Stop::Failed(err)
+    }
+}
+
+/// The outcome of a transaction
+pub(crate) enum Outcome {
+    /// The results of the operations, and the tables before the commit if
+    /// they all succeeded
+    Done(Vec<Value>, Option<Tables>),
+
+    /// A `wait` operation is not satisfied yet, so the transaction must run
+    /// again after the next commit, or by the deadline of its next `wait`
+    Blocked(Option<Instant>),
+}
+
+impl Database {
+    pub fn new(raw_schema: Value) -> Result<Self, serde_json::Error> {
+        let schema: DatabaseSchema = serde_json::from_value(raw_schema.clone())?;
+        let tables = schema
+            .tables
+            .keys()
+            .map(|name| (name.clone(), BTreeMap::new()))
+            .collect();
+
+        Ok(Database {
+            raw_schema,
+            schema,
+            tables,
+        })
+    }
+
+    /// Run the operations of a transaction, committing them only if they all
+    /// succeed and leave no dangling strong reference. The rows no root row
+    /// references anymore are deleted at commit.
+    ///
+    /// `wait` operations time out once their timeout has elapsed since the
+    /// transaction started, or at once without a start.
+    pub fn transact(&mut self, operations: &[Value], started: Option<Instant>) -> Outcome {
+        let mut tables = self.tables.clone();
+        let mut results = Vec::with_capacity(operations.len());
+
+        // NOTE(mnaser): Named UUIDs can be referenced by any operation of the
+        //               transaction, so they are all allocated upfront.
+        let named: HashMap<String, Uuid> = operations
+            .iter()
+            .filter(|op| op["op"] == "insert")
+            .filter_map(|op| op["uuid-name"].as_str())
+            .map(|name| (name.to_owned(), Uuid::new_v4()))
+            .collect();
+
+        for operation in operations {
+            let operation = resolve_named_uuids(operation, &named);
+            match self.execute(&mut tables, &operation, &named, started) {
+                Ok(result) => results.push(result),
+                Err(Stop::Failed(err)) => {
+                    results.push(err.into_json());
+                    return Outcome::Done(results, None);
+                }
+                Err(Stop::Blocked) => return Outcome::Blocked(next_deadline(operations, started)),
+            }
+        }
+
+        // NOTE(mnaser): Errors found at commit are reported after the results
+        //               of the operations, as ovsdb-server does.
+        self.collect_garbage(&mut tables);
+        if let Err(err) = self.check_references(&tables) {
+            results.push(err.into_json());
+            return Outcome::Done(results, None);
+        }
+
+        let old = std::mem::replace(&mut self.tables, tables);
+        Outcome::Done(results, Some(old))
+    }
+
+    /// Delete the rows of the tables which are not roots that no row of a
+    /// root table reaches through strong references.
+    fn collect_garbage(&self, tables: &mut Tables) {
+        // NOTE(mnaser): Every table is a root in a schema where none of them sets
+        //               isRoot, as in RFC 7047, so nothing is collected.
+        let is_root = |table: &str| self.schema.tables[table].is_root.unwrap_or(false);
+        if !self.schema.tables.keys().any(|table| is_root(table)) {
+            return;
+        }
+
+        let mut reached: HashSet<(&str, Uuid)> = HashSet::new();
+        let mut pending: Vec<(&str, Uuid)> = self
+            .schema
+            .tables
+            .keys()
+            .filter(|table| is_root(table))
+            .flat_map(|table| {
+                tables[table]
+                    .keys()
+                    .map(move |uuid| (table.as_str(), *uuid))
+            })
+            .collect();
+
+        while let Some((table, uuid)) = pending.pop() {
+            let Some(row) = tables[table].get(&uuid) else {
+                continue;
+            };
+            for (_, ref_table, ref_uuid) in references(&self.schema.tables[table], row) {
+                if !is_root(ref_table) && reached.insert((ref_table, ref_uuid)) {
+                    pending.push((ref_table, ref_uuid));
+                }
+            }
+        }
+
+        for (table, rows) in tables.iter_mut() {
+            if !is_root(table) {
+                rows.retain(|uuid, _| reached.contains(&(table.as_str(), *uuid)));
+            }
+        }
+    }
+
+    /// Fail if a row references strongly a row which does not exist, either
+    /// because it never did or because the transaction deleted it.
+    fn check_references(&self, tables: &Tables) -> Result<(), OperationError> {
+        for (table, schema) in &self.schema.tables {
+            for (uuid, row) in &tables[table] {
+                for (column, ref_table, ref_uuid) in references(schema, row) {
+                    let exists = |tables: &Tables| {
+                        tables
+                            .get(ref_table)
+                            .is_some_and(|rows| rows.contains_key(&ref_uuid))
+                    };
+                    if exists(tables) {
+                        continue;
+                    }
+
+                    let details = if exists(&self.tables) {
+                        format!(
+                            "cannot delete {ref_table} row {ref_uuid} because of remaining references from {table} row {uuid}"
+                        )
+                    } else {
+                        format!(
+                            "table {table} column {column} row {uuid} references nonexistent row {ref_uuid} in table {ref_table}"
+                        )
+                    };
+                    return Err(OperationError::new(
+                        "referential integrity violation",
+                        details,
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Apply the `<table-updates>` sent by an upstream monitor, keeping the
+    /// UUIDs of its rows. Returns the tables before the update.
+    pub fn apply_update(&mut self, updates: &Value) -> Tables {
+        let mut tables = self.tables.clone();
+
+        for (table, rows) in updates.as_object().into_iter().flatten() {
+            let Some(stored) = tables.get_mut(table) else {
+                continue;
+            };
+            for (uuid, update) in rows.as_object().into_iter().flatten() {
+                let Ok(uuid) = Uuid::parse_str(uuid) else {
+                    continue;
+                };
+                match update.get("new").and_then(Value::as_object) {
+                    Some(new) => {
+                        let mut row = new.clone();
+                        row.remove("_uuid");
+                        row.entry("_version")
+                            .or_insert_with(|| uuid_json(Uuid::new_v4()));
+                        stored.insert(uuid, row);
+                    }
+                    None => {
+                        stored.remove(&uuid);
+                    }
+                }
+            }
+        }
+
+        std::mem::replace(&mut self.tables, tables)
+    }
+
+    fn table_schema(&self, operation: &Value) -> Result<(String, &TableSchema), OperationError> {
+        let table = operation["table"]
+            .as_str()
+            .ok_or_else(|| OperationError::new("syntax error", "missing table"))?;
+        let schema = self
+            .schema
+            .tables
+            .get(table)
+            .ok_or_else(|| OperationError::new("unknown table", table))?;
+
+        Ok((table.to_owned(), schema))
+    }
+
+    fn execute(
+        &self,
+        tables: &mut Tables,
+        operation: &Value,
+        named: &HashMap<String, Uuid>,
+        started: Option<Instant>,
+    ) -> Result<Value, Stop> {
+        let op = operation["op"].as_str().unwrap_or_default();
+        if op == "comment" {
+            return Ok(json!({}));
+        }
+        if op == "abort" {
+            return Err(OperationError::new("aborted", "aborted by request").into());
+        }
+
+        let (table, schema) = self.table_schema(operation)?;
+        let rows = tables.get_mut(&table).unwrap();
+
+        match op {
+            "insert" => {
+                let row = object(&operation["row"])?;
+                validate_row(schema, &row)
+                    .map_err(|e| OperationError::new("constraint violation", e.to_string()))?;
+
+                let uuid = match operation["uuid-name"].as_str() {
+                    Some(name) => named[name],
+                    None => Uuid::new_v4(),
+                };
+                let mut stored = Row::new();
+                for (name, column) in &schema.columns {
+                    let value = match row.get(name) {
+                        Some(value) => canonical(&column.r#type, atoms(value)),
+                        None => default_value(&column.r#type),
+                    };
+                    stored.insert(name.clone(), value);
+                }
+                stored.insert("_version".to_owned(), uuid_json(Uuid::new_v4()));
+                rows.insert(uuid, stored);
+
+                Ok(json!({"uuid": uuid_json(uuid)}))
+            }
+            "select" => {
+                let columns = operation["columns"].as_array();
+                let selected = matching(rows, &operation["where"])?
+                    .into_iter()
+                    .map(|uuid| {
+                        let row = with_uuid(uuid, &rows[&uuid]);
+                        match columns {
+                            Some(columns) => Value::Object(filter(&row, columns)),
+                            None => Value::Object(row),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                Ok(json!({"rows": selected}))
+            }
+            "update" => {
+                let row = object(&operation["row"])?;
+                validate_update(schema, &row)
+                    .map_err(|e| OperationError::new("constraint violation", e.to_string()))?;
+
+                let matched = matching(rows, &operation["where"])?;
+                for uuid in &matched {
+                    let stored = rows.get_mut(uuid).unwrap();
+                    for (name, value) in &row {
+                        let column = &schema.columns[name];
+                        stored.insert(name.clone(), canonical(&column.r#type, atoms(value)));
+                    }
+                    stored.insert("_version".to_owned(), uuid_json(Uuid::new_v4()));
+                }
+
+                Ok(json!({"count": matched.len()}))
+            }
+            "mutate" => {
+                let mutations = operation["mutations"]
+                    .as_array()
+                    .ok_or_else(|| OperationError::new("syntax error", "missing mutations"))?;
+
+                let matched = matching(rows, &operation["where"])?;
+                for uuid in &matched {
+                    let stored = rows.get_mut(uuid).unwrap();
+                    for mutation in mutations {
+                        mutate(schema, stored, mutation)?;
+                    }
+                    stored.insert("_version".to_owned(), uuid_json(Uuid::new_v4()));
+                }
+
+                Ok(json!({"count": matched.len()}))
+            }
+            "delete" => {
+                let matched = matching(rows, &operation["where"])?;
+                for uuid in &matched {
+                    rows.remove(uuid);
+                }
+
+                Ok(json!({"count": matched.len()}))
+            }
+            "wait" => {
+                let columns = operation["columns"]
+                    .as_array()
+                    .ok_or_else(|| OperationError::new("syntax error", "missing columns"))?;
+                let expected = operation["rows"]
+                    .as_array()
+                    .ok_or_else(|| OperationError::new("syntax error", "missing rows"))?;
+                let equal = match operation["until"].as_str() {
+                    Some("==") => true,
+                    Some("!=") => false,
+                    _ => {
+                        return Err(
+                            OperationError::new("syntax error", "until must be == or !=").into(),
+                        );
+                    }
+                };
+
+                let mut selected = matching(rows, &operation["where"])?
+                    .into_iter()
+                    .map(|uuid| {
+                        let row = with_uuid(uuid, &rows[&uuid]);
+                        projection(schema, &row, columns)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut expected = expected
+                    .iter()
+                    .map(|row| {
+                        let row = row.as_object().ok_or_else(|| {
+                            OperationError::new("syntax error", "row must be an object")
+                        })?;
+                        projection(schema, row, columns)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                // NOTE(mnaser): The rows are compared regardless of their order.
+                selected.sort_by_key(|row| Value::from(row.clone()).to_string());
+                expected.sort_by_key(|row| Value::from(row.clone()).to_string());
+                if (selected == expected) == equal {
+                    return Ok(json!({}));
+                }
+
+                let timeout = wait_timeout(operation);
+                match started {
+                    Some(started) if timeout.is_none_or(|timeout| started.elapsed() < timeout) => {
+                        Err(Stop::Blocked)
+                    }
+                    _ => Err(OperationError::new("timed out", "wait condition not met").into()),
+                }
+            }
+            _ => Err(OperationError::new("not supported", op.to_owned()).into()),
+        }
+    }
+}
+
+/// Returns the timeout of a `wait` operation, none if it waits forever
+fn wait_timeout(operation: &Value) -> Option<Duration> {
+    operation["timeout"].as_u64().map(Duration::from_millis)
+}
+
+/// Returns the earliest deadline of the `wait` operations of a transaction
+/// which is still ahead
+fn next_deadline(operations: &[Value], started: Option<Instant>) -> Option<Instant> {
+    let started = started?;
+    operations
+        .iter()
+        .filter(|op| op["op"] == "wait")
+        .filter_map(wait_timeout)
+        .map(|timeout| started + timeout)
+        .filter(|deadline| *deadline > Instant::now())
+        .min()
+}
+
+/// Returns the rows a row references strongly, with the column holding each
+/// reference and its table
+fn references<'a>(schema: &'a TableSchema, row: &Row) -> Vec<(&'a str, &'a str, Uuid)> {
+    schema
+        .columns
+        .iter()
+        .filter_map(|(name, column)| Some((name, column, row.get(name)?)))
+        .flat_map(|(name, column, value)| {
+            column
+                .r#type
+                .strong_references(value)
+                .into_iter()
+                .map(move |(table, uuid)| (name.as_str(), table, uuid))
+        })
+        .collect()
+}
+
+/// Returns the values of the given columns of a row, in their canonical form
+fn projection(
+    schema: &TableSchema,
+    row: &Map<String, Value>,
+    columns: &[Value],
+) -> Result<Vec<Value>, OperationError> {
+    columns
+        .iter()
+        .map(|column| {
+            let name = column
+                .as_str()
+                .ok_or_else(|| OperationError::new("syntax error", column.to_string()))?;
+            let value = row.get(name).cloned().unwrap_or(Value::Null);
+            match schema.columns.get(name) {
+                Some(column) => Ok(canonical(&column.r#type, atoms(&value))),
+                None if name == "_uuid" || name == "_version" => Ok(value),
+                None => Err(OperationError::new("unknown column", name)),
+            }
+        })
+        .collect()
+}
+
+fn object(value: &Value) -> Result<HashMap<String, Value>, OperationError> {
+    value
+        .as_object()
+        .map(|row| row.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .ok_or_else(|| OperationError::new("syntax error", "row must be an object"))
+}
+
+fn uuid_json(uuid: Uuid) -> Value {
+    json!(["uuid", uuid.to_string()])
+}
+
+/// Returns a copy of the row including its `_uuid` column
+pub(crate) fn with_uuid(uuid: Uuid, row: &Row) -> Row {
+    let mut row = row.clone();
+    row.insert("_uuid".to_owned(), uuid_json(uuid));
+    row
+}
+
+/// Keep only the given columns of a row
+pub(crate) fn filter(row: &Row, columns: &[Value]) -> Row {
+    columns
+        .iter()
+        .filter_map(Value::as_str)
+        .filter_map(|column| row.get(column).map(|v| (column.to_owned(), v.clone())))
+        .collect()
+}
+
+/// Replace `["named-uuid", name]` by the UUID allocated to the name
+fn resolve_named_uuids(value: &Value, named: &HashMap<String, Uuid>) -> Value {
+    match value {
+        Value::Array(items) => match items.as_slice() {
+            [Value::String(tag), Value::String(name)] if tag == "named-uuid" => named
+                .get(name)
+                .map(|uuid| uuid_json(*uuid))
+                .unwrap_or_else(|| value.clone()),
+            _ => Value::Array(
+                items
+                    .iter()
+                    .map(|v| resolve_named_uuids(v, named))
+                    .collect(),
+            ),
+        },
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), resolve_named_uuids(v, named)))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+/// Split a value into its atoms, or `[key, value]` pairs for maps
+fn atoms(value: &Value) -> Vec<Value> {
+    let mut atoms = match value.as_array().map(Vec::as_slice) {
+        Some([Value::String(tag), Value::Array(items)]) if tag == "set" || tag == "map" => {
+            items.clone()
+        }
+        Some([]) => vec![],
+        _ => vec![value.clone()],
+    };
+    atoms.sort_by_key(Value::to_string);
+    atoms.dedup();
+    atoms
+}
+
+/// Build the value of a column from its atoms, the way ovsdb-server does
+fn canonical(r#type: &ColumnType, atoms: Vec<Value>) -> Value {
+    if r#type.is_map() {
+        json!(["map", atoms])
+    } else if atoms.len() == 1 {
+        atoms.into_iter().next().unwrap()
+    } else {
+        json!(["set", atoms])
+    }
+}
+
+fn default_value(r#type: &ColumnType) -> Value {
+    if r#type.min == 0 || r#type.is_map() {
+        return canonical(r#type, vec![]);
+    }
+
+    match r#type.key.r#type {
+        AtomicType::Integer => json!(0),
+        AtomicType::Real => json!(0.0),
+        AtomicType::Boolean => json!(false),
+        AtomicType::String => json!(""),
+        AtomicType::Uuid => uuid_json(Uuid::nil()),
+    }
+}
+
+/// Returns the UUIDs of the rows matching every condition of a `where` clause
+fn matching(rows: &BTreeMap<Uuid, Row>, conditions: &Value) -> Result<Vec<Uuid>, OperationError> {
+    let conditions = match conditions {
+        Value::Null => &vec![],
+        Value::Array(conditions) => conditions,
+        _ => {
+            return Err(OperationError::new(
+                "syntax error",
+                "where must be an array",
+            ));
+        }
+    };
+
+    let mut matched = vec![];
+    for (uuid, row) in rows {
+        let row = with_uuid(*uuid, row);
+        let mut all = true;
+        for condition in conditions {
+            if !evaluate(&row, condition)? {
+                all = false;
+                break;
+            }
+        }
+        if all {
+            matched.push(*uuid);
+        }
+    }
+
+    Ok(matched)
+}
+
+fn evaluate(row: &Row, condition: &Value) -> Result<bool, OperationError> {
+    let (column, function, arg) = match condition.as_array().map(Vec::as_slice) {
+        Some([Value::String(column), Value::String(function), arg]) => (column, function, arg),
+        _ => return Err(OperationError::new("syntax error", condition.to_string())),
+    };
+    let value = row
+        .get(column)
+        .ok_or_else(|| OperationError::new("unknown column", column.clone()))?;
+
+    let (value_atoms, arg_atoms) = (atoms(value), atoms(arg));
+    let compare = |f: fn(f64, f64) -> bool| match (value.as_f64(), arg.as_f64()) {
+        (Some(a), Some(b)) => Ok(f(a, b)),
+        _ => Err(OperationError::new("domain error", condition.to_string())),
+    };
+
+    match function.as_str() {
+        "==" => Ok(value_atoms == arg_atoms),
+        "!=" => Ok(value_atoms != arg_atoms),
+        "<" => compare(|a, b| a < b),
+        "<=" => compare(|a, b| a <= b),
+        ">" => compare(|a, b| a > b),
+        ">=" => compare(|a, b| a >= b),
+        "includes" => Ok(arg_atoms.iter().all(|atom| value_atoms.contains(atom))),
+        "excludes" => Ok(arg_atoms.iter().all(|atom| !value_atoms.contains(atom))),
+        _ => Err(OperationError::new("unknown function", function.clone())),
+    }
+}
+
+fn mutate(schema: &TableSchema, row: &mut Row, mutation: &Value) -> Result<(), OperationError> {
+    let (name, mutator, arg) = match mutation.as_array().map(Vec::as_slice) {
+        Some([Value::String(name), Value::String(mutator), arg]) => (name, mutator, arg),
+        _ => return Err(OperationError::new("syntax error", mutation.to_string())),
+    };
+    let column = schema
+        .columns
+        .get(name)
+        .ok_or_else(|| OperationError::new("unknown column", name.clone()))?;
+    let value = atoms(&row[name]);
+    let arg_atoms = atoms(arg);
+
+    let mutated = match mutator.as_str() {
+        "insert" if column.r#type.is_map() => {
+            let mut value = value;
+            for pair in arg_atoms {
+                if !value.iter().any(|existing| existing[0] == pair[0]) {
+                    value.push(pair);
+                }
+            }
+            value
+        }
+        "insert" => value.into_iter().chain(arg_atoms).collect(),
+        // NOTE(mnaser): Deleting from a map takes either a set of keys or a map
+        //               of key/value pairs which must both match.
+        "delete" if column.r#type.is_map() && arg.get(0) == Some(&json!("map")) => value
+            .into_iter()
+            .filter(|pair| !arg_atoms.contains(pair))
+            .collect(),
+        "delete" if column.r#type.is_map() => value
+            .into_iter()
+            .filter(|pair| !arg_atoms.contains(&pair[0]))
+            .collect(),
+        "delete" => value
+            .into_iter()
+            .filter(|atom| !arg_atoms.contains(atom))
+            .collect(),
+        "+=" | "-=" | "*=" | "/=" | "%=" => value
+            .into_iter()
+            .map(|atom| arithmetic(mutator, &atom, arg))
+            .collect::<Result<_, _>>()?,
+        _ => return Err(OperationError::new("unknown mutator", mutator.clone())),
+    };
+
+    let mutated = canonical(&column.r#type, atoms(&json!(["set", mutated])));
+    ovsdb_client::validation::validate_value(name, &column.r#type, &mutated)
+        .map_err(|e| OperationError::new("constraint violation", e.to_string()))?;
+    row.insert(name.clone(), mutated);
+
+    Ok(())
+}
+
+fn arithmetic(mutator: &str, atom: &Value, arg: &Value) -> Result<Value, OperationError> {
+    let domain = || OperationError::new("domain error", format!("{} {} {}", atom, mutator, arg));
+
+    if let (Some(a), Some(b)) = (atom.as_i64(), arg.as_i64()) {
+        let result = match mutator {
+            "+=" => a.checked_add(b),
+            "-=" => a.checked_sub(b),
+            "*=" => a.checked_mul(b),
+            "/=" => a.checked_div(b),
+            _ => a.checked_rem(b),
+        };
+        return result.map(Value::from).ok_or_else(domain);
+    }
+
+    match (atom.as_f64(), arg.as_f64()) {
+        (Some(a), Some(b)) => match mutator {
+            "+=" => Ok(json!(a + b)),
+            "-=" => Ok(json!(a - b)),
+            "*=" => Ok(json!(a * b)),
+            "/=" if b != 0.0 => Ok(json!(a / b)),
+            _ => Err(domain()),
+        },
+        _ => Err(domain()),
+    }
+}
@@ -0,0 +1,423 @@
+//! An embeddable OVSDB server
+//!
+//! [`Server`] hosts databases loaded from their schemas and keeps their rows
+//! in memory. It answers `list_dbs`, `get_schema`, `transact`, `monitor`,
+//! `monitor_cancel` and `echo`, sending `update` notifications to monitors
+//! after every committed transaction, on `punix:` and `ptcp:` listeners or any
+//! other stream.
+//!
+//! [`Server::relay`] mirrors a database of another server instead, answering
+//! monitors locally and forwarding transactions upstream, as `ovsdb-server`
+//! does for `relay:<db>:<remote>` databases.
+
+mod database;
+mod monitor;
+mod relay;
+
+use database::{Database, Outcome, Tables, with_uuid};
+use monitor::Monitor;
+use ovsdb_client::transports::listener::{
+    Connection, Listener, ListenerError, MessageReceiver, MessageSender, framed,
+};
+use relay::Upstream;
+use serde_json::{Map, Value, json};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+use thiserror::Error;
+use tokio::{sync::mpsc, task::JoinHandle};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Listener error: {0}")]
+    Listener(#[from] ListenerError),
+
+    #[error("Upstream error: {0}")]
+    Rpc(#[from] jsonrpsee::core::ClientError),
+}
+
+struct Session {
+    sender: mpsc::UnboundedSender<Value>,
+
+    monitors: Vec<Monitor>,
+}
+
+/// A transaction of a client blocked by one of its `wait` operations
+struct Blocked {
+    connection: u64,
+
+    id: Value,
+
+    db_name: String,
+
+    operations: Vec<Value>,
+
+    started: Instant,
+}
+
+#[derive(Default)]
+struct State {
+    databases: BTreeMap<String, Database>,
+
+    connections: HashMap<u64, Session>,
+
+    next_connection: u64,
+
+    /// Transactions run again after each commit to their database
+    blocked: BTreeMap<u64, Blocked>,
+
+    next_blocked: u64,
+
+    /// Databases relayed from another server, which run their transactions
+    upstreams: HashMap<String, Upstream>,
+}
+
+/// An in-process OVSDB server storing its rows in memory
+///
+/// Cloning the server gives another handle to the same databases.
+#[derive(Clone, Default)]
+pub struct Server {
+    state: Arc<Mutex<State>>,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Host an empty database described by the schema.
+    pub fn with_database(self, schema: Value) -> Result<Self, Error> {
+        let database = Database::new(schema)?;
+        self.state
+            .lock()
+            .unwrap()
+            .databases
+            .insert(database.schema.name.clone(), database);
+
+        Ok(self)
+    }
+
+    /// Host an empty database described by a `.ovsschema` file.
+    pub fn with_schema_file(self, path: impl AsRef<Path>) -> Result<Self, Error> {
+        let schema = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        self.with_database(schema)
+    }
+
+    /// Listen for clients on a passive remote, such as `punix:/run/db.sock`
+    /// or `ptcp:6641:127.0.0.1`, until the task is aborted.
+    pub async fn listen(&self, remote: &str) -> Result<JoinHandle<()>, Error> {
+        Ok(self.accept(Listener::bind(remote).await?))
+    }
+
+    /// Answer the clients connecting to the listener until the task is
+    /// aborted.
+    pub fn accept(&self, listener: Listener) -> JoinHandle<()> {
+        let server = self.clone();
+
+        tokio::spawn(async move {
+            while let Ok((sender, receiver)) = listener.accept().await {
+                server.serve_framed(sender, receiver);
+            }
+        })
+    }
+
+    /// Answer the requests of a client connected over the stream.
+    pub fn serve(&self, connection: impl Connection + 'static) -> JoinHandle<()> {
+        let (sender, receiver) = framed(connection);
+        self.serve_framed(sender, receiver)
+    }
+
+    /// Answer the requests of a client connected over framed halves.
+    pub fn serve_framed(
+        &self,
+        mut sender: MessageSender,
+        mut receiver: MessageReceiver,
+    ) -> JoinHandle<()> {
+        let (outgoing, mut messages) = mpsc::unbounded_channel::<Value>();
+        let id = {
+            let mut state = self.state.lock().unwrap();
+            let id = state.next_connection;
+            state.next_connection += 1;
+            state.connections.insert(
+                id,
+                Session {
+                    sender: outgoing,
+                    monitors: vec![],
+                },
+            );
+            id
+        };
+
+        tokio::spawn(async move {
+            while let Some(message) = messages.recv().await {
+                if sender.send(&message).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let server = self.clone();
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = receiver.receive().await {
+                server.handle(id, message);
+            }
+
+            let mut state = server.state.lock().unwrap();
+            state.connections.remove(&id);
+            state.blocked.retain(|_, blocked| blocked.connection != id);
+        })
+    }
+
+    /// Returns the rows of a table, including their `_uuid` column.
+    pub fn rows(&self, db_name: &str, table: &str) -> Vec<Map<String, Value>> {
+        let state = self.state.lock().unwrap();
+        state
+            .databases
+            .get(db_name)
+            .and_then(|db| db.tables.get(table))
+            .map(|rows| {
+                rows.iter()
+                    .map(|(uuid, row)| with_uuid(*uuid, row))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Run a transaction directly, such as to seed rows, notifying the
+    /// monitors of connected clients. Its `wait` operations which are not
+    /// satisfied time out at once.
+    pub fn transact(&self, db_name: &str, operations: Vec<Value>) -> Vec<Value> {
+        let mut state = self.state.lock().unwrap();
+        match state.transact(db_name, &operations, None) {
+            Ok(Outcome::Done(results, _)) => results,
+            Ok(Outcome::Blocked(_)) => unreachable!("transactions without a start never block"),
+            Err(error) => vec![error],
+        }
+    }
+
+    /// Run a blocked transaction again at each of its deadlines, until it
+    /// is answered.
+    fn expire(&self, key: u64, deadline: Instant) {
+        let server = self.clone();
+
+        tokio::spawn(async move {
+            let mut deadline = deadline;
+            loop {
+                tokio::time::sleep_until(deadline.into()).await;
+                let next = server.state.lock().unwrap().retry(key);
+                match next {
+                    Some(next) => deadline = next,
+                    None => break,
+                }
+            }
+        });
+    }
+
+    fn handle(&self, connection: u64, message: Value) {
+        let Some(method) = message["method"].as_str().map(str::to_owned) else {
+            // Replies to requests sent by the server are ignored
+            return;
+        };
+
+        let mut state = self.state.lock().unwrap();
+
+        let params = message["params"].as_array().cloned().unwrap_or_default();
+        if method == "transact" {
+            if let Some(upstream) = params
+                .first()
+                .and_then(Value::as_str)
+                .and_then(|db_name| state.upstreams.get(db_name))
+            {
+                let Some(session) = state.connections.get(&connection) else {
+                    return;
+                };
+                let (upstream, sender) = (upstream.clone(), session.sender.clone());
+
+                // NOTE(mnaser): The reply of the upstream server is awaited outside of the
+                //               lock, so other requests are answered in the meantime.
+                tokio::spawn(async move {
+                    let _ = sender.send(response(&message["id"], upstream(params).await));
+                });
+                return;
+            }
+        }
+
+        let result = match method.as_str() {
+            "list_dbs" => Ok(json!(state.databases.keys().collect::<Vec<_>>())),
+            "get_schema" => state.database(&params).map(|db| db.raw_schema.clone()),
+            "transact" => match params.split_first() {
+                Some((Value::String(db_name), operations)) => {
+                    let started = Instant::now();
+                    match state.transact(db_name, operations, Some(started)) {
+                        Ok(Outcome::Done(results, _)) => Ok(Value::from(results)),
+                        Ok(Outcome::Blocked(deadline)) => {
+                            let key = state.block(Blocked {
+                                connection,
+                                id: message["id"].clone(),
+                                db_name: db_name.clone(),
+                                operations: operations.to_vec(),
+                                started,
+                            });
+                            if let Some(deadline) = deadline {
+                                self.expire(key, deadline);
+                            }
+                            return;
+                        }
+                        Err(error) => Err(error),
+                    }
+                }
+                _ => Err(error("syntax error", "expected a database name")),
+            },
+            "monitor" => state.monitor(connection, &params),
+            "monitor_cancel" => state.monitor_cancel(connection, &params),
+            "echo" => Ok(Value::Array(params)),
+            _ => Err(error("unknown method", &method)),
+        };
+
+        if let Some(connection) = state.connections.get(&connection) {
+            let _ = connection.sender.send(response(&message["id"], result));
+        }
+    }
+}
+
+fn response(id: &Value, result: Result<Value, Value>) -> Value {
+    match result {
+        Ok(result) => json!({"id": id, "result": result, "error": null}),
+        Err(error) => json!({"id": id, "result": null, "error": error}),
+    }
+}
+
+fn error(error: &str, details: &str) -> Value {
+    json!({"error": error, "details": details})
+}
+
+impl State {
+    fn database(&self, params: &[Value]) -> Result<&Database, Value> {
+        let db_name = params.first().and_then(Value::as_str).unwrap_or_default();
+        self.databases
+            .get(db_name)
+            .ok_or_else(|| error("unknown database", db_name))
+    }
+
+    fn transact(
+        &mut self,
+        db_name: &str,
+        operations: &[Value],
+        started: Option<Instant>,
+    ) -> Result<Outcome, Value> {
+        let database = self
+            .databases
+            .get_mut(db_name)
+            .ok_or_else(|| error("unknown database", db_name))?;
+
+        let outcome = database.transact(operations, started);
+        if let Outcome::Done(_, Some(old)) = &outcome {
+            self.notify(db_name, old);
+            self.unblock(db_name);
+        }
+
+        Ok(outcome)
+    }
+
+    fn block(&mut self, blocked: Blocked) -> u64 {
+        let key = self.next_blocked;
+        self.next_blocked += 1;
+        self.blocked.insert(key, blocked);
+        key
+    }
+
+    /// Run again the transactions blocked on a database which just changed.
+    fn unblock(&mut self, db_name: &str) {
+        let keys: Vec<u64> = self
+            .blocked
+            .iter()
+            .filter(|(_, blocked)| blocked.db_name == db_name)
+            .map(|(key, _)| *key)
+            .collect();
+
+        for key in keys {
+            self.retry(key);
+        }
+    }
+
+    /// Run a blocked transaction again, answering it unless it is still
+    /// blocked. Returns its next deadline if it is.
+    fn retry(&mut self, key: u64) -> Option<Instant> {
+        let blocked = self.blocked.remove(&key)?;
+        let result =
+            match self.transact(&blocked.db_name, &blocked.operations, Some(blocked.started)) {
+                Ok(Outcome::Blocked(deadline)) => {
+                    self.blocked.insert(key, blocked);
+                    return deadline;
+                }
+                Ok(Outcome::Done(results, _)) => Ok(Value::from(results)),
+                Err(error) => Err(error),
+            };
+
+        if let Some(connection) = self.connections.get(&blocked.connection) {
+            let _ = connection.sender.send(response(&blocked.id, result));
+        }
+        None
+    }
+
+    /// Send the changes made to a database since `old` to its monitors.
+    fn notify(&self, db_name: &str, old: &Tables) {
+        let Some(database) = self.databases.get(db_name) else {
+            return;
+        };
+
+        for connection in self.connections.values() {
+            for monitor in connection.monitors.iter().filter(|m| m.db_name == db_name) {
+                if let Some(updates) = monitor.updates(old, &database.tables) {
+                    let notification =
+                        json!({"id": null, "method": "update", "params": [monitor.id, updates]});
+                    let _ = connection.sender.send(notification);
+                }
+            }
+        }
+    }
+
+    fn monitor(&mut self, connection: u64, params: &[Value]) -> Result<Value, Value> {
+        let (db_name, id, requests) = match params {
+            [Value::String(db_name), id, Value::Object(requests)] => (db_name, id, requests),
+            _ => return Err(error("syntax error", "expected [db, id, requests]")),
+        };
+        let database = self
+            .databases
+            .get(db_name)
+            .ok_or_else(|| error("unknown database", db_name))?;
+        let monitor = Monitor::new(db_name, id.clone(), requests, database)?;
+
+        let connection = self.connections.get_mut(&connection).unwrap();
+        if connection.monitors.iter().any(|m| &m.id == id) {
+            return Err(error("duplicate monitor ID", &id.to_string()));
+        }
+
+        let initial = monitor.initial(&database.tables);
+        connection.monitors.push(monitor);
+
+        Ok(initial)
+    }
+
+    fn monitor_cancel(&mut self, connection: u64, params: &[Value]) -> Result<Value, Value> {
+        let id = params.first().cloned().unwrap_or_default();
+        let connection = self.connections.get_mut(&connection).unwrap();
+
+        let count = connection.monitors.len();
+        connection.monitors.retain(|m| m.id != id);
+        if connection.monitors.len() == count {
+            return Err(error("unknown monitor", &id.to_string()));
+        }
+
+        Ok(json!({}))
+    }
+}
@@ -0,0 +1,152 @@
+use crate::database::{Database, Row, Tables, filter};
+use serde_json::{Map, Value, json};
+
+/// What a monitor reports for one table
+struct TableMonitor {
+    /// Monitored columns, `None` meaning all of them
+    columns: Option<Vec<Value>>,
+
+    initial: bool,
+
+    insert: bool,
+
+    delete: bool,
+
+    modify: bool,
+}
+
+impl TableMonitor {
+    /// Merge the `<monitor-request>` objects given for a table.
+    fn new(requests: &[Value]) -> Self {
+        let select = |name: &str| {
+            requests
+                .iter()
+                .any(|request| request["select"][name].as_bool().unwrap_or(true))
+        };
+        let columns = requests
+            .iter()
+            .map(|request| request["columns"].as_array().cloned())
+            .collect::<Option<Vec<_>>>()
+            .map(|columns| columns.into_iter().flatten().collect());
+
+        TableMonitor {
+            columns,
+            initial: select("initial"),
+            insert: select("insert"),
+            delete: select("delete"),
+            modify: select("modify"),
+        }
+    }
+
+    /// Keep only the monitored columns of a row, or all of them but `_version`
+    fn project(&self, row: &Row) -> Row {
+        match &self.columns {
+            Some(columns) => filter(row, columns),
+            None => row
+                .iter()
+                .filter(|(column, _)| *column != "_version")
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        }
+    }
+}
+
+/// A monitor registered by a client with the `monitor` method
+pub(crate) struct Monitor {
+    pub db_name: String,
+
+    pub id: Value,
+
+    tables: Vec<(String, TableMonitor)>,
+}
+
+impl Monitor {
+    pub fn new(
+        db_name: &str,
+        id: Value,
+        requests: &Map<String, Value>,
+        database: &Database,
+    ) -> Result<Self, Value> {
+        let mut tables = vec![];
+        for (table, requests) in requests {
+            let Some(schema) = database.schema.tables.get(table) else {
+                return Err(json!({"error": "unknown table", "details": table}));
+            };
+            let requests = match requests {
+                Value::Array(requests) => requests.clone(),
+                request => vec![request.clone()],
+            };
+
+            let monitor = TableMonitor::new(&requests);
+            for column in monitor.columns.iter().flatten() {
+                let column = column.as_str().unwrap_or_default();
+                if !schema.columns.contains_key(column) && column != "_version" {
+                    return Err(json!({"error": "unknown column", "details": column}));
+                }
+            }
+            tables.push((table.clone(), monitor));
+        }
+
+        Ok(Monitor {
+            db_name: db_name.to_owned(),
+            id,
+            tables,
+        })
+    }
+
+    /// The `<table-updates>` replied to the `monitor` request
+    pub fn initial(&self, tables: &Tables) -> Value {
+        let mut updates = Map::new();
+        for (table, monitor) in self.tables.iter().filter(|(_, m)| m.initial) {
+            let rows: Map<String, Value> = tables[table]
+                .iter()
+                .map(|(uuid, row)| (uuid.to_string(), json!({"new": monitor.project(row)})))
+                .collect();
+            if !rows.is_empty() {
+                updates.insert(table.clone(), Value::Object(rows));
+            }
+        }
+
+        Value::Object(updates)
+    }
+
+    /// The `<table-updates>` of a committed transaction, if any monitored
+    /// column changed
+    pub fn updates(&self, old: &Tables, new: &Tables) -> Option<Value> {
+        let mut updates = Map::new();
+        for (table, monitor) in &self.tables {
+            let (old, new) = (&old[table], &new[table]);
+            let mut rows = Map::new();
+
+            for (uuid, row) in new {
+                let update = match old.get(uuid) {
+                    None if monitor.insert => json!({"new": monitor.project(row)}),
+                    Some(old_row) if monitor.modify => {
+                        let (old_row, new_row) = (monitor.project(old_row), monitor.project(row));
+                        let changed: Map<String, Value> = old_row
+                            .into_iter()
+                            .filter(|(column, value)| new_row.get(column) != Some(value))
+                            .collect();
+                        if changed.is_empty() {
+                            continue;
+                        }
+                        json!({"old": changed, "new": new_row})
+                    }
+                    _ => continue,
+                };
+                rows.insert(uuid.to_string(), update);
+            }
+            if monitor.delete {
+                for (uuid, row) in old.iter().filter(|(uuid, _)| !new.contains_key(uuid)) {
+                    rows.insert(uuid.to_string(), json!({"old": monitor.project(row)}));
+                }
+            }
+
+            if !rows.is_empty() {
+                updates.insert(table.clone(), Value::Object(rows));
+            }
+        }
+
+        (!updates.is_empty()).then_some(Value::Object(updates))
+    }
+}
@@ -0,0 +1,83 @@
+use crate::{Error, Server, database::Database, error};
+use jsonrpsee::core::{client::SubscriptionClientT, params::ArrayParams, rpc_params};
+use serde_json::{Map, Value, json};
+use std::{future::Future, pin::Pin, sync::Arc};
+use tokio::task::JoinHandle;
+
+/// Runs the `transact` params of a relayed database on the upstream server
+pub(crate) type Upstream = Arc<
+    dyn Fn(Vec<Value>) -> Pin<Box<dyn Future<Output = Result<Value, Value>> + Send>> + Send + Sync,
+>;
+
+impl Server {
+    /// Relay a database of an upstream server, which stays in sync with it
+    /// until the returned task ends.
+    ///
+    /// The whole database is monitored upstream and mirrored in memory, so
+    /// `get_schema` and `monitor` requests are answered locally, while
+    /// `transact` requests are forwarded to the upstream server and its reply
+    /// returned as is. The upstream client receives every `update`
+    /// notification, so a connection is needed per relayed database.
+    pub async fn relay<C>(&self, upstream: C, db_name: &str) -> Result<JoinHandle<()>, Error>
+    where
+        C: SubscriptionClientT + Send + Sync + 'static,
+    {
+        let upstream = Arc::new(upstream);
+
+        let schema: Value = upstream.request("get_schema", rpc_params![db_name]).await?;
+        let mut database = Database::new(schema)?;
+        let requests: Map<String, Value> = database
+            .schema
+            .tables
+            .keys()
+            .map(|table| (table.clone(), json!({})))
+            .collect();
+
+        // NOTE(mnaser): The subscription is made before the monitor so that no update
+        //               sent right after the initial contents can be missed.
+        let mut updates = upstream.subscribe_to_method::<Value>("update").await?;
+        let initial: Value = upstream
+            .request("monitor", rpc_params![db_name, Value::Null, requests])
+            .await?;
+        database.apply_update(&initial);
+
+        let client = upstream.clone();
+        let forward: Upstream = Arc::new(move |params| {
+            let client = client.clone();
+            Box::pin(async move {
+                let mut array = ArrayParams::new();
+                for param in params {
+                    array
+                        .insert(param)
+                        .map_err(|e| error("syntax error", &e.to_string()))?;
+                }
+                client
+                    .request("transact", array)
+                    .await
+                    .map_err(|e| error("upstream error", &e.to_string()))
+            })
+        });
+
+        {
+            let mut state = self.state.lock().unwrap();
+            state.databases.insert(db_name.to_owned(), database);
+            state.upstreams.insert(db_name.to_owned(), forward);
+        }
+
+        let server = self.clone();
+        let db_name = db_name.to_owned();
+        Ok(tokio::spawn(async move {
+            while let Some(Ok(params)) = updates.next().await {
+                let Some(updates) = params.get(1) else {
+                    continue;
+                };
+
+                let mut state = server.state.lock().unwrap();
+                if let Some(database) = state.databases.get_mut(&db_name) {
+                    let old = database.apply_update(updates);
+                    state.notify(&db_name, &old);
+                }
+            }
+        }))
+    }
+}
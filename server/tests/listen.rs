@@ -0,0 +1,106 @@
+use jsonrpsee::core::client::SubscriptionClientT;
+use ovsdb_client::{
+    rpc::{self, RpcClient},
+    schema::{MonitorRequest, UpdateNotification},
+    transact::{Operation, TransactClient},
+    transports::listener::ListenerError,
+};
+use ovsdb_server::{Error, Server};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+fn server() -> Server {
+    Server::new()
+        .with_database(json!({
+            "name": "OVN_Northbound",
+            "version": "7.3.0",
+            "tables": {
+                "Logical_Switch": {
+                    "columns": {"name": {"type": "string"}},
+                    "isRoot": true
+                }
+            }
+        }))
+        .unwrap()
+}
+
+fn insert(name: &str) -> Operation {
+    Operation::Insert {
+        table: "Logical_Switch".to_owned(),
+        row: HashMap::from([("name".to_owned(), json!(name))]),
+        uuid_name: None,
+    }
+}
+
+#[tokio::test]
+async fn test_ptcp() {
+    let server = server();
+
+    // Find a free port, then listen on it through the remote syntax
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let listener = server
+        .listen(&format!("ptcp:{port}:127.0.0.1"))
+        .await
+        .unwrap();
+
+    // Changes made by one client are sent to the monitors of the others
+    let watcher = rpc::connect_tcp(("127.0.0.1", port)).await.unwrap();
+    let mut updates = watcher
+        .subscribe_to_method::<UpdateNotification<Value>>("update")
+        .await
+        .unwrap();
+    let initial = watcher
+        .monitor(
+            "OVN_Northbound",
+            Value::Null,
+            HashMap::from([("Logical_Switch".to_owned(), MonitorRequest::default())]),
+        )
+        .await
+        .unwrap();
+    assert!(initial.is_empty());
+
+    let writer = rpc::connect_tcp(("127.0.0.1", port)).await.unwrap();
+    writer
+        .transact("OVN_Northbound", vec![insert("ls0")])
+        .await
+        .unwrap();
+
+    let update = updates.next().await.unwrap().unwrap();
+    let row = update.message["Logical_Switch"].values().next().unwrap();
+    assert_eq!(row.new.as_ref().unwrap()["name"], "ls0");
+    assert_eq!(server.rows("OVN_Northbound", "Logical_Switch").len(), 1);
+
+    listener.abort();
+}
+
+#[tokio::test]
+async fn test_punix() {
+    let server = server();
+    let path = std::env::temp_dir().join(format!("ovsdb-server-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = server
+        .listen(&format!("punix:{}", path.display()))
+        .await
+        .unwrap();
+
+    let client = rpc::connect_unix(&path).await.unwrap();
+    assert_eq!(
+        client.list_databases().await.unwrap(),
+        vec!["OVN_Northbound"]
+    );
+
+    listener.abort();
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_invalid_remote() {
+    assert!(matches!(
+        server().listen("tcp:127.0.0.1:6641").await,
+        Err(Error::Listener(ListenerError::InvalidRemote(_)))
+    ));
+}
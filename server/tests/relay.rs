@@ -0,0 +1,156 @@
+use jsonrpsee::core::{
+    client::{ClientT, SubscriptionClientT},
+    rpc_params,
+};
+use ovsdb_client::{
+    rpc::{self, RpcClient},
+    schema::{MonitorRequest, UpdateNotification},
+    transact::{Operation, TransactClient},
+};
+use ovsdb_server::Server;
+use serde_json::{Value, json};
+use std::{collections::HashMap, time::Duration};
+
+fn upstream() -> Server {
+    Server::new()
+        .with_database(json!({
+            "name": "OVN_Northbound",
+            "version": "7.3.0",
+            "tables": {
+                "Logical_Switch": {
+                    "columns": {"name": {"type": "string"}},
+                    "isRoot": true
+                }
+            }
+        }))
+        .unwrap()
+}
+
+fn insert(name: &str) -> Operation {
+    Operation::Insert {
+        table: "Logical_Switch".to_owned(),
+        row: HashMap::from([("name".to_owned(), json!(name))]),
+        uuid_name: None,
+    }
+}
+
+fn connect(server: &Server) -> impl SubscriptionClientT + Send + Sync + 'static {
+    let (client, connection) = tokio::io::duplex(64 * 1024);
+    server.serve(connection);
+    rpc::connect_stream(client)
+}
+
+fn names(server: &Server) -> Vec<String> {
+    let mut names = server
+        .rows("OVN_Northbound", "Logical_Switch")
+        .into_iter()
+        .map(|row| row["name"].as_str().unwrap().to_owned())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+async fn wait_for(server: &Server, expected: &[&str]) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while names(server) != expected {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_relay_mirrors_upstream() {
+    let upstream = upstream();
+    upstream.transact(
+        "OVN_Northbound",
+        vec![json!({"op": "insert", "table": "Logical_Switch", "row": {"name": "ls0"}})],
+    );
+
+    let relay = Server::new();
+    let task = relay
+        .relay(connect(&upstream), "OVN_Northbound")
+        .await
+        .unwrap();
+
+    // The initial contents are mirrored with the UUIDs of the upstream rows
+    let client = connect(&relay);
+    assert_eq!(
+        client.list_databases().await.unwrap(),
+        vec!["OVN_Northbound"]
+    );
+    assert_eq!(names(&relay), vec!["ls0"]);
+    assert_eq!(
+        relay.rows("OVN_Northbound", "Logical_Switch")[0]["_uuid"],
+        upstream.rows("OVN_Northbound", "Logical_Switch")[0]["_uuid"]
+    );
+
+    // Changes made upstream reach the relay and its monitors
+    let mut updates = client
+        .subscribe_to_method::<UpdateNotification<Value>>("update")
+        .await
+        .unwrap();
+    client
+        .monitor(
+            "OVN_Northbound",
+            Value::Null,
+            HashMap::from([("Logical_Switch".to_owned(), MonitorRequest::default())]),
+        )
+        .await
+        .unwrap();
+
+    upstream.transact(
+        "OVN_Northbound",
+        vec![json!({"op": "delete", "table": "Logical_Switch", "where": []})],
+    );
+
+    let update = updates.next().await.unwrap().unwrap();
+    let row = update.message["Logical_Switch"].values().next().unwrap();
+    assert_eq!(row.old.as_ref().unwrap()["name"], "ls0");
+    assert!(row.new.is_none());
+    assert!(names(&relay).is_empty());
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn test_relay_forwards_transactions() {
+    let upstream = upstream();
+    let relay = Server::new();
+    let task = relay
+        .relay(connect(&upstream), "OVN_Northbound")
+        .await
+        .unwrap();
+
+    // Writes made through the relay are run by the upstream server
+    let client = connect(&relay);
+    let results = client
+        .transact("OVN_Northbound", vec![insert("ls0")])
+        .await
+        .unwrap();
+    assert_eq!(
+        json!(["uuid", results[0].uuid().unwrap()]),
+        upstream.rows("OVN_Northbound", "Logical_Switch")[0]["_uuid"]
+    );
+    assert_eq!(names(&upstream), vec!["ls0"]);
+    wait_for(&relay, &["ls0"]).await;
+
+    // The results of failed transactions are returned as they are
+    let results: Vec<Value> = client
+        .request(
+            "transact",
+            rpc_params![
+                "OVN_Northbound",
+                json!({"op": "insert", "table": "Logical_Switch", "row": {"name": "ls1"}}),
+                json!({"op": "abort"})
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(results[1]["error"], "aborted");
+    assert_eq!(names(&upstream), vec!["ls0"]);
+    assert_eq!(names(&relay), vec!["ls0"]);
+
+    task.abort();
+}
@@ -0,0 +1,202 @@
+use jsonrpsee::core::{client::ClientT, rpc_params};
+use ovsdb_client::rpc;
+use ovsdb_server::Server;
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+
+fn server() -> Server {
+    Server::new()
+        .with_database(json!({
+            "name": "OVN_Northbound",
+            "version": "7.3.0",
+            "tables": {
+                "Logical_Switch": {
+                    "columns": {
+                        "name": {"type": "string"},
+                        "ports": {
+                            "type": {
+                                "key": {"type": "uuid", "refTable": "Logical_Switch_Port"},
+                                "min": 0,
+                                "max": "unlimited"
+                            }
+                        }
+                    },
+                    "isRoot": true
+                },
+                "Logical_Switch_Port": {
+                    "columns": {"name": {"type": "string"}}
+                }
+            }
+        }))
+        .unwrap()
+}
+
+fn names(server: &Server, table: &str) -> Vec<String> {
+    let mut names = server
+        .rows("OVN_Northbound", table)
+        .into_iter()
+        .map(|row| row["name"].as_str().unwrap().to_owned())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+fn switch_with_port() -> Vec<Value> {
+    vec![
+        json!({"op": "insert", "table": "Logical_Switch_Port", "row": {"name": "lsp0"}, "uuid-name": "lsp0"}),
+        json!({"op": "insert", "table": "Logical_Switch", "row": {"name": "ls0", "ports": ["named-uuid", "lsp0"]}}),
+    ]
+}
+
+fn wait(until: &str, rows: Value, timeout: Option<u64>) -> Value {
+    let mut wait = json!({
+        "op": "wait",
+        "table": "Logical_Switch",
+        "where": [],
+        "columns": ["name"],
+        "until": until,
+        "rows": rows
+    });
+    if let Some(timeout) = timeout {
+        wait["timeout"] = json!(timeout);
+    }
+    wait
+}
+
+#[test]
+fn test_dangling_reference() {
+    let server = server();
+
+    let missing = uuid::Uuid::new_v4().to_string();
+    let results = server.transact(
+        "OVN_Northbound",
+        vec![json!({"op": "insert", "table": "Logical_Switch", "row": {"name": "ls0", "ports": ["uuid", missing]}})],
+    );
+
+    // The commit error follows the results of the operations
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[1]["error"], "referential integrity violation");
+    assert!(names(&server, "Logical_Switch").is_empty());
+}
+
+#[test]
+fn test_delete_referenced_row() {
+    let server = server();
+    server.transact("OVN_Northbound", switch_with_port());
+
+    let results = server.transact(
+        "OVN_Northbound",
+        vec![json!({"op": "delete", "table": "Logical_Switch_Port", "where": []})],
+    );
+    assert_eq!(results[0]["count"], 1);
+    assert_eq!(results[1]["error"], "referential integrity violation");
+    assert_eq!(names(&server, "Logical_Switch_Port"), ["lsp0"]);
+
+    // Dropping the reference in the same transaction allows the deletion
+    let results = server.transact(
+        "OVN_Northbound",
+        vec![
+            json!({"op": "update", "table": "Logical_Switch", "where": [], "row": {"ports": ["set", []]}}),
+            json!({"op": "delete", "table": "Logical_Switch_Port", "where": []}),
+        ],
+    );
+    assert_eq!(results.len(), 2);
+    assert!(names(&server, "Logical_Switch_Port").is_empty());
+}
+
+#[test]
+fn test_garbage_collection() {
+    let server = server();
+
+    // Rows of tables which are not roots are kept only while referenced
+    let mut operations = switch_with_port();
+    operations
+        .push(json!({"op": "insert", "table": "Logical_Switch_Port", "row": {"name": "orphan"}}));
+    server.transact("OVN_Northbound", operations);
+    assert_eq!(names(&server, "Logical_Switch_Port"), ["lsp0"]);
+
+    server.transact(
+        "OVN_Northbound",
+        vec![json!({"op": "delete", "table": "Logical_Switch", "where": []})],
+    );
+    assert!(names(&server, "Logical_Switch").is_empty());
+    assert!(names(&server, "Logical_Switch_Port").is_empty());
+}
+
+#[test]
+fn test_wait() {
+    let server = server();
+    server.transact("OVN_Northbound", switch_with_port());
+
+    let results = server.transact(
+        "OVN_Northbound",
+        vec![wait("==", json!([{"name": "ls0"}]), Some(0))],
+    );
+    assert_eq!(results, [json!({})]);
+
+    let results = server.transact(
+        "OVN_Northbound",
+        vec![wait("!=", json!([{"name": "ls0"}]), Some(0))],
+    );
+    assert_eq!(results[0]["error"], "timed out");
+}
+
+#[tokio::test]
+async fn test_wait_blocks() {
+    let server = server();
+    let (client, connection) = tokio::io::duplex(64 * 1024);
+    server.serve(connection);
+    let client = rpc::connect_stream(client);
+
+    // The transaction runs once another one inserts the awaited row
+    let waiting = tokio::spawn(async move {
+        client
+            .request::<Vec<Value>, _>(
+                "transact",
+                rpc_params![
+                    "OVN_Northbound",
+                    wait("==", json!([{"name": "ls0"}]), None),
+                    json!({"op": "insert", "table": "Logical_Switch", "row": {"name": "ls1"}})
+                ],
+            )
+            .await
+            .unwrap()
+    });
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!waiting.is_finished());
+    server.transact(
+        "OVN_Northbound",
+        vec![json!({"op": "insert", "table": "Logical_Switch", "row": {"name": "ls0"}})],
+    );
+
+    let results = tokio::time::timeout(Duration::from_secs(5), waiting)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(results[0], json!({}));
+    assert_eq!(names(&server, "Logical_Switch"), ["ls0", "ls1"]);
+}
+
+#[tokio::test]
+async fn test_wait_times_out() {
+    let server = server();
+    let (client, connection) = tokio::io::duplex(64 * 1024);
+    server.serve(connection);
+    let client = rpc::connect_stream(client);
+
+    let started = Instant::now();
+    let results = client
+        .request::<Vec<Value>, _>(
+            "transact",
+            rpc_params![
+                "OVN_Northbound",
+                wait("==", json!([{"name": "ls0"}]), Some(100))
+            ],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(results[0]["error"], "timed out");
+    assert!(started.elapsed() >= Duration::from_millis(100));
+}
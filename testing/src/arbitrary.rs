@@ -0,0 +1,128 @@
+//! Proptest strategies generating values valid for a schema
+//!
+//! The strategies follow the column types of a schema: atoms respect the
+//! `enum`, integer, real and length constraints of their base type, sets and
+//! maps hold distinct keys and as many elements as the column allows, up to
+//! [`MAX_ELEMENTS`].
+
+use ovsdb_client::schema::{AtomicType, BaseType, ColumnType, TableSchema};
+use ovsdb_schema::{OvsdbAtom, OvsdbValue, json_to_ovsdb_atom};
+use proptest::{collection, prelude::*, sample};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Largest number of elements generated for a set or a map, whatever the
+/// maximum of its column.
+pub const MAX_ELEMENTS: usize = 8;
+
+/// Longest string generated when its base type has no `maxLength`.
+const MAX_LENGTH: u64 = 16;
+
+/// Generate atoms of the base type.
+///
+/// # Panics
+///
+/// If the `enum` of the base type holds values which are not atoms.
+pub fn atom(base: &BaseType) -> BoxedStrategy<OvsdbAtom> {
+    if let Some(values) = &base.r#enum {
+        let atoms: Vec<OvsdbAtom> = values
+            .iter()
+            .map(|value| json_to_ovsdb_atom(value).expect("enum values must be atoms"))
+            .collect();
+        return sample::select(atoms).boxed();
+    }
+
+    match base.r#type {
+        AtomicType::Integer => (base.min_integer.unwrap_or(i64::MIN)
+            ..=base.max_integer.unwrap_or(i64::MAX))
+            .prop_map(OvsdbAtom::Integer)
+            .boxed(),
+        AtomicType::Real => match (base.min_real, base.max_real) {
+            (None, None) => {
+                use proptest::num::f64::{NEGATIVE, NORMAL, POSITIVE, SUBNORMAL, ZERO};
+                (POSITIVE | NEGATIVE | NORMAL | SUBNORMAL | ZERO)
+                    .prop_map(OvsdbAtom::Real)
+                    .boxed()
+            }
+            // NOTE(mnaser): The missing bound is halved so that the width of
+            //               the range stays finite.
+            (min, max) => (min.unwrap_or(f64::MIN / 2.0)..=max.unwrap_or(f64::MAX / 2.0))
+                .prop_map(OvsdbAtom::Real)
+                .boxed(),
+        },
+        AtomicType::Boolean => any::<bool>().prop_map(OvsdbAtom::Boolean).boxed(),
+        AtomicType::String => {
+            let min = base.min_length.unwrap_or(0);
+            let max = base.max_length.unwrap_or(min + MAX_LENGTH).max(min);
+            collection::vec(any::<char>(), min as usize..=max as usize)
+                .prop_map(|chars| OvsdbAtom::String(chars.into_iter().collect()))
+                .boxed()
+        }
+        AtomicType::Uuid => any::<u128>()
+            .prop_map(|uuid| OvsdbAtom::Uuid(Uuid::from_u128(uuid)))
+            .boxed(),
+    }
+}
+
+/// Generate values of the column type: a bare atom for columns holding
+/// exactly one, and a set or a map otherwise.
+pub fn value(column: &ColumnType) -> BoxedStrategy<OvsdbValue> {
+    if column.is_scalar() {
+        return atom(&column.key).prop_map(OvsdbValue::Atom).boxed();
+    }
+
+    let size = elements(column);
+    match &column.value {
+        Some(value) => collection::btree_map(atom(&column.key), atom(value), size)
+            .prop_map(|map| OvsdbValue::Map(map.into_iter().collect()))
+            .boxed(),
+        None => collection::btree_set(atom(&column.key), size)
+            .prop_map(|set| OvsdbValue::Set(set.into_iter().collect()))
+            .boxed(),
+    }
+}
+
+/// Generate rows of the table, with a value for each of its columns.
+pub fn row(table: &TableSchema) -> BoxedStrategy<HashMap<String, OvsdbValue>> {
+    table
+        .columns
+        .iter()
+        .map(|(name, column)| {
+            let name = name.clone();
+            value(&column.r#type).prop_map(move |value| (name.clone(), value))
+        })
+        .collect::<Vec<_>>()
+        .prop_map(|columns| columns.into_iter().collect())
+        .boxed()
+}
+
+/// The range of the number of elements of a set or a map of the column.
+fn elements(column: &ColumnType) -> std::ops::RangeInclusive<usize> {
+    let min = usize::try_from(column.min).unwrap_or(usize::MAX);
+    let max = column
+        .max
+        .and_then(|max| usize::try_from(max).ok())
+        .unwrap_or(usize::MAX)
+        .min(MAX_ELEMENTS)
+        .min(distinct(&column.key).unwrap_or(usize::MAX));
+
+    min..=max.max(min)
+}
+
+/// The number of distinct atoms of the base type, if there are few enough to
+/// bound the size of a set.
+fn distinct(base: &BaseType) -> Option<usize> {
+    if let Some(values) = &base.r#enum {
+        return Some(values.len());
+    }
+
+    match base.r#type {
+        AtomicType::Boolean => Some(2),
+        AtomicType::Integer => {
+            let min = base.min_integer.unwrap_or(i64::MIN);
+            let max = base.max_integer.unwrap_or(i64::MAX);
+            usize::try_from(max.abs_diff(min)).ok()?.checked_add(1)
+        }
+        _ => None,
+    }
+}
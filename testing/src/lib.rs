@@ -0,0 +1,169 @@
+//! An in-memory OVSDB server for tests
+//!
+//! [`MockServer`] wraps the embeddable [`ovsdb_server::Server`], which hosts
+//! databases loaded from their schemas, keeps their rows in memory and
+//! answers `list_dbs`, `get_schema`, `transact`, `monitor`, `monitor_cancel`
+//! and `echo`. It connects clients over in-memory streams or a Unix socket and
+//! logs their requests, so clients can be tested without an `ovsdb-server`
+//! binary.
+//!
+//! [`Recorder`] captures the messages exchanged with a live server to a file
+//! and [`Replayer`] plays back its server side, for regression tests against
+//! real traffic.
+//!
+//! With the `proptest` feature, [`arbitrary`] generates values and rows valid
+//! for the columns of a schema, for property tests, and with the
+//! `live-tests` feature, [`live`] runs a real `ovsdb-server` for conformance
+//! tests.
+
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+#[cfg(feature = "live-tests")]
+pub mod live;
+mod recording;
+
+use ovsdb_client::{
+    OvsdbClient,
+    transports::listener::{Connection, Listener, ListenerError, framed},
+};
+use ovsdb_server::Server;
+pub use recording::{Frame, Recorder, Replayer};
+use serde_json::{Map, Value};
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+use thiserror::Error;
+use tokio::task::JoinHandle;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Listener error: {0}")]
+    Listener(#[from] ListenerError),
+
+    #[error("Server error: {0}")]
+    Server(#[from] ovsdb_server::Error),
+
+    #[error("Client error: {0}")]
+    Client(#[from] ovsdb_client::Error),
+
+    #[error("{command} failed: {output}")]
+    Command { command: String, output: String },
+
+    #[error("Expected {expected} from the client, got {actual}")]
+    Mismatch { expected: Value, actual: Value },
+
+    #[error("Unexpected message from the client: {0}")]
+    Unexpected(Value),
+
+    #[error("Client disconnected with {0} recorded frames left")]
+    Incomplete(usize),
+}
+
+/// An in-process OVSDB server storing its rows in memory
+///
+/// Cloning the server gives another handle to the same databases.
+#[derive(Clone, Default)]
+pub struct MockServer {
+    server: Server,
+
+    requests: Arc<Mutex<Vec<Value>>>,
+}
+
+impl MockServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Host an empty database described by the schema.
+    pub fn with_database(self, schema: Value) -> Result<Self, Error> {
+        Ok(MockServer {
+            server: self.server.with_database(schema)?,
+            ..self
+        })
+    }
+
+    /// Host an empty database described by a `.ovsschema` file.
+    pub fn with_schema_file(self, path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(MockServer {
+            server: self.server.with_schema_file(path)?,
+            ..self
+        })
+    }
+
+    /// Returns the underlying server.
+    pub fn server(&self) -> &Server {
+        &self.server
+    }
+
+    /// Connect a client through an in-memory stream.
+    pub fn connect(&self) -> OvsdbClient {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        self.serve(server);
+
+        ovsdb_client::rpc::connect_stream(client)
+    }
+
+    /// Listen for clients on a Unix socket until the task is aborted.
+    pub fn listen_unix(&self, path: impl AsRef<Path>) -> Result<JoinHandle<()>, Error> {
+        let listener = Listener::bind_unix(path)?;
+        let server = self.clone();
+
+        Ok(tokio::spawn(async move {
+            while let Ok((connection, _)) = listener.accept_connection().await {
+                server.serve(connection);
+            }
+        }))
+    }
+
+    /// Answer the requests of a client connected over the stream, logging
+    /// them before they reach the server.
+    pub fn serve(&self, connection: impl Connection + 'static) -> JoinHandle<()> {
+        let (proxy, server) = tokio::io::duplex(64 * 1024);
+        self.server.serve(server);
+
+        let (mut client_sender, mut client_receiver) = framed(connection);
+        let (mut server_sender, mut server_receiver) = framed(proxy);
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = server_receiver.receive().await {
+                if client_sender.send(&message).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let requests = self.requests.clone();
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = client_receiver.receive().await {
+                if message.get("method").is_some() {
+                    requests.lock().unwrap().push(message.clone());
+                }
+                if server_sender.send(&message).await.is_err() {
+                    break;
+                }
+            }
+        })
+    }
+
+    /// Returns every request received so far, in order.
+    pub fn requests(&self) -> Vec<Value> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Returns the rows of a table, including their `_uuid` column.
+    pub fn rows(&self, db_name: &str, table: &str) -> Vec<Map<String, Value>> {
+        self.server.rows(db_name, table)
+    }
+
+    /// Run a transaction directly, such as to seed rows before a test,
+    /// notifying the monitors of connected clients.
+    pub fn transact(&self, db_name: &str, operations: Vec<Value>) -> Vec<Value> {
+        self.server.transact(db_name, operations)
+    }
+}
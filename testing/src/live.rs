@@ -0,0 +1,175 @@
+//! A real `ovsdb-server` to run conformance tests against
+//!
+//! [`LiveServer`] either starts `ovsdb-server` in a temporary directory,
+//! hosting a database created by `ovsdb-tool` for each schema, or uses a
+//! server which is already running, such as the one of a development
+//! deployment of OVN, given by the socket in `OVSDB_SOCKET`.
+
+use crate::Error;
+use ovsdb_client::{OvsdbClient, rpc};
+use std::{
+    env,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    time::Duration,
+};
+use tempfile::TempDir;
+use tokio::net::UnixStream;
+
+/// The environment variable giving the socket of a running server, which
+/// is used instead of starting one
+pub const SOCKET_ENV: &str = "OVSDB_SOCKET";
+
+/// How long to wait for a started server to listen on its socket
+const START_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A real OVSDB server, listening on a Unix socket
+///
+/// The server started by the tests is stopped and its directory removed
+/// once dropped.
+pub struct LiveServer {
+    socket: PathBuf,
+
+    /// The server started for the tests and the directory of its files
+    process: Option<(Child, TempDir)>,
+}
+
+impl LiveServer {
+    /// Start `ovsdb-server` hosting an empty database for each schema.
+    ///
+    /// The binaries are looked up in the `PATH`, unless given by the
+    /// `OVSDB_SERVER` and `OVSDB_TOOL` environment variables.
+    pub async fn start<P: AsRef<Path>>(
+        schemas: impl IntoIterator<Item = P>,
+    ) -> Result<Self, Error> {
+        let dir = tempfile::tempdir()?;
+
+        let mut databases = Vec::new();
+        for (index, schema) in schemas.into_iter().enumerate() {
+            let database = dir.path().join(format!("db{index}.db"));
+            let output = Command::new(program("OVSDB_TOOL", "ovsdb-tool"))
+                .arg("create")
+                .arg(&database)
+                .arg(schema.as_ref())
+                .output()
+                .map_err(|e| failed("ovsdb-tool", e))?;
+            if !output.status.success() {
+                return Err(Error::Command {
+                    command: "ovsdb-tool create".to_owned(),
+                    output: String::from_utf8_lossy(&output.stderr).into_owned(),
+                });
+            }
+            databases.push(database);
+        }
+
+        let socket = dir.path().join("db.sock");
+        let log = dir.path().join("ovsdb-server.log");
+        let child = Command::new(program("OVSDB_SERVER", "ovsdb-server"))
+            .arg(format!("--remote=punix:{}", socket.display()))
+            .arg(format!(
+                "--unixctl={}",
+                dir.path().join("ovsdb-server.ctl").display()
+            ))
+            .arg(format!("--log-file={}", log.display()))
+            .arg("--no-chdir")
+            .args(&databases)
+            .env("OVS_RUNDIR", dir.path())
+            .env("OVS_DBDIR", dir.path())
+            .env("OVS_LOGDIR", dir.path())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| failed("ovsdb-server", e))?;
+
+        let mut server = LiveServer {
+            socket,
+            process: Some((child, dir)),
+        };
+        server.wait_listening(&log).await?;
+        Ok(server)
+    }
+
+    /// Use the server listening on the socket, which is left running.
+    pub fn with_socket(socket: impl Into<PathBuf>) -> Self {
+        LiveServer {
+            socket: socket.into(),
+            process: None,
+        }
+    }
+
+    /// Use the server given by `OVSDB_SOCKET` if it is set, and start one
+    /// hosting the schemas otherwise.
+    pub async fn from_env<P: AsRef<Path>>(
+        schemas: impl IntoIterator<Item = P>,
+    ) -> Result<Self, Error> {
+        match env::var_os(SOCKET_ENV) {
+            Some(socket) => Ok(Self::with_socket(socket)),
+            None => Self::start(schemas).await,
+        }
+    }
+
+    /// Returns the path of the socket of the server.
+    pub fn socket(&self) -> &Path {
+        &self.socket
+    }
+
+    /// Returns whether the server was started for the tests, rather than
+    /// given by its socket.
+    pub fn is_started(&self) -> bool {
+        self.process.is_some()
+    }
+
+    /// Connect a client to the server.
+    pub async fn connect(&self) -> Result<OvsdbClient, Error> {
+        Ok(rpc::connect_unix(&self.socket).await?)
+    }
+
+    /// Wait for the started server to accept connections, failing with its
+    /// log if it exits or does not listen in time.
+    async fn wait_listening(&mut self, log: &Path) -> Result<(), Error> {
+        let Some((child, _)) = &mut self.process else {
+            return Ok(());
+        };
+
+        let exited = |reason: String| Error::Command {
+            command: "ovsdb-server".to_owned(),
+            output: std::fs::read_to_string(log).unwrap_or(reason),
+        };
+
+        let deadline = tokio::time::Instant::now() + START_TIMEOUT;
+        loop {
+            if UnixStream::connect(&self.socket).await.is_ok() {
+                return Ok(());
+            }
+            if let Some(status) = child.try_wait()? {
+                return Err(exited(format!("exited with {status}")));
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(exited(format!("did not listen within {START_TIMEOUT:?}")));
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+}
+
+impl Drop for LiveServer {
+    fn drop(&mut self) {
+        if let Some((child, _)) = &mut self.process {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+fn failed(command: &str, error: std::io::Error) -> Error {
+    Error::Command {
+        command: command.to_owned(),
+        output: error.to_string(),
+    }
+}
+
+fn program(var: &str, default: &str) -> OsString {
+    env::var_os(var).unwrap_or_else(|| default.into())
+}
@@ -0,0 +1,187 @@
+use crate::Error;
+use jsonrpsee::core::client::SubscriptionClientT;
+use ovsdb_client::transports::listener::{Connection, MessageReceiver, MessageSender, framed};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+};
+use tokio::{io::DuplexStream, task::JoinHandle};
+
+/// A message exchanged over a connection, stored as one line of a recording
+/// such as `{"client": {"id": 0, "method": "list_dbs", "params": []}}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Frame {
+    /// Sent by the client
+    Client(Value),
+
+    /// Sent by the server
+    Server(Value),
+}
+
+/// Records the messages exchanged over connections to a file
+///
+/// Cloning the recorder gives another handle writing to the same file.
+#[derive(Clone)]
+pub struct Recorder {
+    file: Arc<Mutex<File>>,
+}
+
+impl Recorder {
+    /// Record to a new file, truncating it if it exists.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(Recorder {
+            file: Arc::new(Mutex::new(File::create(path)?)),
+        })
+    }
+
+    /// Wrap a live connection to a server, returning a stream to build the
+    /// client on, such as with `ovsdb_client::rpc::connect_stream`.
+    pub fn wrap(&self, connection: impl Connection + 'static) -> DuplexStream {
+        let (client, proxy) = tokio::io::duplex(64 * 1024);
+        let (client_sender, client_receiver) = framed(proxy);
+        let (server_sender, server_receiver) = framed(connection);
+
+        tokio::spawn(
+            self.clone()
+                .pump(client_receiver, server_sender, Frame::Client),
+        );
+        tokio::spawn(
+            self.clone()
+                .pump(server_receiver, client_sender, Frame::Server),
+        );
+
+        client
+    }
+
+    /// Forward the messages received from one side to the other, recording
+    /// them.
+    async fn pump(
+        self,
+        mut receiver: MessageReceiver,
+        mut sender: MessageSender,
+        frame: fn(Value) -> Frame,
+    ) -> Result<(), Error> {
+        while let Some(message) = receiver.receive().await.transpose()? {
+            // NOTE(mnaser): The message is recorded before it is forwarded, so that a
+            //               request is always written to the file before its reply.
+            self.record(&frame(message.clone()))?;
+            sender.send(&message).await?;
+        }
+
+        sender.close().await?;
+        Ok(())
+    }
+
+    fn record(&self, frame: &Frame) -> Result<(), Error> {
+        let mut line = serde_json::to_vec(frame)?;
+        line.push(b'\n');
+        self.file.lock().unwrap().write_all(&line)?;
+
+        Ok(())
+    }
+}
+
+/// Plays back the server side of a recording
+///
+/// The server messages are sent in the recorded order, each batch once the
+/// client messages recorded before it have been received, so replies and
+/// update notifications interleave exactly as they did on the live
+/// connection. Every client message must match the recorded one, except for
+/// its `id`, which is substituted in the replies.
+#[derive(Clone, Debug)]
+pub struct Replayer {
+    frames: Arc<Vec<Frame>>,
+}
+
+impl Replayer {
+    pub fn new(frames: Vec<Frame>) -> Self {
+        Replayer {
+            frames: Arc::new(frames),
+        }
+    }
+
+    /// Load a recording written by [`Recorder`].
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let mut frames = vec![];
+        for line in BufReader::new(File::open(path)?).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                frames.push(serde_json::from_str(&line)?);
+            }
+        }
+
+        Ok(Self::new(frames))
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Connect a client through an in-memory stream, returning it with the
+    /// task replaying the recording.
+    pub fn connect(&self) -> (impl SubscriptionClientT, JoinHandle<Result<(), Error>>) {
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let replay = self.serve(server);
+
+        (ovsdb_client::rpc::connect_stream(client), replay)
+    }
+
+    /// Replay the recording to a client connected over the stream.
+    ///
+    /// The task completes once the client disconnects. It fails on the first
+    /// client message that differs from the recording, and if the client
+    /// disconnects before sending every recorded message.
+    pub fn serve(&self, connection: impl Connection + 'static) -> JoinHandle<Result<(), Error>> {
+        let frames = self.frames.clone();
+
+        tokio::spawn(async move {
+            let (mut sender, mut receiver) = framed(connection);
+            let mut ids = HashMap::new();
+
+            for (index, frame) in frames.iter().enumerate() {
+                match frame {
+                    Frame::Client(expected) => {
+                        let Some(actual) = receiver.receive().await.transpose()? else {
+                            return Err(Error::Incomplete(frames.len() - index));
+                        };
+                        if without_id(expected) != without_id(&actual) {
+                            return Err(Error::Mismatch {
+                                expected: expected.clone(),
+                                actual,
+                            });
+                        }
+                        if !expected["id"].is_null() {
+                            ids.insert(expected["id"].to_string(), actual["id"].clone());
+                        }
+                    }
+                    Frame::Server(message) => {
+                        let mut message = message.clone();
+                        if let Some(id) = ids.get(&message["id"].to_string()) {
+                            message["id"] = id.clone();
+                        }
+                        sender.send(&message).await?;
+                    }
+                }
+            }
+
+            match receiver.receive().await.transpose()? {
+                Some(message) => Err(Error::Unexpected(message)),
+                None => Ok(()),
+            }
+        })
+    }
+}
+
+fn without_id(message: &Value) -> Value {
+    let mut message = message.clone();
+    if let Some(message) = message.as_object_mut() {
+        message.remove("id");
+    }
+    message
+}
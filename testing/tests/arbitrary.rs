@@ -0,0 +1,111 @@
+use ovsdb_client::schema::{ColumnType, DatabaseSchema, TableSchema};
+use ovsdb_schema::{OvsdbValue, json_to_ovsdb_value, ovsdb_atom_to_json, ovsdb_value_to_json};
+use ovsdb_testing::arbitrary;
+use proptest::prelude::*;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+const SCHEMAS: [&str; 3] = [
+    include_str!("../../bindings/schemas/ovn-nb.ovsschema"),
+    include_str!("../../bindings/schemas/ovn-sb.ovsschema"),
+    include_str!("../../bindings/schemas/vswitch.ovsschema"),
+];
+
+fn schemas() -> Vec<DatabaseSchema> {
+    SCHEMAS
+        .iter()
+        .map(|schema| serde_json::from_str(schema).unwrap())
+        .collect()
+}
+
+/// Every column type of the schemas, with the name of its column
+fn columns() -> Vec<(String, ColumnType)> {
+    let mut columns: Vec<(String, ColumnType)> = schemas()
+        .into_iter()
+        .flat_map(|schema| schema.tables.into_iter())
+        .flat_map(|(table, schema)| {
+            schema
+                .columns
+                .into_iter()
+                .map(move |(column, schema)| (format!("{table}.{column}"), schema.r#type))
+        })
+        .collect();
+    columns.sort_by(|a, b| a.0.cmp(&b.0));
+    columns
+}
+
+fn column_values() -> impl Strategy<Value = (String, ColumnType, OvsdbValue)> {
+    prop::sample::select(columns()).prop_flat_map(|(name, column)| {
+        let value = arbitrary::value(&column);
+        (Just(name), Just(column), value)
+    })
+}
+
+fn rows() -> impl Strategy<Value = HashMap<String, OvsdbValue>> {
+    let tables: Vec<TableSchema> = schemas()
+        .into_iter()
+        .flat_map(|schema| schema.tables.into_values())
+        .collect();
+    prop::strategy::Union::new(tables.iter().map(arbitrary::row))
+}
+
+/// The value as it is decoded from the wire, where a set of one element is
+/// indistinguishable from its atom
+fn received(value: &OvsdbValue) -> OvsdbValue {
+    match value {
+        OvsdbValue::Set(set) if set.len() == 1 => OvsdbValue::Atom(set[0].clone()),
+        value => value.clone(),
+    }
+}
+
+proptest! {
+    #[test]
+    fn test_values_round_trip((name, _, value) in column_values()) {
+        let json = ovsdb_value_to_json(&value).unwrap();
+        prop_assert_eq!(serde_json::to_value(&value).unwrap(), json.clone(), "{}", name);
+
+        let text = serde_json::to_string(&value).unwrap();
+        let parsed: OvsdbValue = serde_json::from_str(&text).unwrap();
+        prop_assert_eq!(&parsed, &received(&value), "{}", name);
+        prop_assert_eq!(json_to_ovsdb_value(&json).unwrap(), parsed, "{}", name);
+    }
+
+    #[test]
+    fn test_wire_format((name, column, value) in column_values()) {
+        let json = ovsdb_value_to_json(&value).unwrap();
+        match &value {
+            OvsdbValue::Atom(atom) => {
+                prop_assert!(column.is_scalar(), "{}", name);
+                prop_assert_eq!(json, ovsdb_atom_to_json(atom).unwrap(), "{}", name);
+            }
+            OvsdbValue::Set(set) => match set.as_slice() {
+                [] => prop_assert_eq!(json, json!([]), "{}", name),
+                [atom] => prop_assert_eq!(json, ovsdb_atom_to_json(atom).unwrap(), "{}", name),
+                atoms => prop_assert_eq!(
+                    json,
+                    json!(["set", atoms.iter().map(|atom| ovsdb_atom_to_json(atom).unwrap()).collect::<Vec<_>>()]),
+                    "{}",
+                    name
+                ),
+            },
+            OvsdbValue::Map(map) => {
+                prop_assert!(column.is_map(), "{}", name);
+                prop_assert_eq!(&json[0], "map", "{}", name);
+                prop_assert_eq!(json[1].as_array().unwrap().len(), map.len(), "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rows_round_trip(row in rows()) {
+        let text = serde_json::to_string(&row).unwrap();
+        let object: HashMap<String, Value> = serde_json::from_str(&text).unwrap();
+        let parsed: HashMap<String, OvsdbValue> = serde_json::from_str(&text).unwrap();
+
+        prop_assert_eq!(parsed.len(), row.len());
+        for (column, value) in &row {
+            prop_assert_eq!(&parsed[column], &received(value), "{}", column);
+            prop_assert_eq!(&object[column], &ovsdb_value_to_json(value).unwrap(), "{}", column);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use ovsdb_client::{
+    Error,
+    backup::{self, Backup},
+    file::Database,
+};
+use ovsdb_testing::MockServer;
+use serde_json::{Value, json};
+
+fn schema() -> Value {
+    json!({
+        "name": "OVN_Northbound",
+        "version": "7.3.0",
+        "tables": {
+            "Logical_Switch": {
+                "columns": {
+                    "name": {"type": "string"},
+                    "ports": {"type": {"key": {"type": "uuid", "refTable": "Logical_Switch_Port"},
+                                       "min": 0, "max": "unlimited"}}
+                },
+                "isRoot": true
+            },
+            "Logical_Switch_Port": {
+                "columns": {"name": {"type": "string"}}
+            }
+        }
+    })
+}
+
+fn server() -> MockServer {
+    MockServer::new().with_database(schema()).unwrap()
+}
+
+#[tokio::test]
+async fn test_dump_and_restore() {
+    let source = server();
+    source.transact(
+        "OVN_Northbound",
+        vec![
+            json!({"op": "insert", "table": "Logical_Switch_Port",
+                   "row": {"name": "lsp0"}, "uuid-name": "lsp0"}),
+            json!({"op": "insert", "table": "Logical_Switch",
+                   "row": {"name": "ls0", "ports": ["named-uuid", "lsp0"]}}),
+        ],
+    );
+
+    let backup = backup::dump_database(&source.connect(), "OVN_Northbound")
+        .await
+        .unwrap();
+    assert_eq!(backup.schema, schema());
+    assert_eq!(backup.tables["Logical_Switch"].len(), 1);
+    assert_eq!(backup.tables["Logical_Switch_Port"].len(), 1);
+
+    let mut file = Vec::new();
+    backup.write(&mut file).unwrap();
+    assert_eq!(Backup::read(file.as_slice()).unwrap(), backup);
+    let database = Database::read(file.as_slice()).unwrap();
+    assert_eq!(
+        database.tables["Logical_Switch"],
+        backup.tables["Logical_Switch"]
+    );
+
+    let target = server();
+    target.transact(
+        "OVN_Northbound",
+        vec![json!({"op": "insert", "table": "Logical_Switch", "row": {"name": "stale"}})],
+    );
+    backup::restore(&target.connect(), "OVN_Northbound", &backup)
+        .await
+        .unwrap();
+
+    let switches = target.rows("OVN_Northbound", "Logical_Switch");
+    let ports = target.rows("OVN_Northbound", "Logical_Switch_Port");
+    assert_eq!(switches.len(), 1);
+    assert_eq!(switches[0]["name"], "ls0");
+    assert_eq!(ports.len(), 1);
+    assert_eq!(ports[0]["name"], "lsp0");
+    assert_eq!(switches[0]["ports"], ports[0]["_uuid"]);
+
+    let mut other = backup.clone();
+    other.schema["name"] = json!("OVN_Southbound");
+    let error = backup::restore(&target.connect(), "OVN_Northbound", &other)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::InvalidSchema(_)));
+}
@@ -0,0 +1,417 @@
+//! Conformance tests against a real `ovsdb-server`, run with
+//! `cargo test -p ovsdb-testing --features live-tests`.
+//!
+//! A server hosting the OVN schemas is started for each test, unless
+//! `OVSDB_SOCKET` gives the socket of a running one. The tests then only
+//! touch the rows and locks they create, whose names are unique.
+
+use jsonrpsee::{
+    core::client::{ClientT, SubscriptionClientT},
+    rpc_params,
+};
+use ovsdb_client::{
+    OvsdbClient, OvsdbErrorKind,
+    rpc::{Method, RpcClient},
+    schema::{
+        MonitorCondRequest, MonitorRequest, RowUpdate2, Update2Notification, UpdateNotification,
+    },
+    transact::{Condition, Function, Operation, OperationResult, TransactClient},
+};
+use ovsdb_schema::{Mutation, Mutator, OvsdbAtom, OvsdbSerializable, OvsdbValue};
+use ovsdb_testing::live::LiveServer;
+use serde_json::{Value, json};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const NB: &str = "OVN_Northbound";
+
+async fn server() -> LiveServer {
+    let schemas = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../bindings/schemas");
+    LiveServer::from_env([
+        schemas.join("ovn-nb.ovsschema"),
+        schemas.join("ovn-sb.ovsschema"),
+    ])
+    .await
+    .unwrap()
+}
+
+/// Returns a name which no other test, nor another run against the same
+/// server, uses.
+fn unique(prefix: &str) -> String {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    format!(
+        "{prefix}-{}-{nanos}-{}",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn named(name: &str) -> Vec<Condition> {
+    vec![Condition::new("name", Function::Equal, json!(name))]
+}
+
+fn insert(table: &str, row: Value, uuid_name: Option<&str>) -> Operation {
+    Operation::Insert {
+        table: table.to_owned(),
+        row: serde_json::from_value(row).unwrap(),
+        uuid_name: uuid_name.map(str::to_owned),
+    }
+}
+
+fn select(table: &str, name: &str) -> Operation {
+    Operation::Select {
+        table: table.to_owned(),
+        conditions: named(name),
+        columns: None,
+    }
+}
+
+fn delete(table: &str, name: &str) -> Operation {
+    Operation::Delete {
+        table: table.to_owned(),
+        conditions: named(name),
+    }
+}
+
+fn rows(result: &OperationResult) -> &[HashMap<String, Value>] {
+    result.rows.as_deref().unwrap()
+}
+
+/// Request the lock, returning whether the client got it at once.
+async fn locked(client: &OvsdbClient, method: &str, lock: &str) -> bool {
+    let result: Value = client.request(method, rpc_params![lock]).await.unwrap();
+    result["locked"].as_bool().unwrap()
+}
+
+/// Returns the next notification accepted by the filter, such as those of
+/// a monitor, skipping the others.
+async fn next_update<T: serde::de::DeserializeOwned>(
+    updates: &mut jsonrpsee::core::client::Subscription<T>,
+    id: impl Fn(&T) -> bool,
+) -> T {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let update = updates.next().await.unwrap().unwrap();
+            if id(&update) {
+                return update;
+            }
+        }
+    })
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_schemas() {
+    let server = server().await;
+    let client = server.connect().await.unwrap();
+
+    let databases = client.list_databases().await.unwrap();
+    assert!(databases.iter().any(|db| db == NB), "{databases:?}");
+    assert!(client.has_database("OVN_Southbound").await.unwrap());
+
+    let schema = client.get_schema(NB).await.unwrap();
+    assert_eq!(schema.name, NB);
+    assert!(
+        schema.tables["Logical_Switch"]
+            .columns
+            .contains_key("ports")
+    );
+
+    for method in [Method::Monitor, Method::MonitorCond] {
+        assert!(client.server_supports(method).await.unwrap(), "{method:?}");
+    }
+    assert_eq!(
+        client.echo(vec![json!("ping"), json!(1)]).await.unwrap(),
+        vec![json!("ping"), json!(1)]
+    );
+}
+
+#[tokio::test]
+async fn test_transact() {
+    let server = server().await;
+    let client = server.connect().await.unwrap();
+    let switch = unique("ls");
+    let port = unique("lsp");
+
+    let results = client
+        .transact(
+            NB,
+            vec![
+                insert(
+                    "Logical_Switch_Port",
+                    json!({"name": port, "addresses": ["set", ["00:00:00:00:00:01 10.0.0.1", "unknown"]]}),
+                    Some("lsp"),
+                ),
+                insert(
+                    "Logical_Switch",
+                    json!({
+                        "name": switch,
+                        "ports": ["named-uuid", "lsp"],
+                        "external_ids": ["map", [["owner", "live"]]],
+                    }),
+                    None,
+                ),
+                select("Logical_Switch", &switch),
+            ],
+        )
+        .await
+        .unwrap();
+    let port_uuid = results[0].uuid().unwrap();
+    let row = &rows(&results[2])[0];
+    assert_eq!(row["ports"], json!(["uuid", port_uuid.to_string()]));
+    assert_eq!(row["external_ids"], json!(["map", [["owner", "live"]]]));
+    assert_eq!(row["other_config"], json!(["map", []]));
+
+    let results = client
+        .transact(
+            NB,
+            vec![
+                Operation::Mutate {
+                    table: "Logical_Switch".to_owned(),
+                    conditions: named(&switch),
+                    mutations: vec![Mutation::new(
+                        "external_ids",
+                        Mutator::Insert,
+                        HashMap::from([("team".to_owned(), "net".to_owned())]).to_ovsdb(),
+                    )],
+                },
+                Operation::Update {
+                    table: "Logical_Switch_Port".to_owned(),
+                    conditions: named(&port),
+                    row: HashMap::from([("tag_request".to_owned(), json!(100))]),
+                },
+                select("Logical_Switch", &switch),
+                select("Logical_Switch_Port", &port),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(results[0].count, Some(1));
+    assert_eq!(results[1].count, Some(1));
+    assert_eq!(
+        rows(&results[2])[0]["external_ids"],
+        json!(["map", [["owner", "live"], ["team", "net"]]])
+    );
+    assert_eq!(rows(&results[3])[0]["tag_request"], json!(100));
+
+    // NOTE(mnaser): The second port is added to the switch, as the server
+    //               would otherwise collect it before checking the index.
+    let err = client
+        .transact(
+            NB,
+            vec![
+                insert("Logical_Switch_Port", json!({"name": port}), Some("dup")),
+                Operation::Mutate {
+                    table: "Logical_Switch".to_owned(),
+                    conditions: named(&switch),
+                    mutations: vec![Mutation::new(
+                        "ports",
+                        Mutator::Insert,
+                        OvsdbValue::Atom(OvsdbAtom::NamedUuid("dup".to_owned())),
+                    )],
+                },
+            ],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.ovsdb_kind(), Some(&OvsdbErrorKind::ConstraintViolation));
+
+    // The port is not a root row, so it goes away with the switch
+    let results = client
+        .transact(NB, vec![delete("Logical_Switch", &switch)])
+        .await
+        .unwrap();
+    assert_eq!(results[0].count, Some(1));
+    let results = client
+        .transact(NB, vec![select("Logical_Switch_Port", &port)])
+        .await
+        .unwrap();
+    assert!(rows(&results[0]).is_empty());
+}
+
+#[tokio::test]
+async fn test_monitor() {
+    let server = server().await;
+    let client = server.connect().await.unwrap();
+    let other = server.connect().await.unwrap();
+    let switch = unique("ls");
+    let id = json!(["live", unique("monitor")]);
+
+    let mut updates = client
+        .subscribe_to_method::<UpdateNotification<Value>>("update")
+        .await
+        .unwrap();
+    client
+        .monitor(
+            NB,
+            id.clone(),
+            HashMap::from([(
+                "Logical_Switch".to_owned(),
+                MonitorRequest {
+                    columns: Some(vec!["name".to_owned(), "external_ids".to_owned()]),
+                    ..Default::default()
+                },
+            )]),
+        )
+        .await
+        .unwrap();
+
+    let results = other
+        .transact(
+            NB,
+            vec![insert("Logical_Switch", json!({"name": switch}), None)],
+        )
+        .await
+        .unwrap();
+    let uuid = results[0].uuid().unwrap();
+
+    let ours = |update: &UpdateNotification<Value>| {
+        update.id == id
+            && update
+                .message
+                .get("Logical_Switch")
+                .is_some_and(|rows| rows.contains_key(&uuid))
+    };
+    let update = next_update(&mut updates, ours).await;
+    let row = &update.message["Logical_Switch"][&uuid];
+    assert_eq!(row.old, None);
+    assert_eq!(
+        row.new,
+        Some(json!({"name": switch, "external_ids": ["map", []]}))
+    );
+
+    // The old row of a modification only holds the columns which changed
+    other
+        .transact(
+            NB,
+            vec![Operation::Update {
+                table: "Logical_Switch".to_owned(),
+                conditions: named(&switch),
+                row: HashMap::from([("external_ids".to_owned(), json!(["map", [["k", "v"]]]))]),
+            }],
+        )
+        .await
+        .unwrap();
+    let update = next_update(&mut updates, ours).await;
+    let row = &update.message["Logical_Switch"][&uuid];
+    assert_eq!(row.old, Some(json!({"external_ids": ["map", []]})));
+    assert_eq!(
+        row.new.as_ref().unwrap()["external_ids"],
+        json!(["map", [["k", "v"]]])
+    );
+
+    other
+        .transact(NB, vec![delete("Logical_Switch", &switch)])
+        .await
+        .unwrap();
+    let update = next_update(&mut updates, ours).await;
+    let row = &update.message["Logical_Switch"][&uuid];
+    assert_eq!(row.new, None);
+    assert_eq!(row.old.as_ref().unwrap()["name"], json!(switch));
+
+    client.shutdown().await.unwrap();
+    other.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_monitor_cond() {
+    let server = server().await;
+    let client = server.connect().await.unwrap();
+    let switch = unique("ls");
+    let unmatched = unique("ls");
+    let id = json!(["live", unique("monitor")]);
+
+    let mut updates = client
+        .subscribe_to_method::<Update2Notification<Value>>("update2")
+        .await
+        .unwrap();
+    let initial = client
+        .monitor_cond(
+            NB,
+            id.clone(),
+            HashMap::from([(
+                "Logical_Switch".to_owned(),
+                MonitorCondRequest {
+                    columns: Some(vec!["name".to_owned()]),
+                    conditions: Some(named(&switch)),
+                    ..Default::default()
+                },
+            )]),
+        )
+        .await
+        .unwrap();
+    assert!(initial.get("Logical_Switch").is_none_or(HashMap::is_empty));
+
+    let results = client
+        .transact(
+            NB,
+            vec![
+                insert("Logical_Switch", json!({"name": unmatched}), None),
+                insert("Logical_Switch", json!({"name": switch}), None),
+            ],
+        )
+        .await
+        .unwrap();
+    let uuid = results[1].uuid().unwrap();
+
+    // Only the switch matching the condition is reported
+    let update = next_update(&mut updates, |update| update.id == id).await;
+    let rows = &update.message["Logical_Switch"];
+    assert_eq!(rows.len(), 1);
+    assert!(matches!(
+        &rows[&uuid],
+        RowUpdate2::Insert(row) if row == &json!({"name": switch})
+    ));
+
+    client
+        .transact(
+            NB,
+            vec![
+                delete("Logical_Switch", &switch),
+                delete("Logical_Switch", &unmatched),
+            ],
+        )
+        .await
+        .unwrap();
+    let update = next_update(&mut updates, |update| update.id == id).await;
+    assert!(matches!(
+        update.message["Logical_Switch"][&uuid],
+        RowUpdate2::Delete
+    ));
+
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_locks() {
+    let server = server().await;
+    let owner = server.connect().await.unwrap();
+    let waiter = server.connect().await.unwrap();
+    let lock = unique("lock");
+
+    let mut notifications = waiter.subscribe_to_method::<Value>("locked").await.unwrap();
+    assert!(locked(&owner, "lock", &lock).await);
+    assert!(!locked(&waiter, "lock", &lock).await);
+
+    // The lock goes to the client waiting for it once released
+    let _: Value = owner.request("unlock", rpc_params![&lock]).await.unwrap();
+    let notification = next_update(&mut notifications, |_| true).await;
+    assert_eq!(notification, json!([lock]));
+
+    let mut stolen = waiter.subscribe_to_method::<Value>("stolen").await.unwrap();
+    assert!(locked(&owner, "steal", &lock).await);
+    let notification = next_update(&mut stolen, |_| true).await;
+    assert_eq!(notification, json!([lock]));
+
+    owner.shutdown().await.unwrap();
+    waiter.shutdown().await.unwrap();
+}
@@ -0,0 +1,120 @@
+use ovsdb_client::{
+    crud,
+    transact::{Condition, Operation, TransactClient},
+};
+use ovsdb_derive::ovsdb_object;
+use ovsdb_schema::{Mutation, Mutator, OvsdbSerializable};
+use ovsdb_testing::MockServer;
+use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[ovsdb_object(table = "QoS")]
+#[derive(Debug, PartialEq)]
+pub struct Qos {
+    pub r#type: String,
+    pub queues: HashMap<i64, Uuid>,
+    pub other_config: HashMap<String, String>,
+}
+
+#[ovsdb_object(table = "Queue")]
+#[derive(Debug, PartialEq)]
+pub struct Queue {
+    pub dscp: Option<i64>,
+    pub other_config: HashMap<String, String>,
+}
+
+fn server() -> MockServer {
+    MockServer::new()
+        .with_database(json!({
+            "name": "Open_vSwitch",
+            "version": "8.8.0",
+            "tables": {
+                "QoS": {
+                    "columns": {
+                        "type": {"type": "string"},
+                        "queues": {
+                            "type": {"key": {"type": "integer",
+                                             "minInteger": 0, "maxInteger": 4294967295u64},
+                                     "value": {"type": "uuid", "refTable": "Queue"},
+                                     "min": 0, "max": "unlimited"}},
+                        "other_config": {
+                            "type": {"key": "string", "value": "string",
+                                     "min": 0, "max": "unlimited"}}
+                    },
+                    "isRoot": true
+                },
+                "Queue": {
+                    "columns": {
+                        "dscp": {"type": {"key": "integer", "min": 0, "max": 1}},
+                        "other_config": {
+                            "type": {"key": "string", "value": "string",
+                                     "min": 0, "max": "unlimited"}}
+                    },
+                    "isRoot": true
+                }
+            }
+        }))
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_integer_to_uuid_map() {
+    let server = server();
+    let client = server.connect();
+
+    let queue = Queue {
+        dscp: Some(46),
+        other_config: HashMap::from([("max-rate".to_owned(), "1000000".to_owned())]),
+        ..Default::default()
+    };
+    let q0 = crud::insert(&client, "Open_vSwitch", &queue).await.unwrap();
+    let q1 = crud::insert(&client, "Open_vSwitch", &Queue::default())
+        .await
+        .unwrap();
+
+    let qos = Qos {
+        r#type: "linux-htb".to_owned(),
+        queues: HashMap::from([(0, q0)]),
+        ..Default::default()
+    };
+    let uuid = crud::insert(&client, "Open_vSwitch", &qos).await.unwrap();
+
+    let fetched: Qos = crud::get(&client, "Open_vSwitch", uuid)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(fetched.queues, qos.queues);
+    assert!(fetched.other_config.is_empty());
+
+    // NOTE(mnaser): Entries are added to the map with a mutation, as ovs-vsctl does.
+    let mutation = Mutation::new(
+        "queues",
+        Mutator::Insert,
+        HashMap::from([(1i64, q1)]).to_ovsdb(),
+    );
+    client
+        .transact(
+            "Open_vSwitch",
+            vec![Operation::Mutate {
+                table: "QoS".to_owned(),
+                conditions: vec![Condition::uuid(uuid)],
+                mutations: vec![mutation],
+            }],
+        )
+        .await
+        .unwrap();
+
+    let fetched: Qos = crud::get(&client, "Open_vSwitch", uuid)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(fetched.queues, HashMap::from([(0, q0), (1, q1)]));
+
+    let fetched: Queue = crud::get(&client, "Open_vSwitch", q0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(fetched.dscp, Some(46));
+    assert_eq!(fetched.other_config["max-rate"], "1000000");
+}
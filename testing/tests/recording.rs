@@ -0,0 +1,137 @@
+use jsonrpsee::core::client::SubscriptionClientT;
+use ovsdb_client::{
+    rpc::{self, RpcClient},
+    schema::{MonitorRequest, UpdateNotification},
+    transact::{Operation, TransactClient},
+};
+use ovsdb_testing::{Error, Frame, MockServer, Recorder, Replayer};
+use serde_json::{Value, json};
+use std::{collections::HashMap, path::PathBuf};
+
+fn server() -> MockServer {
+    MockServer::new()
+        .with_database(json!({
+            "name": "OVN_Northbound",
+            "version": "7.3.0",
+            "tables": {
+                "Logical_Switch": {
+                    "columns": {"name": {"type": "string"}},
+                    "isRoot": true
+                }
+            }
+        }))
+        .unwrap()
+}
+
+fn recording(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("ovsdb-testing-{}-{name}.jsonl", std::process::id()))
+}
+
+fn insert(name: &str) -> Operation {
+    Operation::Insert {
+        table: "Logical_Switch".to_owned(),
+        row: HashMap::from([("name".to_owned(), json!(name))]),
+        uuid_name: None,
+    }
+}
+
+/// Monitor the switches, create one and return the initial rows, the result
+/// of the transaction and the update notification.
+async fn scenario(client: &(impl SubscriptionClientT + Sync)) -> (Value, Value, Value) {
+    let mut updates = client
+        .subscribe_to_method::<UpdateNotification<Value>>("update")
+        .await
+        .unwrap();
+
+    let initial = client
+        .monitor(
+            "OVN_Northbound",
+            Value::Null,
+            HashMap::from([("Logical_Switch".to_owned(), MonitorRequest::default())]),
+        )
+        .await
+        .unwrap();
+    let results = client
+        .transact("OVN_Northbound", vec![insert("ls1")])
+        .await
+        .unwrap();
+    let update = updates.next().await.unwrap().unwrap();
+
+    (
+        serde_json::to_value(initial).unwrap(),
+        json!(results[0].uuid()),
+        serde_json::to_value(update.message).unwrap(),
+    )
+}
+
+#[tokio::test]
+async fn test_record_and_replay() {
+    let server = server();
+    server.transact(
+        "OVN_Northbound",
+        vec![json!({"op": "insert", "table": "Logical_Switch", "row": {"name": "ls0"}})],
+    );
+
+    let path = recording("replay");
+    let recorder = Recorder::create(&path).unwrap();
+    let (connection, stream) = tokio::io::duplex(64 * 1024);
+    server.serve(stream);
+    let client = rpc::connect_stream(recorder.wrap(connection));
+    let recorded = scenario(&client).await;
+    drop(client);
+
+    let replayer = Replayer::open(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        &replayer.frames()[..2],
+        [Frame::Client(request), Frame::Server(_)] if request["method"] == "monitor"
+    ));
+
+    // The update notification is replayed before the reply to the transaction
+    let methods = replayer
+        .frames()
+        .iter()
+        .map(|frame| match frame {
+            Frame::Client(message) => format!("client {}", message["method"]),
+            Frame::Server(message) => format!("server {}", message["method"]),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        methods,
+        vec![
+            "client \"monitor\"",
+            "server null",
+            "client \"transact\"",
+            "server \"update\"",
+            "server null",
+        ]
+    );
+
+    let (client, replay) = replayer.connect();
+    assert_eq!(scenario(&client).await, recorded);
+    drop(client);
+    replay.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_replay_mismatch() {
+    let replayer = Replayer::new(vec![
+        Frame::Client(json!({"id": 0, "method": "list_dbs", "params": []})),
+        Frame::Server(json!({"id": 0, "result": ["OVN_Northbound"], "error": null})),
+    ]);
+
+    let (client, replay) = replayer.connect();
+    assert_eq!(
+        client.list_databases().await.unwrap(),
+        vec!["OVN_Northbound"]
+    );
+    drop(client);
+    replay.await.unwrap().unwrap();
+
+    let (client, replay) = replayer.connect();
+    assert!(client.get_schema("OVN_Northbound").await.is_err());
+    assert!(matches!(
+        replay.await.unwrap(),
+        Err(Error::Mismatch { actual, .. }) if actual["method"] == "get_schema"
+    ));
+}
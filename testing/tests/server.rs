@@ -0,0 +1,347 @@
+use jsonrpsee::core::client::SubscriptionClientT;
+use ovsdb_client::{
+    Error, OvsdbErrorKind, crud,
+    rpc::{self, Method, RpcClient},
+    schema::{MonitorRequest, UpdateNotification},
+    transact::{Condition, Function, Operation, TransactClient},
+};
+use ovsdb_derive::ovsdb_object;
+use ovsdb_testing::MockServer;
+use serde_json::{Value, json};
+use std::{collections::HashMap, time::Duration};
+
+#[ovsdb_object(table = "Logical_Switch")]
+#[derive(Debug, PartialEq)]
+pub struct LogicalSwitch {
+    pub name: String,
+    pub ports: Vec<String>,
+    pub external_ids: HashMap<String, String>,
+}
+
+fn server() -> MockServer {
+    MockServer::new()
+        .with_database(json!({
+            "name": "OVN_Northbound",
+            "version": "7.3.0",
+            "cksum": "1 1",
+            "tables": {
+                "Logical_Switch": {
+                    "columns": {
+                        "name": {"type": "string"},
+                        "ports": {"type": {"key": "string", "min": 0, "max": "unlimited"}},
+                        "external_ids": {
+                            "type": {"key": "string", "value": "string",
+                                     "min": 0, "max": "unlimited"}}
+                    },
+                    "isRoot": true
+                }
+            }
+        }))
+        .unwrap()
+}
+
+fn insert(name: &str) -> Operation {
+    Operation::Insert {
+        table: "Logical_Switch".to_owned(),
+        row: HashMap::from([("name".to_owned(), json!(name))]),
+        uuid_name: None,
+    }
+}
+
+#[tokio::test]
+async fn test_schema_methods() {
+    let server = server();
+    let client = server.connect();
+
+    assert_eq!(
+        client.list_databases().await.unwrap(),
+        vec!["OVN_Northbound"]
+    );
+
+    let schema = client.get_schema("OVN_Northbound").await.unwrap();
+    assert_eq!(schema.checksum.as_deref(), Some("1 1"));
+    assert!(schema.tables.contains_key("Logical_Switch"));
+
+    assert_eq!(
+        client.echo(vec![json!("ping")]).await.unwrap(),
+        vec![json!(["ping"])]
+    );
+    assert_eq!(server.requests()[0]["method"], "list_dbs");
+}
+
+#[tokio::test]
+async fn test_capabilities() {
+    let server = server();
+    let client = server.connect();
+
+    assert!(client.has_database("OVN_Northbound").await.unwrap());
+    assert!(!client.has_database("OVN_Southbound").await.unwrap());
+
+    assert!(client.server_supports(Method::Monitor).await.unwrap());
+    assert!(
+        !client
+            .server_supports(Method::MonitorCondSince)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_crud() {
+    let server = server();
+    let client = server.connect();
+
+    let mut switch = LogicalSwitch::new();
+    switch.name = "ls0".to_owned();
+    switch.ports = vec!["p1".to_owned(), "p2".to_owned()];
+    let uuid = crud::insert(&client, "OVN_Northbound", &switch)
+        .await
+        .unwrap();
+
+    let fetched: LogicalSwitch = crud::get(&client, "OVN_Northbound", uuid)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(fetched._uuid, Some(uuid));
+    assert_eq!(fetched.name, "ls0");
+    assert_eq!(fetched.ports, vec!["p1", "p2"]);
+
+    let updated = crud::update::<LogicalSwitch>(
+        &client,
+        "OVN_Northbound",
+        uuid,
+        HashMap::from([("name".to_owned(), json!("ls1"))]),
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated, 1);
+    assert_eq!(
+        server.rows("OVN_Northbound", "Logical_Switch")[0]["name"],
+        "ls1"
+    );
+
+    assert_eq!(
+        crud::delete::<LogicalSwitch>(&client, "OVN_Northbound", uuid)
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(server.rows("OVN_Northbound", "Logical_Switch").is_empty());
+}
+
+#[tokio::test]
+async fn test_transact() {
+    let server = server();
+    let client = server.connect();
+
+    let results = client
+        .transact(
+            "OVN_Northbound",
+            vec![
+                Operation::Insert {
+                    table: "Logical_Switch".to_owned(),
+                    row: HashMap::from([
+                        ("name".to_owned(), json!("ls0")),
+                        ("external_ids".to_owned(), json!(["map", [["owner", "a"]]])),
+                    ]),
+                    uuid_name: Some("ls".to_owned()),
+                },
+                Operation::Mutate {
+                    table: "Logical_Switch".to_owned(),
+                    conditions: vec![Condition::new("name", Function::Equal, json!("ls0"))],
+                    mutations: vec![
+                        serde_json::from_value(json!(["ports", "insert", ["set", ["p1", "p2"]]]))
+                            .unwrap(),
+                    ],
+                },
+                Operation::Select {
+                    table: "Logical_Switch".to_owned(),
+                    conditions: vec![Condition::new(
+                        "external_ids",
+                        Function::Includes,
+                        json!(["map", [["owner", "a"]]]),
+                    )],
+                    columns: Some(vec!["name".to_owned(), "ports".to_owned()]),
+                },
+            ],
+        )
+        .await
+        .unwrap();
+    assert!(results[0].uuid().is_some());
+    assert_eq!(results[1].count, Some(1));
+    assert_eq!(
+        serde_json::to_value(&results[2].rows).unwrap(),
+        json!([{"name": "ls0", "ports": ["set", ["p1", "p2"]]}])
+    );
+
+    // A failed operation rolls back the whole transaction
+    let err = client
+        .transact(
+            "OVN_Northbound",
+            vec![
+                insert("ls1"),
+                Operation::Update {
+                    table: "Logical_Switch".to_owned(),
+                    conditions: vec![],
+                    row: HashMap::from([("unknown".to_owned(), json!(1))]),
+                },
+            ],
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.ovsdb_kind(), Some(&OvsdbErrorKind::ConstraintViolation));
+    assert_eq!(server.rows("OVN_Northbound", "Logical_Switch").len(), 1);
+}
+
+#[tokio::test]
+async fn test_monitor() {
+    let server = server();
+    server.transact(
+        "OVN_Northbound",
+        vec![json!({"op": "insert", "table": "Logical_Switch", "row": {"name": "ls0"}})],
+    );
+
+    let client = server.connect();
+    let mut updates = client
+        .subscribe_to_method::<UpdateNotification<Value>>("update")
+        .await
+        .unwrap();
+
+    let initial = client
+        .monitor(
+            "OVN_Northbound",
+            Value::Null,
+            HashMap::from([(
+                "Logical_Switch".to_owned(),
+                MonitorRequest {
+                    columns: Some(vec!["name".to_owned()]),
+                    ..Default::default()
+                },
+            )]),
+        )
+        .await
+        .unwrap();
+    let rows = &initial["Logical_Switch"];
+    assert_eq!(rows.len(), 1);
+    assert_eq!(
+        rows.values().next().unwrap().new,
+        Some(json!({"name": "ls0"}))
+    );
+
+    // Changes to columns which are not monitored are not reported
+    client
+        .transact(
+            "OVN_Northbound",
+            vec![Operation::Update {
+                table: "Logical_Switch".to_owned(),
+                conditions: vec![],
+                row: HashMap::from([("ports".to_owned(), json!("p1"))]),
+            }],
+        )
+        .await
+        .unwrap();
+    client
+        .transact(
+            "OVN_Northbound",
+            vec![Operation::Update {
+                table: "Logical_Switch".to_owned(),
+                conditions: vec![],
+                row: HashMap::from([("name".to_owned(), json!("ls1"))]),
+            }],
+        )
+        .await
+        .unwrap();
+
+    let update = updates.next().await.unwrap().unwrap();
+    assert_eq!(update.id, Value::Null);
+    let row = update.message["Logical_Switch"].values().next().unwrap();
+    assert_eq!(row.old, Some(json!({"name": "ls0"})));
+    assert_eq!(row.new, Some(json!({"name": "ls1"})));
+
+    client
+        .transact(
+            "OVN_Northbound",
+            vec![Operation::Delete {
+                table: "Logical_Switch".to_owned(),
+                conditions: vec![],
+            }],
+        )
+        .await
+        .unwrap();
+    let update = updates.next().await.unwrap().unwrap();
+    let row = update.message["Logical_Switch"].values().next().unwrap();
+    assert_eq!(row.old, Some(json!({"name": "ls1"})));
+    assert!(row.new.is_none());
+}
+
+#[tokio::test]
+async fn test_wait_until() {
+    let server = server();
+    let (connection, stream) = tokio::io::duplex(64 * 1024);
+    server.serve(stream);
+    let client = rpc::connect_stream(connection);
+
+    let waiting = client.wait_until::<LogicalSwitch>(
+        "OVN_Northbound",
+        |switch| switch.name == "ls1",
+        Duration::from_secs(5),
+    );
+    let inserting = async {
+        client
+            .transact("OVN_Northbound", vec![insert("ls0")])
+            .await
+            .unwrap();
+        client
+            .transact("OVN_Northbound", vec![insert("ls1")])
+            .await
+            .unwrap();
+    };
+    let (switch, ()) = tokio::join!(waiting, inserting);
+    assert_eq!(switch.unwrap().name, "ls1");
+
+    // Rows which already satisfy the predicate are found at once
+    let switch = client
+        .wait_until::<LogicalSwitch>(
+            "OVN_Northbound",
+            |switch| switch.name == "ls0",
+            Duration::from_secs(5),
+        )
+        .await
+        .unwrap();
+    assert_eq!(switch.name, "ls0");
+
+    let error = client
+        .wait_until::<LogicalSwitch>(
+            "OVN_Northbound",
+            |switch| switch.name == "ls2",
+            Duration::from_millis(50),
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(error, Error::TimedOut));
+
+    let cancels = server
+        .requests()
+        .iter()
+        .filter(|request| request["method"] == "monitor_cancel")
+        .count();
+    assert_eq!(cancels, 3);
+}
+
+#[tokio::test]
+async fn test_unix_socket() {
+    let server = server();
+    let path = std::env::temp_dir().join(format!("ovsdb-testing-{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let listener = server.listen_unix(&path).unwrap();
+
+    let client = rpc::connect_unix(&path).await.unwrap();
+    client
+        .transact("OVN_Northbound", vec![insert("ls0")])
+        .await
+        .unwrap();
+    assert_eq!(server.rows("OVN_Northbound", "Logical_Switch").len(), 1);
+
+    listener.abort();
+    std::fs::remove_file(&path).unwrap();
+}